@@ -0,0 +1,7 @@
+-- Last seen state of each boosted hex, the encoded BoostedHexInfoV1, from
+-- which the watcher derives lifecycle events
+create table boosted_hex_states (
+    location bigint primary key not null,
+    info bytea not null,
+    updated_at timestamptz not null default now()
+);
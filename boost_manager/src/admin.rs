@@ -0,0 +1,225 @@
+//
+// Operator tooling for boosted hex activations, shared by the `activations`
+// cli commands and the admin http api
+//
+// Activations are identified by their h3 cell index in hex, as in explorers.
+//
+
+use crate::{
+    db::{self, ActivationRow},
+    OnChainStatus,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use solana::start_boost::SolanaNetwork;
+use sqlx::{Pool, Postgres};
+use std::num::ParseIntError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum AdminError {
+    #[error("invalid location {0}: {1}")]
+    InvalidLocation(String, ParseIntError),
+    #[error("no activation for location {0:x}")]
+    NotFound(u64),
+    #[error("activation for location {location:x} is {status:?}")]
+    Status {
+        location: u64,
+        status: OnChainStatus,
+    },
+    #[error("activation for location {location:x} awaits confirmation of txn {txn_id}")]
+    AwaitingConfirmation { location: u64, txn_id: String },
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+pub fn parse_location(location: &str) -> Result<u64, AdminError> {
+    u64::from_str_radix(location.trim_start_matches("0x"), 16)
+        .map_err(|err| AdminError::InvalidLocation(location.to_string(), err))
+}
+
+/// Where the transaction of an activation stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Confirmation {
+    /// Waiting to be included in a batch
+    Unsubmitted,
+    /// Submitted in a batch txn which is not yet known to be on chain
+    AwaitingConfirmation,
+    Confirmed,
+    /// Retries exhausted
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Activation {
+    pub location: String,
+    pub activation_ts: DateTime<Utc>,
+    pub boosted_hex_pubkey: String,
+    pub boost_config_pubkey: String,
+    pub status: OnChainStatus,
+    /// Signature of the batch transaction the activation was submitted in
+    pub txn_id: Option<String>,
+    pub confirmation: Confirmation,
+    pub retries: i32,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl From<ActivationRow> for Activation {
+    fn from(row: ActivationRow) -> Self {
+        let confirmation = match (row.status, &row.txn_id) {
+            (OnChainStatus::Success, _) => Confirmation::Confirmed,
+            (OnChainStatus::Failed, _) => Confirmation::Failed,
+            (OnChainStatus::Cancelled, _) => Confirmation::Cancelled,
+            (OnChainStatus::Pending, _) | (OnChainStatus::Queued, Some(_)) => {
+                Confirmation::AwaitingConfirmation
+            }
+            (OnChainStatus::Queued, None) => Confirmation::Unsubmitted,
+        };
+        Self {
+            location: format!("{:x}", row.location),
+            activation_ts: row.activation_ts,
+            boosted_hex_pubkey: row.boosted_hex_pubkey,
+            boost_config_pubkey: row.boost_config_pubkey,
+            status: row.status,
+            txn_id: row.txn_id,
+            confirmation,
+            retries: row.retries,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+pub async fn list(
+    pool: &Pool<Postgres>,
+    status: Option<OnChainStatus>,
+) -> Result<Vec<Activation>, AdminError> {
+    Ok(db::list_activations(pool, status)
+        .await?
+        .into_iter()
+        .map(Activation::from)
+        .collect())
+}
+
+/// Queues an activation again with its retries reset
+pub async fn requeue(pool: &Pool<Postgres>, location: u64) -> Result<Activation, AdminError> {
+    if !db::requeue_activation(pool, location).await? {
+        return Err(refusal(pool, location).await);
+    }
+    tracing::info!(location = format!("{location:x}"), "re-queued activation");
+    fetch(pool, location).await
+}
+
+/// Cancels an activation that has not been submitted
+pub async fn cancel(pool: &Pool<Postgres>, location: u64) -> Result<Activation, AdminError> {
+    if !db::cancel_activation(pool, location).await? {
+        return Err(refusal(pool, location).await);
+    }
+    tracing::info!(location = format!("{location:x}"), "cancelled activation");
+    fetch(pool, location).await
+}
+
+async fn fetch(pool: &Pool<Postgres>, location: u64) -> Result<Activation, AdminError> {
+    db::get_activation(pool, location)
+        .await?
+        .map(Activation::from)
+        .ok_or(AdminError::NotFound(location))
+}
+
+/// Why an activation was left as it is
+async fn refusal(pool: &Pool<Postgres>, location: u64) -> AdminError {
+    match db::get_activation(pool, location).await {
+        Ok(Some(ActivationRow {
+            status,
+            txn_id: Some(txn_id),
+            ..
+        })) if status != OnChainStatus::Success => {
+            AdminError::AwaitingConfirmation { location, txn_id }
+        }
+        Ok(Some(row)) => AdminError::Status {
+            location,
+            status: row.status,
+        },
+        Ok(None) => AdminError::NotFound(location),
+        Err(err) => err.into(),
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchDryRun {
+    pub locations: Vec<String>,
+    pub instructions: Vec<InstructionDryRun>,
+    /// Why the instructions of the batch could not be built
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InstructionDryRun {
+    pub location: String,
+    pub start_ts: i64,
+    pub program_id: String,
+    pub accounts: Vec<AccountDryRun>,
+    /// Instruction data in hex
+    pub data: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountDryRun {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// The `StartBoostV0` instructions the updater would submit for the queued
+/// activations, batched as it would batch them. Nothing is signed or
+/// submitted.
+pub async fn dry_run<S: SolanaNetwork>(
+    pool: &Pool<Postgres>,
+    solana: &S,
+    batch_size: usize,
+) -> Result<Vec<BatchDryRun>, AdminError> {
+    let activations = db::get_queued_batch(pool).await?;
+    Ok(activations
+        .chunks(batch_size.max(1))
+        .map(|batch| {
+            let locations = batch
+                .iter()
+                .map(|activation| format!("{:x}", activation.location))
+                .collect();
+            match solana.start_boost_instructions(batch) {
+                Ok(instructions) => BatchDryRun {
+                    locations,
+                    instructions: batch
+                        .iter()
+                        .zip(instructions)
+                        .map(|(activation, instruction)| InstructionDryRun {
+                            location: format!("{:x}", activation.location),
+                            start_ts: activation.activation_ts.timestamp(),
+                            program_id: instruction.program_id.to_string(),
+                            accounts: instruction
+                                .accounts
+                                .iter()
+                                .map(|account| AccountDryRun {
+                                    pubkey: account.pubkey.to_string(),
+                                    is_signer: account.is_signer,
+                                    is_writable: account.is_writable,
+                                })
+                                .collect(),
+                            data: instruction
+                                .data
+                                .iter()
+                                .map(|byte| format!("{byte:02x}"))
+                                .collect(),
+                        })
+                        .collect(),
+                    error: None,
+                },
+                Err(err) => BatchDryRun {
+                    locations,
+                    instructions: vec![],
+                    error: Some(err.to_string()),
+                },
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,198 @@
+//
+// Admin api for boosted hex activations
+// Lists activations with their batch txn and confirmation state, re-queues
+// or cancels them and dry-runs the next batches. It changes activations, so
+// every request must carry the admin api token as `Authorization: Bearer
+// <token>` and the api should only be reachable by operators.
+//
+// Endpoints:
+//   GET  /v1/activations?status=<queued|pending|success|failed|cancelled>
+//   POST /v1/activations/:location/requeue
+//   POST /v1/activations/:location/cancel
+//   GET  /v1/dry-run
+//
+// Locations are h3 cell indexes in hex. Re-queueing is refused for successful
+// activations and those awaiting confirmation of a submitted txn, cancelling
+// for all but unsubmitted queued or failed activations.
+//
+
+use crate::{
+    admin::{self, Activation, AdminError, BatchDryRun},
+    OnChainStatus,
+};
+use axum::{
+    extract::{Path, Query, Request, State},
+    http::{header::AUTHORIZATION, StatusCode},
+    middleware::{self, Next},
+    response::Response,
+    routing::{get, post},
+    Json, Router,
+};
+use futures::{future::LocalBoxFuture, TryFutureExt};
+use serde::Deserialize;
+use solana::start_boost::SolanaNetwork;
+use sqlx::{Pool, Postgres};
+use std::{future::IntoFuture, net::SocketAddr, sync::Arc};
+use subtle::ConstantTimeEq;
+use task_manager::ManagedTask;
+use tokio::net::TcpListener;
+
+#[derive(Debug, Deserialize)]
+pub struct ListParams {
+    status: Option<OnChainStatus>,
+}
+
+struct ApiState<S> {
+    pool: Pool<Postgres>,
+    solana: Arc<S>,
+    batch_size: usize,
+}
+
+impl<S> Clone for ApiState<S> {
+    fn clone(&self) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            solana: self.solana.clone(),
+            batch_size: self.batch_size,
+        }
+    }
+}
+
+pub struct AdminApiServer<S> {
+    socket_addr: SocketAddr,
+    api_token: Arc<String>,
+    state: ApiState<S>,
+}
+
+impl<S> ManagedTask for AdminApiServer<S>
+where
+    S: SolanaNetwork,
+{
+    fn start_task(
+        self: Box<Self>,
+        shutdown: triggered::Listener,
+    ) -> LocalBoxFuture<'static, anyhow::Result<()>> {
+        let handle = tokio::spawn(self.run(shutdown));
+        Box::pin(
+            handle
+                .map_err(anyhow::Error::from)
+                .and_then(|result| async move { result }),
+        )
+    }
+}
+
+impl<S> AdminApiServer<S>
+where
+    S: SolanaNetwork,
+{
+    pub fn new(
+        socket_addr: SocketAddr,
+        api_token: &str,
+        pool: Pool<Postgres>,
+        solana: S,
+        batch_size: usize,
+    ) -> Self {
+        Self {
+            socket_addr,
+            api_token: Arc::new(format!("Bearer {api_token}")),
+            state: ApiState {
+                pool,
+                solana: Arc::new(solana),
+                batch_size,
+            },
+        }
+    }
+
+    async fn run(self, shutdown: triggered::Listener) -> anyhow::Result<()> {
+        tracing::info!(
+            listen = self.socket_addr.to_string(),
+            "starting admin api server"
+        );
+        let app = Router::new()
+            .route("/v1/activations", get(list::<S>))
+            .route("/v1/activations/:location/requeue", post(requeue::<S>))
+            .route("/v1/activations/:location/cancel", post(cancel::<S>))
+            .route("/v1/dry-run", get(dry_run::<S>))
+            .with_state(self.state)
+            .layer(middleware::from_fn_with_state(self.api_token, authorize))
+            .layer(custom_tracing::http_layer::new_with_span(make_span));
+        let listener = TcpListener::bind(self.socket_addr).await?;
+        axum::serve(listener, app)
+            .with_graceful_shutdown(shutdown)
+            .into_future()
+            .await?;
+        tracing::info!("stopping admin api server");
+        Ok(())
+    }
+}
+
+async fn authorize(
+    State(api_token): State<Arc<String>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    match request.headers().get(AUTHORIZATION) {
+        Some(token) if bool::from(token.as_bytes().ct_eq(api_token.as_bytes())) => {
+            Ok(next.run(request).await)
+        }
+        _ => Err(StatusCode::UNAUTHORIZED),
+    }
+}
+
+async fn list<S: SolanaNetwork>(
+    State(state): State<ApiState<S>>,
+    Query(params): Query<ListParams>,
+) -> Result<Json<Vec<Activation>>, (StatusCode, String)> {
+    admin::list(&state.pool, params.status)
+        .await
+        .map(Json)
+        .map_err(into_response)
+}
+
+async fn requeue<S: SolanaNetwork>(
+    State(state): State<ApiState<S>>,
+    Path(location): Path<String>,
+) -> Result<Json<Activation>, (StatusCode, String)> {
+    let location = admin::parse_location(&location).map_err(into_response)?;
+    admin::requeue(&state.pool, location)
+        .await
+        .map(Json)
+        .map_err(into_response)
+}
+
+async fn cancel<S: SolanaNetwork>(
+    State(state): State<ApiState<S>>,
+    Path(location): Path<String>,
+) -> Result<Json<Activation>, (StatusCode, String)> {
+    let location = admin::parse_location(&location).map_err(into_response)?;
+    admin::cancel(&state.pool, location)
+        .await
+        .map(Json)
+        .map_err(into_response)
+}
+
+async fn dry_run<S: SolanaNetwork>(
+    State(state): State<ApiState<S>>,
+) -> Result<Json<Vec<BatchDryRun>>, (StatusCode, String)> {
+    admin::dry_run(&state.pool, state.solana.as_ref(), state.batch_size)
+        .await
+        .map(Json)
+        .map_err(into_response)
+}
+
+fn into_response(err: AdminError) -> (StatusCode, String) {
+    let status = match err {
+        AdminError::InvalidLocation(..) => StatusCode::BAD_REQUEST,
+        AdminError::NotFound(_) => StatusCode::NOT_FOUND,
+        AdminError::Status { .. } | AdminError::AwaitingConfirmation { .. } => StatusCode::CONFLICT,
+        AdminError::Db(ref err) => {
+            tracing::warn!("admin api query failed: {err:?}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    };
+    (status, err.to_string())
+}
+
+fn make_span(_request: &axum::http::Request<axum::body::Body>) -> tracing::Span {
+    tracing::info_span!(custom_tracing::DEFAULT_SPAN)
+}
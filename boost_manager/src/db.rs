@@ -33,6 +33,19 @@ pub struct StatusRow {
     pub status: OnChainStatus,
 }
 
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct ActivationRow {
+    #[sqlx(try_from = "i64")]
+    pub location: u64,
+    pub activation_ts: DateTime<Utc>,
+    pub boosted_hex_pubkey: String,
+    pub boost_config_pubkey: String,
+    pub status: OnChainStatus,
+    pub txn_id: Option<String>,
+    pub retries: i32,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
 pub async fn insert_activated_hex(
     txn: &mut Transaction<'_, Postgres>,
     location: u64,
@@ -85,6 +98,81 @@ pub async fn get_queued_batch(
     .collect::<Vec<BoostedHexActivation>>())
 }
 
+pub async fn list_activations(
+    db: &Pool<Postgres>,
+    status: Option<OnChainStatus>,
+) -> Result<Vec<ActivationRow>, sqlx::Error> {
+    sqlx::query_as::<_, ActivationRow>(
+        r#"
+            SELECT location, activation_ts, boosted_hex_pubkey, boost_config_pubkey,
+                status, txn_id, retries, updated_at
+            FROM activated_hexes
+            WHERE $1::onchain_status IS NULL OR status = $1
+            ORDER BY activation_ts ASC, location ASC
+        "#,
+    )
+    .bind(status)
+    .fetch_all(db)
+    .await
+}
+
+pub async fn get_activation(
+    db: &Pool<Postgres>,
+    location: u64,
+) -> Result<Option<ActivationRow>, sqlx::Error> {
+    sqlx::query_as::<_, ActivationRow>(
+        r#"
+            SELECT location, activation_ts, boosted_hex_pubkey, boost_config_pubkey,
+                status, txn_id, retries, updated_at
+            FROM activated_hexes
+            WHERE location = $1
+        "#,
+    )
+    .bind(location as i64)
+    .fetch_optional(db)
+    .await
+}
+
+/// Queues an activation again with its retries reset. Successful activations
+/// and those with a transaction, submitted or about to be, are left as they
+/// are. Returns whether the activation was re-queued.
+pub async fn requeue_activation(db: &Pool<Postgres>, location: u64) -> Result<bool, sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE activated_hexes
+        SET status = $1, retries = 0, updated_at = $2
+        WHERE location = $3 AND status != $4 AND txn_id IS NULL
+        "#,
+    )
+    .bind(OnChainStatus::Queued)
+    .bind(Utc::now())
+    .bind(location as i64)
+    .bind(OnChainStatus::Success)
+    .execute(db)
+    .await
+    .map(|result| result.rows_affected() > 0)
+}
+
+/// Cancels a queued or failed activation that has no submitted transaction.
+/// Returns whether the activation was cancelled.
+pub async fn cancel_activation(db: &Pool<Postgres>, location: u64) -> Result<bool, sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE activated_hexes
+        SET status = $1, updated_at = $2
+        WHERE location = $3 AND status IN ($4, $5) AND txn_id IS NULL
+        "#,
+    )
+    .bind(OnChainStatus::Cancelled)
+    .bind(Utc::now())
+    .bind(location as i64)
+    .bind(OnChainStatus::Queued)
+    .bind(OnChainStatus::Failed)
+    .execute(db)
+    .await
+    .map(|result| result.rows_affected() > 0)
+}
+
 pub async fn query_activation_statuses(db: &Pool<Postgres>) -> anyhow::Result<Vec<StatusRow>> {
     Ok(sqlx::query_as::<_, StatusRow>(
         r#"
@@ -96,55 +184,76 @@ pub async fn query_activation_statuses(db: &Pool<Postgres>) -> anyhow::Result<Ve
     .await?)
 }
 
+/// Claims the activations of a batch for its transaction. Only activations
+/// still queued without a transaction are claimed, so ones cancelled or
+/// claimed since the batch was read are not. Returns the number claimed.
 pub async fn save_batch_txn_id(
     db: &Pool<Postgres>,
     txn_id: &str,
     hexes: &[u64],
-) -> anyhow::Result<()> {
+) -> anyhow::Result<u64> {
     let hexes = hexes.iter().map(|x| *x as i64).collect::<Vec<i64>>();
     Ok(sqlx::query(
         r#"
         UPDATE activated_hexes
         SET txn_id = $1, updated_at = $2
-        WHERE location IN (SELECT * FROM UNNEST($3))
+        WHERE location IN (SELECT * FROM UNNEST($3)) AND status = $4 AND txn_id IS NULL
         "#,
     )
     .bind(txn_id)
     .bind(Utc::now())
     .bind(hexes)
+    .bind(OnChainStatus::Queued)
+    .execute(db)
+    .await
+    .map(|result| result.rows_affected())?)
+}
+
+/// Releases the activations claimed by a transaction that is not submitted
+pub async fn release_batch_txn_id(db: &Pool<Postgres>, txn_id: &str) -> anyhow::Result<()> {
+    Ok(sqlx::query(
+        r#"
+        UPDATE activated_hexes
+        SET txn_id = NULL, updated_at = $1
+        WHERE txn_id = $2 AND status = $3
+        "#,
+    )
+    .bind(Utc::now())
+    .bind(txn_id)
+    .bind(OnChainStatus::Queued)
     .execute(db)
     .await
     .map(|_| ())?)
 }
 
-pub async fn update_success_batch(db: &Pool<Postgres>, hexes: &[u64]) -> anyhow::Result<()> {
-    let hexes = hexes.iter().map(|x| *x as i64).collect::<Vec<i64>>();
+pub async fn update_success_batch(db: &Pool<Postgres>, txn_id: &str) -> anyhow::Result<()> {
     Ok(sqlx::query(
         r#"
         UPDATE activated_hexes
         SET status = $1, updated_at = $2
-        WHERE location IN (SELECT * FROM UNNEST($3))
+        WHERE txn_id = $3 AND status = $4
         "#,
     )
     .bind(OnChainStatus::Success)
     .bind(Utc::now())
-    .bind(hexes)
+    .bind(txn_id)
+    .bind(OnChainStatus::Queued)
     .execute(db)
     .await
     .map(|_| ())?)
 }
 
-pub async fn update_failed_batch(db: &Pool<Postgres>, hexes: &[u64]) -> anyhow::Result<()> {
-    let hexes = hexes.iter().map(|x| *x as i64).collect::<Vec<i64>>();
+pub async fn update_failed_batch(db: &Pool<Postgres>, txn_id: &str) -> anyhow::Result<()> {
     Ok(sqlx::query(
         r#"
         UPDATE activated_hexes
         SET updated_at = $1, retries = retries + 1, txn_id = NULL
-        WHERE location IN (SELECT * FROM UNNEST($2))
+        WHERE txn_id = $2 AND status = $3
         "#,
     )
     .bind(Utc::now())
-    .bind(hexes)
+    .bind(txn_id)
+    .bind(OnChainStatus::Queued)
     .execute(db)
     .await
     .map(|_| ())?)
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
 pub mod activator;
+pub mod admin;
+pub mod admin_api;
 pub mod db;
 pub mod purger;
 pub mod settings;
@@ -9,7 +11,10 @@ pub use settings::Settings;
 pub mod updater;
 pub mod watcher;
 
-#[derive(Debug, Eq, Hash, PartialEq, Copy, Clone, Deserialize, Serialize, sqlx::Type)]
+#[derive(
+    clap::ValueEnum, Debug, Eq, Hash, PartialEq, Copy, Clone, Deserialize, Serialize, sqlx::Type,
+)]
+#[serde(rename_all = "lowercase")]
 #[sqlx(type_name = "onchain_status")]
 #[sqlx(rename_all = "lowercase")]
 pub enum OnChainStatus {
@@ -1,12 +1,13 @@
 use anyhow::{bail, Result};
 use boost_manager::{
-    activator::Activator, purger::Purger, settings::Settings, telemetry, updater::Updater,
-    watcher::Watcher,
+    activator::Activator, admin, admin_api::AdminApiServer, purger::Purger, settings::Settings,
+    telemetry, updater::Updater, watcher::Watcher, OnChainStatus,
 };
 use clap::Parser;
 use file_store::{
     file_info_poller::LookbackBehavior,
     file_source, file_upload,
+    hex_boost::BoostedHexEventV1,
     reward_manifest::RewardManifest,
     traits::{FileSinkCommitStrategy, FileSinkRollTime, FileSinkWriteExt},
     FileStore, FileType,
@@ -44,16 +45,84 @@ impl Cli {
 #[derive(Debug, clap::Subcommand)]
 pub enum Cmd {
     Server(Server),
+    /// Inspect and manage boosted hex activations
+    #[clap(subcommand)]
+    Activations(Activations),
 }
 
 impl Cmd {
     pub async fn run(&self, settings: Settings) -> Result<()> {
         match self {
             Self::Server(cmd) => cmd.run(&settings).await,
+            Self::Activations(cmd) => cmd.run(&settings).await,
         }
     }
 }
 
+#[derive(Debug, clap::Subcommand)]
+pub enum Activations {
+    /// List activations with their batch txn and confirmation state
+    List {
+        #[clap(long, value_enum)]
+        status: Option<OnChainStatus>,
+    },
+    /// Queue activations again with their retries reset
+    Requeue {
+        /// h3 cell indexes in hex
+        #[clap(required = true)]
+        locations: Vec<String>,
+    },
+    /// Cancel activations which have not been submitted
+    Cancel {
+        /// h3 cell indexes in hex
+        #[clap(required = true)]
+        locations: Vec<String>,
+    },
+    /// Print the start boost instructions of the next batches without
+    /// submitting them
+    DryRun,
+}
+
+impl Activations {
+    pub async fn run(&self, settings: &Settings) -> Result<()> {
+        let pool = settings.database.connect(env!("CARGO_PKG_NAME")).await?;
+        sqlx::migrate!().run(&pool).await?;
+
+        let output = match self {
+            Self::List { status } => serde_json::to_value(admin::list(&pool, *status).await?)?,
+            Self::Requeue { locations } => {
+                let mut requeued = vec![];
+                for location in locations {
+                    let location = admin::parse_location(location)?;
+                    requeued.push(admin::requeue(&pool, location).await?);
+                }
+                serde_json::to_value(requeued)?
+            }
+            Self::Cancel { locations } => {
+                let mut cancelled = vec![];
+                for location in locations {
+                    let location = admin::parse_location(location)?;
+                    cancelled.push(admin::cancel(&pool, location).await?);
+                }
+                serde_json::to_value(cancelled)?
+            }
+            Self::DryRun => {
+                let solana = match settings.solana {
+                    Some(ref solana_settings) if settings.enable_solana_integration => {
+                        Some(SolanaRpc::new(solana_settings).await?)
+                    }
+                    _ => None,
+                };
+                serde_json::to_value(
+                    admin::dry_run(&pool, &solana, settings.txn_batch_size()).await?,
+                )?
+            }
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        Ok(())
+    }
+}
+
 #[derive(Debug, clap::Args)]
 pub struct Server {}
 
@@ -112,6 +181,17 @@ impl Server {
         )
         .await?;
 
+        // setup the writer for boosted hex lifecycle events
+        let (boosted_hex_events_sink, boosted_hex_events_sink_server) =
+            BoostedHexEventV1::file_sink(
+                store_base_path,
+                file_upload.clone(),
+                FileSinkCommitStrategy::Automatic,
+                FileSinkRollTime::Duration(Duration::from_secs(5 * 60)),
+                env!("CARGO_PKG_NAME"),
+            )
+            .await?;
+
         // The server to monitor rewards and activate any newly seen boosted hexes
         let verifier_store = FileStore::from_settings(&settings.verifier).await?;
         let activator = Activator::new(
@@ -122,7 +202,25 @@ impl Server {
         )
         .await?;
 
-        let watcher = Watcher::new(pool.clone(), updated_hexes_sink, hex_boosting_client).await?;
+        let watcher = Watcher::new(
+            pool.clone(),
+            updated_hexes_sink,
+            boosted_hex_events_sink,
+            hex_boosting_client,
+        )
+        .await?;
+
+        let admin_api = match (settings.admin_api_listen, &settings.admin_api_token) {
+            (Some(listen), Some(api_token)) => Some(AdminApiServer::new(
+                listen,
+                api_token,
+                pool.clone(),
+                solana.clone(),
+                settings.txn_batch_size(),
+            )),
+            (Some(_), None) => bail!("expected admin api token in settings"),
+            (None, _) => None,
+        };
 
         let updater = Updater::new(
             pool.clone(),
@@ -134,17 +232,20 @@ impl Server {
 
         let purger = Purger::new(pool.clone(), settings.retention_period);
 
-        TaskManager::builder()
+        let mut builder = TaskManager::builder()
             .add_task(file_upload_server)
             .add_task(manifest_server)
             .add_task(updated_hexes_sink_server)
+            .add_task(boosted_hex_events_sink_server)
             .add_task(activator)
             .add_task(watcher)
             .add_task(updater)
-            .add_task(purger)
-            .build()
-            .start()
-            .await
+            .add_task(purger);
+        if let Some(admin_api) = admin_api {
+            builder = builder.add_task(admin_api);
+        }
+
+        builder.build().start().await
     }
 }
 
@@ -2,7 +2,7 @@ use chrono::{DateTime, Utc};
 use config::{Config, Environment, File};
 use humantime_serde::re::humantime;
 use serde::Deserialize;
-use std::{path::Path, time::Duration};
+use std::{net::SocketAddr, path::Path, time::Duration};
 
 #[derive(Debug, Deserialize)]
 pub struct Settings {
@@ -40,6 +40,14 @@ pub struct Settings {
     // default retention period in seconds
     #[serde(with = "humantime_serde", default = "default_retention_period")]
     pub retention_period: Duration,
+    /// Listen address for the admin http api managing activations. The api
+    /// is not started if unset
+    #[serde(default)]
+    pub admin_api_listen: Option<SocketAddr>,
+    /// Token required as part of a Bearer authentication admin api request.
+    /// Required if the admin api is started
+    #[serde(default)]
+    pub admin_api_token: Option<String>,
 }
 
 fn default_retention_period() -> Duration {
@@ -120,7 +120,18 @@ where
             let solana_txn = self.solana.make_start_boost_transaction(batch).await?;
             let transaction_id = solana_txn.get_signature().to_string();
             // update the batch in the db with the txn id
-            db::save_batch_txn_id(&self.pool, &transaction_id, &ids).await?;
+            // if any activation of the batch was cancelled or changed since it was
+            // read then release the batch, it is read again next tick without it
+            let claimed = db::save_batch_txn_id(&self.pool, &transaction_id, &ids).await?;
+            if claimed != batch_size as u64 {
+                tracing::warn!(
+                    claimed,
+                    batch_size,
+                    "activations changed since the batch was read, retrying next tick"
+                );
+                db::release_batch_txn_id(&self.pool, &transaction_id).await?;
+                continue;
+            }
 
             // if activations were processed successfully then
             // update their status in the DB to success
@@ -129,12 +140,13 @@ where
             // will be retried next tick
             match self.solana.submit_transaction(&solana_txn).await {
                 Ok(()) => {
-                    self.handle_submit_txn_success(&ids, batch_size, activations_count)
+                    self.handle_submit_txn_success(&transaction_id, batch_size, activations_count)
                         .await?;
                 }
                 Err(e) => {
                     tracing::warn!("submit txn failed, error: {}", e);
-                    self.handle_submit_txn_failure(&ids, batch_size).await?;
+                    self.handle_submit_txn_failure(&transaction_id, batch_size)
+                        .await?;
                 }
             };
         }
@@ -154,22 +166,26 @@ where
 
     async fn handle_submit_txn_success(
         &self,
-        ids: &[u64],
+        transaction_id: &str,
         batch_size: usize,
         summed_activations_count: u64,
     ) -> Result<()> {
         tracing::info!("processed batch of {} activations successfully", batch_size);
         metrics::counter!("success_activations").increment(summed_activations_count);
-        db::update_success_batch(&self.pool, ids).await?;
+        db::update_success_batch(&self.pool, transaction_id).await?;
         Ok(())
     }
 
-    async fn handle_submit_txn_failure(&self, ids: &[u64], batch_size: usize) -> Result<()> {
+    async fn handle_submit_txn_failure(
+        &self,
+        transaction_id: &str,
+        batch_size: usize,
+    ) -> Result<()> {
         tracing::info!(
             "failed to process batch of {} activations, retrying next tick",
             batch_size
         );
-        db::update_failed_batch(&self.pool, ids).await?;
+        db::update_failed_batch(&self.pool, transaction_id).await?;
         Ok(())
     }
 
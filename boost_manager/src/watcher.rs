@@ -2,14 +2,18 @@ use anyhow::Result;
 use chrono::{DateTime, TimeZone, Utc};
 use db_store::meta;
 use file_store::file_sink::FileSinkClient;
+use file_store::hex_boost::BoostedHexEventV1;
 use file_store::traits::TimestampEncode;
 use futures::{future::LocalBoxFuture, TryFutureExt};
-use helium_proto::BoostedHexUpdateV1 as BoostedHexUpdateProto;
+use helium_proto::{
+    BoostedHexInfoV1 as BoostedHexInfoProto, BoostedHexUpdateV1 as BoostedHexUpdateProto, Message,
+};
 use mobile_config::{
-    boosted_hex_info::BoostedHexes,
+    boosted_hex_info::{BoostedHexInfo, BoostedHexes},
     client::{hex_boosting_client::HexBoostingInfoResolver, ClientError},
 };
-use sqlx::{PgExecutor, Pool, Postgres};
+use sqlx::{PgExecutor, Pool, Postgres, Row};
+use std::collections::HashMap;
 use task_manager::ManagedTask;
 use tokio::time;
 
@@ -20,6 +24,7 @@ pub struct Watcher<A> {
     pub pool: Pool<Postgres>,
     pub hex_boosting_client: A,
     pub file_sink: FileSinkClient<BoostedHexUpdateProto>,
+    pub event_sink: FileSinkClient<BoostedHexEventV1>,
 }
 
 impl<A> ManagedTask for Watcher<A>
@@ -46,11 +51,13 @@ where
     pub async fn new(
         pool: Pool<Postgres>,
         file_sink: FileSinkClient<BoostedHexUpdateProto>,
+        event_sink: FileSinkClient<BoostedHexEventV1>,
         hex_boosting_client: A,
     ) -> Result<Self> {
         Ok(Self {
             pool,
             file_sink,
+            event_sink,
             hex_boosting_client,
         })
     }
@@ -97,11 +104,88 @@ where
             self.file_sink.write(proto, []).await?.await??;
         }
         self.file_sink.commit().await?;
-        save_last_processed_timestamp(&self.pool, &now).await?;
+
+        // expirations and period changes are not modifications, so the
+        // lifecycle events are worked out from the last seen state of every
+        // boost with the modified boosts applied. Without any seen states yet
+        // all boosts are fetched once.
+        let previous_states = fetch_boosted_hex_states(&self.pool).await?;
+        let mut current = if previous_states.is_empty() {
+            BoostedHexes::get_all(&self.hex_boosting_client)
+                .await?
+                .hexes
+        } else {
+            previous_states
+                .values()
+                .map(|(info, _)| (info.location, info.clone()))
+                .collect()
+        };
+        current.extend(boosted_hexes.hexes);
+
+        let mut changed = vec![];
+        for info in current.values() {
+            let location = info.location.into_raw();
+            let previous = previous_states.get(&location);
+            for event in
+                info.lifecycle_events(previous.map(|(info, _)| info), last_processed_ts, now)
+            {
+                self.event_sink.write(event.into(), []).await?.await??;
+            }
+            let encoded = BoostedHexInfoProto::try_from(info.clone())?.encode_to_vec();
+            if previous.map_or(true, |(_, bytes)| *bytes != encoded) {
+                changed.push((location, encoded));
+            }
+        }
+        self.event_sink.commit().await?;
+
+        let mut txn = self.pool.begin().await?;
+        save_boosted_hex_states(&mut *txn, changed).await?;
+        save_last_processed_timestamp(&mut *txn, &now).await?;
+        txn.commit().await?;
         Ok(())
     }
 }
 
+/// The last seen state of each boosted hex, with its encoding
+pub async fn fetch_boosted_hex_states(
+    db: impl PgExecutor<'_>,
+) -> Result<HashMap<u64, (BoostedHexInfo, Vec<u8>)>> {
+    sqlx::query(" SELECT location, info FROM boosted_hex_states ")
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|row| {
+            let location = row.get::<i64, _>("location") as u64;
+            let bytes = row.get::<Vec<u8>, _>("info");
+            let info = BoostedHexInfo::try_from(BoostedHexInfoProto::decode(bytes.as_slice())?)?;
+            Ok((location, (info, bytes)))
+        })
+        .collect()
+}
+
+pub async fn save_boosted_hex_states(
+    db: impl PgExecutor<'_>,
+    states: Vec<(u64, Vec<u8>)>,
+) -> Result<()> {
+    let (locations, infos): (Vec<i64>, Vec<Vec<u8>>) = states
+        .into_iter()
+        .map(|(location, info)| (location as i64, info))
+        .unzip();
+    sqlx::query(
+        r#"
+        INSERT INTO boosted_hex_states (location, info)
+        SELECT * FROM UNNEST($1::bigint[], $2::bytea[])
+        ON CONFLICT (location) DO UPDATE
+        SET info = EXCLUDED.info, updated_at = now()
+        "#,
+    )
+    .bind(locations)
+    .bind(infos)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
 pub async fn fetch_last_processed_timestamp(
     db: impl PgExecutor<'_>,
 ) -> db_store::Result<DateTime<Utc>> {
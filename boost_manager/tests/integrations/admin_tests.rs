@@ -0,0 +1,166 @@
+use boost_manager::{
+    admin::{self, AdminError, Confirmation},
+    db, OnChainStatus,
+};
+use chrono::{Duration, Utc};
+use solana::start_boost::SolanaRpc;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+const BOOST_HEX_PUBKEY: &str = "J9JiLTpjaShxL8eMvUs8txVw6TZ36E38SiJ89NxnMbLU";
+const BOOST_CONFIG_PUBKEY: &str = "BZM1QTud72B2cpTW7PhEnFmRX7ZWzvY7DpPpNJJuDrWG";
+
+const QUEUED: u64 = 0x8c2681a306601ff_u64;
+const SUBMITTED: u64 = 0x8c2681a306602ff_u64;
+const FAILED: u64 = 0x8c2681a306603ff_u64;
+const SUCCESS: u64 = 0x8c2681a306604ff_u64;
+
+#[sqlx::test]
+async fn test_list_requeue_and_cancel(pool: PgPool) -> anyhow::Result<()> {
+    seed_data(&pool).await?;
+
+    let activations = admin::list(&pool, None).await?;
+    assert_eq!(activations.len(), 4);
+    let confirmation = |location: u64| {
+        activations
+            .iter()
+            .find(|activation| activation.location == format!("{location:x}"))
+            .map(|activation| activation.confirmation)
+    };
+    assert_eq!(confirmation(QUEUED), Some(Confirmation::Unsubmitted));
+    assert_eq!(
+        confirmation(SUBMITTED),
+        Some(Confirmation::AwaitingConfirmation)
+    );
+    assert_eq!(confirmation(FAILED), Some(Confirmation::Failed));
+    assert_eq!(confirmation(SUCCESS), Some(Confirmation::Confirmed));
+
+    let failed = admin::list(&pool, Some(OnChainStatus::Failed)).await?;
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].retries, 10);
+
+    // a failed activation is queued again with its retries reset
+    let requeued = admin::requeue(&pool, FAILED).await?;
+    assert_eq!(requeued.status, OnChainStatus::Queued);
+    assert_eq!(requeued.retries, 0);
+    assert_eq!(requeued.confirmation, Confirmation::Unsubmitted);
+
+    // successful and submitted activations are left alone
+    assert!(matches!(
+        admin::requeue(&pool, SUCCESS).await,
+        Err(AdminError::Status {
+            status: OnChainStatus::Success,
+            ..
+        })
+    ));
+    assert!(matches!(
+        admin::requeue(&pool, SUBMITTED).await,
+        Err(AdminError::AwaitingConfirmation { .. })
+    ));
+    assert!(matches!(
+        admin::cancel(&pool, SUBMITTED).await,
+        Err(AdminError::AwaitingConfirmation { .. })
+    ));
+    assert!(matches!(
+        admin::cancel(&pool, 0x8c2681a306605ff_u64).await,
+        Err(AdminError::NotFound(_))
+    ));
+
+    let cancelled = admin::cancel(&pool, QUEUED).await?;
+    assert_eq!(cancelled.confirmation, Confirmation::Cancelled);
+
+    // an activation cancelled after the updater read its batch is not claimed
+    assert_eq!(db::save_batch_txn_id(&pool, "txn3", &[QUEUED]).await?, 0);
+    let activation = db::get_activation(&pool, QUEUED).await?.unwrap();
+    assert_eq!(activation.status, OnChainStatus::Cancelled);
+    assert_eq!(activation.txn_id, None);
+
+    // cancelled activations are not picked up by the updater
+    let queued = db::get_queued_batch(&pool).await?;
+    assert_eq!(
+        queued
+            .iter()
+            .map(|boost| boost.location)
+            .collect::<Vec<_>>(),
+        vec![FAILED]
+    );
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_dry_run(pool: PgPool) -> anyhow::Result<()> {
+    seed_data(&pool).await?;
+
+    let solana: Option<Arc<SolanaRpc>> = None;
+    let batches = admin::dry_run(&pool, &solana, 10).await?;
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].locations, vec![format!("{QUEUED:x}")]);
+    assert!(batches[0].error.is_none());
+
+    let instruction = &batches[0].instructions[0];
+    assert_eq!(instruction.location, format!("{QUEUED:x}"));
+    let accounts = instruction
+        .accounts
+        .iter()
+        .map(|account| account.pubkey.as_str())
+        .collect::<Vec<_>>();
+    assert!(accounts.contains(&BOOST_HEX_PUBKEY));
+    assert!(accounts.contains(&BOOST_CONFIG_PUBKEY));
+
+    // nothing was submitted
+    let activation = db::get_activation(&pool, QUEUED).await?.unwrap();
+    assert_eq!(activation.txn_id, None);
+
+    Ok(())
+}
+
+async fn seed_data(db: &PgPool) -> anyhow::Result<()> {
+    let now = Utc::now();
+    insert_data(db, QUEUED, OnChainStatus::Queued, None, 0, now).await?;
+    insert_data(
+        db,
+        SUBMITTED,
+        OnChainStatus::Queued,
+        Some("txn1"),
+        0,
+        now - Duration::hours(1),
+    )
+    .await?;
+    insert_data(db, FAILED, OnChainStatus::Failed, None, 10, now).await?;
+    insert_data(db, SUCCESS, OnChainStatus::Success, Some("txn2"), 0, now).await?;
+    Ok(())
+}
+
+async fn insert_data(
+    db: &PgPool,
+    location: u64,
+    status: OnChainStatus,
+    txn_id: Option<&str>,
+    retries: i32,
+    activation_ts: chrono::DateTime<Utc>,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        r#"
+        insert into activated_hexes (
+            location,
+            activation_ts,
+            boosted_hex_pubkey,
+            boost_config_pubkey,
+            status,
+            txn_id,
+            retries
+        ) values ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+    )
+    .bind(location as i64)
+    .bind(activation_ts)
+    .bind(BOOST_HEX_PUBKEY)
+    .bind(BOOST_CONFIG_PUBKEY)
+    .bind(status)
+    .bind(txn_id)
+    .bind(retries)
+    .execute(db)
+    .await?;
+    Ok(())
+}
@@ -39,16 +39,16 @@ impl HexBoostingInfoResolver for MockHexBoostingClient {
     }
 }
 
-pub struct MockFileSinkReceiver {
-    pub receiver: tokio::sync::mpsc::Receiver<SinkMessage<BoostedHexUpdateProto>>,
+pub struct MockFileSinkReceiver<T = BoostedHexUpdateProto> {
+    pub receiver: tokio::sync::mpsc::Receiver<SinkMessage<T>>,
 }
 
-impl MockFileSinkReceiver {
-    pub async fn receive(&mut self) -> Option<Vec<u8>> {
+impl<T: std::fmt::Debug> MockFileSinkReceiver<T> {
+    pub async fn receive_msg(&mut self) -> Option<T> {
         match timeout(seconds(2), self.receiver.recv()).await {
             Ok(Some(SinkMessage::Data(on_write_tx, msg))) => {
                 let _ = on_write_tx.send(Ok(()));
-                Some(msg.encode_to_vec())
+                Some(msg)
             }
             Ok(None) => None,
             Err(e) => panic!("timeout while waiting for message1 {:?}", e),
@@ -64,6 +64,12 @@ impl MockFileSinkReceiver {
             panic!("receiver should have been empty")
         };
     }
+}
+
+impl MockFileSinkReceiver<BoostedHexUpdateProto> {
+    pub async fn receive(&mut self) -> Option<Vec<u8>> {
+        self.receive_msg().await.map(|msg| msg.encode_to_vec())
+    }
 
     pub async fn receive_updated_hex(&mut self) -> BoostedHexInfoProto {
         match self.receive().await {
@@ -81,7 +87,7 @@ impl MockFileSinkReceiver {
     }
 }
 
-pub fn create_file_sink() -> (FileSinkClient<BoostedHexUpdateProto>, MockFileSinkReceiver) {
+pub fn create_file_sink<T>() -> (FileSinkClient<T>, MockFileSinkReceiver<T>) {
     let (tx, rx) = tokio::sync::mpsc::channel(20);
     (
         FileSinkClient {
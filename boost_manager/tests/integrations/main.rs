@@ -1,6 +1,7 @@
 mod common;
 
 mod activator_tests;
+mod admin_tests;
 mod purger_tests;
 mod updater_tests;
 mod watcher_tests;
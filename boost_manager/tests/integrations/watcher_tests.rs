@@ -1,6 +1,7 @@
 use crate::common::{self, MockFileSinkReceiver, MockHexBoostingClient};
 use boost_manager::watcher::{self, Watcher};
 use chrono::{Duration as ChronoDuration, Duration, Utc};
+use file_store::hex_boost::{BoostedHexEvent, BoostedHexEventType, BoostedHexEventV1};
 use helium_proto::BoostedHexInfoV1 as BoostedHexInfoProto;
 use mobile_config::boosted_hex_info::BoostedHexInfo;
 use solana_sdk::pubkey::Pubkey;
@@ -13,6 +14,7 @@ const BOOST_CONFIG_PUBKEY: &str = "BZM1QTud72B2cpTW7PhEnFmRX7ZWzvY7DpPpNJJuDrWG"
 #[sqlx::test]
 async fn test_boosted_hex_updates_to_filestore(pool: PgPool) -> anyhow::Result<()> {
     let (hex_update_client, mut hex_update) = common::create_file_sink();
+    let (event_client, mut events) = common::create_file_sink();
 
     let now = Utc::now();
     let epoch = (now - ChronoDuration::hours(24))..now;
@@ -60,16 +62,54 @@ async fn test_boosted_hex_updates_to_filestore(pool: PgPool) -> anyhow::Result<(
 
     let hex_boosting_client = MockHexBoostingClient::new(boosted_hexes);
 
-    let mut watcher = Watcher::new(pool.clone(), hex_update_client, hex_boosting_client)
-        .await
-        .unwrap();
+    let mut watcher = Watcher::new(
+        pool.clone(),
+        hex_update_client,
+        event_client,
+        hex_boosting_client,
+    )
+    .await
+    .unwrap();
 
     let last_processed_ts = now - Duration::days(1);
     watcher::save_last_processed_timestamp(&pool, &last_processed_ts).await?;
 
-    let (_, boosted_hexes_result) = tokio::join!(
+    let (_, boosted_hexes_result, events) = tokio::join!(
         watcher.handle_tick(),
-        receive_expected_msgs(&mut hex_update)
+        receive_expected_msgs(&mut hex_update),
+        receive_events(&mut events, 4)
+    );
+
+    // both boosts are first seen and move to their next multiplier at the
+    // start of the epoch
+    let events = events
+        .into_iter()
+        .map(|event| (event.location, event.event_type, event.multiplier))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            (
+                0x8a1fb466d2dffff_u64,
+                BoostedHexEventType::Created,
+                Some(10)
+            ),
+            (
+                0x8a1fb466d2dffff_u64,
+                BoostedHexEventType::PeriodStarted,
+                Some(10)
+            ),
+            (
+                0x8a1fb49642dffff_u64,
+                BoostedHexEventType::Created,
+                Some(20)
+            ),
+            (
+                0x8a1fb49642dffff_u64,
+                BoostedHexEventType::PeriodStarted,
+                Some(20)
+            ),
+        ]
     );
 
     if let Ok(boosted_hexes) = boosted_hexes_result {
@@ -96,6 +136,20 @@ async fn test_boosted_hex_updates_to_filestore(pool: PgPool) -> anyhow::Result<(
     Ok(())
 }
 
+async fn receive_events(
+    events: &mut MockFileSinkReceiver<BoostedHexEventV1>,
+    count: usize,
+) -> Vec<BoostedHexEvent> {
+    let mut received = vec![];
+    for _ in 0..count {
+        let event = events.receive_msg().await.expect("boosted hex event");
+        received.push(BoostedHexEvent::try_from(event).expect("valid boosted hex event"));
+    }
+    // ordering of boosts is not guaranteed
+    received.sort_by_key(|event| event.location);
+    received
+}
+
 async fn receive_expected_msgs(
     hex_update: &mut MockFileSinkReceiver,
 ) -> anyhow::Result<Vec<BoostedHexInfoProto>> {
@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use hextree::Cell;
+
+use crate::{
+    BoostedHexMap, CoverageMap, CoverageMapBuilder, CoverageObject, RankedCoverage,
+    UnrankedCoverage,
+};
+
+/// Identifies the radio a [CoverageObject] belongs to
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RadioKey {
+    Wifi(Vec<u8>),
+    Cbrs(String),
+}
+
+impl CoverageObject {
+    pub fn radio_key(&self) -> RadioKey {
+        match self.cbsd_id {
+            Some(ref cbsd_id) => RadioKey::Cbrs(cbsd_id.clone()),
+            None => RadioKey::Wifi(self.hotspot_key.clone()),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct CoveredHexes {
+    indoor: bool,
+    hotspot_key: Vec<u8>,
+    cbsd_id: Option<String>,
+    seniority_timestamp: DateTime<Utc>,
+    coverage: Vec<UnrankedCoverage>,
+}
+
+impl CoveredHexes {
+    fn is_cbrs(&self) -> bool {
+        self.cbsd_id.is_some()
+    }
+
+    fn hexes(&self) -> Vec<Cell> {
+        self.coverage.iter().map(|c| c.location).collect()
+    }
+
+    fn matches(&self, coverage_obj: &CoverageObject) -> bool {
+        self.indoor == coverage_obj.indoor
+            && self.hotspot_key == coverage_obj.hotspot_key
+            && self.cbsd_id == coverage_obj.cbsd_id
+            && self.seniority_timestamp == coverage_obj.seniority_timestamp
+            && self.coverage == coverage_obj.coverage
+    }
+}
+
+/// Hexes are ranked separately for each type of radio
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+struct RankedHexKey {
+    indoor: bool,
+    cbrs: bool,
+    hex: Cell,
+}
+
+/// A coverage map that is kept up to date across epochs by applying and
+/// removing individual coverage objects, rather than being rebuilt.
+///
+/// Each radio has at most one coverage object in the map; applying a new one
+/// replaces the previous. Only the hexes touched by an update are re-ranked, so
+/// a snapshot only needs to apply the boosts for the epoch.
+#[derive(Clone, Default, Debug)]
+pub struct IncrementalCoverageMap {
+    builder: CoverageMapBuilder,
+    radios: HashMap<RadioKey, CoveredHexes>,
+    rankings: HashMap<RankedHexKey, Vec<RankedCoverage>>,
+}
+
+impl IncrementalCoverageMap {
+    /// Adds the coverage object to the map, replacing any coverage object
+    /// previously applied for the same radio. Applying the same coverage
+    /// object again leaves the map untouched.
+    pub fn apply_coverage_object(&mut self, coverage_obj: CoverageObject) {
+        let key = coverage_obj.radio_key();
+        if self
+            .radios
+            .get(&key)
+            .is_some_and(|covered| covered.matches(&coverage_obj))
+        {
+            return;
+        }
+        self.remove_radio(&key);
+        let covered = CoveredHexes {
+            indoor: coverage_obj.indoor,
+            hotspot_key: coverage_obj.hotspot_key.clone(),
+            cbsd_id: coverage_obj.cbsd_id.clone(),
+            seniority_timestamp: coverage_obj.seniority_timestamp,
+            coverage: coverage_obj.coverage.clone(),
+        };
+        self.builder.insert_coverage_object(coverage_obj);
+        self.rerank(covered.indoor, covered.is_cbrs(), &covered.hexes());
+        self.radios.insert(key, covered);
+    }
+
+    /// Removes the coverage of the radio from the map. Returns false if the
+    /// radio had no coverage in the map.
+    pub fn remove_radio(&mut self, key: &RadioKey) -> bool {
+        let Some(covered) = self.radios.remove(key) else {
+            return false;
+        };
+        let hexes = covered.hexes();
+        self.builder.remove_coverage(
+            covered.indoor,
+            &covered.hotspot_key,
+            &covered.cbsd_id,
+            &hexes,
+        );
+        self.rerank(covered.indoor, covered.is_cbrs(), &hexes);
+        true
+    }
+
+    /// Removes the coverage of every radio for which `f` returns false.
+    pub fn retain_radios(&mut self, mut f: impl FnMut(&RadioKey) -> bool) {
+        let removed: Vec<_> = self.radios.keys().filter(|k| !f(k)).cloned().collect();
+        for key in removed {
+            self.remove_radio(&key);
+        }
+    }
+
+    pub fn contains_radio(&self, key: &RadioKey) -> bool {
+        self.radios.contains_key(key)
+    }
+
+    pub fn radios(&self) -> impl Iterator<Item = &RadioKey> {
+        self.radios.keys()
+    }
+
+    pub fn len(&self) -> usize {
+        self.radios.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.radios.is_empty()
+    }
+
+    /// The current coverage, for use with [CoverageMapBuilder::submap]
+    pub fn builder(&self) -> &CoverageMapBuilder {
+        &self.builder
+    }
+
+    /// Boosts the current rankings into a [CoverageMap] for the epoch
+    /// starting at `epoch_start`.
+    pub fn snapshot(
+        &self,
+        boosted_hexes: &impl BoostedHexMap,
+        epoch_start: DateTime<Utc>,
+    ) -> CoverageMap {
+        CoverageMap::from_ranked_coverage(self.rankings.iter().flat_map(|(key, ranked)| {
+            let boosted = boosted_hexes.get_current_multiplier(key.hex, epoch_start);
+            ranked.iter().map(move |coverage| {
+                (
+                    key.indoor,
+                    RankedCoverage {
+                        boosted,
+                        ..coverage.clone()
+                    },
+                )
+            })
+        }))
+    }
+
+    fn rerank(&mut self, indoor: bool, cbrs: bool, hexes: &[Cell]) {
+        for &hex in hexes {
+            let key = RankedHexKey { indoor, cbrs, hex };
+            let ranked = self.builder.rank_hex(indoor, cbrs, hex);
+            if ranked.is_empty() {
+                self.rankings.remove(&key);
+            } else {
+                self.rankings.insert(key, ranked);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::*;
+    use chrono::Duration;
+    use hex_assignments::{assignment::HexAssignments, Assignment};
+    use std::num::NonZeroU32;
+
+    const HEXES: [u64; 3] = [0x8a1fb46622dffff, 0x8c2681a3064d9ff, 0x8c2681a3064dbff];
+
+    fn coverage_object(radio: u8, indoor: bool, cbrs: bool, hexes: &[u64]) -> CoverageObject {
+        CoverageObject {
+            indoor,
+            hotspot_key: vec![radio],
+            cbsd_id: cbrs.then(|| radio.to_string()),
+            seniority_timestamp: DateTime::UNIX_EPOCH + Duration::days(radio as i64),
+            coverage: hexes
+                .iter()
+                .map(|hex| UnrankedCoverage {
+                    location: Cell::from_raw(*hex).expect("valid h3 cell"),
+                    signal_power: -(radio as i32 % 3),
+                    signal_level: if radio % 2 == 0 {
+                        SignalLevel::High
+                    } else {
+                        SignalLevel::Low
+                    },
+                    assignments: HexAssignments {
+                        footfall: Assignment::A,
+                        urbanized: Assignment::A,
+                        landtype: Assignment::A,
+                        service_provider_override: Assignment::C,
+                        assignment_multiplier: None,
+                    },
+                })
+                .collect(),
+        }
+    }
+
+    fn all_objects() -> Vec<CoverageObject> {
+        let mut objects = vec![];
+        for radio in 0..24 {
+            let indoor = radio % 4 < 2;
+            let cbrs = radio % 8 >= 4;
+            let hexes = &HEXES[(radio as usize % 3)..];
+            objects.push(coverage_object(radio, indoor, cbrs, hexes));
+        }
+        objects
+    }
+
+    fn ranks(map: &CoverageMap, objects: &[CoverageObject]) -> Vec<(RadioKey, u64, usize)> {
+        let mut ranks = vec![];
+        for obj in objects {
+            let coverage = match obj.cbsd_id {
+                Some(ref cbsd_id) => map.get_cbrs_coverage(cbsd_id),
+                None => map.get_wifi_coverage(&obj.hotspot_key),
+            };
+            for cov in coverage {
+                ranks.push((obj.radio_key(), cov.hex.into_raw(), cov.rank));
+            }
+        }
+        ranks.sort_by(|a, b| (a.1, a.2).cmp(&(b.1, b.2)));
+        ranks
+    }
+
+    fn full_rebuild(objects: &[CoverageObject]) -> CoverageMap {
+        let mut builder = CoverageMapBuilder::default();
+        for obj in objects.iter().rev() {
+            builder.insert_coverage_object(obj.clone());
+        }
+        builder.build(&NoBoostedHexes, DateTime::UNIX_EPOCH)
+    }
+
+    #[test]
+    fn matches_full_rebuild_after_updates() {
+        let objects = all_objects();
+        let mut incremental = IncrementalCoverageMap::default();
+        for obj in &objects {
+            incremental.apply_coverage_object(obj.clone());
+        }
+
+        // Move some radios to different hexes and remove others
+        let mut current: Vec<_> = objects
+            .iter()
+            .filter(|obj| obj.hotspot_key[0] % 5 != 0)
+            .cloned()
+            .collect();
+        incremental.retain_radios(|key| current.iter().any(|obj| obj.radio_key() == *key));
+        for obj in current.iter_mut().filter(|obj| obj.hotspot_key[0] % 3 == 0) {
+            *obj = coverage_object(
+                obj.hotspot_key[0],
+                obj.indoor,
+                obj.cbsd_id.is_some(),
+                &HEXES[..1],
+            );
+            incremental.apply_coverage_object(obj.clone());
+        }
+
+        assert_eq!(incremental.len(), current.len());
+        let snapshot = incremental.snapshot(&NoBoostedHexes, DateTime::UNIX_EPOCH);
+        assert_eq!(
+            ranks(&snapshot, &objects),
+            ranks(&full_rebuild(&current), &objects)
+        );
+    }
+
+    #[test]
+    fn tied_radios_match_full_rebuild() {
+        // Every radio has the same signal and seniority in every hex, so only
+        // the tie-breaker decides their rank
+        let objects: Vec<_> = (0..16)
+            .map(|radio| {
+                let mut obj = coverage_object(radio, radio % 2 == 0, radio % 4 >= 2, &HEXES);
+                obj.seniority_timestamp = DateTime::UNIX_EPOCH;
+                for cov in &mut obj.coverage {
+                    cov.signal_power = 0;
+                    cov.signal_level = SignalLevel::High;
+                }
+                obj
+            })
+            .collect();
+
+        let mut incremental = IncrementalCoverageMap::default();
+        for obj in &objects {
+            incremental.apply_coverage_object(obj.clone());
+        }
+        // Re-apply some radios so they are inserted in a different order
+        for obj in objects.iter().filter(|obj| obj.hotspot_key[0] % 3 == 0) {
+            incremental.remove_radio(&obj.radio_key());
+            incremental.apply_coverage_object(obj.clone());
+        }
+
+        let snapshot = incremental.snapshot(&NoBoostedHexes, DateTime::UNIX_EPOCH);
+        assert_eq!(
+            ranks(&snapshot, &objects),
+            ranks(&full_rebuild(&objects), &objects)
+        );
+    }
+
+    #[test]
+    fn removing_every_radio_empties_the_map() {
+        let objects = all_objects();
+        let mut incremental = IncrementalCoverageMap::default();
+        for obj in &objects {
+            incremental.apply_coverage_object(obj.clone());
+        }
+        for obj in &objects {
+            assert!(incremental.remove_radio(&obj.radio_key()));
+        }
+        assert!(!incremental.remove_radio(&objects[0].radio_key()));
+        assert!(incremental.is_empty());
+        let snapshot = incremental.snapshot(&NoBoostedHexes, DateTime::UNIX_EPOCH);
+        assert!(ranks(&snapshot, &objects).is_empty());
+    }
+
+    struct BoostFirstHex;
+
+    impl BoostedHexMap for BoostFirstHex {
+        fn get_current_multiplier(&self, cell: Cell, _ts: DateTime<Utc>) -> Option<NonZeroU32> {
+            (cell.into_raw() == HEXES[0]).then(|| NonZeroU32::new(5).unwrap())
+        }
+    }
+
+    #[test]
+    fn snapshot_applies_boosts_for_the_epoch() {
+        let mut incremental = IncrementalCoverageMap::default();
+        for obj in all_objects() {
+            incremental.apply_coverage_object(obj);
+        }
+        let snapshot = incremental.snapshot(&BoostFirstHex, DateTime::UNIX_EPOCH);
+        for (_, cov) in snapshot.ranked_coverage() {
+            assert_eq!(
+                cov.boosted.is_some(),
+                cov.hex.into_raw() == HEXES[0],
+                "hex {:x}",
+                cov.hex.into_raw()
+            );
+        }
+    }
+}
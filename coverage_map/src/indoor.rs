@@ -22,7 +22,7 @@ pub struct IndoorCoverageLevel {
 
 impl PartialEq for IndoorCoverageLevel {
     fn eq(&self, other: &Self) -> bool {
-        self.seniority_timestamp == other.seniority_timestamp
+        self.cmp(other) == Ordering::Equal
     }
 }
 
@@ -34,7 +34,12 @@ impl PartialOrd for IndoorCoverageLevel {
 
 impl Ord for IndoorCoverageLevel {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.seniority_timestamp.cmp(&other.seniority_timestamp)
+        self.seniority_timestamp
+            .cmp(&other.seniority_timestamp)
+            // Radios with equal seniority are ranked by key so the order
+            // never depends on insertion order
+            .then_with(|| self.hotspot_key.cmp(&other.hotspot_key))
+            .then_with(|| self.cbsd_id.cmp(&other.cbsd_id))
     }
 }
 
@@ -71,6 +76,26 @@ pub fn insert_indoor_coverage(
         })
 }
 
+pub fn remove_indoor_coverage(
+    indoor: &mut IndoorCellTree,
+    hexes: &[Cell],
+    hotspot: &[u8],
+    cbsd_id: &Option<String>,
+) {
+    for hex in hexes {
+        let Entry::Occupied(mut levels) = indoor.entry(*hex) else {
+            continue;
+        };
+        levels.get_mut().retain(|_, radios| {
+            radios.retain(|radio| radio.hotspot_key != hotspot || radio.cbsd_id != *cbsd_id);
+            !radios.is_empty()
+        });
+        if levels.get().is_empty() {
+            levels.remove();
+        }
+    }
+}
+
 pub fn clone_indoor_coverage_into_submap(
     submap: &mut IndoorCellTree,
     from: &IndoorCellTree,
@@ -85,6 +110,26 @@ pub fn clone_indoor_coverage_into_submap(
     }
 }
 
+pub fn rank_indoor_hex(indoor: &IndoorCellTree, hex: Cell) -> Vec<RankedCoverage> {
+    let Some(radios) = indoor.get(&hex) else {
+        return vec![];
+    };
+    radios
+        .values()
+        .flat_map(|radios| radios.clone().into_sorted_vec())
+        .enumerate()
+        .map(|(rank, cov)| RankedCoverage {
+            hex,
+            rank: rank + 1,
+            hotspot_key: cov.hotspot_key,
+            cbsd_id: cov.cbsd_id,
+            assignments: cov.assignments,
+            boosted: None,
+            signal_level: cov.signal_level,
+        })
+        .collect()
+}
+
 pub fn into_indoor_coverage_map(
     indoor: IndoorCellTree,
     boosted_hexes: &impl BoostedHexMap,
@@ -194,6 +239,7 @@ mod test {
             urbanized: Assignment::A,
             landtype: Assignment::A,
             service_provider_override: Assignment::C,
+            assignment_multiplier: None,
         }
     }
 
@@ -4,9 +4,11 @@ use chrono::{DateTime, Utc};
 use hex_assignments::assignment::HexAssignments;
 use hextree::Cell;
 
+mod incremental;
 mod indoor;
 mod outdoor;
 
+pub use incremental::{IncrementalCoverageMap, RadioKey};
 use indoor::*;
 use outdoor::*;
 
@@ -31,6 +33,41 @@ impl CoverageMapBuilder {
         }
     }
 
+    /// Removes a radio's coverage of the given hexes from the builder.
+    pub(crate) fn remove_coverage(
+        &mut self,
+        indoor: bool,
+        hotspot_key: &[u8],
+        cbsd_id: &Option<String>,
+        hexes: &[Cell],
+    ) {
+        match (indoor, cbsd_id.is_some()) {
+            (true, true) => {
+                remove_indoor_coverage(&mut self.indoor_cbrs, hexes, hotspot_key, cbsd_id)
+            }
+            (true, false) => {
+                remove_indoor_coverage(&mut self.indoor_wifi, hexes, hotspot_key, cbsd_id)
+            }
+            (false, true) => {
+                remove_outdoor_coverage(&mut self.outdoor_cbrs, hexes, hotspot_key, cbsd_id)
+            }
+            (false, false) => {
+                remove_outdoor_coverage(&mut self.outdoor_wifi, hexes, hotspot_key, cbsd_id)
+            }
+        }
+    }
+
+    /// Ranks the radios of the given type covering `hex`. The returned coverage
+    /// is not boosted.
+    pub(crate) fn rank_hex(&self, indoor: bool, cbrs: bool, hex: Cell) -> Vec<RankedCoverage> {
+        match (indoor, cbrs) {
+            (true, true) => rank_indoor_hex(&self.indoor_cbrs, hex),
+            (true, false) => rank_indoor_hex(&self.indoor_wifi, hex),
+            (false, true) => rank_outdoor_hex(&self.outdoor_cbrs, hex),
+            (false, false) => rank_outdoor_hex(&self.outdoor_wifi, hex),
+        }
+    }
+
     /// Creates a submap from the current `CoverageMapBuilder` and the provided `coverage_objs`.
     ///
     /// A submap only contains the hexes that exist in the provided `coverage_objs` arguments. This
@@ -78,58 +115,63 @@ impl CoverageMapBuilder {
         boosted_hexes: &impl BoostedHexMap,
         epoch_start: DateTime<Utc>,
     ) -> CoverageMap {
-        let mut wifi_hotspots = HashMap::<_, Vec<RankedCoverage>>::new();
-        let mut cbrs_radios = HashMap::<_, Vec<RankedCoverage>>::new();
-        for coverage in into_indoor_coverage_map(self.indoor_cbrs, boosted_hexes, epoch_start)
-            .chain(into_indoor_coverage_map(
-                self.indoor_wifi,
-                boosted_hexes,
-                epoch_start,
-            ))
-            .chain(into_outdoor_coverage_map(
-                self.outdoor_cbrs,
-                boosted_hexes,
-                epoch_start,
-            ))
-            .chain(into_outdoor_coverage_map(
-                self.outdoor_wifi,
-                boosted_hexes,
-                epoch_start,
-            ))
-        {
-            if let Some(ref cbsd_id) = coverage.cbsd_id {
-                cbrs_radios
-                    .entry(cbsd_id.clone())
-                    .or_default()
-                    .push(coverage);
+        let indoor_cbrs = into_indoor_coverage_map(self.indoor_cbrs, boosted_hexes, epoch_start);
+        let indoor_wifi = into_indoor_coverage_map(self.indoor_wifi, boosted_hexes, epoch_start);
+        let outdoor_cbrs = into_outdoor_coverage_map(self.outdoor_cbrs, boosted_hexes, epoch_start);
+        let outdoor_wifi = into_outdoor_coverage_map(self.outdoor_wifi, boosted_hexes, epoch_start);
+        CoverageMap::from_ranked_coverage(
+            indoor_cbrs
+                .chain(indoor_wifi)
+                .map(|coverage| (true, coverage))
+                .chain(
+                    outdoor_cbrs
+                        .chain(outdoor_wifi)
+                        .map(|coverage| (false, coverage)),
+                ),
+        )
+    }
+}
+
+/// Data structure from mapping radios to their ranked hex coverage
+#[derive(Clone, Default, Debug)]
+pub struct CoverageMap {
+    wifi_hotspots: HashMap<Vec<u8>, RadioCoverage>,
+    cbrs_radios: HashMap<String, RadioCoverage>,
+}
+
+#[derive(Clone, Default, Debug)]
+struct RadioCoverage {
+    indoor: bool,
+    coverage: Vec<RankedCoverage>,
+}
+
+impl CoverageMap {
+    fn from_ranked_coverage(ranked_coverage: impl Iterator<Item = (bool, RankedCoverage)>) -> Self {
+        let mut wifi_hotspots = HashMap::<_, RadioCoverage>::new();
+        let mut cbrs_radios = HashMap::<_, RadioCoverage>::new();
+        for (indoor, coverage) in ranked_coverage {
+            let radio = if let Some(ref cbsd_id) = coverage.cbsd_id {
+                cbrs_radios.entry(cbsd_id.clone()).or_default()
             } else {
                 wifi_hotspots
                     .entry(coverage.hotspot_key.clone())
                     .or_default()
-                    .push(coverage);
-            }
+            };
+            radio.indoor = indoor;
+            radio.coverage.push(coverage);
         }
-        CoverageMap {
+        Self {
             wifi_hotspots,
             cbrs_radios,
         }
     }
-}
-
-/// Data structure from mapping radios to their ranked hex coverage
-#[derive(Clone, Default, Debug)]
-pub struct CoverageMap {
-    wifi_hotspots: HashMap<Vec<u8>, Vec<RankedCoverage>>,
-    cbrs_radios: HashMap<String, Vec<RankedCoverage>>,
-}
 
-impl CoverageMap {
     /// Returns the hexes covered by the WiFi hotspot. The returned slice can be empty, indicating that
     /// the hotspot did not meet the criteria to be ranked in any hex.
     pub fn get_wifi_coverage(&self, wifi_hotspot: &[u8]) -> &[RankedCoverage] {
         self.wifi_hotspots
             .get(wifi_hotspot)
-            .map(Vec::as_slice)
+            .map(|radio| radio.coverage.as_slice())
             .unwrap_or(&[])
     }
 
@@ -138,9 +180,49 @@ impl CoverageMap {
     pub fn get_cbrs_coverage(&self, cbrs_radio: &str) -> &[RankedCoverage] {
         self.cbrs_radios
             .get(cbrs_radio)
-            .map(Vec::as_slice)
+            .map(|radio| radio.coverage.as_slice())
             .unwrap_or(&[])
     }
+
+    /// Iterates over the ranked coverage of every radio in the map, along with
+    /// the category the radio was ranked in.
+    pub fn ranked_coverage(&self) -> impl Iterator<Item = (RadioCategory, &RankedCoverage)> {
+        let wifi = self.wifi_hotspots.values().flat_map(|radio| {
+            let category = RadioCategory::new(radio.indoor, false);
+            radio
+                .coverage
+                .iter()
+                .map(move |coverage| (category, coverage))
+        });
+        let cbrs = self.cbrs_radios.values().flat_map(|radio| {
+            let category = RadioCategory::new(radio.indoor, true);
+            radio
+                .coverage
+                .iter()
+                .map(move |coverage| (category, coverage))
+        });
+        wifi.chain(cbrs)
+    }
+}
+
+/// Radios are only ranked against other radios of the same category
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RadioCategory {
+    IndoorWifi,
+    IndoorCbrs,
+    OutdoorWifi,
+    OutdoorCbrs,
+}
+
+impl RadioCategory {
+    pub fn new(indoor: bool, cbrs: bool) -> Self {
+        match (indoor, cbrs) {
+            (true, false) => Self::IndoorWifi,
+            (true, true) => Self::IndoorCbrs,
+            (false, false) => Self::OutdoorWifi,
+            (false, true) => Self::OutdoorCbrs,
+        }
+    }
 }
 
 /// Coverage data given as input to the [CoverageMapBuilder]
@@ -154,7 +236,7 @@ pub struct CoverageObject {
 }
 
 /// Unranked hex coverage data given as input to the [CoverageMapBuilder]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct UnrankedCoverage {
     pub location: Cell,
     pub signal_power: i32,
@@ -325,6 +407,7 @@ mod test {
             urbanized: Assignment::A,
             landtype: Assignment::A,
             service_provider_override: Assignment::C,
+            assignment_multiplier: None,
         }
     }
 
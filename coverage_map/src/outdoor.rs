@@ -24,8 +24,7 @@ pub struct OutdoorCoverageLevel {
 
 impl PartialEq for OutdoorCoverageLevel {
     fn eq(&self, other: &Self) -> bool {
-        self.signal_power == other.signal_power
-            && self.seniority_timestamp == other.seniority_timestamp
+        self.cmp(other) == Ordering::Equal
     }
 }
 
@@ -41,6 +40,10 @@ impl Ord for OutdoorCoverageLevel {
             .cmp(&other.signal_power)
             .reverse()
             .then_with(|| self.seniority_timestamp.cmp(&other.seniority_timestamp))
+            // Radios with equal signal power and seniority are ranked by key
+            // so the order never depends on insertion order
+            .then_with(|| self.hotspot_key.cmp(&other.hotspot_key))
+            .then_with(|| self.cbsd_id.cmp(&other.cbsd_id))
     }
 }
 
@@ -79,6 +82,25 @@ pub fn insert_outdoor_coverage(
         });
 }
 
+pub fn remove_outdoor_coverage(
+    outdoor: &mut OutdoorCellTree,
+    hexes: &[Cell],
+    hotspot: &[u8],
+    cbsd_id: &Option<String>,
+) {
+    for hex in hexes {
+        let Entry::Occupied(mut radios) = outdoor.entry(*hex) else {
+            continue;
+        };
+        radios
+            .get_mut()
+            .retain(|radio| radio.hotspot_key != hotspot || radio.cbsd_id != *cbsd_id);
+        if radios.get().is_empty() {
+            radios.remove();
+        }
+    }
+}
+
 pub fn clone_outdoor_coverage_into_submap(
     submap: &mut OutdoorCellTree,
     from: &OutdoorCellTree,
@@ -93,6 +115,27 @@ pub fn clone_outdoor_coverage_into_submap(
     }
 }
 
+pub fn rank_outdoor_hex(outdoor: &OutdoorCellTree, hex: Cell) -> Vec<RankedCoverage> {
+    let Some(radios) = outdoor.get(&hex) else {
+        return vec![];
+    };
+    radios
+        .clone()
+        .into_sorted_vec()
+        .into_iter()
+        .enumerate()
+        .map(|(rank, cov)| RankedCoverage {
+            hex,
+            rank: rank + 1,
+            hotspot_key: cov.hotspot_key,
+            cbsd_id: cov.cbsd_id,
+            assignments: cov.assignments,
+            boosted: None,
+            signal_level: cov.signal_level,
+        })
+        .collect()
+}
+
 pub fn into_outdoor_coverage_map(
     outdoor: OutdoorCellTree,
     boosted_hexes: &impl BoostedHexMap,
@@ -155,6 +198,7 @@ mod test {
             urbanized: Assignment::A,
             landtype: Assignment::A,
             service_provider_override: Assignment::C,
+            assignment_multiplier: None,
         }
     }
 
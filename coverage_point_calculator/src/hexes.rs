@@ -160,6 +160,7 @@ mod tests {
                 landtype: Assignment::C,
                 urbanized: Assignment::C,
                 service_provider_override: Assignment::C,
+                assignment_multiplier: None,
             },
             boosted: NonZeroU32::new(0),
         };
@@ -199,6 +200,7 @@ mod tests {
                 landtype: Assignment::A,
                 urbanized: Assignment::A,
                 service_provider_override: Assignment::C,
+                assignment_multiplier: None,
             },
             boosted: NonZeroU32::new(0),
         };
@@ -247,6 +249,7 @@ mod tests {
                 landtype: Assignment::C,
                 urbanized: Assignment::C,
                 service_provider_override: Assignment::C,
+                assignment_multiplier: None,
             },
             boosted: NonZeroU32::new(0),
         };
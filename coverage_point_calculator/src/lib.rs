@@ -32,6 +32,7 @@
 //!   - [HIP-74][modeled-coverage]
 //!   - added "Good" speedtest tier [HIP-98][qos-score]
 //!     - latency is explicitly under limit in HIP <https://github.com/helium/oracles/pull/737>
+//!   - tier thresholds can be overridden per radio type from a given time [SpeedtestTierTable]
 //!
 //! ## Notable Conditions:
 //! - [LocationTrust]
@@ -75,13 +76,17 @@
 pub use crate::{
     hexes::{CoveredHex, HexPoints},
     location::{asserted_distance_to_trust_multiplier, LocationTrust},
-    service_provider_boosting::SPBoostedRewardEligibility,
-    speedtest::{BytesPs, Speedtest, SpeedtestTier},
+    service_provider_boosting::{
+        SPBoostedRewardEligibility, MAX_AVERAGE_DISTANCE, MIN_WIFI_TRUST_MULTIPLIER,
+    },
+    speedtest::{
+        BytesPs, Speedtest, SpeedtestTier, SpeedtestTierProfile, SpeedtestTierTable,
+        SpeedtestTierThresholds, TierThreshold,
+    },
 };
 use coverage_map::SignalLevel;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
-use service_provider_boosting::{MAX_AVERAGE_DISTANCE, MIN_WIFI_TRUST_MULTIPLIER};
 
 mod hexes;
 pub mod location;
@@ -140,6 +145,8 @@ pub struct CoveragePoints {
     ///
     /// Backhaul of a Radio
     pub speedtest_multiplier: Decimal,
+    /// Tier of the average speedtest, determines `speedtest_multiplier`
+    pub speedtest_tier: SpeedtestTier,
     /// Input Radio Type
     pub radio_type: RadioType,
     /// Input SPBoostedRewardEligibility
@@ -166,6 +173,28 @@ impl CoveragePoints {
         location_trust_scores: Vec<LocationTrust>,
         ranked_coverage: Vec<coverage_map::RankedCoverage>,
         oracle_boost_status: OracleBoostingStatus,
+    ) -> Result<CoveragePoints> {
+        Self::new_with_speedtest_thresholds(
+            radio_type,
+            service_provider_boosted_reward_eligibility,
+            speedtests,
+            location_trust_scores,
+            ranked_coverage,
+            oracle_boost_status,
+            &SpeedtestTierThresholds::DEFAULT,
+        )
+    }
+
+    /// Same as [CoveragePoints::new] with the speedtest tier thresholds for
+    /// the radio type and epoch, see [SpeedtestTierTable]
+    pub fn new_with_speedtest_thresholds(
+        radio_type: RadioType,
+        service_provider_boosted_reward_eligibility: SPBoostedRewardEligibility,
+        speedtests: Vec<Speedtest>,
+        location_trust_scores: Vec<LocationTrust>,
+        ranked_coverage: Vec<coverage_map::RankedCoverage>,
+        oracle_boost_status: OracleBoostingStatus,
+        speedtest_thresholds: &SpeedtestTierThresholds,
     ) -> Result<CoveragePoints> {
         let location_trust_multiplier = location::multiplier(radio_type, &location_trust_scores);
 
@@ -186,12 +215,13 @@ impl CoveragePoints {
         let hex_coverage_points = hexes::calculated_coverage_points(&covered_hexes);
 
         let speedtests = speedtest::clean_speedtests(speedtests);
-        let (speedtest_multiplier, speedtest_avg) = speedtest::multiplier(&speedtests);
+        let (speedtest_tier, speedtest_avg) = speedtest::tier(&speedtests, speedtest_thresholds);
 
         Ok(CoveragePoints {
             coverage_points: hex_coverage_points,
             location_trust_multiplier,
-            speedtest_multiplier,
+            speedtest_multiplier: speedtest_tier.multiplier(),
+            speedtest_tier,
             speedtest_avg,
             radio_type,
             service_provider_boosted_reward_eligibility,
@@ -642,6 +672,7 @@ mod tests {
                     landtype,
                     urbanized,
                     service_provider_override,
+                    assignment_multiplier: None,
                 },
                 boosted: None,
             }
@@ -992,6 +1023,7 @@ mod tests {
             landtype: Assignment::A,
             urbanized: Assignment::A,
             service_provider_override: Assignment::C,
+            assignment_multiplier: None,
         }
     }
 
@@ -1006,6 +1038,7 @@ mod tests {
             landtype: assignment,
             urbanized: assignment,
             service_provider_override: service_provider_override_assignment,
+            assignment_multiplier: None,
         }
     }
 
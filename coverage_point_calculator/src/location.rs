@@ -36,7 +36,7 @@ pub fn asserted_distance_to_trust_multiplier(
     }
 }
 
-pub(crate) fn average_distance(radio_type: RadioType, trust_scores: &[LocationTrust]) -> Decimal {
+pub fn average_distance(radio_type: RadioType, trust_scores: &[LocationTrust]) -> Decimal {
     // CBRS radios are always trusted because they have internal GPS
     if radio_type.is_cbrs() {
         return dec!(0);
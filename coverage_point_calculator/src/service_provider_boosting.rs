@@ -7,7 +7,7 @@ use rust_decimal_macros::dec;
 // [HIP-93: Add Wifi to Mobile Dao][add-wifi-aps]
 //
 // [add-wifi-aps]: https://github.com/helium/HIP/blob/main/0093-addition-of-wifi-aps-to-mobile-subdao.md#341-indoor-access-points-rewards
-pub(crate) const MIN_WIFI_TRUST_MULTIPLIER: Decimal = dec!(0.75);
+pub const MIN_WIFI_TRUST_MULTIPLIER: Decimal = dec!(0.75);
 
 // In order for access points to be eligible for boosted Service Provider
 // rewards defined in HIP-84, the asserted distances must be 50 meters or
@@ -17,7 +17,7 @@ pub(crate) const MIN_WIFI_TRUST_MULTIPLIER: Decimal = dec!(0.75);
 // [HIP-119: Gaming Loopholes][gaming-loopholes]
 //
 // [gaming-loopholes]: https://github.com/helium/HIP/blob/main/0119-closing-gaming-loopholes-within-the-mobile-network.md#maximum-asserted-distance-for-boosted-hexes
-pub(crate) const MAX_AVERAGE_DISTANCE: Decimal = dec!(50);
+pub const MAX_AVERAGE_DISTANCE: Decimal = dec!(50);
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SPBoostedRewardEligibility {
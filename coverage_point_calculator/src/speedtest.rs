@@ -1,6 +1,9 @@
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
+use serde::Deserialize;
+
+use crate::RadioType;
 
 pub const MIN_REQUIRED_SPEEDTEST_SAMPLES: usize = 2;
 pub const MAX_ALLOWED_SPEEDTEST_SAMPLES: usize = 6;
@@ -39,15 +42,18 @@ pub(crate) fn clean_speedtests(speedtests: Vec<Speedtest>) -> Vec<Speedtest> {
     cleaned
 }
 
-// Returns multiplier and speedtest (average)
-pub(crate) fn multiplier(speedtests: &[Speedtest]) -> (Decimal, Speedtest) {
+// Returns tier and speedtest (average)
+pub(crate) fn tier(
+    speedtests: &[Speedtest],
+    thresholds: &SpeedtestTierThresholds,
+) -> (SpeedtestTier, Speedtest) {
     let avg = Speedtest::avg(speedtests);
 
     if speedtests.len() < MIN_REQUIRED_SPEEDTEST_SAMPLES {
-        return (dec!(0), avg);
+        return (SpeedtestTier::Fail, avg);
     }
 
-    (avg.multiplier(), avg)
+    (avg.tier_with(thresholds), avg)
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -91,10 +97,17 @@ impl Speedtest {
         self.tier().multiplier()
     }
 
+    /// Tier according to the [HIP-98][qos-score] thresholds
+    ///
+    /// [qos-score]: https://github.com/helium/HIP/blob/main/0098-mobile-subdao-quality-of-service-requirements.md
     pub fn tier(&self) -> SpeedtestTier {
-        let upload = SpeedtestTier::from_upload(self.upload_speed);
-        let download = SpeedtestTier::from_download(self.download_speed);
-        let latency = SpeedtestTier::from_latency(self.latency_millis);
+        self.tier_with(&SpeedtestTierThresholds::DEFAULT)
+    }
+
+    pub fn tier_with(&self, thresholds: &SpeedtestTierThresholds) -> SpeedtestTier {
+        let upload = thresholds.upload_tier(self.upload_speed);
+        let download = thresholds.download_tier(self.download_speed);
+        let latency = thresholds.latency_tier(self.latency_millis);
         upload.min(download).min(latency)
     }
 
@@ -146,35 +159,155 @@ impl SpeedtestTier {
             SpeedtestTier::Fail => dec!(0),
         }
     }
+}
 
-    fn from_download(bytes: BytesPs) -> Self {
-        match bytes.as_mbps() {
-            100.. => Self::Good,
-            75.. => Self::Acceptable,
-            50.. => Self::Degraded,
-            30.. => Self::Poor,
-            _ => Self::Fail,
-        }
+/// Minimum requirements a speedtest must meet for a tier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct TierThreshold {
+    pub min_download_mbps: u64,
+    pub min_upload_mbps: u64,
+    pub max_latency_millis: Millis,
+}
+
+/// Requirements for each speedtest tier above [SpeedtestTier::Fail]. Each
+/// of download, upload and latency is tiered on its own and the lowest of
+/// the three is the tier of the speedtest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct SpeedtestTierThresholds {
+    pub good: TierThreshold,
+    pub acceptable: TierThreshold,
+    pub degraded: TierThreshold,
+    pub poor: TierThreshold,
+}
+
+impl SpeedtestTierThresholds {
+    /// [HIP-98][qos-score] thresholds
+    ///
+    /// [qos-score]: https://github.com/helium/HIP/blob/main/0098-mobile-subdao-quality-of-service-requirements.md
+    pub const DEFAULT: Self = Self {
+        good: TierThreshold {
+            min_download_mbps: 100,
+            min_upload_mbps: 10,
+            max_latency_millis: 49,
+        },
+        acceptable: TierThreshold {
+            min_download_mbps: 75,
+            min_upload_mbps: 8,
+            max_latency_millis: 59,
+        },
+        degraded: TierThreshold {
+            min_download_mbps: 50,
+            min_upload_mbps: 5,
+            max_latency_millis: 74,
+        },
+        poor: TierThreshold {
+            min_download_mbps: 30,
+            min_upload_mbps: 2,
+            max_latency_millis: 99,
+        },
+    };
+
+    fn tiers(&self) -> [(SpeedtestTier, &TierThreshold); 4] {
+        [
+            (SpeedtestTier::Good, &self.good),
+            (SpeedtestTier::Acceptable, &self.acceptable),
+            (SpeedtestTier::Degraded, &self.degraded),
+            (SpeedtestTier::Poor, &self.poor),
+        ]
     }
 
-    fn from_upload(bytes: BytesPs) -> Self {
-        match bytes.as_mbps() {
-            10.. => Self::Good,
-            8.. => Self::Acceptable,
-            5.. => Self::Degraded,
-            2.. => Self::Poor,
-            _ => Self::Fail,
-        }
+    fn tier_where(&self, meets: impl Fn(&TierThreshold) -> bool) -> SpeedtestTier {
+        self.tiers()
+            .into_iter()
+            .find(|(_tier, threshold)| meets(threshold))
+            .map_or(SpeedtestTier::Fail, |(tier, _threshold)| tier)
     }
 
-    fn from_latency(millis: Millis) -> Self {
-        match millis {
-            0..=49 => Self::Good,
-            50..=59 => Self::Acceptable,
-            60..=74 => Self::Degraded,
-            75..=99 => Self::Poor,
-            _ => Self::Fail,
-        }
+    pub fn download_tier(&self, bytes: BytesPs) -> SpeedtestTier {
+        self.tier_where(|t| bytes.as_mbps() >= t.min_download_mbps)
+    }
+
+    pub fn upload_tier(&self, bytes: BytesPs) -> SpeedtestTier {
+        self.tier_where(|t| bytes.as_mbps() >= t.min_upload_mbps)
+    }
+
+    pub fn latency_tier(&self, millis: Millis) -> SpeedtestTier {
+        self.tier_where(|t| millis <= t.max_latency_millis)
+    }
+}
+
+impl Default for SpeedtestTierThresholds {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Speedtest tier thresholds which apply from `effective_from` until the
+/// next profile takes effect. Radio types without their own thresholds use
+/// `thresholds`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SpeedtestTierProfile {
+    pub effective_from: DateTime<Utc>,
+    pub thresholds: SpeedtestTierThresholds,
+    #[serde(default)]
+    pub indoor_wifi: Option<SpeedtestTierThresholds>,
+    #[serde(default)]
+    pub outdoor_wifi: Option<SpeedtestTierThresholds>,
+    #[serde(default)]
+    pub indoor_cbrs: Option<SpeedtestTierThresholds>,
+    #[serde(default)]
+    pub outdoor_cbrs: Option<SpeedtestTierThresholds>,
+}
+
+impl SpeedtestTierProfile {
+    pub fn thresholds(&self, radio_type: RadioType) -> &SpeedtestTierThresholds {
+        let radio_thresholds = match radio_type {
+            RadioType::IndoorWifi => &self.indoor_wifi,
+            RadioType::OutdoorWifi => &self.outdoor_wifi,
+            RadioType::IndoorCbrs => &self.indoor_cbrs,
+            RadioType::OutdoorCbrs => &self.outdoor_cbrs,
+        };
+        radio_thresholds.as_ref().unwrap_or(&self.thresholds)
+    }
+}
+
+/// All speedtest tier profiles over time. Before the first profile takes
+/// effect [SpeedtestTierThresholds::DEFAULT] applies so historical epochs
+/// keep the thresholds they were rewarded with.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct SpeedtestTierTable {
+    profiles: Vec<SpeedtestTierProfile>,
+}
+
+impl SpeedtestTierTable {
+    pub fn new(profiles: Vec<SpeedtestTierProfile>) -> Self {
+        Self { profiles }
+    }
+
+    /// Thresholds for `radio_type` from the latest profile effective at `at`
+    pub fn thresholds(&self, radio_type: RadioType, at: DateTime<Utc>) -> &SpeedtestTierThresholds {
+        self.profile_at(at)
+            .map_or(&SpeedtestTierThresholds::DEFAULT, |profile| {
+                profile.thresholds(radio_type)
+            })
+    }
+
+    /// Thresholds from the latest profile effective at `at` which apply to
+    /// radio types without their own thresholds, for where the radio type
+    /// of a speedtest is not known
+    pub fn base_thresholds(&self, at: DateTime<Utc>) -> &SpeedtestTierThresholds {
+        self.profile_at(at)
+            .map_or(&SpeedtestTierThresholds::DEFAULT, |profile| {
+                &profile.thresholds
+            })
+    }
+
+    fn profile_at(&self, at: DateTime<Utc>) -> Option<&SpeedtestTierProfile> {
+        self.profiles
+            .iter()
+            .filter(|profile| profile.effective_from <= at)
+            .max_by_key(|profile| profile.effective_from)
     }
 }
 
@@ -185,26 +318,70 @@ mod tests {
     #[test]
     fn speedtest_teirs() {
         use SpeedtestTier::*;
+        let thresholds = SpeedtestTierThresholds::DEFAULT;
         // download
-        assert_eq!(Good, SpeedtestTier::from_download(BytesPs::mbps(100)));
-        assert_eq!(Acceptable, SpeedtestTier::from_download(BytesPs::mbps(80)));
-        assert_eq!(Degraded, SpeedtestTier::from_download(BytesPs::mbps(62)));
-        assert_eq!(Poor, SpeedtestTier::from_download(BytesPs::mbps(42)));
-        assert_eq!(Fail, SpeedtestTier::from_download(BytesPs::mbps(20)));
+        assert_eq!(Good, thresholds.download_tier(BytesPs::mbps(100)));
+        assert_eq!(Acceptable, thresholds.download_tier(BytesPs::mbps(80)));
+        assert_eq!(Degraded, thresholds.download_tier(BytesPs::mbps(62)));
+        assert_eq!(Poor, thresholds.download_tier(BytesPs::mbps(42)));
+        assert_eq!(Fail, thresholds.download_tier(BytesPs::mbps(20)));
 
         // upload
-        assert_eq!(Good, SpeedtestTier::from_upload(BytesPs::mbps(10)));
-        assert_eq!(Acceptable, SpeedtestTier::from_upload(BytesPs::mbps(8)));
-        assert_eq!(Degraded, SpeedtestTier::from_upload(BytesPs::mbps(6)));
-        assert_eq!(Poor, SpeedtestTier::from_upload(BytesPs::mbps(4)));
-        assert_eq!(Fail, SpeedtestTier::from_upload(BytesPs::mbps(1)));
+        assert_eq!(Good, thresholds.upload_tier(BytesPs::mbps(10)));
+        assert_eq!(Acceptable, thresholds.upload_tier(BytesPs::mbps(8)));
+        assert_eq!(Degraded, thresholds.upload_tier(BytesPs::mbps(6)));
+        assert_eq!(Poor, thresholds.upload_tier(BytesPs::mbps(4)));
+        assert_eq!(Fail, thresholds.upload_tier(BytesPs::mbps(1)));
 
         // latency
-        assert_eq!(Good, SpeedtestTier::from_latency(49));
-        assert_eq!(Acceptable, SpeedtestTier::from_latency(59));
-        assert_eq!(Degraded, SpeedtestTier::from_latency(74));
-        assert_eq!(Poor, SpeedtestTier::from_latency(99));
-        assert_eq!(Fail, SpeedtestTier::from_latency(101));
+        assert_eq!(Good, thresholds.latency_tier(49));
+        assert_eq!(Acceptable, thresholds.latency_tier(59));
+        assert_eq!(Degraded, thresholds.latency_tier(74));
+        assert_eq!(Poor, thresholds.latency_tier(99));
+        assert_eq!(Fail, thresholds.latency_tier(101));
+    }
+
+    #[test]
+    fn speedtest_tier_table_by_effective_time_and_radio_type() {
+        let stricter = SpeedtestTierThresholds {
+            good: TierThreshold {
+                min_download_mbps: 200,
+                ..SpeedtestTierThresholds::DEFAULT.good
+            },
+            ..SpeedtestTierThresholds::DEFAULT
+        };
+        let effective_from = "2025-01-01T00:00:00Z".parse().unwrap();
+        let table = SpeedtestTierTable::new(vec![SpeedtestTierProfile {
+            effective_from,
+            thresholds: SpeedtestTierThresholds::DEFAULT,
+            indoor_wifi: None,
+            outdoor_wifi: Some(stricter),
+            indoor_cbrs: None,
+            outdoor_cbrs: None,
+        }]);
+        let before = effective_from - chrono::Duration::seconds(1);
+
+        assert_eq!(
+            &SpeedtestTierThresholds::DEFAULT,
+            table.thresholds(RadioType::OutdoorWifi, before)
+        );
+        assert_eq!(
+            &stricter,
+            table.thresholds(RadioType::OutdoorWifi, effective_from)
+        );
+        assert_eq!(
+            &SpeedtestTierThresholds::DEFAULT,
+            table.thresholds(RadioType::IndoorWifi, effective_from)
+        );
+
+        let speedtest = Speedtest {
+            upload_speed: BytesPs::mbps(15),
+            download_speed: BytesPs::mbps(150),
+            latency_millis: 15,
+            timestamp: Utc::now(),
+        };
+        assert_eq!(SpeedtestTier::Good, speedtest.tier());
+        assert_eq!(SpeedtestTier::Acceptable, speedtest.tier_with(&stricter));
     }
 
     #[test]
@@ -225,13 +402,18 @@ mod tests {
         };
         let speedtests = |num: usize| std::iter::repeat(speedtest).take(num).collect::<Vec<_>>();
 
+        let thresholds = SpeedtestTierThresholds::DEFAULT;
         assert_eq!(
             dec!(0),
-            multiplier(&speedtests(MIN_REQUIRED_SPEEDTEST_SAMPLES - 1)).0
+            tier(&speedtests(MIN_REQUIRED_SPEEDTEST_SAMPLES - 1), &thresholds)
+                .0
+                .multiplier()
         );
         assert_eq!(
             dec!(1),
-            multiplier(&speedtests(MIN_REQUIRED_SPEEDTEST_SAMPLES)).0
+            tier(&speedtests(MIN_REQUIRED_SPEEDTEST_SAMPLES), &thresholds)
+                .0
+                .multiplier()
         );
     }
 
@@ -282,7 +464,12 @@ mod tests {
         ]);
 
         // Old speedtests should be unused
-        assert_eq!(dec!(1), multiplier(&speedtests).0);
+        assert_eq!(
+            dec!(1),
+            tier(&speedtests, &SpeedtestTierThresholds::DEFAULT)
+                .0
+                .multiplier()
+        );
     }
 
     #[test]
@@ -292,7 +479,7 @@ mod tests {
         // constructor, so will always be consistent with each other.
         assert_eq!(
             SpeedtestTier::Acceptable,
-            SpeedtestTier::from_download(BytesPs::new(11_702_687))
+            SpeedtestTierThresholds::DEFAULT.download_tier(BytesPs::new(11_702_687))
         );
     }
 
@@ -41,6 +41,7 @@ fn base_radio_coverage_points() {
             landtype: Assignment::A,
             urbanized: Assignment::A,
             service_provider_override: Assignment::C,
+            assignment_multiplier: None,
         },
         boosted: NonZeroU32::new(0),
     }];
@@ -84,6 +85,7 @@ fn radios_with_coverage() {
             landtype: Assignment::A,
             urbanized: Assignment::A,
             service_provider_override: Assignment::C,
+            assignment_multiplier: None,
         },
         boosted: NonZeroU32::new(0),
     };
@@ -429,6 +431,7 @@ fn ranked_coverage(hex: u64, rank: usize, signal_level: SignalLevel) -> RankedCo
             landtype: Assignment::A,
             urbanized: Assignment::A,
             service_provider_override: Assignment::C,
+            assignment_multiplier: None,
         },
         boosted: None,
         signal_level,
@@ -445,6 +448,7 @@ fn unranked_coverage(hex: u64, signal_level: SignalLevel) -> UnrankedCoverage {
             landtype: Assignment::A,
             urbanized: Assignment::A,
             service_provider_override: Assignment::C,
+            assignment_multiplier: None,
         },
     }
 }
@@ -0,0 +1,189 @@
+//! Authoring of signed denylist filters.
+//!
+//! A denylist is built from a [`SourceList`] of denied gateway keys and
+//! denied (beaconer, witness) edges. Source lists are plain text files with
+//! one entry per line, either a single b58 encoded key or two comma separated
+//! keys for an edge. Blank lines and lines starting with `#` are ignored.
+//!
+//! The resulting filter bin is compatible with [`crate::denylist::filter_from_bin`]
+//! and can be published as a `filter.bin` release asset or written directly
+//! to a verifier's local filter cache.
+
+use crate::{denylist::filter_from_bin, Error, Result};
+use helium_crypto::{Keypair, PublicKeyBinary, Sign};
+use serde::Serialize;
+use std::{collections::BTreeSet, fmt, fs, path::Path, str::FromStr};
+use xorf_generator::{edge_hash, public_key_hash, Filter};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SourceList {
+    pub keys: BTreeSet<PublicKeyBinary>,
+    pub edges: BTreeSet<(PublicKeyBinary, PublicKeyBinary)>,
+}
+
+#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+pub struct SourceListDiff {
+    pub added_keys: Vec<PublicKeyBinary>,
+    pub removed_keys: Vec<PublicKeyBinary>,
+    pub added_edges: Vec<(PublicKeyBinary, PublicKeyBinary)>,
+    pub removed_edges: Vec<(PublicKeyBinary, PublicKeyBinary)>,
+}
+
+impl SourceList {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty() && self.edges.is_empty()
+    }
+
+    /// the filter hashes of all keys and edges, sorted and deduplicated
+    pub fn hashes(&self) -> Vec<u64> {
+        let mut hashes: Vec<u64> = self
+            .keys
+            .iter()
+            .map(public_key_hash)
+            .chain(self.edges.iter().map(|(a, b)| edge_hash(a, b)))
+            .collect();
+        hashes.sort_unstable();
+        hashes.dedup();
+        hashes
+    }
+
+    /// entries in `self` which are not in `previous` are reported as added,
+    /// entries in `previous` which are not in `self` as removed
+    pub fn diff(&self, previous: &SourceList) -> SourceListDiff {
+        SourceListDiff {
+            added_keys: self.keys.difference(&previous.keys).cloned().collect(),
+            removed_keys: previous.keys.difference(&self.keys).cloned().collect(),
+            added_edges: self.edges.difference(&previous.edges).cloned().collect(),
+            removed_edges: previous.edges.difference(&self.edges).cloned().collect(),
+        }
+    }
+}
+
+impl FromStr for SourceList {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut list = SourceList::default();
+        for (idx, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let parse_key = |key: &str| {
+                PublicKeyBinary::from_str(key.trim()).map_err(|_| {
+                    Error::invalid_source(format!("line {}: invalid key {key}", idx + 1))
+                })
+            };
+            match line.split_once(',') {
+                Some((beaconer, witness)) => {
+                    list.edges
+                        .insert((parse_key(beaconer)?, parse_key(witness)?));
+                }
+                None => {
+                    list.keys.insert(parse_key(line)?);
+                }
+            }
+        }
+        Ok(list)
+    }
+}
+
+impl fmt::Display for SourceList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for key in &self.keys {
+            writeln!(f, "{key}")?;
+        }
+        for (beaconer, witness) in &self.edges {
+            writeln!(f, "{beaconer},{witness}")?;
+        }
+        Ok(())
+    }
+}
+
+impl SourceListDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_keys.is_empty()
+            && self.removed_keys.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+/// Build an unsigned filter with the given serial from a source list
+pub fn build_filter(serial: u32, source: &SourceList) -> Result<Filter> {
+    let hashes = source.hashes();
+    Filter::new(serial, xorf_generator::xorf::Xor32::from(&hashes))
+        .map_err(|_| Error::invalid_filter("filter"))
+}
+
+/// The serial to use for the next filter, one more than the serial of the
+/// previously published filter if any
+pub fn next_serial(previous: Option<&Filter>) -> u32 {
+    previous.map_or(1, |filter| filter.serial.saturating_add(1))
+}
+
+/// Build a filter from the source list, sign it with the given keypair and
+/// return the filter bin.
+///
+/// The bin is verified against the signing key before being returned so a
+/// filter which a verifier would reject is never produced.
+pub fn build_signed_filter_bin(
+    serial: u32,
+    source: &SourceList,
+    keypair: &Keypair,
+) -> Result<Vec<u8>> {
+    if source.is_empty() {
+        return Err(Error::invalid_source("empty source list"));
+    }
+    let mut filter = build_filter(serial, source)?;
+    let msg = filter
+        .signing_bytes()
+        .map_err(|_| Error::invalid_filter("signing bytes"))?;
+    filter.signature = keypair.sign(&msg)?;
+    let bin = filter
+        .to_bytes()
+        .map_err(|_| Error::invalid_filter("serialize"))?;
+    filter_from_bin(&bin, &[keypair.public_key().clone()])?;
+    Ok(bin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_A: &str = "112NqN2WWMwtK29PMzRby62fDydBJfsCLkCAf392stdok48ovNT6";
+    const KEY_B: &str = "11sctWiP9r5wDJVuDe1Th4XSL2vaawaLLSQF8f8iokAoMAJHxqp";
+
+    #[test]
+    fn parse_source_list() {
+        let list: SourceList = format!("# denied\n{KEY_A}\n\n{KEY_A}, {KEY_B}\n{KEY_A}\n")
+            .parse()
+            .unwrap();
+        assert_eq!(1, list.keys.len());
+        assert_eq!(1, list.edges.len());
+        assert_eq!(list, list.to_string().parse().unwrap());
+        assert!("not-a-key".parse::<SourceList>().is_err());
+    }
+
+    #[test]
+    fn diff_source_lists() {
+        let previous: SourceList = format!("{KEY_A}\n{KEY_A},{KEY_B}").parse().unwrap();
+        let current: SourceList = format!("{KEY_B}\n{KEY_A},{KEY_B}").parse().unwrap();
+        let diff = current.diff(&previous);
+        assert_eq!(
+            vec![KEY_B.parse::<PublicKeyBinary>().unwrap()],
+            diff.added_keys
+        );
+        assert_eq!(
+            vec![KEY_A.parse::<PublicKeyBinary>().unwrap()],
+            diff.removed_keys
+        );
+        assert!(diff.added_edges.is_empty());
+        assert!(diff.removed_edges.is_empty());
+        assert!(current.diff(&current).is_empty());
+    }
+}
@@ -23,7 +23,7 @@ impl DenyListClient {
         Ok(Self { client })
     }
 
-    pub async fn get_metadata(&mut self, url: &String) -> Result<DenyListMetaData> {
+    pub async fn get_metadata(&self, url: &str) -> Result<DenyListMetaData> {
         let response = self.client.get(url).send().await?;
         match response.status() {
             reqwest::StatusCode::OK => {
@@ -34,7 +34,7 @@ impl DenyListClient {
         }
     }
 
-    pub async fn get_bin(&mut self, url: &String) -> Result<Vec<u8>> {
+    pub async fn get_bin(&self, url: &str) -> Result<Vec<u8>> {
         let response = self.client.get(url).send().await?;
         match response.status() {
             reqwest::StatusCode::OK => {
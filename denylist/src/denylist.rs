@@ -1,23 +1,43 @@
-use crate::{client::DenyListClient, models::metadata::Asset, Error, Result, Settings};
+use crate::{
+    settings::FailurePolicy,
+    source::{self, DenyListSource, FilterBin},
+    Error, Result, Settings,
+};
+use chrono::{DateTime, Utc};
 use helium_crypto::{PublicKey, PublicKeyBinary};
 use serde::Serialize;
-use std::{fs, path};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 use xorf_generator::{edge_hash, public_key_hash, Filter};
 
 pub const SERIAL_SIZE: usize = 32;
-/// a copy of the last saved filter bin downloaded from github
-/// if present will be used to initialise the denylist upon verifier startup
-// TODO: look at using the tempfile crate to handle this
-const FILTER_BIN_PATH: &str = "./tmp/last_saved_filter.bin";
 
-#[derive(Serialize)]
+const TAG_GAUGE: &str = concat!("oracles_", env!("CARGO_PKG_NAME"), "_tag");
+const AGE_GAUGE: &str = concat!("oracles_", env!("CARGO_PKG_NAME"), "_age_seconds");
+const FILTER_ACTIVE_GAUGE: &str = concat!("oracles_", env!("CARGO_PKG_NAME"), "_filter_active");
+
+/// Cloning a denylist is cheap, the source and filter are shared
+#[derive(Clone, Serialize)]
 pub struct DenyList {
     pub tag_name: u64,
     #[serde(skip_serializing)]
-    pub client: DenyListClient,
+    pub source: Option<Arc<dyn DenyListSource>>,
     #[serde(skip_serializing)]
-    pub filter: Option<Filter>,
+    pub filter: Option<Arc<Filter>>,
     pub sign_keys: Vec<PublicKey>,
+    /// a copy of the last saved filter bin downloaded from the source
+    /// if present will be used to initialise the denylist upon verifier startup
+    #[serde(skip_serializing)]
+    pub cache_path: PathBuf,
+    #[serde(skip_serializing)]
+    pub policy: FailurePolicy,
+    /// when the filter was last confirmed to be the latest available from
+    /// the source, or when the cached filter was saved
+    pub last_updated: Option<DateTime<Utc>>,
 }
 
 impl TryFrom<Vec<PublicKeyBinary>> for DenyList {
@@ -26,13 +46,7 @@ impl TryFrom<Vec<PublicKeyBinary>> for DenyList {
         let keys: Vec<u64> = v.iter().map(public_key_hash).collect();
         let filter = Filter::new(0, xorf_generator::xorf::Xor32::from(&keys))
             .map_err(|_| Error::invalid_filter("filter"))?;
-        let client = DenyListClient::new()?;
-        Ok(Self {
-            tag_name: 0,
-            client,
-            filter: Some(filter),
-            sign_keys: vec![],
-        })
+        Ok(Self::with_filter(filter))
     }
 }
 
@@ -42,101 +56,153 @@ impl TryFrom<Vec<(PublicKeyBinary, PublicKeyBinary)>> for DenyList {
         let keys: Vec<u64> = v.iter().map(|e| edge_hash(&e.0, &e.1)).collect();
         let filter = Filter::new(0, xorf_generator::xorf::Xor32::from(&keys))
             .map_err(|_| Error::invalid_filter("filter"))?;
-        let client = DenyListClient::new()?;
-        Ok(Self {
-            tag_name: 0,
-            client,
-            filter: Some(filter),
-            sign_keys: vec![],
-        })
+        Ok(Self::with_filter(filter))
     }
 }
 
 impl DenyList {
+    fn with_filter(filter: Filter) -> Self {
+        Self {
+            tag_name: 0,
+            source: None,
+            filter: Some(Arc::new(filter)),
+            sign_keys: vec![],
+            cache_path: PathBuf::from(crate::settings::DEFAULT_FILTER_BIN_PATH),
+            policy: FailurePolicy::default(),
+            last_updated: Some(Utc::now()),
+        }
+    }
+
     pub fn new(settings: &Settings) -> Result<Self> {
         tracing::debug!("initializing new denylist");
         // if exists default to the local saved filter bin, otherwise default to
         // empty filter a local filter should always be present after the
         // verifier has been run at least once in the current dir and has
-        // previously successfully downloaded a filter from github
+        // previously successfully downloaded a filter from the source
         let sign_keys = settings.sign_keys()?;
-        let filter = fs::read(FILTER_BIN_PATH)
+        let cache_path = settings.cache_path.clone();
+        let (filter, last_updated) = fs::read(&cache_path)
             .map_err(Error::from)
             .and_then(|bytes| filter_from_bin(&bytes, &sign_keys))
-            .map(Some)
+            .map(|filter| {
+                let filter = Arc::new(filter);
+                let saved_at = fs::metadata(&cache_path)
+                    .and_then(|metadata| metadata.modified())
+                    .map(DateTime::<Utc>::from)
+                    .ok();
+                (Some(filter), saved_at)
+            })
             .unwrap_or_else(|_| {
                 tracing::warn!(
                     "failed to initialise with a denylist filter, filter is currently empty"
                 );
-                None
+                (None, None)
             });
 
-        let client = DenyListClient::new()?;
-        Ok(Self {
+        let deny_list = Self {
             // default tag to 0, proper tag name will be set on first call to
             // update_to_latest
             tag_name: 0,
-            client,
+            source: Some(source::from_settings(&settings.source())?.into()),
             filter,
             sign_keys,
-        })
-    }
-
-    pub async fn update_to_latest(&mut self, metadata_url: &String) -> Result {
-        tracing::info!("checking for updated denylist, url: {metadata_url} ");
-
-        let metadata = self.client.get_metadata(metadata_url).await?;
-        let new_tag_name = metadata.tag_name.parse::<u64>()?;
-        tracing::info!(
-            "local denylist tag: {:?}, remote denylist tag: {:?}",
-            self.tag_name,
-            new_tag_name
-        );
-        if new_tag_name > self.tag_name {
-            tracing::info!(
-                "remote tag is newer, updating denylist to {:?}",
-                new_tag_name
-            );
-            // get the asset
-            // filter out any assets which do not have a name == "filter.bin"
-            let assets: Vec<Asset> = metadata
-                .assets
-                .into_iter()
-                .filter(|a| a.name == "filter.bin")
-                .collect();
-            // we should be left with a single asset
-            // at least this is the assumption the erlang implementation followed
-            if let Some(asset) = assets.first() {
-                tracing::debug!("found asset for tag");
-                let asset_url = &asset.browser_download_url;
-                let bin = self.client.get_bin(asset_url).await?;
-                if let Ok(filter) = filter_from_bin(&bin, &self.sign_keys) {
-                    self.filter = Some(filter);
-                    self.tag_name = new_tag_name;
-                    save_local_filter_bin(&bin, FILTER_BIN_PATH)?;
-                }
-            }
+            cache_path,
+            policy: settings.policy,
+            last_updated,
+        };
+        deny_list.record_metrics();
+        Ok(deny_list)
+    }
+
+    /// A copy of the denylist updated to the latest filter from its source.
+    /// A shared denylist then only needs to be locked to swap the copy in,
+    /// not for the whole fetch
+    pub async fn latest(&self) -> Result<Self> {
+        let mut latest = self.clone();
+        latest.update_to_latest().await?;
+        Ok(latest)
+    }
+
+    pub async fn update_to_latest(&mut self) -> Result {
+        let result = self.fetch_latest().await;
+        self.record_metrics();
+        result
+    }
+
+    async fn fetch_latest(&mut self) -> Result {
+        let Some(source) = &self.source else {
+            return Ok(());
+        };
+        tracing::info!(source = source.name(), "checking for updated denylist");
+        if let Some(FilterBin { tag, bin }) = source.fetch_newer(self.tag_name).await? {
+            tracing::info!("remote tag is newer, updating denylist to {:?}", tag);
+            let filter = filter_from_bin(&bin, &self.sign_keys)?;
+            self.filter = Some(Arc::new(filter));
+            self.tag_name = tag;
+            save_local_filter_bin(&bin, &self.cache_path)?;
         }
+        self.last_updated = Some(Utc::now());
         Ok(())
     }
 
+    /// time since the filter was last confirmed to be the latest available
+    pub fn age(&self) -> Option<Duration> {
+        self.last_updated
+            .map(|last_updated| (Utc::now() - last_updated).to_std().unwrap_or_default())
+    }
+
     pub fn contains_key(&self, key: &PublicKeyBinary) -> bool {
-        if let Some(filter) = &self.filter {
-            filter.contains(key)
-        } else {
-            tracing::warn!("empty denylist filter, rejecting key");
-            true
+        match self.active_filter() {
+            Some(filter) => filter.contains(key),
+            None => self.deny_without_filter("key"),
         }
     }
 
     pub fn contains_edge(&self, beaconer: &PublicKeyBinary, witness: &PublicKeyBinary) -> bool {
-        if let Some(filter) = &self.filter {
-            filter.contains_edge(beaconer, witness)
-        } else {
-            tracing::warn!("empty denylist filter, rejecting edge");
-            true
+        match self.active_filter() {
+            Some(filter) => filter.contains_edge(beaconer, witness),
+            None => self.deny_without_filter("edge"),
+        }
+    }
+
+    /// the filter to check keys and edges against, none if there is no
+    /// filter or the filter is too old for the last known good policy
+    fn active_filter(&self) -> Option<&Filter> {
+        let filter = self.filter.as_deref()?;
+        match self.policy {
+            FailurePolicy::LastKnownGood { max_age }
+                if self.age().map_or(true, |age| age > max_age) =>
+            {
+                None
+            }
+            _ => Some(filter),
         }
     }
+
+    fn deny_without_filter(&self, kind: &str) -> bool {
+        match self.policy {
+            FailurePolicy::FailClosed => {
+                tracing::warn!("empty denylist filter, rejecting {kind}");
+                true
+            }
+            FailurePolicy::FailOpen | FailurePolicy::LastKnownGood { .. } => {
+                tracing::warn!("empty or stale denylist filter, allowing {kind}");
+                false
+            }
+        }
+    }
+
+    fn record_metrics(&self) {
+        metrics::gauge!(TAG_GAUGE).set(self.tag_name as f64);
+        if let Some(age) = self.age() {
+            metrics::gauge!(AGE_GAUGE).set(age.as_secs() as f64);
+        }
+        metrics::gauge!(FILTER_ACTIVE_GAUGE).set(if self.active_filter().is_some() {
+            1.0
+        } else {
+            0.0
+        });
+    }
 }
 
 /// deconstruct bytes into the filter component parts
@@ -159,10 +225,57 @@ pub fn filter_from_bin(bin: &[u8], sign_keys: &[PublicKey]) -> Result<Filter> {
 
 /// save a copy of the xor file locally
 // the local copy will be used should during init github be unreachable
-pub fn save_local_filter_bin(bin: &Vec<u8>, path: &str) -> Result {
-    if let Some(parent) = path::PathBuf::from(path).parent() {
+pub fn save_local_filter_bin(bin: &[u8], path: impl AsRef<Path>) -> Result {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
         fs::write(path, bin)?;
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const DENIED: &str = "112NqN2WWMwtK29PMzRby62fDydBJfsCLkCAf392stdok48ovNT6";
+    const ALLOWED: &str = "11sctWiP9r5wDJVuDe1Th4XSL2vaawaLLSQF8f8iokAoMAJHxqp";
+
+    fn deny_list(policy: FailurePolicy) -> DenyList {
+        let mut deny_list: DenyList = vec![PublicKeyBinary::from_str(DENIED).unwrap()]
+            .try_into()
+            .unwrap();
+        deny_list.policy = policy;
+        deny_list
+    }
+
+    #[test]
+    fn missing_filter_policy() {
+        let allowed = PublicKeyBinary::from_str(ALLOWED).unwrap();
+
+        let mut fail_closed = deny_list(FailurePolicy::FailClosed);
+        assert!(!fail_closed.contains_key(&allowed));
+        fail_closed.filter = None;
+        assert!(fail_closed.contains_key(&allowed));
+
+        let mut fail_open = deny_list(FailurePolicy::FailOpen);
+        fail_open.filter = None;
+        assert!(!fail_open.contains_key(&allowed));
+    }
+
+    #[test]
+    fn last_known_good_max_age() {
+        let denied = PublicKeyBinary::from_str(DENIED).unwrap();
+        let mut deny_list = deny_list(FailurePolicy::LastKnownGood {
+            max_age: Duration::from_secs(3600),
+        });
+        assert!(deny_list.contains_key(&denied));
+
+        deny_list.last_updated = Some(Utc::now() - chrono::Duration::hours(2));
+        assert!(!deny_list.contains_key(&denied));
+
+        deny_list.last_updated = None;
+        assert!(!deny_list.contains_key(&denied));
+    }
+}
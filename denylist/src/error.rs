@@ -7,6 +7,8 @@ pub enum Error {
     Request(#[from] reqwest::Error),
     #[error("filter error")]
     InvalidFilter(String),
+    #[error("invalid source list: {0}")]
+    InvalidSource(String),
     #[error("unexpected value")]
     Value(serde_json::Value),
     #[error("invalid decimals in {0}, only 8 allowed")]
@@ -32,6 +34,10 @@ impl Error {
         Self::InvalidFilter(msg.to_string())
     }
 
+    pub fn invalid_source<E: ToString>(msg: E) -> Self {
+        Self::InvalidSource(msg.to_string())
+    }
+
     pub fn value(value: serde_json::Value) -> Self {
         Self::Value(value)
     }
@@ -0,0 +1,128 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use denylist::{
+    builder::{build_signed_filter_bin, next_serial, SourceList},
+    denylist::{filter_from_bin, save_local_filter_bin},
+};
+use helium_crypto::{Keypair, PublicKey};
+use std::{fs, path::PathBuf};
+use xorf_generator::Filter;
+
+#[derive(Debug, clap::Parser)]
+#[clap(version = env!("CARGO_PKG_VERSION"))]
+#[clap(about = "Helium Denylist Authoring Tool")]
+pub struct Cli {
+    #[clap(subcommand)]
+    cmd: Cmd,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum Cmd {
+    /// Build and sign a filter bin from a source list
+    Build(Build),
+    /// Show the changes between two source lists
+    Diff(Diff),
+    /// Verify a filter bin and show its serial
+    Inspect(Inspect),
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Build {
+    /// Source list of denied keys and edges
+    #[clap(long)]
+    source: PathBuf,
+    /// File from which to load the signing keypair
+    #[clap(long)]
+    keypair: PathBuf,
+    /// Serial of the new filter. Defaults to one more than the serial of
+    /// the `--previous` filter bin
+    #[clap(long)]
+    serial: Option<u32>,
+    /// Previously published filter bin, used to bump the serial
+    #[clap(long)]
+    previous: Option<PathBuf>,
+    /// Output path for the filter bin
+    #[clap(long, default_value = "filter.bin")]
+    output: PathBuf,
+    /// Also write the filter bin to the given local filter cache, as used by
+    /// the verifiers on startup, eg ./tmp/last_saved_filter.bin
+    #[clap(long)]
+    write_cache: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Diff {
+    /// The previous source list
+    previous: PathBuf,
+    /// The current source list
+    current: PathBuf,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Inspect {
+    /// The filter bin to inspect
+    filter: PathBuf,
+    /// b58 encoded keys allowed to sign the filter
+    #[clap(long = "sign-key", required = true)]
+    sign_keys: Vec<PublicKey>,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    match cli.cmd {
+        Cmd::Build(cmd) => cmd.run(),
+        Cmd::Diff(cmd) => cmd.run(),
+        Cmd::Inspect(cmd) => cmd.run(),
+    }
+}
+
+impl Build {
+    fn run(self) -> Result<()> {
+        let source = SourceList::from_file(&self.source)
+            .with_context(|| format!("reading source list {}", self.source.display()))?;
+        let keypair = fs::read(&self.keypair)
+            .map_err(anyhow::Error::from)
+            .and_then(|data| Ok(Keypair::try_from(&data[..])?))
+            .with_context(|| format!("reading keypair {}", self.keypair.display()))?;
+        let serial = match (self.serial, &self.previous) {
+            (Some(serial), _) => serial,
+            (None, Some(previous)) => {
+                let previous = Filter::from_bytes(&fs::read(previous)?)
+                    .map_err(|_| anyhow::anyhow!("invalid previous filter bin"))?;
+                next_serial(Some(&previous))
+            }
+            (None, None) => next_serial(None),
+        };
+
+        let bin = build_signed_filter_bin(serial, &source, &keypair)?;
+        fs::write(&self.output, &bin)?;
+        if let Some(cache_path) = &self.write_cache {
+            save_local_filter_bin(&bin, cache_path)?;
+        }
+        println!(
+            "wrote filter serial {serial} with {} keys and {} edges to {}",
+            source.keys.len(),
+            source.edges.len(),
+            self.output.display()
+        );
+        Ok(())
+    }
+}
+
+impl Diff {
+    fn run(self) -> Result<()> {
+        let previous = SourceList::from_file(&self.previous)?;
+        let current = SourceList::from_file(&self.current)?;
+        let diff = current.diff(&previous);
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+        Ok(())
+    }
+}
+
+impl Inspect {
+    fn run(self) -> Result<()> {
+        let filter = filter_from_bin(&fs::read(&self.filter)?, &self.sign_keys)?;
+        println!("valid filter, serial: {}", filter.serial);
+        Ok(())
+    }
+}
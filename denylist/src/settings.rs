@@ -3,7 +3,14 @@ use config::{Config, Environment, File};
 use helium_crypto::PublicKey;
 use humantime_serde::re::humantime;
 use serde::Deserialize;
-use std::{path::Path, str::FromStr, time::Duration};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+/// Default location of the local copy of the last fetched filter bin
+pub const DEFAULT_FILTER_BIN_PATH: &str = "./tmp/last_saved_filter.bin";
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Settings {
@@ -11,9 +18,22 @@ pub struct Settings {
     /// "denylist=debug"
     #[serde(default = "default_log")]
     pub log: String,
-    /// Listen address for http requests for entropy. Default "0.0.0.0:8080"
+    /// GitHub releases api url of the denylist. Used when no `source` is
+    /// configured
     #[serde(default = "default_denylist_url")]
     pub denylist_url: String,
+    /// Source of the denylist filter. Defaults to the GitHub releases at
+    /// `denylist_url`
+    #[serde(default)]
+    pub source: Option<SourceSettings>,
+    /// Local copy of the last fetched filter bin, used to initialise the
+    /// denylist on startup. Default "./tmp/last_saved_filter.bin"
+    #[serde(default = "default_cache_path")]
+    pub cache_path: PathBuf,
+    /// Behaviour when no filter, or only a stale one, is available.
+    /// Default fail closed
+    #[serde(default)]
+    pub policy: FailurePolicy,
     /// Cadence at which we poll for an updated denylist (Default: 6hours)
     #[serde(with = "humantime_serde", default = "default_trigger_interval")]
     pub trigger_interval: Duration,
@@ -23,6 +43,34 @@ pub struct Settings {
     pub sign_keys: Vec<String>,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SourceSettings {
+    /// GitHub releases api url, the latest release must have a `filter.bin`
+    /// asset and a numeric tag
+    Github { url: String },
+    /// Url serving a filter bin directly
+    Http { url: String },
+    /// Directory containing a `filter.bin`
+    Local { path: PathBuf },
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum FailurePolicy {
+    /// Deny every key and edge when no filter is available
+    #[default]
+    FailClosed,
+    /// Allow every key and edge when no filter is available
+    FailOpen,
+    /// Use the last fetched filter until it is older than `max_age`, after
+    /// which allow every key and edge
+    LastKnownGood {
+        #[serde(with = "humantime_serde")]
+        max_age: Duration,
+    },
+}
+
 fn default_log() -> String {
     "denylist=debug".to_string()
 }
@@ -31,6 +79,10 @@ fn default_denylist_url() -> String {
     "https://api.github.com/repos/helium/denylist/releases/latest".to_string()
 }
 
+fn default_cache_path() -> PathBuf {
+    PathBuf::from(DEFAULT_FILTER_BIN_PATH)
+}
+
 fn default_trigger_interval() -> Duration {
     humantime::parse_duration("6 hours").unwrap()
 }
@@ -59,6 +111,14 @@ impl Settings {
             .map_err(Error::from)
     }
 
+    pub fn source(&self) -> SourceSettings {
+        self.source
+            .clone()
+            .unwrap_or_else(|| SourceSettings::Github {
+                url: self.denylist_url.clone(),
+            })
+    }
+
     pub fn sign_keys(&self) -> std::result::Result<Vec<PublicKey>, helium_crypto::Error> {
         self.sign_keys
             .iter()
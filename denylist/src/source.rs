@@ -0,0 +1,151 @@
+//! Sources from which signed denylist filter bins are fetched.
+//!
+//! Each source reports a tag alongside the filter bin. The GitHub source
+//! uses the release tag, the mirror and local directory sources use the
+//! serial of the filter itself.
+
+use crate::{
+    client::DenyListClient, models::metadata::Asset, settings::SourceSettings, Error, Result,
+};
+use std::path::PathBuf;
+use xorf_generator::Filter;
+
+/// The name of the filter bin asset, both as a release asset and within a
+/// local directory
+pub const FILTER_BIN_NAME: &str = "filter.bin";
+
+pub struct FilterBin {
+    pub tag: u64,
+    pub bin: Vec<u8>,
+}
+
+#[async_trait::async_trait]
+pub trait DenyListSource: Send + Sync {
+    /// Name of the source, used in logs
+    fn name(&self) -> &str;
+
+    /// Fetch the latest filter bin, returning `None` if the latest tag is not
+    /// newer than `current_tag`
+    async fn fetch_newer(&self, current_tag: u64) -> Result<Option<FilterBin>>;
+}
+
+pub fn from_settings(settings: &SourceSettings) -> Result<Box<dyn DenyListSource>> {
+    Ok(match settings {
+        SourceSettings::Github { url } => Box::new(GithubSource::new(url.clone())?),
+        SourceSettings::Http { url } => Box::new(HttpSource::new(url.clone())?),
+        SourceSettings::Local { path } => Box::new(LocalDirSource::new(path.clone())),
+    })
+}
+
+/// Latest release of a GitHub repository with a `filter.bin` release asset,
+/// the release tag must be numeric
+pub struct GithubSource {
+    url: String,
+    client: DenyListClient,
+}
+
+impl GithubSource {
+    pub fn new(url: String) -> Result<Self> {
+        Ok(Self {
+            url,
+            client: DenyListClient::new()?,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl DenyListSource for GithubSource {
+    fn name(&self) -> &str {
+        "github"
+    }
+
+    async fn fetch_newer(&self, current_tag: u64) -> Result<Option<FilterBin>> {
+        let metadata = self.client.get_metadata(&self.url).await?;
+        let tag = metadata.tag_name.parse::<u64>()?;
+        tracing::info!(
+            "local denylist tag: {:?}, remote denylist tag: {:?}",
+            current_tag,
+            tag
+        );
+        if tag <= current_tag {
+            return Ok(None);
+        }
+        // filter out any assets which do not have a name == "filter.bin"
+        // we should be left with a single asset
+        // at least this is the assumption the erlang implementation followed
+        let assets: Vec<Asset> = metadata
+            .assets
+            .into_iter()
+            .filter(|a| a.name == FILTER_BIN_NAME)
+            .collect();
+        match assets.first() {
+            Some(asset) => {
+                tracing::debug!("found asset for tag");
+                let bin = self.client.get_bin(&asset.browser_download_url).await?;
+                Ok(Some(FilterBin { tag, bin }))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// A filter bin served directly over http(s), eg a mirror of the GitHub
+/// release asset
+pub struct HttpSource {
+    url: String,
+    client: DenyListClient,
+}
+
+impl HttpSource {
+    pub fn new(url: String) -> Result<Self> {
+        Ok(Self {
+            url,
+            client: DenyListClient::new()?,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl DenyListSource for HttpSource {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    async fn fetch_newer(&self, current_tag: u64) -> Result<Option<FilterBin>> {
+        let bin = self.client.get_bin(&self.url).await?;
+        newer_by_serial(bin, current_tag)
+    }
+}
+
+/// A `filter.bin` in a local directory, eg as written by the denylist
+/// authoring tool
+pub struct LocalDirSource {
+    path: PathBuf,
+}
+
+impl LocalDirSource {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            path: dir.join(FILTER_BIN_NAME),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl DenyListSource for LocalDirSource {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    async fn fetch_newer(&self, current_tag: u64) -> Result<Option<FilterBin>> {
+        let bin = std::fs::read(&self.path)?;
+        newer_by_serial(bin, current_tag)
+    }
+}
+
+fn newer_by_serial(bin: Vec<u8>, current_tag: u64) -> Result<Option<FilterBin>> {
+    let tag = Filter::from_bytes(&bin)
+        .map_err(|_| Error::invalid_filter("filter"))?
+        .serial as u64;
+    Ok((tag > current_tag).then_some(FilterBin { tag, bin }))
+}
@@ -1,5 +1,6 @@
 use crate::{
     heartbeat::{cli::ValidatedHeartbeat, CbrsHeartbeat},
+    hex_boost::BoostedHexEvent,
     iot_beacon_report::IotBeaconIngestReport,
     iot_valid_poc::IotPoc,
     iot_witness_report::IotWitnessIngestReport,
@@ -178,7 +179,9 @@ impl Get {
 /// Locate specific records in a time range
 #[derive(Debug, clap::Args)]
 pub struct Locate {
-    gateway: PublicKey,
+    /// Gateway public key. For boosted hex events the boosted hex pubkey or
+    /// the h3 location in hex
+    key: String,
 
     #[clap(flatten)]
     filter: FileFilter,
@@ -189,13 +192,13 @@ impl Locate {
         let store = FileStore::from_settings(settings).await?;
         let file_infos = self.filter.list(&store);
         let prefix = self.filter.prefix.clone();
-        let gateway = &self.gateway.clone();
+        let key = &self.key.clone();
         let mut events = store
             .source(file_infos)
-            .map_ok(|buf| (buf, gateway))
-            .try_filter_map(|(buf, gateway)| {
+            .map_ok(|buf| (buf, key))
+            .try_filter_map(|(buf, key)| {
                 let prefix = prefix.clone();
-                async move { locate(&prefix, gateway, &buf) }
+                async move { locate(&prefix, key, &buf) }
             })
             .boxed();
         let mut ser = serde_json::Serializer::new(io::stdout());
@@ -208,9 +211,15 @@ impl Locate {
     }
 }
 
-fn locate(prefix: &str, gateway: &PublicKey, buf: &[u8]) -> Result<Option<serde_json::Value>> {
-    let pub_key = gateway.to_vec();
-    match FileType::from_str(prefix)? {
+fn locate(prefix: &str, key: &str, buf: &[u8]) -> Result<Option<serde_json::Value>> {
+    let file_type = FileType::from_str(prefix)?;
+    if file_type == FileType::BoostedHexEvent {
+        let event = BoostedHexEvent::decode(buf)?;
+        let matches = event.boosted_hex_pubkey == key || format!("{:x}", event.location) == key;
+        return matches.then(|| event.to_value()).transpose();
+    }
+    let pub_key = PublicKey::from_str(key)?.to_vec();
+    match file_type {
         FileType::CbrsHeartbeat => {
             CbrsHeartbeat::decode(buf).and_then(|event| event.to_value_if(pub_key))
         }
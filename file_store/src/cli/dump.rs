@@ -3,6 +3,7 @@ use crate::{
     coverage::CoverageObject,
     file_source,
     heartbeat::{CbrsHeartbeat, CbrsHeartbeatIngestReport},
+    hex_boost::BoostedHexEvent,
     iot_packet::IotValidPacket,
     mobile_radio_invalidated_threshold::VerifiedInvalidatedRadioThresholdIngestReport,
     mobile_radio_threshold::VerifiedRadioThresholdIngestReport,
@@ -440,6 +441,9 @@ impl Cmd {
                         "carrier_key": req.carrier_key,
                     }))?;
                 }
+                FileType::BoostedHexEvent => {
+                    print_json(&BoostedHexEvent::decode(msg)?)?;
+                }
                 missing_filetype => println!("No dump for {missing_filetype}"),
             }
         }
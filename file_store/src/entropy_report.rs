@@ -41,3 +41,313 @@ impl TryFrom<EntropyReportV1> for EntropyReport {
         })
     }
 }
+
+/// Entropy version in which the entropy data is a [`ChainedEntropy`]
+/// committing to the previously published entropy and signed by the oracle
+pub const ENTROPY_VERSION_CHAINED: u32 = 1;
+
+const DIGEST_SIZE: usize = 32;
+const SOURCE_OFFSET: usize = DIGEST_SIZE;
+const SLOT_OFFSET: usize = SOURCE_OFFSET + 1;
+const TIMESTAMP_OFFSET: usize = SLOT_OFFSET + 8;
+const BLOCKHASH_OFFSET: usize = TIMESTAMP_OFFSET + 8;
+const SIGNATURE_OFFSET: usize = BLOCKHASH_OFFSET + DIGEST_SIZE;
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum ChainedEntropyError {
+    #[error("invalid chained entropy length: {0}")]
+    InvalidLength(usize),
+    #[error("invalid blockhash length: {0}")]
+    InvalidBlockhash(usize),
+    #[error("chained entropy timestamp {0} does not match report timestamp {1}")]
+    TimestampMismatch(i64, i64),
+    #[error("chained entropy does not commit to the previous entropy")]
+    BrokenChain,
+    #[error("chained entropy signature is not from a known oracle key")]
+    InvalidSignature,
+    #[error("invalid chained entropy source: {0}")]
+    InvalidSource(u8),
+}
+
+/// The kind of source a [`ChainedEntropy`] was derived from, which determines
+/// what its `slot` counts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum EntropySourceKind {
+    /// `slot` is a solana slot and `blockhash` its blockhash
+    Solana = 0,
+    /// `slot` is a drand round and `blockhash` its randomness
+    Drand = 1,
+    /// `slot` is a round of a local, deterministic test source
+    Local = 2,
+}
+
+impl TryFrom<u8> for EntropySourceKind {
+    type Error = ChainedEntropyError;
+
+    fn try_from(v: u8) -> std::result::Result<Self, Self::Error> {
+        match v {
+            0 => Ok(Self::Solana),
+            1 => Ok(Self::Drand),
+            2 => Ok(Self::Local),
+            other => Err(ChainedEntropyError::InvalidSource(other)),
+        }
+    }
+}
+
+/// Version 1 entropy data.
+///
+/// Each entropy commits to the previously published entropy through `prev`,
+/// the blake3 digest of the previous entropy's data, forming a hash chain.
+/// The oracle signs `(prev, source, slot, timestamp, blockhash)` with its ed25519 key.
+/// The signature authenticates the origin of the entropy and its link in the
+/// chain; it is a plain signature and not a VRF, so it does not by itself stop
+/// an oracle from choosing among candidate slots and blockhashes.
+///
+/// The serialized layout is
+/// `prev (32) | source (1) | slot (8, le) | timestamp (8, le) | blockhash (32) | signature`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChainedEntropy {
+    pub prev: [u8; DIGEST_SIZE],
+    pub source: EntropySourceKind,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub blockhash: [u8; DIGEST_SIZE],
+    pub signature: Vec<u8>,
+}
+
+impl ChainedEntropy {
+    /// The prev value of the first entropy published by an oracle,
+    /// or the first entropy after an oracle restart
+    pub const GENESIS: [u8; DIGEST_SIZE] = [0; DIGEST_SIZE];
+
+    pub fn new(
+        keypair: &helium_crypto::Keypair,
+        prev: [u8; DIGEST_SIZE],
+        source: EntropySourceKind,
+        slot: u64,
+        timestamp: i64,
+        blockhash: &[u8],
+    ) -> Result<Self> {
+        use helium_crypto::Sign;
+        let blockhash: [u8; DIGEST_SIZE] = blockhash
+            .try_into()
+            .map_err(|_| ChainedEntropyError::InvalidBlockhash(blockhash.len()))
+            .map_err(|err| Error::ExternalError(Box::new(err)))?;
+        let mut entropy = Self {
+            prev,
+            source,
+            slot,
+            timestamp,
+            blockhash,
+            signature: vec![],
+        };
+        entropy.signature = keypair
+            .sign(&entropy.signing_message())
+            .map_err(|err| Error::Crypto(Box::new(err)))?;
+        Ok(entropy)
+    }
+
+    /// The digest a following entropy commits to. This is the same digest
+    /// the verifiers use as the id of an entropy
+    pub fn digest(data: &[u8]) -> [u8; DIGEST_SIZE] {
+        *blake3::hash(data).as_bytes()
+    }
+
+    fn signing_message(&self) -> Vec<u8> {
+        let mut msg = Vec::with_capacity(SIGNATURE_OFFSET);
+        msg.extend_from_slice(&self.prev);
+        msg.push(self.source as u8);
+        msg.extend_from_slice(&self.slot.to_le_bytes());
+        msg.extend_from_slice(&self.timestamp.to_le_bytes());
+        msg.extend_from_slice(&self.blockhash);
+        msg
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.signing_message();
+        bytes.extend_from_slice(&self.signature);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> std::result::Result<Self, ChainedEntropyError> {
+        if bytes.len() <= SIGNATURE_OFFSET {
+            return Err(ChainedEntropyError::InvalidLength(bytes.len()));
+        }
+        let le_u64 = |offset: usize| {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(&bytes[offset..offset + 8]);
+            buf
+        };
+        let mut prev = [0u8; DIGEST_SIZE];
+        prev.copy_from_slice(&bytes[..SOURCE_OFFSET]);
+        let mut blockhash = [0u8; DIGEST_SIZE];
+        blockhash.copy_from_slice(&bytes[BLOCKHASH_OFFSET..SIGNATURE_OFFSET]);
+        Ok(Self {
+            prev,
+            source: EntropySourceKind::try_from(bytes[SOURCE_OFFSET])?,
+            slot: u64::from_le_bytes(le_u64(SLOT_OFFSET)),
+            timestamp: i64::from_le_bytes(le_u64(TIMESTAMP_OFFSET)),
+            blockhash,
+            signature: bytes[SIGNATURE_OFFSET..].to_vec(),
+        })
+    }
+
+    /// Verify the entropy was signed by one of the given oracle keys and,
+    /// if the previous entropy's data is known, that it extends the chain
+    pub fn verify(
+        &self,
+        oracle_keys: &[helium_crypto::PublicKey],
+        prev_data: Option<&[u8]>,
+    ) -> std::result::Result<(), ChainedEntropyError> {
+        use helium_crypto::Verify;
+        if let Some(prev_data) = prev_data {
+            if Self::digest(prev_data) != self.prev {
+                return Err(ChainedEntropyError::BrokenChain);
+            }
+        }
+        let msg = self.signing_message();
+        if oracle_keys
+            .iter()
+            .any(|key| key.verify(&msg, &self.signature).is_ok())
+        {
+            Ok(())
+        } else {
+            Err(ChainedEntropyError::InvalidSignature)
+        }
+    }
+}
+
+impl EntropyReport {
+    /// Verify a version 1 entropy report, see [`ChainedEntropy::verify`].
+    /// Reports of any other version carry nothing to verify
+    pub fn verify_chained(
+        &self,
+        oracle_keys: &[helium_crypto::PublicKey],
+        prev_data: Option<&[u8]>,
+    ) -> std::result::Result<(), ChainedEntropyError> {
+        if self.version != ENTROPY_VERSION_CHAINED {
+            return Ok(());
+        }
+        let chained = ChainedEntropy::from_bytes(&self.data)?;
+        if chained.timestamp != self.timestamp.timestamp() {
+            return Err(ChainedEntropyError::TimestampMismatch(
+                chained.timestamp,
+                self.timestamp.timestamp(),
+            ));
+        }
+        chained.verify(oracle_keys, prev_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use helium_crypto::{KeyTag, Keypair};
+    use rand::rngs::OsRng;
+
+    fn keypair() -> Keypair {
+        Keypair::generate(KeyTag::default(), &mut OsRng)
+    }
+
+    #[test]
+    fn chained_entropy_roundtrip_and_verify() {
+        let keypair = keypair();
+        let other = keypair();
+        let blockhash = [7u8; 32];
+
+        let first = ChainedEntropy::new(
+            &keypair,
+            ChainedEntropy::GENESIS,
+            EntropySourceKind::Solana,
+            100,
+            1_700_000_000,
+            &blockhash,
+        )
+        .expect("first entropy");
+        let first_data = first.to_bytes();
+        assert_eq!(first, ChainedEntropy::from_bytes(&first_data).unwrap());
+
+        let second = ChainedEntropy::new(
+            &keypair,
+            ChainedEntropy::digest(&first_data),
+            EntropySourceKind::Drand,
+            160,
+            1_700_000_060,
+            &blockhash,
+        )
+        .expect("second entropy");
+
+        let keys = [keypair.public_key().clone()];
+        assert_eq!(Ok(()), second.verify(&keys, Some(&first_data)));
+        assert_eq!(Ok(()), second.verify(&keys, None));
+        assert_eq!(
+            Err(ChainedEntropyError::BrokenChain),
+            second.verify(&keys, Some(&second.to_bytes()))
+        );
+        assert_eq!(
+            Err(ChainedEntropyError::InvalidSignature),
+            second.verify(&[other.public_key().clone()], Some(&first_data))
+        );
+
+        let mut tampered = second.clone();
+        tampered.slot += 1;
+        assert_eq!(
+            Err(ChainedEntropyError::InvalidSignature),
+            tampered.verify(&keys, None)
+        );
+
+        let mut tampered = second.clone();
+        tampered.source = EntropySourceKind::Solana;
+        assert_eq!(
+            Err(ChainedEntropyError::InvalidSignature),
+            tampered.verify(&keys, None)
+        );
+
+        let report = EntropyReport {
+            data: second.to_bytes(),
+            timestamp: Utc.timestamp_opt(1_700_000_060, 0).unwrap(),
+            version: ENTROPY_VERSION_CHAINED,
+        };
+        assert_eq!(Ok(()), report.verify_chained(&keys, Some(&first_data)));
+        let report = EntropyReport {
+            timestamp: Utc.timestamp_opt(1_700_000_000, 0).unwrap(),
+            ..report
+        };
+        assert_eq!(
+            Err(ChainedEntropyError::TimestampMismatch(
+                1_700_000_060,
+                1_700_000_000
+            )),
+            report.verify_chained(&keys, Some(&first_data))
+        );
+    }
+
+    #[test]
+    fn chained_entropy_rejects_short_data() {
+        assert_eq!(
+            Err(ChainedEntropyError::InvalidLength(32)),
+            ChainedEntropy::from_bytes(&[0u8; 32])
+        );
+    }
+
+    #[test]
+    fn chained_entropy_rejects_unknown_source() {
+        let mut data = ChainedEntropy::new(
+            &keypair(),
+            ChainedEntropy::GENESIS,
+            EntropySourceKind::Local,
+            1,
+            1_700_000_000,
+            &[7u8; 32],
+        )
+        .expect("entropy")
+        .to_bytes();
+        data[SOURCE_OFFSET] = 42;
+        assert_eq!(
+            Err(ChainedEntropyError::InvalidSource(42)),
+            ChainedEntropy::from_bytes(&data)
+        );
+    }
+}
@@ -81,6 +81,8 @@ pub enum DecodeError {
     FileStreamTryDecode(String),
     #[error("unsupported token type {0}")]
     UnsupportedTokenType(String, i32),
+    #[error("unsupported boosted hex event type, type: {0}, value: {1}")]
+    UnsupportedBoostedHexEventType(String, i32),
 }
 
 #[derive(Error, Debug)]
@@ -153,6 +155,10 @@ impl DecodeError {
         Error::Decode(Self::UnsupportedInvalidReason(msg1.to_string(), msg2))
     }
 
+    pub fn unsupported_boosted_hex_event_type<E: ToString>(msg1: E, msg2: i32) -> Error {
+        Error::Decode(Self::UnsupportedBoostedHexEventType(msg1.to_string(), msg2))
+    }
+
     pub fn invalid_timestamp(v: u64) -> Error {
         Error::Decode(Self::InvalidTimestamp(v))
     }
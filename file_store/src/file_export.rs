@@ -0,0 +1,56 @@
+use crate::FileStore;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+/// Writes whole files, such as reports, to a local directory and uploads them
+/// as is to a bucket, if one is configured, under their file name.
+///
+/// Unlike a [`crate::FileSink`] the files are neither framed nor compressed,
+/// so they can be read directly.
+#[derive(Debug, Clone)]
+pub struct FileExporter {
+    directory: PathBuf,
+    file_store: Option<FileStore>,
+}
+
+impl FileExporter {
+    pub fn new(directory: PathBuf, file_store: Option<FileStore>) -> Self {
+        Self {
+            directory,
+            file_store,
+        }
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Creates `file_name` in the directory and fills it with `write` on a
+    /// blocking thread, then uploads it. Returns the path of the file.
+    pub async fn export<F>(&self, file_name: &str, write: F) -> anyhow::Result<PathBuf>
+    where
+        F: FnOnce(&mut BufWriter<File>) -> anyhow::Result<()> + Send + 'static,
+    {
+        let path = self.directory.join(file_name);
+        {
+            let directory = self.directory.clone();
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+                std::fs::create_dir_all(&directory)?;
+                let mut writer = BufWriter::new(File::create(&path)?);
+                write(&mut writer)?;
+                writer.flush()?;
+                Ok(())
+            })
+            .await??;
+        }
+
+        if let Some(file_store) = &self.file_store {
+            file_store.put(&path).await?;
+        }
+        Ok(path)
+    }
+}
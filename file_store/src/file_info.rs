@@ -175,6 +175,8 @@ pub const VERIFIED_PROMOTION_REWARD: &str = "verified_promotion_reward";
 pub const SERVICE_PROVIDER_PROMOTION_FUND: &str = "service_provider_promotion_fund";
 pub const UNIQUE_CONNECTIONS_REPORT: &str = "unique_connections_report";
 pub const VERIFIED_UNIQUE_CONNECTIONS_REPORT: &str = "verified_unique_connections_report";
+pub const RADIO_REWARD_EXPLANATION: &str = "radio_reward_explanation";
+pub const BOOSTED_HEX_EVENT: &str = "boosted_hex_event";
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Copy, strum::EnumCount)]
 #[serde(rename_all = "snake_case")]
@@ -241,6 +243,8 @@ pub enum FileType {
     RadioUsageStatsReq,
     UniqueConnectionsReport,
     VerifiedUniqueConnectionsReport,
+    RadioRewardExplanation,
+    BoostedHexEvent,
 }
 
 impl fmt::Display for FileType {
@@ -328,6 +332,8 @@ impl FileType {
             Self::RadioUsageStatsReq => RADIO_USAGE_STATS_REQ,
             Self::UniqueConnectionsReport => UNIQUE_CONNECTIONS_REPORT,
             Self::VerifiedUniqueConnectionsReport => VERIFIED_UNIQUE_CONNECTIONS_REPORT,
+            Self::RadioRewardExplanation => RADIO_REWARD_EXPLANATION,
+            Self::BoostedHexEvent => BOOSTED_HEX_EVENT,
         }
     }
 }
@@ -411,6 +417,8 @@ impl FromStr for FileType {
             RADIO_USAGE_STATS_REQ => Self::RadioUsageStatsReq,
             UNIQUE_CONNECTIONS_REPORT => Self::UniqueConnectionsReport,
             VERIFIED_UNIQUE_CONNECTIONS_REPORT => Self::VerifiedUniqueConnectionsReport,
+            RADIO_REWARD_EXPLANATION => Self::RadioRewardExplanation,
+            BOOSTED_HEX_EVENT => Self::BoostedHexEvent,
             _ => return Err(Error::from(io::Error::from(io::ErrorKind::InvalidInput))),
         };
         Ok(result)
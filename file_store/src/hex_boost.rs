@@ -1,4 +1,10 @@
+use crate::{
+    error::DecodeError,
+    traits::{MsgDecode, TimestampDecode, TimestampEncode},
+    Error, Result,
+};
 use chrono::{DateTime, Utc};
+use serde::Serialize;
 
 #[derive(Debug, Clone)]
 pub struct BoostedHexActivation {
@@ -7,3 +13,181 @@ pub struct BoostedHexActivation {
     pub boosted_hex_pubkey: String,
     pub boost_config_pubkey: String,
 }
+
+/// A lifecycle transition of a boosted hex. Events are written to their own
+/// file_store prefix as [BoostedHexEventV1].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BoostedHexEvent {
+    /// When the transition took effect, or was first seen for creations and
+    /// updates
+    pub timestamp: DateTime<Utc>,
+    pub event_type: BoostedHexEventType,
+    pub location: u64,
+    pub boosted_hex_pubkey: String,
+    pub boost_config_pubkey: String,
+    pub version: u32,
+    pub start_ts: Option<DateTime<Utc>>,
+    pub end_ts: Option<DateTime<Utc>>,
+    pub period_length_secs: i64,
+    pub multipliers: Vec<u32>,
+    /// Multiplier in effect from `timestamp`, none once the boost expired
+    pub multiplier: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BoostedHexEventType {
+    Created,
+    /// The multipliers, period length or boost config changed
+    Updated,
+    Activated,
+    /// A period with the next multiplier started
+    PeriodStarted,
+    Expired,
+}
+
+/// Encoding of a [BoostedHexEvent] in file_store. Timestamps are unix
+/// seconds, with 0 for an unset start or end.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BoostedHexEventV1 {
+    #[prost(uint64, tag = "1")]
+    pub timestamp: u64,
+    #[prost(enumeration = "BoostedHexEventTypeV1", tag = "2")]
+    pub event_type: i32,
+    #[prost(uint64, tag = "3")]
+    pub location: u64,
+    #[prost(string, tag = "4")]
+    pub boosted_hex_pubkey: String,
+    #[prost(string, tag = "5")]
+    pub boost_config_pubkey: String,
+    #[prost(uint32, tag = "6")]
+    pub version: u32,
+    #[prost(uint64, tag = "7")]
+    pub start_ts: u64,
+    #[prost(uint64, tag = "8")]
+    pub end_ts: u64,
+    #[prost(uint64, tag = "9")]
+    pub period_length: u64,
+    #[prost(uint32, repeated, tag = "10")]
+    pub multipliers: Vec<u32>,
+    #[prost(uint32, optional, tag = "11")]
+    pub multiplier: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum BoostedHexEventTypeV1 {
+    Created = 0,
+    Updated = 1,
+    Activated = 2,
+    PeriodStarted = 3,
+    Expired = 4,
+}
+
+impl From<BoostedHexEventType> for BoostedHexEventTypeV1 {
+    fn from(event_type: BoostedHexEventType) -> Self {
+        match event_type {
+            BoostedHexEventType::Created => Self::Created,
+            BoostedHexEventType::Updated => Self::Updated,
+            BoostedHexEventType::Activated => Self::Activated,
+            BoostedHexEventType::PeriodStarted => Self::PeriodStarted,
+            BoostedHexEventType::Expired => Self::Expired,
+        }
+    }
+}
+
+impl From<BoostedHexEventTypeV1> for BoostedHexEventType {
+    fn from(event_type: BoostedHexEventTypeV1) -> Self {
+        match event_type {
+            BoostedHexEventTypeV1::Created => Self::Created,
+            BoostedHexEventTypeV1::Updated => Self::Updated,
+            BoostedHexEventTypeV1::Activated => Self::Activated,
+            BoostedHexEventTypeV1::PeriodStarted => Self::PeriodStarted,
+            BoostedHexEventTypeV1::Expired => Self::Expired,
+        }
+    }
+}
+
+impl MsgDecode for BoostedHexEvent {
+    type Msg = BoostedHexEventV1;
+}
+
+impl TryFrom<BoostedHexEventV1> for BoostedHexEvent {
+    type Error = Error;
+
+    fn try_from(v: BoostedHexEventV1) -> Result<Self> {
+        let event_type = BoostedHexEventTypeV1::try_from(v.event_type).map_err(|_| {
+            DecodeError::unsupported_boosted_hex_event_type("boosted_hex_event_v1", v.event_type)
+        })?;
+        let optional_ts = |ts: u64| (ts != 0).then(|| ts.to_timestamp()).transpose();
+        Ok(Self {
+            timestamp: v.timestamp.to_timestamp()?,
+            event_type: event_type.into(),
+            location: v.location,
+            boosted_hex_pubkey: v.boosted_hex_pubkey,
+            boost_config_pubkey: v.boost_config_pubkey,
+            version: v.version,
+            start_ts: optional_ts(v.start_ts)?,
+            end_ts: optional_ts(v.end_ts)?,
+            period_length_secs: i64::try_from(v.period_length).map_err(DecodeError::from)?,
+            multipliers: v.multipliers,
+            multiplier: v.multiplier,
+        })
+    }
+}
+
+impl From<BoostedHexEvent> for BoostedHexEventV1 {
+    fn from(v: BoostedHexEvent) -> Self {
+        Self {
+            timestamp: v.timestamp.encode_timestamp(),
+            event_type: BoostedHexEventTypeV1::from(v.event_type).into(),
+            location: v.location,
+            boosted_hex_pubkey: v.boosted_hex_pubkey,
+            boost_config_pubkey: v.boost_config_pubkey,
+            version: v.version,
+            start_ts: v.start_ts.map_or(0, |ts| ts.encode_timestamp()),
+            end_ts: v.end_ts.map_or(0, |ts| ts.encode_timestamp()),
+            period_length: v.period_length_secs as u64,
+            multipliers: v.multipliers,
+            multiplier: v.multiplier,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    #[test]
+    fn boosted_hex_event_roundtrip() {
+        let event = BoostedHexEvent {
+            timestamp: "2024-06-01T00:00:00Z".parse().unwrap(),
+            event_type: BoostedHexEventType::PeriodStarted,
+            location: 0x8a1fb466d2dffff,
+            boosted_hex_pubkey: "J9JiLTpjaShxL8eMvUs8txVw6TZ36E38SiJ89NxnMbLU".to_string(),
+            boost_config_pubkey: "BZM1QTud72B2cpTW7PhEnFmRX7ZWzvY7DpPpNJJuDrWG".to_string(),
+            version: 2,
+            start_ts: Some("2024-05-02T00:00:00Z".parse().unwrap()),
+            end_ts: Some("2024-07-01T00:00:00Z".parse().unwrap()),
+            period_length_secs: 30 * 24 * 60 * 60,
+            multipliers: vec![2, 10],
+            multiplier: Some(10),
+        };
+        let bytes = BoostedHexEventV1::from(event.clone()).encode_to_vec();
+        assert_eq!(event, BoostedHexEvent::decode(bytes.as_slice()).unwrap());
+
+        let not_started = BoostedHexEvent {
+            event_type: BoostedHexEventType::Created,
+            start_ts: None,
+            end_ts: None,
+            multiplier: Some(2),
+            ..event
+        };
+        let bytes = BoostedHexEventV1::from(not_started.clone()).encode_to_vec();
+        assert_eq!(
+            not_started,
+            BoostedHexEvent::decode(bytes.as_slice()).unwrap()
+        );
+    }
+}
@@ -2,6 +2,7 @@ pub mod cli;
 pub mod coverage;
 pub mod entropy_report;
 mod error;
+pub mod file_export;
 pub mod file_info;
 pub mod file_info_poller;
 pub mod file_sink;
@@ -34,6 +35,7 @@ pub mod wifi_heartbeat;
 pub use crate::file_store::FileStore;
 pub use cli::bucket::FileFilter;
 pub use error::{Error, Result};
+pub use file_export::FileExporter;
 pub use file_info::{FileInfo, FileType};
 pub use file_sink::{FileSink, FileSinkBuilder};
 pub use iot_valid_poc::SCALING_PRECISION;
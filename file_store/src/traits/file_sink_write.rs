@@ -278,6 +278,11 @@ impl_file_sink!(
     FileType::BoostedHexUpdate.to_str(),
     "boosted_hex_update"
 );
+impl_file_sink!(
+    crate::hex_boost::BoostedHexEventV1,
+    FileType::BoostedHexEvent.to_str(),
+    "boosted_hex_event"
+);
 impl_file_sink!(
     proto::EntropyReportV1,
     FileType::EntropyReport.to_str(),
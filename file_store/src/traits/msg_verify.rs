@@ -73,6 +73,7 @@ impl_msg_verify!(iot_config::AdminLoadRegionReqV1, signature);
 impl_msg_verify!(iot_config::AdminRemoveKeyReqV1, signature);
 impl_msg_verify!(iot_config::GatewayInfoReqV1, signature);
 impl_msg_verify!(iot_config::GatewayInfoStreamReqV1, signature);
+impl_msg_verify!(iot_config::GatewayInfoAtTimestampReqV1, signature);
 impl_msg_verify!(iot_config::RegionParamsReqV1, signature);
 impl_msg_verify!(iot_config::GatewayInfoResV1, signature);
 impl_msg_verify!(iot_config::GatewayInfoStreamResV1, signature);
@@ -95,6 +96,8 @@ impl_msg_verify!(mobile_config::GatewayInfoStreamReqV2, signature);
 impl_msg_verify!(mobile_config::GatewayInfoResV1, signature);
 impl_msg_verify!(mobile_config::GatewayInfoResV2, signature);
 impl_msg_verify!(mobile_config::GatewayInfoBatchReqV1, signature);
+impl_msg_verify!(mobile_config::GatewayInfoAtTimestampReqV1, signature);
+impl_msg_verify!(mobile_config::GatewayChangesSinceReqV1, signature);
 impl_msg_verify!(mobile_config::GatewayInfoStreamResV1, signature);
 impl_msg_verify!(mobile_config::GatewayInfoStreamResV2, signature);
 impl_msg_verify!(mobile_config::BoostedHexInfoStreamReqV1, signature);
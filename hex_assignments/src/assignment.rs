@@ -1,8 +1,7 @@
-use super::HexAssignment;
+use super::{rules::AssignmentRuleTable, HexAssignment};
 use anyhow::Result;
 use helium_proto::services::poc_mobile::OracleBoostingAssignment as ProtoAssignment;
 use rust_decimal::Decimal;
-use rust_decimal_macros::dec;
 use std::fmt;
 
 #[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
@@ -11,11 +10,16 @@ pub struct HexAssignments {
     pub landtype: Assignment,
     pub urbanized: Assignment,
     pub service_provider_override: Assignment,
+    /// Multiplier given by the configured assignment rule table when the hex
+    /// was assigned. Hexes without one use the HIP-103 table
+    #[sqlx(default)]
+    pub assignment_multiplier: Option<Decimal>,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug, sqlx::Type)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, sqlx::Type, serde::Serialize, serde::Deserialize)]
 #[sqlx(type_name = "oracle_assignment")]
 #[sqlx(rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
 pub enum Assignment {
     A,
     B,
@@ -88,45 +92,8 @@ impl HexAssignments {
     }
 
     pub fn boosting_multiplier(&self) -> Decimal {
-        let HexAssignments {
-            footfall,
-            landtype,
-            urbanized,
-            service_provider_override,
-        } = self;
-
-        use Assignment::*;
-        match (footfall, landtype, urbanized, service_provider_override) {
-            // service provider override hex
-            // Overrides other dataset assignments if set
-            (_, _, _, A) => dec!(1.00),
-            // yellow - POI ≥ 1 Urbanized
-            (A, A, A, _) => dec!(1.00),
-            (A, B, A, _) => dec!(1.00),
-            (A, C, A, _) => dec!(1.00),
-            // orange - POI ≥ 1 Not Urbanized
-            (A, A, B, _) => dec!(1.00),
-            (A, B, B, _) => dec!(1.00),
-            (A, C, B, _) => dec!(1.00),
-            // light green - Point of Interest Urbanized
-            (B, A, A, _) => dec!(0.70),
-            (B, B, A, _) => dec!(0.70),
-            (B, C, A, _) => dec!(0.70),
-            // dark green - Point of Interest Not Urbanized
-            (B, A, B, _) => dec!(0.50),
-            (B, B, B, _) => dec!(0.50),
-            (B, C, B, _) => dec!(0.50),
-            // light blue - No POI Urbanized
-            (C, A, A, _) => dec!(0.40),
-            (C, B, A, _) => dec!(0.30),
-            (C, C, A, _) => dec!(0.05),
-            // dark blue - No POI Not Urbanized
-            (C, A, B, _) => dec!(0.20),
-            (C, B, B, _) => dec!(0.15),
-            (C, C, B, _) => dec!(0.03),
-            // gray - Outside of USA
-            (_, _, C, _) => dec!(0.00),
-        }
+        self.assignment_multiplier
+            .unwrap_or_else(|| AssignmentRuleTable::hip_103().multiplier(self))
     }
 }
 
@@ -180,6 +147,7 @@ impl HexAssignmentsBuilder {
             urbanized: urbanized?,
             landtype: landtype?,
             service_provider_override: service_provider_override?,
+            assignment_multiplier: None,
         })
     }
 }
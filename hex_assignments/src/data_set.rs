@@ -0,0 +1,191 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use hextree::disktree::DiskTreeMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{rules, Assignment, HexAssignment};
+
+/// A data set in addition to footfall, landtype, urbanization and service
+/// provider override, and how the single byte values of its disktree map to
+/// assignments
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataSetDefinition {
+    /// Name the assignment rules refer to the data set by
+    pub name: String,
+    /// Disktree file of the data set
+    pub path: PathBuf,
+    pub values: Vec<DataSetValue>,
+    /// Assignment for hexes not in the disktree
+    #[serde(default = "default_missing_assignment")]
+    pub missing: Assignment,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataSetValue {
+    pub value: u8,
+    pub assignment: Assignment,
+}
+
+fn default_missing_assignment() -> Assignment {
+    Assignment::C
+}
+
+/// A named data set backed by its own disktree file
+pub struct DiskTreeDataSet {
+    pub definition: DataSetDefinition,
+    pub disktree: DiskTreeMap,
+}
+
+impl DiskTreeDataSet {
+    pub fn open(definition: DataSetDefinition) -> anyhow::Result<Self> {
+        let disktree = DiskTreeMap::open(&definition.path)?;
+        Ok(Self {
+            definition,
+            disktree,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.definition.name
+    }
+}
+
+impl HexAssignment for DiskTreeDataSet {
+    fn assignment(&self, cell: hextree::Cell) -> anyhow::Result<Assignment> {
+        let Some((_, vals)) = self.disktree.get(cell)? else {
+            return Ok(self.definition.missing);
+        };
+
+        match vals {
+            [val] => self
+                .definition
+                .values
+                .iter()
+                .find(|value| value.value == *val)
+                .map(|value| value.assignment)
+                .ok_or_else(|| anyhow::anyhow!("unexpected {} value: {cell:?} {val}", self.name())),
+            _ => anyhow::bail!(
+                "unexpected {} disktree data: {cell:?} {vals:?}",
+                self.name()
+            ),
+        }
+    }
+}
+
+/// Data sets in addition to footfall, landtype, urbanization and service
+/// provider override, looked up by name.
+#[derive(Default)]
+pub struct DataSetRegistry {
+    data_sets: Vec<DiskTreeDataSet>,
+}
+
+impl DataSetRegistry {
+    pub fn new(data_sets: Vec<DiskTreeDataSet>) -> anyhow::Result<Self> {
+        for (i, data_set) in data_sets.iter().enumerate() {
+            let name = data_set.name();
+            if rules::BUILT_IN_DATA_SETS.contains(&name) {
+                anyhow::bail!("data set {name} is built in");
+            }
+            if data_sets[..i].iter().any(|other| other.name() == name) {
+                anyhow::bail!("data set {name} is defined more than once");
+            }
+        }
+        Ok(Self { data_sets })
+    }
+
+    /// Opens the disktree file of every definition
+    pub fn open(definitions: &[DataSetDefinition]) -> anyhow::Result<Self> {
+        Self::new(
+            definitions
+                .iter()
+                .cloned()
+                .map(DiskTreeDataSet::open)
+                .collect::<anyhow::Result<_>>()?,
+        )
+    }
+
+    pub fn get(&self, name: &str) -> Option<&DiskTreeDataSet> {
+        self.data_sets
+            .iter()
+            .find(|data_set| data_set.name() == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &DiskTreeDataSet> {
+        self.data_sets.iter()
+    }
+
+    /// Assignments of the cell in every registered data set
+    pub fn assignments(&self, cell: hextree::Cell) -> anyhow::Result<BTreeMap<String, Assignment>> {
+        self.data_sets
+            .iter()
+            .map(|data_set| Ok((data_set.name().to_string(), data_set.assignment(cell)?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hextree::{Cell, HexTreeMap};
+    use std::io::{Cursor, Write};
+
+    fn population(buf: Vec<u8>) -> anyhow::Result<DiskTreeDataSet> {
+        Ok(DiskTreeDataSet {
+            definition: DataSetDefinition {
+                name: "population".to_string(),
+                path: PathBuf::from("population.res10.h3tree"),
+                values: vec![
+                    DataSetValue {
+                        value: 2,
+                        assignment: Assignment::A,
+                    },
+                    DataSetValue {
+                        value: 1,
+                        assignment: Assignment::B,
+                    },
+                ],
+                missing: Assignment::C,
+            },
+            disktree: DiskTreeMap::with_buf(buf)?,
+        })
+    }
+
+    #[test]
+    fn registry_assignments() -> anyhow::Result<()> {
+        let dense = Cell::from_raw(0x8a1fb46622dffff)?;
+        let sparse = Cell::from_raw(0x8c2681a3064d9ff)?;
+        let missing = Cell::from_raw(0x8c2681a3064dbff)?;
+        let unknown = Cell::from_raw(0x8c2681a3064ddff)?;
+
+        let mut population_map = HexTreeMap::new();
+        population_map.insert(dense, 2_u8);
+        population_map.insert(sparse, 1_u8);
+        population_map.insert(unknown, 7_u8);
+        let mut buf = vec![];
+        population_map.to_disktree(Cursor::new(&mut buf), |w, v| w.write_all(&[*v]))?;
+
+        let registry = DataSetRegistry::new(vec![population(buf)?])?;
+
+        let get =
+            |cell| -> anyhow::Result<Assignment> { Ok(registry.assignments(cell)?["population"]) };
+        assert_eq!(get(dense)?, Assignment::A);
+        assert_eq!(get(sparse)?, Assignment::B);
+        assert_eq!(get(missing)?, Assignment::C);
+        assert!(get(unknown).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn names_must_be_unique_and_not_built_in() -> anyhow::Result<()> {
+        let mut buf = vec![];
+        HexTreeMap::<u8>::new().to_disktree(Cursor::new(&mut buf), |w, v| w.write_all(&[*v]))?;
+
+        let duplicate = vec![population(buf.clone())?, population(buf.clone())?];
+        assert!(DataSetRegistry::new(duplicate).is_err());
+
+        let mut built_in = population(buf)?;
+        built_in.definition.name = rules::FOOTFALL.to_string();
+        assert!(DataSetRegistry::new(vec![built_in]).is_err());
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 pub mod assignment;
+pub mod data_set;
 pub mod footfall;
 pub mod landtype;
+pub mod rules;
 pub mod service_provider_override;
 pub mod urbanization;
 
@@ -8,6 +10,10 @@ use std::collections::{HashMap, HashSet};
 
 pub use assignment::Assignment;
 use assignment::HexAssignments;
+pub use data_set::{DataSetDefinition, DataSetRegistry};
+pub use rules::AssignmentRuleTable;
+use rules::ExtendedAssignments;
+use rust_decimal::Decimal;
 
 pub trait HexAssignment: Send + Sync + 'static {
     fn assignment(&self, cell: hextree::Cell) -> anyhow::Result<Assignment>;
@@ -45,11 +51,31 @@ pub struct HexBoostData<Foot, Land, Urban, ServiceProviderOverride> {
     pub landtype: Land,
     pub urbanization: Urban,
     pub service_provider_override: ServiceProviderOverride,
+    /// Data sets the assignment rules can refer to in addition to the above
+    #[builder(default)]
+    pub extra: DataSetRegistry,
+    /// Combines the assignments of a hex into its boosting multiplier
+    #[builder(default)]
+    pub rules: AssignmentRuleTable,
 }
 impl<F, L, U, S> HexBoostData<F, L, U, S> {
     pub fn builder() -> HexBoostDataBuilder<F, L, U, S> {
         HexBoostDataBuilder::default()
     }
+
+    /// Boosting multiplier of the cell according to the rule table, given its
+    /// assignments in the built-in data sets
+    pub fn assignment_multiplier(
+        &self,
+        cell: hextree::Cell,
+        assignments: &HexAssignments,
+    ) -> anyhow::Result<Decimal> {
+        let extra = self.extra.assignments(cell)?;
+        Ok(self.rules.multiplier(&ExtendedAssignments {
+            assignments,
+            extra: &extra,
+        }))
+    }
 }
 
 impl<Foot, Land, Urban, ServiceProviderOverride> HexBoostDataAssignments
@@ -280,47 +306,102 @@ mod tests {
         {
             use Assignment::*;
             // yellow
-            assert_eq!(HexAssignments { footfall: A, landtype: A, urbanized: A, service_provider_override: C}, data.assignments(poi_built_urbanized)?);
-            assert_eq!(HexAssignments { footfall: A, landtype: B, urbanized: A, service_provider_override: C }, data.assignments(poi_grass_urbanized)?);
-            assert_eq!(HexAssignments { footfall: A, landtype: C, urbanized: A, service_provider_override: C }, data.assignments(poi_water_urbanized)?);
+            assert_eq!(HexAssignments { footfall: A, landtype: A, urbanized: A, service_provider_override: C, assignment_multiplier: None }, data.assignments(poi_built_urbanized)?);
+            assert_eq!(HexAssignments { footfall: A, landtype: B, urbanized: A, service_provider_override: C, assignment_multiplier: None }, data.assignments(poi_grass_urbanized)?);
+            assert_eq!(HexAssignments { footfall: A, landtype: C, urbanized: A, service_provider_override: C, assignment_multiplier: None }, data.assignments(poi_water_urbanized)?);
             // orange
-            assert_eq!(HexAssignments { footfall: A, landtype: A, urbanized: B, service_provider_override: C }, data.assignments(poi_built_not_urbanized)?);
-            assert_eq!(HexAssignments { footfall: A, landtype: B, urbanized: B, service_provider_override: C }, data.assignments(poi_grass_not_urbanized)?);
-            assert_eq!(HexAssignments { footfall: A, landtype: C, urbanized: B, service_provider_override: C }, data.assignments(poi_water_not_urbanized)?);
+            assert_eq!(HexAssignments { footfall: A, landtype: A, urbanized: B, service_provider_override: C, assignment_multiplier: None }, data.assignments(poi_built_not_urbanized)?);
+            assert_eq!(HexAssignments { footfall: A, landtype: B, urbanized: B, service_provider_override: C, assignment_multiplier: None }, data.assignments(poi_grass_not_urbanized)?);
+            assert_eq!(HexAssignments { footfall: A, landtype: C, urbanized: B, service_provider_override: C, assignment_multiplier: None }, data.assignments(poi_water_not_urbanized)?);
             // light green
-            assert_eq!(HexAssignments { footfall: B, landtype: A, urbanized: A, service_provider_override: C }, data.assignments(poi_no_data_built_urbanized)?);
-            assert_eq!(HexAssignments { footfall: B, landtype: B, urbanized: A, service_provider_override: C }, data.assignments(poi_no_data_grass_urbanized)?);
-            assert_eq!(HexAssignments { footfall: B, landtype: C, urbanized: A, service_provider_override: C }, data.assignments(poi_no_data_water_urbanized)?);
+            assert_eq!(HexAssignments { footfall: B, landtype: A, urbanized: A, service_provider_override: C, assignment_multiplier: None }, data.assignments(poi_no_data_built_urbanized)?);
+            assert_eq!(HexAssignments { footfall: B, landtype: B, urbanized: A, service_provider_override: C, assignment_multiplier: None }, data.assignments(poi_no_data_grass_urbanized)?);
+            assert_eq!(HexAssignments { footfall: B, landtype: C, urbanized: A, service_provider_override: C, assignment_multiplier: None }, data.assignments(poi_no_data_water_urbanized)?);
             // green
-            assert_eq!(HexAssignments { footfall: B, landtype: A, urbanized: B, service_provider_override: C }, data.assignments(poi_no_data_built_not_urbanized)?);
-            assert_eq!(HexAssignments { footfall: B, landtype: B, urbanized: B, service_provider_override: C }, data.assignments(poi_no_data_grass_not_urbanized)?);
-            assert_eq!(HexAssignments { footfall: B, landtype: C, urbanized: B, service_provider_override: C }, data.assignments(poi_no_data_water_not_urbanized)?);
+            assert_eq!(HexAssignments { footfall: B, landtype: A, urbanized: B, service_provider_override: C, assignment_multiplier: None }, data.assignments(poi_no_data_built_not_urbanized)?);
+            assert_eq!(HexAssignments { footfall: B, landtype: B, urbanized: B, service_provider_override: C, assignment_multiplier: None }, data.assignments(poi_no_data_grass_not_urbanized)?);
+            assert_eq!(HexAssignments { footfall: B, landtype: C, urbanized: B, service_provider_override: C, assignment_multiplier: None }, data.assignments(poi_no_data_water_not_urbanized)?);
             // light blue
-            assert_eq!(HexAssignments { footfall: C, landtype: A, urbanized: A, service_provider_override: C }, data.assignments(no_poi_built_urbanized)?);
-            assert_eq!(HexAssignments { footfall: C, landtype: B, urbanized: A, service_provider_override: C }, data.assignments(no_poi_grass_urbanized)?);
-            assert_eq!(HexAssignments { footfall: C, landtype: C, urbanized: A, service_provider_override: C }, data.assignments(no_poi_water_urbanized)?);
+            assert_eq!(HexAssignments { footfall: C, landtype: A, urbanized: A, service_provider_override: C, assignment_multiplier: None }, data.assignments(no_poi_built_urbanized)?);
+            assert_eq!(HexAssignments { footfall: C, landtype: B, urbanized: A, service_provider_override: C, assignment_multiplier: None }, data.assignments(no_poi_grass_urbanized)?);
+            assert_eq!(HexAssignments { footfall: C, landtype: C, urbanized: A, service_provider_override: C, assignment_multiplier: None }, data.assignments(no_poi_water_urbanized)?);
             // dark blue
-            assert_eq!(HexAssignments { footfall: C, landtype: A, urbanized: B, service_provider_override: C }, data.assignments(no_poi_built_not_urbanized)?);
-            assert_eq!(HexAssignments { footfall: C, landtype: B, urbanized: B, service_provider_override: C }, data.assignments(no_poi_grass_not_urbanized)?);
-            assert_eq!(HexAssignments { footfall: C, landtype: C, urbanized: B, service_provider_override: C }, data.assignments(no_poi_water_not_urbanized)?);
+            assert_eq!(HexAssignments { footfall: C, landtype: A, urbanized: B, service_provider_override: C, assignment_multiplier: None }, data.assignments(no_poi_built_not_urbanized)?);
+            assert_eq!(HexAssignments { footfall: C, landtype: B, urbanized: B, service_provider_override: C, assignment_multiplier: None }, data.assignments(no_poi_grass_not_urbanized)?);
+            assert_eq!(HexAssignments { footfall: C, landtype: C, urbanized: B, service_provider_override: C, assignment_multiplier: None }, data.assignments(no_poi_water_not_urbanized)?);
             // gray
-            assert_eq!(HexAssignments { footfall: A, landtype: A, urbanized: C, service_provider_override: C }, data.assignments(poi_built_outside_us)?);
-            assert_eq!(HexAssignments { footfall: A, landtype: B, urbanized: C, service_provider_override: C }, data.assignments(poi_grass_outside_us)?);
-            assert_eq!(HexAssignments { footfall: A, landtype: C, urbanized: C, service_provider_override: C }, data.assignments(poi_water_outside_us)?);
-            assert_eq!(HexAssignments { footfall: B, landtype: A, urbanized: C, service_provider_override: C }, data.assignments(poi_no_data_built_outside_us)?);
-            assert_eq!(HexAssignments { footfall: B, landtype: B, urbanized: C, service_provider_override: C }, data.assignments(poi_no_data_grass_outside_us)?);
-            assert_eq!(HexAssignments { footfall: B, landtype: C, urbanized: C, service_provider_override: C }, data.assignments(poi_no_data_water_outside_us)?);
-            assert_eq!(HexAssignments { footfall: C, landtype: A, urbanized: C, service_provider_override: C }, data.assignments(no_poi_built_outside_us)?);
-            assert_eq!(HexAssignments { footfall: C, landtype: B, urbanized: C, service_provider_override: C }, data.assignments(no_poi_grass_outside_us)?);
-            assert_eq!(HexAssignments { footfall: C, landtype: C, urbanized: C, service_provider_override: C }, data.assignments(no_poi_water_outside_us)?);
+            assert_eq!(HexAssignments { footfall: A, landtype: A, urbanized: C, service_provider_override: C, assignment_multiplier: None }, data.assignments(poi_built_outside_us)?);
+            assert_eq!(HexAssignments { footfall: A, landtype: B, urbanized: C, service_provider_override: C, assignment_multiplier: None }, data.assignments(poi_grass_outside_us)?);
+            assert_eq!(HexAssignments { footfall: A, landtype: C, urbanized: C, service_provider_override: C, assignment_multiplier: None }, data.assignments(poi_water_outside_us)?);
+            assert_eq!(HexAssignments { footfall: B, landtype: A, urbanized: C, service_provider_override: C, assignment_multiplier: None }, data.assignments(poi_no_data_built_outside_us)?);
+            assert_eq!(HexAssignments { footfall: B, landtype: B, urbanized: C, service_provider_override: C, assignment_multiplier: None }, data.assignments(poi_no_data_grass_outside_us)?);
+            assert_eq!(HexAssignments { footfall: B, landtype: C, urbanized: C, service_provider_override: C, assignment_multiplier: None }, data.assignments(poi_no_data_water_outside_us)?);
+            assert_eq!(HexAssignments { footfall: C, landtype: A, urbanized: C, service_provider_override: C, assignment_multiplier: None }, data.assignments(no_poi_built_outside_us)?);
+            assert_eq!(HexAssignments { footfall: C, landtype: B, urbanized: C, service_provider_override: C, assignment_multiplier: None }, data.assignments(no_poi_grass_outside_us)?);
+            assert_eq!(HexAssignments { footfall: C, landtype: C, urbanized: C, service_provider_override: C, assignment_multiplier: None }, data.assignments(no_poi_water_outside_us)?);
             // service provider override
-            assert_eq!(HexAssignments { footfall: B, landtype: B, urbanized: B, service_provider_override: A }, data.assignments(poi_no_data_grass_not_urbanized_and_service_provider_override)?);
-            assert_eq!(HexAssignments { footfall: C, landtype: C, urbanized: C, service_provider_override: A }, data.assignments(service_provider_override_outside_us)?);
+            assert_eq!(HexAssignments { footfall: B, landtype: B, urbanized: B, service_provider_override: A, assignment_multiplier: None }, data.assignments(poi_no_data_grass_not_urbanized_and_service_provider_override)?);
+            assert_eq!(HexAssignments { footfall: C, landtype: C, urbanized: C, service_provider_override: A, assignment_multiplier: None }, data.assignments(service_provider_override_outside_us)?);
 
             // never inserted
-            assert_eq!(HexAssignments { footfall: C, landtype: C, urbanized: C, service_provider_override: C  }, data.assignments(unknown_cell)?);
+            assert_eq!(HexAssignments { footfall: C, landtype: C, urbanized: C, service_provider_override: C, assignment_multiplier: None }, data.assignments(unknown_cell)?);
         };
 
         Ok(())
     }
+    #[test]
+    fn assignment_multiplier_uses_rules_and_extra_data_sets() -> anyhow::Result<()> {
+        use data_set::{DataSetValue, DiskTreeDataSet};
+        use rust_decimal_macros::dec;
+        use std::io::Write;
+
+        let dense = hextree::Cell::from_raw(0x8c2681a3064d9ff)?;
+        let sparse = hextree::Cell::from_raw(0x8c2681a3064dbff)?;
+
+        let mut population = HexTreeMap::<u8>::new();
+        population.insert(dense, 1);
+        let mut population_buf = vec![];
+        population.to_disktree(Cursor::new(&mut population_buf), |w, v| w.write_all(&[*v]))?;
+        let extra = DataSetRegistry::new(vec![DiskTreeDataSet {
+            definition: DataSetDefinition {
+                name: "population".to_string(),
+                path: "population.res10.h3tree".into(),
+                values: vec![DataSetValue {
+                    value: 1,
+                    assignment: Assignment::A,
+                }],
+                missing: Assignment::C,
+            },
+            disktree: DiskTreeMap::with_buf(population_buf)?,
+        }])?;
+        let rules: AssignmentRuleTable = serde_json::from_str(
+            r#"[
+                { "when": { "population": "a", "urbanized": "a" }, "multiplier": "1.5" },
+                { "when": { "urbanized": "a" }, "multiplier": "0.5" }
+            ]"#,
+        )?;
+
+        let data = HexBoostData::builder()
+            .footfall(Assignment::C)
+            .landtype(Assignment::C)
+            .urbanization(Assignment::A)
+            .service_provider_override(Assignment::C)
+            .extra(extra)
+            .rules(rules)
+            .build()?;
+
+        let dense_assignments = data.assignments(dense)?;
+        assert_eq!(
+            data.assignment_multiplier(dense, &dense_assignments)?,
+            dec!(1.5)
+        );
+        let sparse_assignments = data.assignments(sparse)?;
+        assert_eq!(
+            data.assignment_multiplier(sparse, &sparse_assignments)?,
+            dec!(0.5)
+        );
+        // Without the configured table the HIP-103 one applies
+        assert_eq!(sparse_assignments.boosting_multiplier(), dec!(0.05));
+        Ok(())
+    }
 }
@@ -0,0 +1,287 @@
+use std::{collections::BTreeMap, sync::OnceLock};
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::{assignment::HexAssignments, Assignment};
+
+pub const FOOTFALL: &str = "footfall";
+pub const LANDTYPE: &str = "landtype";
+pub const URBANIZED: &str = "urbanized";
+pub const SERVICE_PROVIDER_OVERRIDE: &str = "service_provider_override";
+
+pub const BUILT_IN_DATA_SETS: [&str; 4] =
+    [FOOTFALL, LANDTYPE, URBANIZED, SERVICE_PROVIDER_OVERRIDE];
+
+/// Looks up a hex's assignment for a data set by name
+pub trait NamedAssignments {
+    fn get(&self, data_set: &str) -> Option<Assignment>;
+}
+
+impl NamedAssignments for HexAssignments {
+    fn get(&self, data_set: &str) -> Option<Assignment> {
+        match data_set {
+            FOOTFALL => Some(self.footfall),
+            LANDTYPE => Some(self.landtype),
+            URBANIZED => Some(self.urbanized),
+            SERVICE_PROVIDER_OVERRIDE => Some(self.service_provider_override),
+            _ => None,
+        }
+    }
+}
+
+impl NamedAssignments for BTreeMap<String, Assignment> {
+    fn get(&self, data_set: &str) -> Option<Assignment> {
+        BTreeMap::get(self, data_set).copied()
+    }
+}
+
+/// Built-in assignments extended with those of additional data sets
+pub struct ExtendedAssignments<'a> {
+    pub assignments: &'a HexAssignments,
+    pub extra: &'a BTreeMap<String, Assignment>,
+}
+
+impl NamedAssignments for ExtendedAssignments<'_> {
+    fn get(&self, data_set: &str) -> Option<Assignment> {
+        self.assignments
+            .get(data_set)
+            .or_else(|| self.extra.get(data_set))
+    }
+}
+
+/// A row of an [AssignmentRuleTable]. Data sets not listed in `when` match any
+/// assignment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssignmentRule {
+    #[serde(default)]
+    pub when: BTreeMap<String, Assignment>,
+    pub multiplier: Decimal,
+}
+
+impl AssignmentRule {
+    fn new<const N: usize>(when: [(&str, Assignment); N], multiplier: Decimal) -> Self {
+        Self {
+            when: when
+                .into_iter()
+                .map(|(data_set, assignment)| (data_set.to_string(), assignment))
+                .collect(),
+            multiplier,
+        }
+    }
+
+    pub fn matches(&self, assignments: &impl NamedAssignments) -> bool {
+        self.when
+            .iter()
+            .all(|(data_set, expected)| assignments.get(data_set) == Some(*expected))
+    }
+}
+
+/// Maps combinations of data set assignments to a boosting multiplier. Rules
+/// are checked in order and the first match wins. Hexes matching no rule get
+/// a multiplier of 0.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AssignmentRuleTable {
+    rules: Vec<AssignmentRule>,
+}
+
+impl AssignmentRuleTable {
+    pub fn new(rules: Vec<AssignmentRule>) -> Self {
+        Self { rules }
+    }
+
+    /// The HIP-103 table, with the service provider override from HIP-134
+    pub fn hip_103() -> &'static Self {
+        static TABLE: OnceLock<AssignmentRuleTable> = OnceLock::new();
+        TABLE.get_or_init(|| {
+            use Assignment::*;
+            let row = |footfall, landtype, urbanized, multiplier| {
+                AssignmentRule::new(
+                    [
+                        (FOOTFALL, footfall),
+                        (LANDTYPE, landtype),
+                        (URBANIZED, urbanized),
+                    ],
+                    multiplier,
+                )
+            };
+            Self::new(vec![
+                // service provider override hex
+                // Overrides other dataset assignments if set
+                AssignmentRule::new([(SERVICE_PROVIDER_OVERRIDE, A)], dec!(1.00)),
+                // yellow - POI ≥ 1 Urbanized
+                row(A, A, A, dec!(1.00)),
+                row(A, B, A, dec!(1.00)),
+                row(A, C, A, dec!(1.00)),
+                // orange - POI ≥ 1 Not Urbanized
+                row(A, A, B, dec!(1.00)),
+                row(A, B, B, dec!(1.00)),
+                row(A, C, B, dec!(1.00)),
+                // light green - Point of Interest Urbanized
+                row(B, A, A, dec!(0.70)),
+                row(B, B, A, dec!(0.70)),
+                row(B, C, A, dec!(0.70)),
+                // dark green - Point of Interest Not Urbanized
+                row(B, A, B, dec!(0.50)),
+                row(B, B, B, dec!(0.50)),
+                row(B, C, B, dec!(0.50)),
+                // light blue - No POI Urbanized
+                row(C, A, A, dec!(0.40)),
+                row(C, B, A, dec!(0.30)),
+                row(C, C, A, dec!(0.05)),
+                // dark blue - No POI Not Urbanized
+                row(C, A, B, dec!(0.20)),
+                row(C, B, B, dec!(0.15)),
+                row(C, C, B, dec!(0.03)),
+                // gray - Outside of USA
+                AssignmentRule::new([(URBANIZED, C)], dec!(0.00)),
+            ])
+        })
+    }
+
+    pub fn rules(&self) -> &[AssignmentRule] {
+        &self.rules
+    }
+
+    pub fn multiplier(&self, assignments: &impl NamedAssignments) -> Decimal {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(assignments))
+            .map_or(dec!(0), |rule| rule.multiplier)
+    }
+}
+
+impl Default for AssignmentRuleTable {
+    fn default() -> Self {
+        Self::hip_103().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assignments(
+        footfall: Assignment,
+        landtype: Assignment,
+        urbanized: Assignment,
+        service_provider_override: Assignment,
+    ) -> HexAssignments {
+        HexAssignments {
+            footfall,
+            landtype,
+            urbanized,
+            service_provider_override,
+            assignment_multiplier: None,
+        }
+    }
+
+    fn legacy_multiplier(hex: &HexAssignments) -> Decimal {
+        let HexAssignments {
+            footfall,
+            landtype,
+            urbanized,
+            service_provider_override,
+            ..
+        } = hex;
+
+        use Assignment::*;
+        match (footfall, landtype, urbanized, service_provider_override) {
+            // service provider override hex
+            // Overrides other dataset assignments if set
+            (_, _, _, A) => dec!(1.00),
+            // yellow - POI ≥ 1 Urbanized
+            (A, A, A, _) => dec!(1.00),
+            (A, B, A, _) => dec!(1.00),
+            (A, C, A, _) => dec!(1.00),
+            // orange - POI ≥ 1 Not Urbanized
+            (A, A, B, _) => dec!(1.00),
+            (A, B, B, _) => dec!(1.00),
+            (A, C, B, _) => dec!(1.00),
+            // light green - Point of Interest Urbanized
+            (B, A, A, _) => dec!(0.70),
+            (B, B, A, _) => dec!(0.70),
+            (B, C, A, _) => dec!(0.70),
+            // dark green - Point of Interest Not Urbanized
+            (B, A, B, _) => dec!(0.50),
+            (B, B, B, _) => dec!(0.50),
+            (B, C, B, _) => dec!(0.50),
+            // light blue - No POI Urbanized
+            (C, A, A, _) => dec!(0.40),
+            (C, B, A, _) => dec!(0.30),
+            (C, C, A, _) => dec!(0.05),
+            // dark blue - No POI Not Urbanized
+            (C, A, B, _) => dec!(0.20),
+            (C, B, B, _) => dec!(0.15),
+            (C, C, B, _) => dec!(0.03),
+            // gray - Outside of USA
+            (_, _, C, _) => dec!(0.00),
+        }
+    }
+
+    #[test]
+    fn hip_103_table_matches_match_arms() {
+        use Assignment::*;
+        for footfall in [A, B, C] {
+            for landtype in [A, B, C] {
+                for urbanized in [A, B, C] {
+                    for sp_override in [A, B, C] {
+                        let hex = assignments(footfall, landtype, urbanized, sp_override);
+                        assert_eq!(
+                            hex.boosting_multiplier(),
+                            legacy_multiplier(&hex),
+                            "{hex:?}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn extra_data_sets_take_part_in_rules() {
+        let table: AssignmentRuleTable = serde_json::from_str(
+            r#"[
+                { "when": { "population": "a", "urbanized": "a" }, "multiplier": "1.5" },
+                { "when": { "population": "c" }, "multiplier": "0.1" },
+                { "multiplier": "0.5" }
+            ]"#,
+        )
+        .unwrap();
+        let hex = assignments(Assignment::C, Assignment::C, Assignment::A, Assignment::C);
+
+        let extra = BTreeMap::from([("population".to_string(), Assignment::A)]);
+        let extended = ExtendedAssignments {
+            assignments: &hex,
+            extra: &extra,
+        };
+        assert_eq!(table.multiplier(&extended), dec!(1.5));
+
+        let extra = BTreeMap::from([("population".to_string(), Assignment::C)]);
+        let extended = ExtendedAssignments {
+            assignments: &hex,
+            extra: &extra,
+        };
+        assert_eq!(table.multiplier(&extended), dec!(0.1));
+
+        // Rules for a data set the hex has no assignment for never match
+        assert_eq!(table.multiplier(&hex), dec!(0.5));
+    }
+
+    #[test]
+    fn assigned_multiplier_takes_precedence() {
+        let mut hex = assignments(Assignment::C, Assignment::C, Assignment::C, Assignment::C);
+        assert_eq!(hex.boosting_multiplier(), dec!(0));
+
+        hex.assignment_multiplier = Some(dec!(0.25));
+        assert_eq!(hex.boosting_multiplier(), dec!(0.25));
+    }
+
+    #[test]
+    fn empty_table_gives_zero() {
+        let hex = assignments(Assignment::A, Assignment::A, Assignment::A, Assignment::A);
+        assert_eq!(AssignmentRuleTable::new(vec![]).multiplier(&hex), dec!(0));
+    }
+}
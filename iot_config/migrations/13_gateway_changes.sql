@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS gateway_changes (
+    address TEXT NOT NULL,
+    changed_at TIMESTAMPTZ NOT NULL,
+    location BIGINT,
+    elevation INT,
+    gain INT,
+    region INT,
+    is_full_hotspot BOOLEAN NOT NULL,
+    PRIMARY KEY (address, changed_at)
+);
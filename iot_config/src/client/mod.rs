@@ -1,4 +1,5 @@
 use crate::gateway_info::{self, GatewayInfo, GatewayInfoStream};
+use chrono::{DateTime, Utc};
 use file_store::traits::MsgVerify;
 use futures::stream::{self, StreamExt};
 use helium_crypto::{Keypair, PublicKey, PublicKeyBinary, Sign};
@@ -6,6 +7,7 @@ use helium_proto::{
     services::{iot_config, Channel, Endpoint},
     BlockchainRegionParamV1, Message, Region,
 };
+use retainer::Cache;
 use std::{sync::Arc, time::Duration};
 
 pub mod org_client;
@@ -38,6 +40,16 @@ pub trait Gateways: Clone + Send + Sync + 'static {
         address: &PublicKeyBinary,
     ) -> Result<Option<GatewayInfo>, Self::Error>;
 
+    /// Gateway info as it was at `at`, such as the time a report was
+    /// received. Resolvers without a history resolve the current info.
+    async fn resolve_gateway_info_at(
+        &mut self,
+        address: &PublicKeyBinary,
+        _at: DateTime<Utc>,
+    ) -> Result<Option<GatewayInfo>, Self::Error> {
+        self.resolve_gateway_info(address).await
+    }
+
     async fn stream_gateways_info(&mut self) -> Result<GatewayInfoStream, Self::Error>;
 
     async fn resolve_region_params(
@@ -53,6 +65,49 @@ pub struct Client {
     signing_key: Arc<Keypair>,
     config_pubkey: PublicKey,
     batch_size: u32,
+    history: Option<GatewayHistory>,
+}
+
+/// Caches gateway info resolved through the gateway history of the iot config
+/// server, by gateway and bucket of time. Gateway info is resolved as of the
+/// start of the bucket.
+#[derive(Clone)]
+struct GatewayHistory {
+    cache: Arc<Cache<(PublicKeyBinary, i64), Option<GatewayInfo>>>,
+    cache_ttl: Duration,
+    bucket: Duration,
+}
+
+impl std::fmt::Debug for GatewayHistory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GatewayHistory")
+            .field("cache_ttl", &self.cache_ttl)
+            .field("bucket", &self.bucket)
+            .finish()
+    }
+}
+
+impl GatewayHistory {
+    fn new(cache_ttl: Duration, bucket: Duration) -> Self {
+        let cache = Arc::new(Cache::new());
+        let cloned_cache = cache.clone();
+        tokio::spawn(async move {
+            cloned_cache
+                .monitor(4, 0.25, Duration::from_secs(60 * 60))
+                .await
+        });
+        Self {
+            cache,
+            cache_ttl,
+            bucket,
+        }
+    }
+
+    /// Start of the bucket `at` falls in, as unix seconds
+    fn bucket_start(&self, at: DateTime<Utc>) -> i64 {
+        let bucket = self.bucket.as_secs().max(1) as i64;
+        at.timestamp().div_euclid(bucket) * bucket
+    }
 }
 
 macro_rules! call_with_retry {
@@ -95,8 +150,56 @@ impl Client {
             signing_key: settings.signing_keypair()?,
             config_pubkey: settings.config_pubkey()?,
             batch_size: settings.batch_size,
+            history: settings
+                .resolve_history
+                .then(|| GatewayHistory::new(settings.history_cache_ttl, settings.history_bucket)),
         })
     }
+
+    /// The latest recorded state of the gateway at the start of the history
+    /// bucket `at` falls in. None if resolving history is not enabled or the
+    /// time is before the recorded history of the gateway, callers then
+    /// resolve the current info themselves.
+    pub async fn resolve_gateway_history_at(
+        &self,
+        address: &PublicKeyBinary,
+        at: DateTime<Utc>,
+    ) -> Result<Option<gateway_info::GatewayInfo>, ClientError> {
+        let Some(history) = &self.history else {
+            return Ok(None);
+        };
+
+        let key = (address.clone(), history.bucket_start(at));
+        if let Some(cached) = history.cache.get(&key).await {
+            return Ok(cached.value().clone());
+        }
+
+        let mut request = iot_config::GatewayInfoAtTimestampReqV1 {
+            address: address.clone().into(),
+            timestamp: key.1 as u64,
+            signer: self.signing_key.public_key().into(),
+            signature: vec![],
+        };
+        request.signature = self.signing_key.sign(&request.encode_to_vec())?;
+        tracing::debug!(pubkey = address.to_string(), %at, "fetching gateway history");
+        let mut gateway_client = self.gateway_client.clone();
+        let response = match call_with_retry!(gateway_client.info_at_timestamp(request.clone())) {
+            Ok(info_resp) => {
+                let response = info_resp.into_inner();
+                response.verify(&self.config_pubkey)?;
+                response.info.map(gateway_info::GatewayInfo::from)
+            }
+            Err(status) if status.code() == tonic::Code::NotFound => None,
+            Err(status) => Err(status)?,
+        };
+
+        history
+            .cache
+            .insert(key, response.clone(), history.cache_ttl)
+            .await;
+
+        Ok(response)
+    }
 }
 
 #[async_trait::async_trait]
@@ -148,6 +251,20 @@ impl Gateways for Client {
         Ok(response)
     }
 
+    /// Uses the latest recorded state of the gateway at `at` if resolving
+    /// history is enabled. The current info is used for times before
+    /// the recorded history of a gateway.
+    async fn resolve_gateway_info_at(
+        &mut self,
+        address: &PublicKeyBinary,
+        at: DateTime<Utc>,
+    ) -> Result<Option<gateway_info::GatewayInfo>, Self::Error> {
+        match self.resolve_gateway_history_at(address, at).await? {
+            Some(info) => Ok(Some(info)),
+            None => self.resolve_gateway_info(address).await,
+        }
+    }
+
     async fn stream_gateways_info(
         &mut self,
     ) -> Result<gateway_info::GatewayInfoStream, Self::Error> {
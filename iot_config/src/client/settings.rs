@@ -1,5 +1,6 @@
+use humantime_serde::re::humantime;
 use serde::Deserialize;
-use std::{str::FromStr, sync::Arc};
+use std::{str::FromStr, sync::Arc, time::Duration};
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct Settings {
@@ -19,6 +20,19 @@ pub struct Settings {
     /// Batch size for gateway info stream results. Default 1000
     #[serde(default = "default_batch_size")]
     pub batch_size: u32,
+    /// Resolve gateway info as of a given time through the gateway history
+    /// of the iot config server. Gateway info as of a given time is the
+    /// current info if unset
+    #[serde(default)]
+    pub resolve_history: bool,
+    /// How long gateway info resolved through the history is cached.
+    /// Default 1 hour
+    #[serde(with = "humantime_serde", default = "default_history_cache_ttl")]
+    pub history_cache_ttl: Duration,
+    /// Gateway info is resolved through the history as of the start of the
+    /// bucket of this duration a time falls in. Default 10 minutes
+    #[serde(with = "humantime_serde", default = "default_history_bucket")]
+    pub history_bucket: Duration,
 }
 
 fn default_connect_timeout() -> u64 {
@@ -33,6 +47,14 @@ fn default_batch_size() -> u32 {
     1000
 }
 
+fn default_history_cache_ttl() -> Duration {
+    humantime::parse_duration("1 hour").unwrap()
+}
+
+fn default_history_bucket() -> Duration {
+    humantime::parse_duration("10 minutes").unwrap()
+}
+
 impl Settings {
     pub fn signing_keypair(
         &self,
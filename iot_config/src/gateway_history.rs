@@ -0,0 +1,322 @@
+//
+// Tracks changes to the gateway info served by the gateway service, so the
+// info of a gateway can be resolved as it was at a given time, such as the
+// timestamp of a beacon or witness report
+//
+
+use crate::{
+    gateway_info::{self, GatewayInfo, GatewayMetadata},
+    region_map::RegionMapReader,
+};
+use chrono::{DateTime, Utc};
+use futures::{future, Stream, StreamExt, TryFutureExt, TryStreamExt};
+use helium_crypto::PublicKeyBinary;
+use helium_proto::Region;
+use sqlx::{PgExecutor, Pool, Postgres, QueryBuilder, Row};
+use std::{collections::HashMap, time::Duration};
+use task_manager::ManagedTask;
+
+/// The info of a gateway from the time it changed to these values until its
+/// next recorded change. The metadata fields are set for asserted gateways
+/// with a known region.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GatewayState {
+    pub address: PublicKeyBinary,
+    pub changed_at: DateTime<Utc>,
+    pub location: Option<u64>,
+    pub elevation: Option<i32>,
+    pub gain: Option<i32>,
+    pub region: Option<i32>,
+    pub is_full_hotspot: bool,
+}
+
+impl GatewayState {
+    pub fn new(info: &GatewayInfo, changed_at: DateTime<Utc>) -> Self {
+        let metadata = info.metadata.as_ref();
+        Self {
+            address: info.address.clone(),
+            changed_at,
+            location: metadata.map(|metadata| metadata.location),
+            elevation: metadata.map(|metadata| metadata.elevation),
+            gain: metadata.map(|metadata| metadata.gain),
+            region: metadata.map(|metadata| metadata.region as i32),
+            is_full_hotspot: info.is_full_hotspot,
+        }
+    }
+
+    fn same_info(&self, other: &Self) -> bool {
+        Self {
+            changed_at: other.changed_at,
+            ..self.clone()
+        } == *other
+    }
+}
+
+impl From<GatewayState> for GatewayInfo {
+    fn from(state: GatewayState) -> Self {
+        let metadata = match (state.location, state.elevation, state.gain, state.region) {
+            (Some(location), Some(elevation), Some(gain), Some(region)) => {
+                Region::try_from(region).ok().map(|region| GatewayMetadata {
+                    location,
+                    elevation,
+                    gain,
+                    region,
+                })
+            }
+            _ => None,
+        };
+        Self {
+            address: state.address,
+            metadata,
+            is_full_hotspot: state.is_full_hotspot,
+        }
+    }
+}
+
+impl sqlx::FromRow<'_, sqlx::postgres::PgRow> for GatewayState {
+    fn from_row(row: &sqlx::postgres::PgRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            address: row.try_get("address")?,
+            changed_at: row.try_get("changed_at")?,
+            location: row
+                .try_get::<Option<i64>, &str>("location")?
+                .map(|location| location as u64),
+            elevation: row.try_get("elevation")?,
+            gain: row.try_get("gain")?,
+            region: row.try_get("region")?,
+            is_full_hotspot: row.try_get("is_full_hotspot")?,
+        })
+    }
+}
+
+pub struct GatewayTracker {
+    pool: Pool<Postgres>,
+    metadata: Pool<Postgres>,
+    region_map: RegionMapReader,
+    interval: Duration,
+}
+
+impl ManagedTask for GatewayTracker {
+    fn start_task(
+        self: Box<Self>,
+        shutdown: triggered::Listener,
+    ) -> futures::future::LocalBoxFuture<'static, anyhow::Result<()>> {
+        Box::pin(
+            tokio::spawn(self.run(shutdown))
+                .map_err(anyhow::Error::from)
+                .and_then(|result| async move { result }),
+        )
+    }
+}
+
+impl GatewayTracker {
+    pub fn new(
+        pool: Pool<Postgres>,
+        metadata: Pool<Postgres>,
+        region_map: RegionMapReader,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            pool,
+            metadata,
+            region_map,
+            interval,
+        }
+    }
+
+    async fn run(self, mut shutdown: triggered::Listener) -> anyhow::Result<()> {
+        tracing::info!("starting gateway tracker");
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            tokio::select! {
+                biased;
+                _ = &mut shutdown => break,
+                _ = interval.tick() => {
+                    if let Err(err) = track_changes(&self.pool, &self.metadata, &self.region_map).await {
+                        tracing::error!(?err, "error in tracking changes to gateways");
+                    }
+                }
+            }
+        }
+
+        tracing::info!("stopping gateway tracker");
+        Ok(())
+    }
+}
+
+pub async fn track_changes(
+    pool: &Pool<Postgres>,
+    metadata: &Pool<Postgres>,
+    region_map: &RegionMapReader,
+) -> anyhow::Result<()> {
+    tracing::info!("looking for changes to gateways");
+    let latest = get_latest_states(pool).await?;
+    let checked_at = Utc::now();
+    let gateways = gateway_info::db::all_info_stream(metadata).map(|metadata| {
+        // The metadata row is refreshed when the gateway changes on chain, rows
+        // that were never refreshed fall back to the time of this check
+        let changed_at = metadata.refreshed_at.unwrap_or(checked_at);
+        (
+            GatewayInfo::chain_metadata_to_info(metadata, region_map),
+            changed_at,
+        )
+    });
+
+    let changes = identify_changes(gateways, &latest).await;
+    tracing::info!("recording gateway changes: {}", changes.len());
+
+    insert_states(pool, changes).await
+}
+
+/// States of the gateways whose info differs from their latest recorded
+/// state, or that have no recorded state yet, as of the time their info
+/// changed
+async fn identify_changes(
+    gateways: impl Stream<Item = (GatewayInfo, DateTime<Utc>)>,
+    latest: &HashMap<PublicKeyBinary, GatewayState>,
+) -> Vec<GatewayState> {
+    gateways
+        .map(|(info, changed_at)| GatewayState::new(&info, changed_at))
+        .filter(|state| {
+            future::ready(
+                !latest
+                    .get(&state.address)
+                    .is_some_and(|latest| latest.same_info(state)),
+            )
+        })
+        .collect()
+        .await
+}
+
+async fn get_latest_states(
+    pool: &Pool<Postgres>,
+) -> anyhow::Result<HashMap<PublicKeyBinary, GatewayState>> {
+    sqlx::query_as::<_, GatewayState>(
+        r#"
+        SELECT DISTINCT ON (address) * FROM gateway_changes
+        ORDER BY address, changed_at DESC
+        "#,
+    )
+    .fetch(pool)
+    .map_ok(|state| (state.address.clone(), state))
+    .try_collect()
+    .map_err(anyhow::Error::from)
+    .await
+}
+
+/// The state of a gateway at `at`, from its latest change at or before then
+pub async fn get_gateway_as_of(
+    db: impl PgExecutor<'_>,
+    address: &PublicKeyBinary,
+    at: DateTime<Utc>,
+) -> anyhow::Result<Option<GatewayState>> {
+    sqlx::query_as::<_, GatewayState>(
+        r#"
+        SELECT * FROM gateway_changes
+        WHERE address = $1 AND changed_at <= $2
+        ORDER BY changed_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(address)
+    .bind(at)
+    .fetch_optional(db)
+    .await
+    .map_err(anyhow::Error::from)
+}
+
+async fn insert_states(pool: &Pool<Postgres>, states: Vec<GatewayState>) -> anyhow::Result<()> {
+    let mut txn = pool.begin().await?;
+
+    const BATCH_SIZE: usize = (u16::MAX / 7) as usize;
+
+    for chunk in states.chunks(BATCH_SIZE) {
+        QueryBuilder::new(
+            r#"
+            INSERT INTO gateway_changes(address, changed_at, location, elevation, gain, region,
+                is_full_hotspot)
+            "#,
+        )
+        .push_values(chunk, |mut b, state| {
+            b.push_bind(&state.address)
+                .push_bind(state.changed_at)
+                .push_bind(state.location.map(|location| location as i64))
+                .push_bind(state.elevation)
+                .push_bind(state.gain)
+                .push_bind(state.region)
+                .push_bind(state.is_full_hotspot);
+        })
+        .push(" ON CONFLICT (address, changed_at) DO NOTHING")
+        .build()
+        .execute(&mut txn)
+        .await?;
+    }
+
+    txn.commit().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream;
+    use helium_crypto::{KeyTag, Keypair};
+
+    fn gateway_info(location: Option<u64>) -> GatewayInfo {
+        GatewayInfo {
+            address: Keypair::generate(KeyTag::default(), &mut rand::rngs::OsRng)
+                .public_key()
+                .to_vec()
+                .into(),
+            metadata: location.map(|location| GatewayMetadata {
+                location,
+                elevation: 10,
+                gain: 12,
+                region: Region::Us915,
+            }),
+            is_full_hotspot: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn records_new_and_changed_gateways() {
+        let earlier = Utc::now() - chrono::Duration::hours(2);
+        let refreshed = earlier + chrono::Duration::hours(1);
+        let unchanged = gateway_info(Some(1));
+        let moved = gateway_info(Some(2));
+        let new = gateway_info(None);
+
+        let latest = HashMap::from([
+            (
+                unchanged.address.clone(),
+                GatewayState::new(&unchanged, earlier),
+            ),
+            (moved.address.clone(), GatewayState::new(&moved, earlier)),
+        ]);
+        let mut moved_now = moved.clone();
+        moved_now.metadata.as_mut().unwrap().location = 3;
+
+        let changes = identify_changes(
+            stream::iter(vec![
+                (unchanged, refreshed),
+                (moved_now.clone(), refreshed),
+                (new.clone(), earlier),
+            ]),
+            &latest,
+        )
+        .await;
+
+        assert_eq!(
+            changes,
+            vec![
+                GatewayState::new(&moved_now, refreshed),
+                GatewayState::new(&new, earlier),
+            ]
+        );
+
+        let info = GatewayInfo::from(changes[0].clone());
+        assert_eq!(info.metadata.map(|metadata| metadata.location), Some(3));
+        assert!(GatewayInfo::from(changes[1].clone()).metadata.is_none());
+    }
+}
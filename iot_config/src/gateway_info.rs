@@ -113,6 +113,7 @@ impl TryFrom<GatewayInfo> for GatewayInfoProto {
 }
 
 pub(crate) mod db {
+    use chrono::{DateTime, Utc};
     use futures::stream::{Stream, StreamExt};
     use helium_crypto::PublicKeyBinary;
     use sqlx::{PgExecutor, Row};
@@ -129,10 +130,11 @@ pub(crate) mod db {
         pub elevation: i32,
         pub gain: i32,
         pub is_full_hotspot: bool,
+        pub refreshed_at: Option<DateTime<Utc>>,
     }
 
     const GET_METADATA_SQL: &str = r#"
-            select kta.entity_key, infos.location::bigint, CAST(infos.elevation AS integer), CAST(infos.gain as integer), infos.is_full_hotspot
+            select kta.entity_key, infos.location::bigint, CAST(infos.elevation AS integer), CAST(infos.gain as integer), infos.is_full_hotspot, infos.refreshed_at
             from iot_hotspot_infos infos
             join key_to_assets kta on infos.asset = kta.asset
         "#;
@@ -174,6 +176,7 @@ pub(crate) mod db {
                     .unwrap_or(DEFAULT_ELEVATION),
                 gain: row.get::<Option<i32>, &str>("gain").unwrap_or(DEFAULT_GAIN),
                 is_full_hotspot: row.get("is_full_hotspot"),
+                refreshed_at: row.get("refreshed_at"),
             })
         }
     }
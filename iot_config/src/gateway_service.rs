@@ -1,20 +1,21 @@
 use crate::{
     admin::AuthCache,
+    gateway_history,
     gateway_info::{self, GatewayInfo},
     org,
     region_map::RegionMapReader,
     telemetry, verify_public_key, GrpcResult, GrpcStreamResult, Settings,
 };
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{TimeZone, Utc};
 use file_store::traits::{MsgVerify, TimestampEncode};
 use futures::stream::StreamExt;
 use helium_crypto::{Keypair, PublicKey, PublicKeyBinary, Sign};
 use helium_proto::{
     services::iot_config::{
-        self, GatewayInfoReqV1, GatewayInfoResV1, GatewayInfoStreamReqV1, GatewayInfoStreamResV1,
-        GatewayLocationReqV1, GatewayLocationResV1, GatewayRegionParamsReqV1,
-        GatewayRegionParamsResV1,
+        self, GatewayInfoAtTimestampReqV1, GatewayInfoReqV1, GatewayInfoResV1,
+        GatewayInfoStreamReqV1, GatewayInfoStreamResV1, GatewayLocationReqV1, GatewayLocationResV1,
+        GatewayRegionParamsReqV1, GatewayRegionParamsResV1,
     },
     Message, Region,
 };
@@ -31,6 +32,7 @@ const CACHE_TTL: Duration = Duration::from_secs(60 * 60 * 3);
 pub struct GatewayService {
     auth_cache: AuthCache,
     gateway_cache: Arc<Cache<PublicKeyBinary, GatewayInfo>>,
+    pool: Pool<Postgres>,
     metadata_pool: Pool<Postgres>,
     region_map: RegionMapReader,
     signing_key: Arc<Keypair>,
@@ -40,6 +42,7 @@ pub struct GatewayService {
 impl GatewayService {
     pub fn new(
         settings: &Settings,
+        pool: Pool<Postgres>,
         metadata_pool: Pool<Postgres>,
         region_map: RegionMapReader,
         auth_cache: AuthCache,
@@ -52,6 +55,7 @@ impl GatewayService {
         Ok(Self {
             auth_cache,
             gateway_cache,
+            pool,
             metadata_pool,
             region_map,
             signing_key: Arc::new(settings.signing_keypair()?),
@@ -272,6 +276,49 @@ impl iot_config::Gateway for GatewayService {
         Ok(Response::new(resp))
     }
 
+    async fn info_at_timestamp(
+        &self,
+        request: Request<GatewayInfoAtTimestampReqV1>,
+    ) -> GrpcResult<GatewayInfoResV1> {
+        let request = request.into_inner();
+        telemetry::count_request("gateway", "info-at-timestamp");
+        custom_tracing::record_b58("pub_key", &request.address);
+        custom_tracing::record_b58("signer", &request.signer);
+
+        let signer = verify_public_key(&request.signer)?;
+        self.verify_request_signature(&signer, &request)?;
+
+        let address: &PublicKeyBinary = &request.address.into();
+        let timestamp = Utc
+            .timestamp_opt(request.timestamp as i64, 0)
+            .single()
+            .ok_or_else(|| Status::invalid_argument("invalid timestamp"))?;
+        tracing::debug!(pubkey = %address, %timestamp, "fetching gateway info at timestamp");
+
+        let gateway_info: GatewayInfo =
+            gateway_history::get_gateway_as_of(&self.pool, address, timestamp)
+                .await
+                .map_err(|_| Status::internal("error fetching gateway history"))?
+                .ok_or_else(|| {
+                    Status::not_found(format!(
+                        "gateway not recorded at {timestamp}: pubkey = {address}"
+                    ))
+                })?
+                .into();
+
+        let mut resp = GatewayInfoResV1 {
+            info: Some(gateway_info.try_into().map_err(|_| {
+                Status::internal("unexpected error converting gateway info to protobuf")
+            })?),
+            timestamp: Utc::now().encode_timestamp(),
+            signer: self.signing_key.public_key().into(),
+            signature: vec![],
+        };
+        resp.signature = self.sign_response(&resp.encode_to_vec())?;
+
+        Ok(Response::new(resp))
+    }
+
     type info_streamStream = GrpcStreamResult<GatewayInfoStreamResV1>;
     async fn info_stream(
         &self,
@@ -9,8 +9,8 @@ use helium_proto::services::{
 use iot_config::sub_dao_service::SubDaoService;
 use iot_config::{
     admin::AuthCache, admin_service::AdminService, db_cleaner::DbCleaner,
-    gateway_service::GatewayService, org, org_service::OrgService, region_map::RegionMapReader,
-    route_service::RouteService, settings::Settings, telemetry,
+    gateway_history::GatewayTracker, gateway_service::GatewayService, org, org_service::OrgService,
+    region_map::RegionMapReader, route_service::RouteService, settings::Settings, telemetry,
 };
 use std::{net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
 use task_manager::{ManagedTask, TaskManager};
@@ -74,8 +74,16 @@ impl Daemon {
 
         let signing_keypair = Arc::new(settings.signing_keypair()?);
 
+        let gateway_tracker = GatewayTracker::new(
+            pool.clone(),
+            metadata_pool.clone(),
+            region_map.clone(),
+            settings.gateway_tracker_interval,
+        );
+
         let gateway_svc = GatewayService::new(
             settings,
+            pool.clone(),
             metadata_pool.clone(),
             region_map.clone(),
             auth_cache.clone(),
@@ -125,6 +133,7 @@ impl Daemon {
         TaskManager::builder()
             .add_task(grpc_server)
             .add_task(db_cleaner)
+            .add_task(gateway_tracker)
             .build()
             .start()
             .await
@@ -20,6 +20,10 @@ pub struct Settings {
     pub admin: String,
     #[serde(with = "humantime_serde", default = "default_deleted_entry_retention")]
     pub deleted_entry_retention: Duration,
+    /// How often gateway info is checked for changes to record in the
+    /// gateway history. Default 1 hour
+    #[serde(with = "humantime_serde", default = "default_gateway_tracker_interval")]
+    pub gateway_tracker_interval: Duration,
     pub database: db_store::Settings,
     /// Settings passed to the db_store crate for connecting to
     /// the database for Solana on-chain data
@@ -35,6 +39,10 @@ fn default_listen_addr() -> SocketAddr {
     "0.0.0.0:8080".parse().unwrap()
 }
 
+fn default_gateway_tracker_interval() -> Duration {
+    humantime::parse_duration("1 hour").unwrap()
+}
+
 fn default_deleted_entry_retention() -> Duration {
     humantime::parse_duration("48 hours").unwrap()
 }
@@ -0,0 +1,9 @@
+create table gateway_invalid_reason (
+    id bytea not null,
+    report_type reporttype not null,
+    reason text not null,
+    bucket date not null,
+    count bigint not null default 0,
+    last_timestamp timestamptz not null,
+    primary key (id, report_type, reason, bucket)
+);
@@ -0,0 +1,3 @@
+alter table gateway_invalid_reason add column participant_side text not null default 'side_none';
+alter table gateway_invalid_reason drop constraint gateway_invalid_reason_pkey;
+alter table gateway_invalid_reason add primary key (id, report_type, reason, participant_side, bucket);
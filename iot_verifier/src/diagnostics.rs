@@ -0,0 +1,334 @@
+//
+// Read only diagnostics api
+// Provides a single view over the data the poc verifications use for any given gateway
+// The intent is to answer the question of why a gateway is not earning without
+// having to query the last_beacon, last_witness and reciprocity tables, the gateway cache,
+// the hex density map and the invalid report files separately
+//
+// Endpoints:
+//   GET /v1/gateways/:address/diagnostics
+//
+// *denylist*
+// The diagnostics server reads the runner's denylist, so it reports the
+// denylist the verifications are actually using
+//
+
+use crate::{
+    gateway_cache::GatewayCache,
+    gateway_invalid_reason::{GatewayInvalidReason, INVALID_REASON_RETENTION},
+    hex_density::HexDensityMap,
+    last_beacon::LastBeacon,
+    last_beacon_reciprocity::LastBeaconReciprocity,
+    poc::DEFAULT_TX_SCALE,
+    region_cache::RegionCache,
+    runner::RECIPROCITY_WINDOW,
+    witness_updater::WitnessUpdater,
+    Settings,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use denylist::DenyList;
+use futures::{future::LocalBoxFuture, TryFutureExt};
+use helium_crypto::PublicKeyBinary;
+use iot_config::client::Gateways;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::{future::IntoFuture, net::SocketAddr, str::FromStr, sync::Arc};
+use task_manager::ManagedTask;
+use tokio::{net::TcpListener, sync::RwLock};
+
+#[derive(Debug, Serialize)]
+pub struct GatewayDiagnostics {
+    pub address: PublicKeyBinary,
+    /// false if the gateway is unknown to the gateway cache,
+    /// all beacons and witnesses from such a gateway will be invalid
+    pub gateway_found: bool,
+    pub is_full_hotspot: Option<bool>,
+    /// asserted location as an h3 index hex string
+    pub location: Option<String>,
+    pub hex_scale: Option<Decimal>,
+    pub last_beacon: Option<DateTime<Utc>>,
+    pub last_witness: Option<DateTime<Utc>>,
+    pub reciprocity: ReciprocityStatus,
+    pub denylist: DenyListStatus,
+    pub region: Option<RegionStatus>,
+    pub invalid_reasons_since: DateTime<Utc>,
+    pub invalid_reasons: Vec<GatewayInvalidReason>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReciprocityStatus {
+    pub last_beacon_reciprocity: Option<DateTime<Utc>>,
+    /// a beacon received now would pass the beacon reciprocity check
+    pub beacon_reciprocity_valid: bool,
+    /// a witness received now would pass the witness reciprocity check
+    pub witness_reciprocity_valid: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DenyListStatus {
+    pub tag_name: u64,
+    pub denied: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegionStatus {
+    pub region: String,
+    pub channels: Vec<RegionChannel>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegionChannel {
+    pub channel_frequency: u64,
+    pub bandwidth: u64,
+    pub max_eirp: u64,
+}
+
+pub struct DiagnosticsServer<G> {
+    socket_addr: SocketAddr,
+    state: DiagnosticsState<G>,
+}
+
+#[derive(Clone)]
+struct DiagnosticsState<G> {
+    pool: PgPool,
+    gateway_cache: GatewayCache,
+    region_cache: RegionCache<G>,
+    hex_density_map: HexDensityMap,
+    witness_updater: WitnessUpdater,
+    deny_list: Arc<RwLock<DenyList>>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum DiagnosticsError {
+    #[error("invalid gateway address: {0}")]
+    InvalidAddress(String),
+    #[error("diagnostics lookup error: {0}")]
+    Lookup(#[from] anyhow::Error),
+}
+
+impl DiagnosticsError {
+    fn into_response(self) -> (StatusCode, String) {
+        match self {
+            Self::InvalidAddress(_) => (StatusCode::BAD_REQUEST, self.to_string()),
+            Self::Lookup(_) => {
+                tracing::warn!("diagnostics lookup failed: {self:?}");
+                (StatusCode::INTERNAL_SERVER_ERROR, self.to_string())
+            }
+        }
+    }
+}
+
+impl<G> ManagedTask for DiagnosticsServer<G>
+where
+    G: Gateways,
+{
+    fn start_task(
+        self: Box<Self>,
+        shutdown: triggered::Listener,
+    ) -> LocalBoxFuture<'static, anyhow::Result<()>> {
+        let handle = tokio::spawn(self.run(shutdown));
+        Box::pin(
+            handle
+                .map_err(anyhow::Error::from)
+                .and_then(|result| async move { result }),
+        )
+    }
+}
+
+impl<G> DiagnosticsServer<G>
+where
+    G: Gateways,
+{
+    pub async fn from_settings(
+        settings: &Settings,
+        gateways: G,
+        pool: PgPool,
+        gateway_cache: GatewayCache,
+        hex_density_map: HexDensityMap,
+        witness_updater: WitnessUpdater,
+        deny_list: Arc<RwLock<DenyList>>,
+    ) -> anyhow::Result<Option<Self>> {
+        let Some(socket_addr) = settings.diagnostics_listen else {
+            return Ok(None);
+        };
+        let region_cache = RegionCache::new(settings.region_params_refresh_interval, gateways)?;
+
+        Ok(Some(Self {
+            socket_addr,
+            state: DiagnosticsState {
+                pool,
+                gateway_cache,
+                region_cache,
+                hex_density_map,
+                witness_updater,
+                deny_list,
+            },
+        }))
+    }
+
+    pub async fn run(self, shutdown: triggered::Listener) -> anyhow::Result<()> {
+        tracing::info!(
+            listen = self.socket_addr.to_string(),
+            "starting diagnostics server"
+        );
+        let app = Router::new()
+            .route(
+                "/v1/gateways/:address/diagnostics",
+                get(gateway_diagnostics::<G>),
+            )
+            .with_state(self.state)
+            .layer(custom_tracing::http_layer::new_with_span(make_span));
+        let listener = TcpListener::bind(self.socket_addr).await?;
+        axum::serve(listener, app)
+            .with_graceful_shutdown(shutdown)
+            .into_future()
+            .await?;
+        tracing::info!("stopping diagnostics server");
+        Ok(())
+    }
+}
+
+async fn gateway_diagnostics<G>(
+    State(state): State<DiagnosticsState<G>>,
+    Path(address): Path<String>,
+) -> Result<Json<GatewayDiagnostics>, (StatusCode, String)>
+where
+    G: Gateways,
+{
+    let address = PublicKeyBinary::from_str(&address)
+        .map_err(|_| DiagnosticsError::InvalidAddress(address).into_response())?;
+    state
+        .diagnose(address, Utc::now())
+        .await
+        .map(Json)
+        .map_err(|err| DiagnosticsError::from(err).into_response())
+}
+
+impl<G> DiagnosticsState<G>
+where
+    G: Gateways,
+{
+    async fn diagnose(
+        &self,
+        address: PublicKeyBinary,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<GatewayDiagnostics> {
+        metrics::counter!("oracles_iot_verifier_diagnostics_request").increment(1);
+
+        let gateway_info = self.gateway_cache.resolve_gateway_info(&address).await.ok();
+        let metadata = gateway_info.as_ref().and_then(|info| info.metadata.clone());
+
+        let hex_scale = match &metadata {
+            Some(metadata) => Some(
+                self.hex_density_map
+                    .get(metadata.location)
+                    .await
+                    .unwrap_or(*DEFAULT_TX_SCALE),
+            ),
+            None => None,
+        };
+
+        let region = match &metadata {
+            Some(metadata) => match self.region_cache.resolve_region_info(metadata.region).await {
+                Ok(region_info) => Some(RegionStatus {
+                    region: region_info.region.as_str_name().to_string(),
+                    channels: region_info
+                        .region_params
+                        .iter()
+                        .map(|param| RegionChannel {
+                            channel_frequency: param.channel_frequency,
+                            bandwidth: param.bandwidth,
+                            max_eirp: param.max_eirp,
+                        })
+                        .collect(),
+                }),
+                Err(err) => {
+                    tracing::warn!("diagnostics failed to resolve region params: {err:?}");
+                    Some(RegionStatus {
+                        region: metadata.region.as_str_name().to_string(),
+                        channels: vec![],
+                    })
+                }
+            },
+            None => None,
+        };
+
+        let last_beacon = LastBeacon::last_timestamp(&self.pool, &address).await?;
+        let last_witness = self
+            .witness_updater
+            .get_last_witness(&address)
+            .await?
+            .map(|lw| lw.timestamp);
+        let last_beacon_reciprocity = LastBeaconReciprocity::get(&self.pool, &address)
+            .await?
+            .map(|lb| lb.timestamp);
+
+        let denylist = {
+            let deny_list = self.deny_list.read().await;
+            DenyListStatus {
+                tag_name: deny_list.tag_name,
+                denied: deny_list.contains_key(&address),
+            }
+        };
+
+        let invalid_reasons_since = now - INVALID_REASON_RETENTION;
+        let invalid_reasons =
+            GatewayInvalidReason::get_since(&self.pool, &address, invalid_reasons_since).await?;
+
+        Ok(GatewayDiagnostics {
+            gateway_found: gateway_info.is_some(),
+            is_full_hotspot: gateway_info.as_ref().map(|info| info.is_full_hotspot),
+            location: metadata
+                .as_ref()
+                .map(|metadata| format!("{:x}", metadata.location)),
+            hex_scale,
+            last_beacon,
+            last_witness,
+            reciprocity: ReciprocityStatus {
+                last_beacon_reciprocity,
+                beacon_reciprocity_valid: within_reciprocity_window(last_witness, now),
+                witness_reciprocity_valid: within_reciprocity_window(last_beacon_reciprocity, now),
+            },
+            denylist,
+            region,
+            invalid_reasons_since,
+            invalid_reasons,
+            address,
+        })
+    }
+}
+
+fn within_reciprocity_window(timestamp: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    timestamp.is_some_and(|ts| now - ts < *RECIPROCITY_WINDOW)
+}
+
+fn make_span(_request: &axum::http::Request<axum::body::Body>) -> tracing::Span {
+    tracing::info_span!(custom_tracing::DEFAULT_SPAN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+
+    #[test]
+    fn reciprocity_window() {
+        let now = Utc::now();
+        assert!(!within_reciprocity_window(None, now));
+        assert!(within_reciprocity_window(
+            Some(now - ChronoDuration::hours(47)),
+            now
+        ));
+        assert!(!within_reciprocity_window(
+            Some(now - ChronoDuration::hours(49)),
+            now
+        ));
+    }
+}
@@ -2,15 +2,19 @@
 // this cache is used to resolve gateway info for a given gateway
 // the gateway info is required by the poc verifications as part of verifying beacon and witness reports
 // the cache is populated / updated by the gateway_updater and is prepopulated at  startup
+// with a gateway history, gateway info as of a report's timestamp is resolved through
+// the iot config client instead
 //
 
 use crate::gateway_updater::MessageReceiver;
+use chrono::{DateTime, Utc};
 use helium_crypto::PublicKeyBinary;
-use iot_config::gateway_info::GatewayInfo;
+use iot_config::{client::Client as IotConfigClient, gateway_info::GatewayInfo};
 
 #[derive(Clone)]
 pub struct GatewayCache {
     gateway_cache_receiver: MessageReceiver,
+    history: Option<IotConfigClient>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -23,9 +27,40 @@ impl GatewayCache {
     pub fn new(gateway_cache_receiver: MessageReceiver) -> Self {
         Self {
             gateway_cache_receiver,
+            history: None,
         }
     }
 
+    /// Resolves gateway info as of a given time through the gateway history
+    /// of the iot config client
+    pub fn with_history(self, history: IotConfigClient) -> Self {
+        Self {
+            history: Some(history),
+            ..self
+        }
+    }
+
+    /// The gateway info at `at`. The current info from the cache is used if
+    /// there is no gateway history, it can't be reached or `at` is before the
+    /// recorded history of the gateway
+    pub async fn resolve_gateway_info_at(
+        &self,
+        address: &PublicKeyBinary,
+        at: DateTime<Utc>,
+    ) -> Result<GatewayInfo, GatewayCacheError> {
+        if let Some(history) = &self.history {
+            match history.resolve_gateway_history_at(address, at).await {
+                Ok(Some(info)) => return Ok(info),
+                Ok(None) => (),
+                Err(err) => {
+                    metrics::counter!("oracles_iot_verifier_gateway_history_error").increment(1);
+                    tracing::warn!(?err, pubkey = %address, "gateway history lookup failed");
+                }
+            }
+        }
+        self.resolve_gateway_info(address).await
+    }
+
     pub async fn resolve_gateway_info(
         &self,
         address: &PublicKeyBinary,
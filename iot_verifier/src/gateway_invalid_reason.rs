@@ -0,0 +1,166 @@
+//
+// DB functions related to the gateway_invalid_reason table
+// the gateway_invalid_reason table keeps a daily count of the invalid reasons
+// assigned to the beacon and witness reports of any given gateway, along with
+// the participant side of the report that failed. A witness report invalidated
+// by its beacon is counted against the witness gateway with the beaconer side
+// These counts are not used by the poc verifications, they exist purely to
+// back the diagnostics api and explain why a gateway is not earning
+//
+use crate::poc_report::ReportType;
+use chrono::{DateTime, NaiveDate, Utc};
+use helium_crypto::PublicKeyBinary;
+use helium_proto::services::poc_lora::{InvalidParticipantSide, InvalidReason};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, FromRow, Row};
+use std::{collections::HashMap, time::Duration};
+
+/// the period for which invalid reason counts are retained
+/// older daily buckets are removed by the purger
+pub const INVALID_REASON_RETENTION: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GatewayInvalidReason {
+    pub report_type: ReportType,
+    pub reason: String,
+    pub participant_side: String,
+    pub count: i64,
+    pub last_timestamp: DateTime<Utc>,
+}
+
+impl FromRow<'_, PgRow> for GatewayInvalidReason {
+    fn from_row(row: &PgRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            report_type: row.try_get("report_type")?,
+            reason: row.try_get("reason")?,
+            participant_side: row.try_get("participant_side")?,
+            count: row.try_get("count")?,
+            last_timestamp: row.try_get("last_timestamp")?,
+        })
+    }
+}
+
+type InvalidReasonKey = (
+    PublicKeyBinary,
+    ReportType,
+    InvalidReason,
+    InvalidParticipantSide,
+    NaiveDate,
+);
+
+/// Accumulates invalid reasons for a batch of reports prior to writing them
+/// to the db in a single statement
+/// Each report is counted in the daily bucket of its own timestamp
+#[derive(Default)]
+pub struct InvalidReasonBatch {
+    entries: HashMap<InvalidReasonKey, (i64, DateTime<Utc>)>,
+}
+
+impl InvalidReasonBatch {
+    pub fn push(
+        &mut self,
+        id: &PublicKeyBinary,
+        report_type: ReportType,
+        reason: InvalidReason,
+        participant_side: InvalidParticipantSide,
+        timestamp: DateTime<Utc>,
+    ) {
+        if reason == InvalidReason::ReasonNone {
+            return;
+        }
+        self.entries
+            .entry((
+                id.clone(),
+                report_type,
+                reason,
+                participant_side,
+                timestamp.date_naive(),
+            ))
+            .and_modify(|(count, last_timestamp)| {
+                *count += 1;
+                *last_timestamp = std::cmp::max(*last_timestamp, timestamp);
+            })
+            .or_insert((1, timestamp));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub async fn save<'c, E>(self, executor: E) -> anyhow::Result<()>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Postgres>,
+    {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let mut query_builder: sqlx::QueryBuilder<sqlx::Postgres> = sqlx::QueryBuilder::new(
+            " insert into gateway_invalid_reason (id, report_type, reason, participant_side, bucket, count, last_timestamp) ",
+        );
+        query_builder.push_values(
+            self.entries,
+            |mut builder,
+             ((id, report_type, reason, participant_side, bucket), (count, last_timestamp))| {
+                builder
+                    .push_bind(id.as_ref().to_vec())
+                    .push_bind(report_type)
+                    .push_bind(reason.as_str_name())
+                    .push_bind(participant_side.as_str_name())
+                    .push_bind(bucket)
+                    .push_bind(count)
+                    .push_bind(last_timestamp);
+            },
+        );
+        query_builder.push(
+            r#" on conflict (id, report_type, reason, participant_side, bucket) do update set
+                count = gateway_invalid_reason.count + EXCLUDED.count,
+                last_timestamp = greatest(gateway_invalid_reason.last_timestamp, EXCLUDED.last_timestamp) "#,
+        );
+        query_builder.build().execute(executor).await?;
+        Ok(())
+    }
+}
+
+impl GatewayInvalidReason {
+    /// returns the invalid reason counts for the given gateway,
+    /// aggregated over all daily buckets since `since`
+    pub async fn get_since<'c, E>(
+        executor: E,
+        id: &PublicKeyBinary,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<Self>>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Postgres>,
+    {
+        Ok(sqlx::query_as::<_, Self>(
+            r#"
+            select report_type, reason, participant_side, sum(count)::bigint as count,
+                max(last_timestamp) as last_timestamp
+            from gateway_invalid_reason
+            where id = $1 and bucket >= $2
+            group by report_type, reason, participant_side
+            order by count desc
+            "#,
+        )
+        .bind(id.as_ref())
+        .bind(since.date_naive())
+        .fetch_all(executor)
+        .await?)
+    }
+
+    pub async fn purge_older_than<'c, E>(
+        executor: E,
+        timestamp: DateTime<Utc>,
+    ) -> anyhow::Result<u64>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Postgres>,
+    {
+        Ok(
+            sqlx::query(r#" delete from gateway_invalid_reason where bucket < $1 "#)
+                .bind(timestamp.date_naive())
+                .execute(executor)
+                .await?
+                .rows_affected(),
+        )
+    }
+}
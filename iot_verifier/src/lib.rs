@@ -1,6 +1,8 @@
+pub mod diagnostics;
 pub mod entropy;
 pub mod entropy_loader;
 pub mod gateway_cache;
+pub mod gateway_invalid_reason;
 pub mod gateway_updater;
 pub mod hex_density;
 pub mod last_beacon;
@@ -20,9 +20,9 @@ use helium_proto::{
 use iot_config::client::sub_dao_client::SubDaoClient;
 use iot_config::client::Client as IotConfigClient;
 use iot_verifier::{
-    entropy_loader, gateway_cache::GatewayCache, gateway_updater::GatewayUpdater, loader,
-    packet_loader, purger, rewarder::Rewarder, runner, telemetry,
-    tx_scaler::Server as DensityScaler, witness_updater::WitnessUpdater, Settings,
+    diagnostics::DiagnosticsServer, entropy_loader, gateway_cache::GatewayCache,
+    gateway_updater::GatewayUpdater, loader, packet_loader, purger, rewarder::Rewarder, runner,
+    telemetry, tx_scaler::Server as DensityScaler, witness_updater::WitnessUpdater, Settings,
 };
 use price::PriceTracker;
 use std::{path, time::Duration};
@@ -95,7 +95,10 @@ impl Server {
         let (gateway_updater_receiver, gateway_updater_server) =
             GatewayUpdater::new(settings.gateway_refresh_interval, iot_config_client.clone())
                 .await?;
-        let gateway_cache = GatewayCache::new(gateway_updater_receiver.clone());
+        let mut gateway_cache = GatewayCache::new(gateway_updater_receiver.clone());
+        if settings.iot_config_client.resolve_history {
+            gateway_cache = gateway_cache.with_history(iot_config_client.clone());
+        }
 
         // *
         // setup the price tracker requirements
@@ -285,11 +288,25 @@ impl Server {
             runner_invalid_witness_sink,
             runner_poc_sink,
             density_scaler.hex_density_map.clone(),
+            witness_updater.clone(),
+        )
+        .await?;
+
+        // *
+        // setup the diagnostics api requirements
+        // *
+        let diagnostics_server = DiagnosticsServer::from_settings(
+            settings,
+            iot_config_client.clone(),
+            pool.clone(),
+            gateway_cache.clone(),
+            density_scaler.hex_density_map.clone(),
             witness_updater,
+            runner.deny_list.clone(),
         )
         .await?;
 
-        TaskManager::builder()
+        let mut builder = TaskManager::builder()
             .add_task(file_upload_server)
             .add_task(gateway_rewards_sink_server)
             .add_task(reward_manifests_sink_server)
@@ -310,10 +327,12 @@ impl Server {
             .add_task(loader)
             .add_task(pk_loader_server)
             .add_task(entropy_loader_server)
-            .add_task(rewarder)
-            .build()
-            .start()
-            .await
+            .add_task(rewarder);
+        if let Some(diagnostics_server) = diagnostics_server {
+            builder = builder.add_task(diagnostics_server);
+        }
+
+        builder.build().start().await
     }
 }
 
@@ -1,5 +1,5 @@
 use crate::{
-    entropy::ENTROPY_LIFESPAN,
+    entropy::{Entropy, ENTROPY_LIFESPAN},
     gateway_cache::{GatewayCache, GatewayCacheError},
     hex_density::HexDensityMap,
     last_beacon::LastBeacon,
@@ -12,12 +12,13 @@ use beacon;
 use chrono::{DateTime, DurationRound, Utc};
 use denylist::denylist::DenyList;
 use file_store::{
+    entropy_report::{ChainedEntropy, ChainedEntropyError, EntropyReport, ENTROPY_VERSION_CHAINED},
     iot_beacon_report::{IotBeaconIngestReport, IotBeaconReport},
     iot_valid_poc::IotVerifiedWitnessReport,
     iot_witness_report::IotWitnessIngestReport,
 };
 use h3o::{CellIndex, LatLng, Resolution};
-use helium_crypto::PublicKeyBinary;
+use helium_crypto::{PublicKey, PublicKeyBinary};
 use helium_proto::{
     services::poc_lora::{
         invalid_details, InvalidDetails, InvalidParticipantSide, InvalidReason, VerificationStatus,
@@ -53,7 +54,7 @@ lazy_static! {
     /// again when an inactive hotspot's h3 index would otherwise be garbage-collected
     /// from density scaling calculations and not finding a value on subsequent lookups
     /// would disqualify the hotspot from validating further beacons
-    static ref DEFAULT_TX_SCALE: Decimal = Decimal::new(2000, 4);
+    pub static ref DEFAULT_TX_SCALE: Decimal = Decimal::new(2000, 4);
     /// max permitted lag between the first witness and all subsequent witnesses
     static ref MAX_WITNESS_LAG: chrono::Duration = chrono::Duration::milliseconds(1500);
     /// max permitted lag between the beaconer and a witness
@@ -73,7 +74,20 @@ pub struct Poc {
     entropy_start: DateTime<Utc>,
     entropy_end: DateTime<Utc>,
     entropy_version: i32,
+    entropy_chain: EntropyChain,
 }
+
+/// The outcome of verifying the remote entropy a poc was built on against
+/// the entropy chain and the entropy oracle keys.
+/// All reports of a poc share the same entropy so this is checked once per poc
+#[derive(Debug, PartialEq)]
+pub enum EntropyChain {
+    /// entropy versions prior to chained entropy carry nothing to verify
+    Unchained,
+    Verified,
+    Invalid(ChainedEntropyError),
+}
+
 #[derive(Clone, Debug)]
 pub struct VerifyBeaconResult {
     pub result: VerificationStatus,
@@ -97,6 +111,7 @@ impl Poc {
         witness_reports: Vec<IotWitnessIngestReport>,
         entropy_start: DateTime<Utc>,
         entropy_version: i32,
+        entropy_chain: EntropyChain,
     ) -> Self {
         let entropy_end = entropy_start + ENTROPY_LIFESPAN;
         Self {
@@ -107,6 +122,7 @@ impl Poc {
             entropy_start,
             entropy_end,
             entropy_version,
+            entropy_chain,
         }
     }
 
@@ -124,7 +140,10 @@ impl Poc {
         let beaconer_pub_key = beacon.pub_key.clone();
 
         // if no gateway info for the gateway available then render beacon invalid
-        let beaconer_info = match gateway_cache.resolve_gateway_info(&beaconer_pub_key).await {
+        let beaconer_info = match gateway_cache
+            .resolve_gateway_info_at(&beaconer_pub_key, self.beacon_report.received_timestamp)
+            .await
+        {
             Ok(res) => res,
             Err(GatewayCacheError::GatewayNotFound(_)) => {
                 return Ok(VerifyBeaconResult::gateway_not_found())
@@ -156,6 +175,7 @@ impl Poc {
             deny_list,
             self.entropy_start,
             self.entropy_end,
+            &self.entropy_chain,
             self.entropy_version,
             last_beacon,
             &self.beacon_report,
@@ -286,7 +306,10 @@ impl Poc {
         let witness = &witness_report.report;
         let witness_pub_key = witness.pub_key.clone();
         // get the witness gateway info
-        let witness_info = match gateway_cache.resolve_gateway_info(&witness_pub_key).await {
+        let witness_info = match gateway_cache
+            .resolve_gateway_info_at(&witness_pub_key, witness_report.received_timestamp)
+            .await
+        {
             Ok(res) => res,
             Err(GatewayCacheError::GatewayNotFound(_)) => {
                 return Ok(IotVerifiedWitnessReport::invalid(
@@ -340,6 +363,7 @@ impl Poc {
             deny_list,
             self.entropy_start,
             self.entropy_end,
+            &self.entropy_chain,
             witness_report,
             &witness_info,
             &self.beacon_report,
@@ -379,6 +403,7 @@ pub fn do_beacon_verifications(
     deny_list: &DenyList,
     entropy_start: DateTime<Utc>,
     entropy_end: DateTime<Utc>,
+    entropy_chain: &EntropyChain,
     entropy_version: i32,
     last_beacon: Option<LastBeacon>,
     beacon_report: &IotBeaconIngestReport,
@@ -401,7 +426,12 @@ pub fn do_beacon_verifications(
         }
     };
     verify_denylist(&beacon_report.report.pub_key, deny_list)?;
-    verify_entropy(entropy_start, entropy_end, beacon_received_ts)?;
+    verify_entropy(
+        entropy_start,
+        entropy_end,
+        entropy_chain,
+        beacon_received_ts,
+    )?;
     verify_gw_capability(beaconer_info.is_full_hotspot)?;
     verify_beacon_schedule(&last_beacon, beacon_received_ts, beacon_interval)?;
     verify_beacon_payload(
@@ -424,6 +454,7 @@ pub fn do_witness_verifications(
     deny_list: &DenyList,
     entropy_start: DateTime<Utc>,
     entropy_end: DateTime<Utc>,
+    entropy_chain: &EntropyChain,
     witness_report: &IotWitnessIngestReport,
     witness_info: &GatewayInfo,
     beacon_report: &IotBeaconIngestReport,
@@ -457,6 +488,7 @@ pub fn do_witness_verifications(
     verify_entropy(
         entropy_start,
         entropy_end,
+        entropy_chain,
         witness_report.received_timestamp,
     )?;
     verify_witness_lag(
@@ -594,9 +626,12 @@ fn verify_edge_denylist(
 
 /// verify remote entropy
 /// if report's received timestamp is outside of entopy start/end then return invalid
+/// if the entropy is chained entropy which failed verification then return invalid,
+/// the packet having been derived from entropy the oracle did not vouch for
 fn verify_entropy(
     entropy_start: DateTime<Utc>,
     entropy_end: DateTime<Utc>,
+    entropy_chain: &EntropyChain,
     received_ts: DateTime<Utc>,
 ) -> GenericVerifyResult {
     if received_ts.timestamp() < entropy_start.timestamp()
@@ -617,9 +652,65 @@ fn verify_entropy(
             details: None,
         });
     }
+    if let EntropyChain::Invalid(err) = entropy_chain {
+        let reason = match err {
+            ChainedEntropyError::InvalidSignature => InvalidReason::BadSignature,
+            _ => InvalidReason::BadEntropy,
+        };
+        tracing::debug!(
+            "report verification failed, reason: {:?}. chained entropy: {err}",
+            reason,
+        );
+        return Err(InvalidResponse {
+            reason,
+            details: None,
+        });
+    }
     Ok(())
 }
 
+/// verify chained ( version 1 ) entropy
+/// the entropy must be signed by one of the entropy oracle keys and, if the
+/// previous entropy is still known, commit to it
+/// the previous entropy may legitimately be missing if it was purged
+pub async fn verify_entropy_chain(
+    pool: &PgPool,
+    entropy_sign_keys: &[PublicKey],
+    data: &[u8],
+    timestamp: DateTime<Utc>,
+    version: i32,
+) -> anyhow::Result<EntropyChain> {
+    if version as u32 != ENTROPY_VERSION_CHAINED {
+        return Ok(EntropyChain::Unchained);
+    }
+    let prev_id = match ChainedEntropy::from_bytes(data) {
+        Ok(chained) => chained.prev,
+        Err(err) => return Ok(EntropyChain::Invalid(err)),
+    };
+    let prev = if prev_id == ChainedEntropy::GENESIS {
+        None
+    } else {
+        let prev = Entropy::get(pool, &prev_id.to_vec()).await?;
+        if prev.is_none() {
+            metrics::counter!("oracles_iot_verifier_entropy_chain_gap").increment(1);
+        }
+        prev
+    };
+    let report = EntropyReport {
+        data: data.to_vec(),
+        timestamp,
+        version: version as u32,
+    };
+    let prev_data = prev.as_ref().map(|prev| prev.data.as_slice());
+    match report.verify_chained(entropy_sign_keys, prev_data) {
+        Ok(()) => Ok(EntropyChain::Verified),
+        Err(err) => {
+            metrics::counter!("oracles_iot_verifier_entropy_invalid").increment(1);
+            Ok(EntropyChain::Invalid(err))
+        }
+    }
+}
+
 /// verify beacon construction
 /// generate a beacon from the beaconers region data and the current entropy
 /// compare the generated beacon with the received beacon
@@ -1241,20 +1332,75 @@ mod tests {
         let now = Utc::now();
         let entropy_start = now - Duration::seconds(60);
         let entropy_end = now - Duration::seconds(10);
-        assert!(verify_entropy(entropy_start, entropy_end, now - Duration::seconds(30)).is_ok());
+        assert!(verify_entropy(
+            entropy_start,
+            entropy_end,
+            &EntropyChain::Unchained,
+            now - Duration::seconds(30)
+        )
+        .is_ok());
         assert_eq!(
             Err(InvalidResponse {
                 reason: InvalidReason::EntropyExpired,
                 details: None
             }),
-            verify_entropy(entropy_start, entropy_end, now - Duration::seconds(1))
+            verify_entropy(
+                entropy_start,
+                entropy_end,
+                &EntropyChain::Unchained,
+                now - Duration::seconds(1)
+            )
         );
         assert_eq!(
             Err(InvalidResponse {
                 reason: InvalidReason::EntropyExpired,
                 details: None
             }),
-            verify_entropy(entropy_start, entropy_end, now - Duration::seconds(65))
+            verify_entropy(
+                entropy_start,
+                entropy_end,
+                &EntropyChain::Unchained,
+                now - Duration::seconds(65)
+            )
+        );
+    }
+
+    #[test]
+    fn test_verify_entropy_chain() {
+        let now = Utc::now();
+        let entropy_start = now - Duration::seconds(60);
+        let entropy_end = now - Duration::seconds(10);
+        let received_ts = now - Duration::seconds(30);
+        assert!(verify_entropy(
+            entropy_start,
+            entropy_end,
+            &EntropyChain::Verified,
+            received_ts
+        )
+        .is_ok());
+        assert_eq!(
+            Err(InvalidResponse {
+                reason: InvalidReason::BadSignature,
+                details: None
+            }),
+            verify_entropy(
+                entropy_start,
+                entropy_end,
+                &EntropyChain::Invalid(ChainedEntropyError::InvalidSignature),
+                received_ts
+            )
+        );
+        assert_eq!(
+            Err(InvalidResponse {
+                reason: InvalidReason::BadEntropy,
+                details: None
+            }),
+            verify_entropy(
+                entropy_start,
+                entropy_end,
+                &EntropyChain::Invalid(ChainedEntropyError::BrokenChain),
+                received_ts
+            )
         );
     }
 
@@ -1564,6 +1710,7 @@ mod tests {
             &deny_list,
             entropy_start,
             entropy_end,
+            &EntropyChain::Unchained,
             ENTROPY_VERSION,
             None,
             &beacon_report1,
@@ -1587,6 +1734,7 @@ mod tests {
             &deny_list,
             entropy_start,
             entropy_end,
+            &EntropyChain::Unchained,
             ENTROPY_VERSION,
             None,
             &beacon_report1,
@@ -1609,6 +1757,7 @@ mod tests {
             &deny_list,
             entropy_start,
             entropy_end,
+            &EntropyChain::Unchained,
             ENTROPY_VERSION,
             None,
             &beacon_report2,
@@ -1634,6 +1783,7 @@ mod tests {
             &deny_list,
             entropy_start,
             entropy_end,
+            &EntropyChain::Unchained,
             ENTROPY_VERSION,
             Some(last_beacon3),
             &beacon_report3,
@@ -1656,6 +1806,7 @@ mod tests {
             &deny_list,
             entropy_start,
             entropy_end,
+            &EntropyChain::Unchained,
             ENTROPY_VERSION,
             None,
             &beacon_report4,
@@ -1677,6 +1828,7 @@ mod tests {
             &deny_list,
             entropy_start,
             entropy_end,
+            &EntropyChain::Unchained,
             ENTROPY_VERSION,
             None,
             &beacon_report5,
@@ -1698,6 +1850,7 @@ mod tests {
             &deny_list,
             entropy_start,
             entropy_end,
+            &EntropyChain::Unchained,
             ENTROPY_VERSION,
             None,
             &beacon_report6,
@@ -1737,6 +1890,7 @@ mod tests {
             &deny_list,
             entropy_start,
             entropy_end,
+            &EntropyChain::Unchained,
             &witness_report1,
             &witness_info,
             &beacon_report,
@@ -1757,6 +1911,7 @@ mod tests {
             &deny_list,
             entropy_start,
             entropy_end,
+            &EntropyChain::Unchained,
             &witness_report2,
             &witness_info,
             &beacon_report,
@@ -1777,6 +1932,7 @@ mod tests {
             &deny_list,
             entropy_start,
             entropy_end,
+            &EntropyChain::Unchained,
             &witness_report3,
             &witness_info,
             &beacon_report,
@@ -1798,6 +1954,7 @@ mod tests {
             &deny_list,
             entropy_start,
             entropy_end,
+            &EntropyChain::Unchained,
             &witness_report4,
             &witness_info4,
             &beacon_report,
@@ -1818,6 +1975,7 @@ mod tests {
             &deny_list,
             entropy_start,
             entropy_end,
+            &EntropyChain::Unchained,
             &witness_report5,
             &witness_info,
             &beacon_report,
@@ -1839,6 +1997,7 @@ mod tests {
             &deny_list,
             entropy_start,
             entropy_end,
+            &EntropyChain::Unchained,
             &witness_report6,
             &witness_info6,
             &beacon_report,
@@ -1860,6 +2019,7 @@ mod tests {
             &deny_list,
             entropy_start,
             entropy_end,
+            &EntropyChain::Unchained,
             &witness_report7,
             &witness_info7,
             &beacon_report,
@@ -1881,6 +2041,7 @@ mod tests {
             &deny_list,
             entropy_start,
             entropy_end,
+            &EntropyChain::Unchained,
             &witness_report8,
             &witness_info8,
             &beacon_report,
@@ -1901,6 +2062,7 @@ mod tests {
             &deny_list,
             entropy_start,
             entropy_end,
+            &EntropyChain::Unchained,
             &witness_report9,
             &witness_info,
             &beacon_report,
@@ -1922,6 +2084,7 @@ mod tests {
             &deny_list,
             entropy_start,
             entropy_end,
+            &EntropyChain::Unchained,
             &witness_report10,
             &witness_info10,
             &beacon_report,
@@ -1944,6 +2107,7 @@ mod tests {
             &deny_list,
             entropy_start,
             entropy_end,
+            &EntropyChain::Unchained,
             &witness_report11,
             &witness_info11,
             &beacon_report,
@@ -1965,6 +2129,7 @@ mod tests {
             &deny_list,
             entropy_start,
             entropy_end,
+            &EntropyChain::Unchained,
             &witness_report12,
             &witness_info12,
             &beacon_report,
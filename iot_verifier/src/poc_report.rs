@@ -20,7 +20,7 @@ const REPORT_INSERT_SQL: &str = "insert into poc_report (
     status
 ) ";
 
-#[derive(sqlx::Type, Serialize, Deserialize, Debug)]
+#[derive(sqlx::Type, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[sqlx(type_name = "reporttype", rename_all = "lowercase")]
 pub enum ReportType {
     Witness,
@@ -5,7 +5,13 @@
 // Entropy data is purged without writing an invalid report as this data has no downstream value
 //
 
-use crate::{entropy::Entropy, poc_report::Report, telemetry};
+use crate::{
+    entropy::Entropy,
+    gateway_invalid_reason::{GatewayInvalidReason, InvalidReasonBatch, INVALID_REASON_RETENTION},
+    poc_report::{Report, ReportType},
+    telemetry,
+};
+use chrono::Utc;
 use file_store::{
     file_sink::FileSinkClient,
     iot_beacon_report::IotBeaconIngestReport,
@@ -115,9 +121,13 @@ impl Purger {
         tracing::info!("purging {:?} stale beacons", stale_beacons.len());
 
         let tx = Mutex::new(self.pool.begin().await?);
+        let invalid_reasons = Mutex::new(InvalidReasonBatch::default());
         stream::iter(stale_beacons)
             .for_each_concurrent(PURGER_WORKERS, |report| async {
-                match self.handle_purged_beacon(&tx, report).await {
+                match self
+                    .handle_purged_beacon(&tx, &invalid_reasons, report)
+                    .await
+                {
                     Ok(()) => (),
                     Err(err) => {
                         tracing::warn!("failed to purge beacon: {err:?}")
@@ -127,6 +137,8 @@ impl Purger {
             .await;
         self.invalid_beacon_sink.commit().await?;
         tx.into_inner().commit().await?;
+        self.save_invalid_reasons(invalid_reasons.into_inner())
+            .await;
 
         let witness_stale_period = self.base_stale_period + self.witness_stale_period;
         tracing::info!(
@@ -139,9 +151,13 @@ impl Purger {
         tracing::info!("purging {num_stale_witnesses} stale witnesses");
 
         let tx = Mutex::new(self.pool.begin().await?);
+        let invalid_reasons = Mutex::new(InvalidReasonBatch::default());
         stream::iter(stale_witnesses)
             .for_each_concurrent(PURGER_WORKERS, |report| async {
-                match self.handle_purged_witness(&tx, report).await {
+                match self
+                    .handle_purged_witness(&tx, &invalid_reasons, report)
+                    .await
+                {
                     Ok(()) => (),
                     Err(err) => {
                         tracing::warn!("failed to purge witness: {err:?}")
@@ -151,6 +167,8 @@ impl Purger {
             .await;
         self.invalid_witness_sink.commit().await?;
         tx.into_inner().commit().await?;
+        self.save_invalid_reasons(invalid_reasons.into_inner())
+            .await;
         tracing::info!("completed purging {num_stale_witnesses} stale witnesses");
 
         // purge any stale entropy, no need to output anything to s3 here
@@ -159,12 +177,28 @@ impl Purger {
             self.base_stale_period + self.entropy_stale_period,
         )
         .await;
+
+        // purge any gateway invalid reason counts which have aged out of the diagnostics window
+        _ = GatewayInvalidReason::purge_older_than(
+            &self.pool,
+            Utc::now() - INVALID_REASON_RETENTION,
+        )
+        .await;
         Ok(())
     }
 
+    // stale reports are counted alongside the runner's invalid reasons for the diagnostics api
+    // a failure to save them should never block the purge
+    async fn save_invalid_reasons(&self, invalid_reasons: InvalidReasonBatch) {
+        if let Err(err) = invalid_reasons.save(&self.pool).await {
+            tracing::warn!("failed to save gateway invalid reasons: {err:?}");
+        }
+    }
+
     async fn handle_purged_beacon(
         &self,
         tx: &Mutex<sqlx::Transaction<'_, Postgres>>,
+        invalid_reasons: &Mutex<InvalidReasonBatch>,
         db_beacon: Report,
     ) -> anyhow::Result<()> {
         let beacon_buf: &[u8] = &db_beacon.report_data;
@@ -191,6 +225,13 @@ impl Purger {
             .await?;
         // delete the report from the DB
         Report::delete_report(tx.lock().await.deref_mut(), &beacon_id).await?;
+        invalid_reasons.lock().await.push(
+            &beacon.pub_key,
+            ReportType::Beacon,
+            InvalidReason::Stale,
+            InvalidParticipantSide::Beaconer,
+            received_timestamp,
+        );
         telemetry::decrement_num_beacons();
         Ok(())
     }
@@ -198,12 +239,14 @@ impl Purger {
     async fn handle_purged_witness(
         &self,
         tx: &Mutex<sqlx::Transaction<'_, Postgres>>,
+        invalid_reasons: &Mutex<InvalidReasonBatch>,
         db_witness: Report,
     ) -> anyhow::Result<()> {
         let witness_buf: &[u8] = &db_witness.report_data;
         let witness_report = IotWitnessIngestReport::decode(witness_buf)?;
         let witness_id = witness_report.ingest_id();
         let received_timestamp = witness_report.received_timestamp;
+        let pub_key = witness_report.report.pub_key.clone();
         let invalid_witness_report_proto: LoraInvalidWitnessReportV1 = IotInvalidWitnessReport {
             received_timestamp,
             report: witness_report.report,
@@ -222,6 +265,13 @@ impl Purger {
 
         // delete the report from the DB
         Report::delete_report(tx.lock().await.deref_mut(), &witness_id).await?;
+        invalid_reasons.lock().await.push(
+            &pub_key,
+            ReportType::Witness,
+            InvalidReason::Stale,
+            InvalidParticipantSide::Witness,
+            received_timestamp,
+        );
         Ok(())
     }
 }
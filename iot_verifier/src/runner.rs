@@ -32,10 +32,11 @@
 
 use crate::{
     gateway_cache::GatewayCache,
+    gateway_invalid_reason::InvalidReasonBatch,
     hex_density::HexDensityMap,
     last_beacon_reciprocity::LastBeaconReciprocity,
-    poc::{Poc, VerifyBeaconResult},
-    poc_report::Report,
+    poc::{self, Poc, VerifyBeaconResult},
+    poc_report::{Report, ReportType},
     region_cache::RegionCache,
     reward_share::GatewayPocShare,
     telemetry,
@@ -54,6 +55,7 @@ use file_store::{
     SCALING_PRECISION,
 };
 use futures::{future::LocalBoxFuture, stream, StreamExt, TryFutureExt};
+use helium_crypto::PublicKey;
 use helium_proto::services::poc_lora::{
     InvalidDetails, InvalidParticipantSide, InvalidReason, LoraInvalidBeaconReportV1,
     LoraInvalidWitnessReportV1, LoraPocV1, VerificationStatus,
@@ -63,9 +65,12 @@ use lazy_static::lazy_static;
 use rust_decimal::{Decimal, MathematicalOps};
 use rust_decimal_macros::dec;
 use sqlx::PgPool;
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 use task_manager::ManagedTask;
-use tokio::time::{self, MissedTickBehavior};
+use tokio::{
+    sync::RwLock,
+    time::{self, MissedTickBehavior},
+};
 
 /// the cadence in seconds at which the DB is polled for ready POCs
 const DB_POLL_TIME: Duration = Duration::from_secs(30);
@@ -78,7 +83,7 @@ const HIP15_TX_REWARD_UNIT_CAP: Decimal = Decimal::TWO;
 
 lazy_static! {
     /// the duration in which a beaconer or witnesser must have a valid opposite report from
-    static ref RECIPROCITY_WINDOW: ChronoDuration = ChronoDuration::hours(48);
+    pub static ref RECIPROCITY_WINDOW: ChronoDuration = ChronoDuration::hours(48);
 }
 
 pub struct Runner<G> {
@@ -87,9 +92,9 @@ pub struct Runner<G> {
     pub max_witnesses_per_poc: u64,
     pub beacon_max_retries: u64,
     pub witness_max_retries: u64,
-    pub deny_list_latest_url: String,
     pub deny_list_trigger_interval: Duration,
-    pub deny_list: DenyList,
+    /// shared with the diagnostics api, kept up to date by the runner
+    pub deny_list: Arc<RwLock<DenyList>>,
     pub gateway_cache: GatewayCache,
     pub region_cache: RegionCache<G>,
     pub invalid_beacon_sink: FileSinkClient<LoraInvalidBeaconReportV1>,
@@ -97,6 +102,8 @@ pub struct Runner<G> {
     pub poc_sink: FileSinkClient<LoraPocV1>,
     pub hex_density_map: HexDensityMap,
     pub witness_updater: WitnessUpdater,
+    /// keys of the entropy oracles, used to verify chained entropy
+    pub entropy_sign_keys: Vec<PublicKey>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -147,7 +154,6 @@ where
         let max_witnesses_per_poc = settings.max_witnesses_per_poc;
         let beacon_max_retries = settings.beacon_max_retries;
         let witness_max_retries = settings.witness_max_retries;
-        let deny_list_latest_url = settings.denylist.denylist_url.clone();
         let mut deny_list = DenyList::new(&settings.denylist)?;
         let region_cache = RegionCache::new(settings.region_params_refresh_interval, gateways)?;
         // force update to latest in order to update the tag name
@@ -156,7 +162,7 @@ where
         // updating it here forces the tag name to be refreshed
         // which will see it carry through to invalid poc reports
         // if we cant update such as github being down then ignore
-        match deny_list.update_to_latest(&deny_list_latest_url).await {
+        match deny_list.update_to_latest().await {
             Ok(()) => (),
             Err(err) => {
                 tracing::error!("error whilst updating denylist to latest: {err:?}");
@@ -171,14 +177,14 @@ where
             region_cache,
             beacon_max_retries,
             witness_max_retries,
-            deny_list_latest_url,
             deny_list_trigger_interval: settings.denylist.trigger_interval,
-            deny_list,
+            deny_list: Arc::new(RwLock::new(deny_list)),
             invalid_beacon_sink,
             invalid_witness_sink,
             poc_sink,
             hex_density_map,
             witness_updater,
+            entropy_sign_keys: settings.entropy_sign_keys()?,
         })
     }
 
@@ -220,12 +226,11 @@ where
         // sink any errors whilst updating the denylist
         // the verifier should not stop just because github
         // could not be reached for example
-        match self
-            .deny_list
-            .update_to_latest(&self.deny_list_latest_url)
-            .await
-        {
-            Ok(()) => (),
+        // fetch the update without holding the lock, so verifications and
+        // diagnostics are only blocked while the updated denylist is swapped in
+        let current = self.deny_list.read().await.clone();
+        match current.latest().await {
+            Ok(latest) => *self.deny_list.write().await = latest,
             Err(e) => tracing::warn!("failed to update denylist: {e}"),
         }
         tracing::info!("completed handling denylist tick");
@@ -295,6 +300,16 @@ where
             .map(|w| IotWitnessIngestReport::decode(w.report_data.as_slice()))
             .collect::<Result<Vec<IotWitnessIngestReport>, _>>()?;
 
+        // verify the entropy the beacon was built on, shared by all of its witnesses
+        let entropy_chain = poc::verify_entropy_chain(
+            &self.pool,
+            &self.entropy_sign_keys,
+            &beacon_report.report.remote_entropy,
+            entropy_start_time,
+            entropy_version,
+        )
+        .await?;
+
         // create the struct defining this POC
         let poc = Poc::new(
             self.pool.clone(),
@@ -303,6 +318,7 @@ where
             witnesses,
             entropy_start_time,
             entropy_version,
+            entropy_chain,
         )
         .await;
 
@@ -311,13 +327,15 @@ where
     }
 
     async fn verify_poc(&self, mut poc: Poc) -> anyhow::Result<()> {
+        // verify against a copy so the lock is not held across the verifications
+        let deny_list = self.deny_list.read().await.clone();
         // verify beacon
         let beacon_verify_result = poc
             .verify_beacon(
                 &self.hex_density_map,
                 &self.gateway_cache,
                 &self.region_cache,
-                &self.deny_list,
+                &deny_list,
             )
             .await?;
 
@@ -333,7 +351,7 @@ where
                         &beacon_info,
                         &self.hex_density_map,
                         &self.gateway_cache,
-                        &self.deny_list,
+                        &deny_list,
                         &self.witness_updater,
                     )
                     .await?;
@@ -425,6 +443,16 @@ where
 
         // collect all the invalid reasons, we will use these later for metrics
         let invalid_reasons = collect_invalid_witness_reasons(&unselected_witnesses);
+        let mut invalid_reason_batch = InvalidReasonBatch::default();
+        for witness in &unselected_witnesses {
+            invalid_reason_batch.push(
+                &witness.report.pub_key,
+                ReportType::Witness,
+                witness.invalid_reason,
+                witness.participant_side,
+                witness.received_timestamp,
+            );
+        }
 
         let iot_poc = create_iot_poc(
             poc,
@@ -457,6 +485,7 @@ where
         }
 
         Report::delete_poc(&self.pool, &packet_data).await?;
+        self.save_invalid_reasons(invalid_reason_batch).await;
 
         // write out metrics for any witness which failed verification
         fire_invalid_witness_metric(invalid_reasons);
@@ -516,7 +545,22 @@ where
         // we will have to clean out any successful writes of other witnesses
         // and also the invalid poc
         // so if a report fails from this point on, it shall be lost for ever more
+        let mut invalid_reason_batch = InvalidReasonBatch::default();
+        invalid_reason_batch.push(
+            &beacon.pub_key,
+            ReportType::Beacon,
+            beacon_invalid_reason,
+            InvalidParticipantSide::Beaconer,
+            poc.beacon_report.received_timestamp,
+        );
         for witness_report in poc.witness_reports {
+            invalid_reason_batch.push(
+                &witness_report.report.pub_key,
+                ReportType::Witness,
+                beacon_invalid_reason,
+                InvalidParticipantSide::Beaconer,
+                witness_report.received_timestamp,
+            );
             let invalid_witness_report: IotInvalidWitnessReport = IotInvalidWitnessReport {
                 received_timestamp: witness_report.received_timestamp,
                 report: witness_report.report,
@@ -542,10 +586,19 @@ where
         }
         // done with these poc reports, purge em from the db
         Report::delete_poc(&self.pool, &beacon_id).await?;
+        self.save_invalid_reasons(invalid_reason_batch).await;
         telemetry::decrement_num_beacons();
         Ok(())
     }
 
+    // the invalid reason counts are informational only and serve the diagnostics api
+    // a failure to save them should never block the processing of a poc
+    async fn save_invalid_reasons(&self, invalid_reason_batch: InvalidReasonBatch) {
+        if let Err(err) = invalid_reason_batch.save(&self.pool).await {
+            tracing::warn!("failed to save gateway invalid reasons: {err:?}");
+        }
+    }
+
     // beacon recropocity checks require that a beaconer must have a prior valid witness report within
     // the RECIPROCITY_WINDOW ( default 48 hours )
     async fn verify_beacon_reciprocity(
@@ -1,8 +1,9 @@
 use anyhow::bail;
 use config::{Config, Environment, File};
+use helium_crypto::PublicKey;
 use humantime_serde::re::humantime;
 use serde::Deserialize;
-use std::{path::Path, time::Duration};
+use std::{net::SocketAddr, path::Path, str::FromStr, time::Duration};
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Settings {
@@ -136,6 +137,21 @@ pub struct Settings {
         default = "default_region_params_refresh_interval"
     )]
     pub region_params_refresh_interval: Duration,
+
+    /// vec of b58 helium encoded pubkeys of the entropy oracle
+    /// used to verify the signature of chained (version 1) entropy reports
+    #[serde(default)]
+    pub entropy_sign_keys: Vec<String>,
+
+    /// whether the entropy oracle issues signed, chained (version 1) entropy.
+    /// Requires at least one of `entropy_sign_keys`. Default false
+    #[serde(default)]
+    pub signed_entropy: bool,
+
+    /// Listen address for the read only gateway diagnostics http api. The
+    /// api is not started if unset
+    #[serde(default)]
+    pub diagnostics_listen: Option<SocketAddr>,
 }
 
 fn default_gateway_refresh_interval() -> Duration {
@@ -251,6 +267,18 @@ impl Settings {
             .and_then(|config| config.try_deserialize())
     }
 
+    pub fn entropy_sign_keys(&self) -> anyhow::Result<Vec<PublicKey>> {
+        // without keys every piece of signed entropy would be rejected
+        if self.signed_entropy && self.entropy_sign_keys.is_empty() {
+            bail!("signed entropy is enabled but no entropy_sign_keys are configured")
+        }
+        Ok(self
+            .entropy_sign_keys
+            .iter()
+            .map(|pubkey| PublicKey::from_str(pubkey))
+            .collect::<Result<_, _>>()?)
+    }
+
     pub fn beacon_interval(&self) -> anyhow::Result<Duration> {
         // validate the beacon_interval value is a factor of 24, if not bail out
         if (24 * 60 * 60) % self.beacon_interval.as_secs() != 0 {
@@ -47,6 +47,7 @@ impl Telemetry {
     }
 }
 
+#[derive(Clone)]
 pub struct WitnessUpdater {
     pool: PgPool,
     cache: Arc<RwLock<WitnessMap>>,
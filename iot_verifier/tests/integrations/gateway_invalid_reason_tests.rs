@@ -0,0 +1,141 @@
+use crate::common;
+use chrono::{Duration as ChronoDuration, NaiveTime, Utc};
+use helium_crypto::PublicKeyBinary;
+use helium_proto::services::poc_lora::{InvalidParticipantSide, InvalidReason};
+use iot_verifier::{
+    gateway_invalid_reason::{GatewayInvalidReason, InvalidReasonBatch},
+    poc_report::ReportType,
+};
+use sqlx::PgPool;
+use std::str::FromStr;
+
+#[sqlx::test]
+async fn test_invalid_reason_counts(pool: PgPool) -> anyhow::Result<()> {
+    let beaconer = PublicKeyBinary::from_str(common::BEACONER1)?;
+    let witness = PublicKeyBinary::from_str(common::WITNESS1)?;
+    let now = Utc::now();
+
+    let mut batch = InvalidReasonBatch::default();
+    batch.push(
+        &beaconer,
+        ReportType::Beacon,
+        InvalidReason::Denied,
+        InvalidParticipantSide::Beaconer,
+        now - ChronoDuration::minutes(10),
+    );
+    batch.push(
+        &beaconer,
+        ReportType::Beacon,
+        InvalidReason::Denied,
+        InvalidParticipantSide::Beaconer,
+        now,
+    );
+    batch.push(
+        &witness,
+        ReportType::Witness,
+        InvalidReason::Denied,
+        InvalidParticipantSide::Witness,
+        now,
+    );
+    // valid reports carry no reason and are never counted
+    batch.push(
+        &witness,
+        ReportType::Witness,
+        InvalidReason::ReasonNone,
+        InvalidParticipantSide::Witness,
+        now,
+    );
+    batch.save(&pool).await?;
+
+    // a second batch for the same day accumulates onto the existing counts
+    let mut batch = InvalidReasonBatch::default();
+    batch.push(
+        &beaconer,
+        ReportType::Beacon,
+        InvalidReason::Denied,
+        InvalidParticipantSide::Beaconer,
+        now,
+    );
+    batch.save(&pool).await?;
+
+    let reasons =
+        GatewayInvalidReason::get_since(&pool, &beaconer, now - ChronoDuration::days(1)).await?;
+    assert_eq!(1, reasons.len());
+    assert_eq!(ReportType::Beacon, reasons[0].report_type);
+    assert_eq!(InvalidReason::Denied.as_str_name(), reasons[0].reason);
+    assert_eq!(
+        InvalidParticipantSide::Beaconer.as_str_name(),
+        reasons[0].participant_side
+    );
+    assert_eq!(3, reasons[0].count);
+    assert_eq!(
+        now.timestamp_millis(),
+        reasons[0].last_timestamp.timestamp_millis()
+    );
+
+    // a witness invalidated by its beacon is counted apart from its own failures
+    let mut batch = InvalidReasonBatch::default();
+    batch.push(
+        &witness,
+        ReportType::Witness,
+        InvalidReason::Denied,
+        InvalidParticipantSide::Beaconer,
+        now,
+    );
+    batch.save(&pool).await?;
+
+    let mut reasons =
+        GatewayInvalidReason::get_since(&pool, &witness, now - ChronoDuration::days(1)).await?;
+    reasons.sort_by(|a, b| a.participant_side.cmp(&b.participant_side));
+    assert_eq!(2, reasons.len());
+    assert_eq!(
+        InvalidParticipantSide::Beaconer.as_str_name(),
+        reasons[0].participant_side
+    );
+    assert_eq!(
+        InvalidParticipantSide::Witness.as_str_name(),
+        reasons[1].participant_side
+    );
+    assert_eq!(1, reasons[1].count);
+
+    // purging everything prior to tomorrow clears all buckets
+    GatewayInvalidReason::purge_older_than(&pool, now + ChronoDuration::days(1)).await?;
+    let reasons =
+        GatewayInvalidReason::get_since(&pool, &beaconer, now - ChronoDuration::days(1)).await?;
+    assert!(reasons.is_empty());
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_invalid_reasons_bucketed_per_report(pool: PgPool) -> anyhow::Result<()> {
+    let beaconer = PublicKeyBinary::from_str(common::BEACONER1)?;
+    let midnight = Utc::now().date_naive().and_time(NaiveTime::MIN).and_utc();
+
+    // a batch spanning midnight counts each report in its own day
+    let mut batch = InvalidReasonBatch::default();
+    batch.push(
+        &beaconer,
+        ReportType::Beacon,
+        InvalidReason::Denied,
+        InvalidParticipantSide::Beaconer,
+        midnight - ChronoDuration::minutes(1),
+    );
+    batch.push(
+        &beaconer,
+        ReportType::Beacon,
+        InvalidReason::Denied,
+        InvalidParticipantSide::Beaconer,
+        midnight + ChronoDuration::minutes(1),
+    );
+    batch.save(&pool).await?;
+
+    let reasons = GatewayInvalidReason::get_since(&pool, &beaconer, midnight).await?;
+    assert_eq!(1, reasons.len());
+    assert_eq!(1, reasons[0].count);
+
+    let reasons =
+        GatewayInvalidReason::get_since(&pool, &beaconer, midnight - ChronoDuration::days(1))
+            .await?;
+    assert_eq!(2, reasons[0].count);
+    Ok(())
+}
@@ -1,5 +1,6 @@
 mod common;
 
+mod gateway_invalid_reason_tests;
 mod purger_tests;
 mod rewarder_operations;
 mod rewarder_oracles;
@@ -20,7 +20,8 @@ use iot_verifier::{
 };
 use lazy_static::lazy_static;
 use sqlx::PgPool;
-use std::{self, str::FromStr, time::Duration};
+use std::{self, str::FromStr, sync::Arc, time::Duration};
+use tokio::sync::RwLock;
 
 lazy_static! {
     static ref BEACON_INTERVAL: Duration = Duration::from_secs(21600);
@@ -99,10 +100,8 @@ impl TestContext {
             max_witnesses_per_poc: 16,
             beacon_max_retries: 2,
             witness_max_retries: 2,
-            deny_list_latest_url: "https://api.github.com/repos/helium/denylist/releases/latest"
-                .to_string(),
             deny_list_trigger_interval: Duration::from_secs(60),
-            deny_list,
+            deny_list: Arc::new(RwLock::new(deny_list)),
             gateway_cache: gateway_cache.clone(),
             region_cache,
             invalid_beacon_sink: invalid_beacon_client,
@@ -110,6 +109,7 @@ impl TestContext {
             poc_sink: valid_poc_client,
             hex_density_map: density_scaler.hex_density_map.clone(),
             witness_updater,
+            entropy_sign_keys: vec![],
         };
 
         // generate a datetime based on a hardcoded timestamp
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS mobile_radio_changes (
+    entity_key BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    changed_at TIMESTAMPTZ NOT NULL,
+    location BIGINT,
+    is_full_hotspot INT,
+    num_location_asserts INT,
+    is_active INT,
+    dc_onboarding_fee_paid BIGINT,
+    device_type TEXT NOT NULL,
+    deployment_info TEXT,
+    PRIMARY KEY (entity_key, changed_at)
+);
+
+CREATE INDEX IF NOT EXISTS mobile_radio_changes_changed_at_idx ON mobile_radio_changes (changed_at);
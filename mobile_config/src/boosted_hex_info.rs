@@ -1,6 +1,9 @@
 use crate::client::{hex_boosting_client::HexBoostingInfoResolver, ClientError};
 use chrono::{DateTime, Duration, Utc};
-use file_store::traits::TimestampDecode;
+use file_store::{
+    hex_boost::{BoostedHexEvent, BoostedHexEventType},
+    traits::TimestampDecode,
+};
 use futures::stream::{BoxStream, StreamExt};
 use helium_proto::services::poc_mobile::BoostedHex as BoostedHexProto;
 use helium_proto::BoostedHexInfoV1 as BoostedHexInfoProto;
@@ -102,6 +105,72 @@ impl BoostedHexInfo {
     }
 }
 
+impl BoostedHexInfo {
+    /// Lifecycle transitions of the boost that took effect in `since..until`,
+    /// given the state the boost was last seen in. A boost not seen before is
+    /// created, and a changed boost updated, as of `until`.
+    pub fn lifecycle_events(
+        &self,
+        previous: Option<&BoostedHexInfo>,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Vec<BoostedHexEvent> {
+        let in_range = |ts: DateTime<Utc>| since <= ts && ts < until;
+        let mut events = vec![];
+        match previous {
+            None => events.push(self.event(BoostedHexEventType::Created, until)),
+            Some(previous)
+                if previous.multipliers != self.multipliers
+                    || previous.period_length != self.period_length
+                    || previous.boost_config_pubkey != self.boost_config_pubkey =>
+            {
+                events.push(self.event(BoostedHexEventType::Updated, until))
+            }
+            Some(_) => (),
+        }
+        if let Some(start_ts) = self.start_ts {
+            let activated = match previous {
+                Some(previous) => previous.start_ts.is_none(),
+                None => in_range(start_ts),
+            };
+            if activated {
+                events.push(self.event(BoostedHexEventType::Activated, start_ts));
+            }
+            events.extend(
+                (1..self.multipliers.len())
+                    .map(|period| start_ts + self.period_length * period as i32)
+                    .filter(|ts| in_range(*ts))
+                    .map(|ts| self.event(BoostedHexEventType::PeriodStarted, ts)),
+            );
+        }
+        if let Some(end_ts) = self.end_ts.filter(|ts| in_range(*ts)) {
+            events.push(self.event(BoostedHexEventType::Expired, end_ts));
+        }
+        events
+    }
+
+    fn event(&self, event_type: BoostedHexEventType, timestamp: DateTime<Utc>) -> BoostedHexEvent {
+        // before activation the first multiplier applies
+        let multiplier = match self.start_ts {
+            Some(start_ts) if timestamp < start_ts => self.multipliers.first().copied(),
+            _ => self.current_multiplier(timestamp).ok().flatten(),
+        };
+        BoostedHexEvent {
+            timestamp,
+            event_type,
+            location: self.location.into_raw(),
+            boosted_hex_pubkey: self.boosted_hex_pubkey.to_string(),
+            boost_config_pubkey: self.boost_config_pubkey.to_string(),
+            version: self.version,
+            start_ts: self.start_ts,
+            end_ts: self.end_ts,
+            period_length_secs: self.period_length.num_seconds(),
+            multipliers: self.multipliers.iter().map(|m| m.get()).collect(),
+            multiplier: multiplier.map(|m| m.get()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct BoostedHexes {
     pub hexes: HashMap<Cell, BoostedHexInfo>,
@@ -405,6 +474,75 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn lifecycle_events_of_boost() -> anyhow::Result<()> {
+        let start_ts = parse_dt("2024-03-14 01:00:00");
+        let period_length = Duration::days(30);
+        let multipliers = vec![NonZeroU32::new(2).unwrap(), NonZeroU32::new(10).unwrap()];
+        let queued = BoostedHexInfo {
+            location: Cell::from_raw(631252734740306943)?,
+            start_ts: None,
+            end_ts: None,
+            period_length,
+            multipliers,
+            boosted_hex_pubkey: Pubkey::from_str(BOOST_HEX_PUBKEY)?,
+            boost_config_pubkey: Pubkey::from_str(BOOST_HEX_CONFIG_PUBKEY)?,
+            version: 1,
+        };
+        let active = BoostedHexInfo {
+            start_ts: Some(start_ts),
+            end_ts: to_end_ts(Some(start_ts), period_length, 2),
+            version: 2,
+            ..queued.clone()
+        };
+        let event_types = |events: Vec<BoostedHexEvent>| {
+            events
+                .into_iter()
+                .map(|event| (event.event_type, event.timestamp, event.multiplier))
+                .collect::<Vec<_>>()
+        };
+
+        let day = Duration::days(1);
+        let created = queued.lifecycle_events(None, start_ts - day * 2, start_ts - day);
+        assert_eq!(
+            event_types(created),
+            vec![(BoostedHexEventType::Created, start_ts - day, Some(2))]
+        );
+
+        let activated = active.lifecycle_events(Some(&queued), start_ts - day, start_ts + day);
+        assert_eq!(
+            event_types(activated),
+            vec![(BoostedHexEventType::Activated, start_ts, Some(2))]
+        );
+
+        // nothing changes mid period
+        assert!(active
+            .lifecycle_events(Some(&active), start_ts + day, start_ts + day * 2)
+            .is_empty());
+
+        let period_start = start_ts + period_length;
+        let end_ts = start_ts + period_length * 2;
+        let later = active.lifecycle_events(Some(&active), start_ts + day, end_ts + day);
+        assert_eq!(
+            event_types(later),
+            vec![
+                (BoostedHexEventType::PeriodStarted, period_start, Some(10)),
+                (BoostedHexEventType::Expired, end_ts, None),
+            ]
+        );
+
+        let updated = BoostedHexInfo {
+            multipliers: vec![NonZeroU32::new(3).unwrap(), NonZeroU32::new(10).unwrap()],
+            ..queued.clone()
+        };
+        let events = updated.lifecycle_events(Some(&queued), start_ts - day, start_ts);
+        assert_eq!(
+            event_types(events),
+            vec![(BoostedHexEventType::Updated, start_ts, Some(3))]
+        );
+        Ok(())
+    }
+
     fn parse_dt(dt: &str) -> DateTime<Utc> {
         NaiveDateTime::parse_from_str(dt, "%Y-%m-%d %H:%M:%S")
             .expect("unable_to_parse")
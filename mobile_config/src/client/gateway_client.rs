@@ -1,5 +1,6 @@
 use super::{call_with_retry, ClientError, Settings, CACHE_EVICTION_FREQUENCY};
 use crate::gateway_info::{self, GatewayInfo, GatewayInfoStream};
+use chrono::{DateTime, Utc};
 use file_store::traits::MsgVerify;
 use futures::stream::{self, StreamExt};
 use helium_crypto::{Keypair, PublicKey, PublicKeyBinary, Sign};
@@ -21,6 +22,24 @@ pub struct GatewayClient {
     batch_size: u32,
     cache: Arc<Cache<PublicKeyBinary, Option<gateway_info::GatewayInfo>>>,
     cache_ttl: Duration,
+    history: Option<RadioHistory>,
+}
+
+/// Caches gateway info resolved through the radio history of the mobile
+/// config server, by radio and bucket of time. Gateway info is resolved as of
+/// the start of the bucket.
+#[derive(Clone)]
+struct RadioHistory {
+    cache: Arc<Cache<(PublicKeyBinary, i64), Option<GatewayInfo>>>,
+    bucket: Duration,
+}
+
+impl RadioHistory {
+    /// Start of the bucket `at` falls in, as unix seconds
+    fn bucket_start(&self, at: DateTime<Utc>) -> i64 {
+        let bucket = self.bucket.as_secs().max(1) as i64;
+        at.timestamp().div_euclid(bucket) * bucket
+    }
 }
 
 impl GatewayClient {
@@ -33,6 +52,20 @@ impl GatewayClient {
                 .await
         });
 
+        let history = settings.resolve_history.then(|| {
+            let cache = Arc::new(Cache::new());
+            let cloned_cache = cache.clone();
+            tokio::spawn(async move {
+                cloned_cache
+                    .monitor(4, 0.25, CACHE_EVICTION_FREQUENCY)
+                    .await
+            });
+            RadioHistory {
+                cache,
+                bucket: settings.history_bucket,
+            }
+        });
+
         Ok(Self {
             client: settings.connect_gateway_client(),
             signing_key: settings.signing_keypair()?,
@@ -40,6 +73,7 @@ impl GatewayClient {
             batch_size: settings.batch_size,
             cache_ttl: settings.cache_ttl,
             cache,
+            history,
         })
     }
 }
@@ -53,6 +87,16 @@ pub trait GatewayInfoResolver: Clone + Send + Sync + 'static {
         address: &PublicKeyBinary,
     ) -> Result<Option<GatewayInfo>, Self::Error>;
 
+    /// Gateway info as it was at `at`, such as the timestamp of a report.
+    /// Resolvers without a history resolve the current info.
+    async fn resolve_gateway_info_at(
+        &self,
+        address: &PublicKeyBinary,
+        _at: DateTime<Utc>,
+    ) -> Result<Option<GatewayInfo>, Self::Error> {
+        self.resolve_gateway_info(address).await
+    }
+
     async fn stream_gateways_info(
         &mut self,
         device_types: &[DeviceType],
@@ -98,6 +142,60 @@ impl GatewayInfoResolver for GatewayClient {
         Ok(response)
     }
 
+    /// Uses the latest recorded state of the radio at the start of the
+    /// history bucket `at` falls in if resolving history is enabled. The
+    /// current info is used for times before the recorded history of a radio.
+    async fn resolve_gateway_info_at(
+        &self,
+        address: &PublicKeyBinary,
+        at: DateTime<Utc>,
+    ) -> Result<Option<gateway_info::GatewayInfo>, Self::Error> {
+        let Some(history) = &self.history else {
+            return self.resolve_gateway_info(address).await;
+        };
+
+        let key = (address.clone(), history.bucket_start(at));
+        let response = match history.cache.get(&key).await {
+            Some(cached_response) => cached_response.value().clone(),
+            None => {
+                let mut request = mobile_config::GatewayInfoAtTimestampReqV1 {
+                    address: address.clone().into(),
+                    timestamp: key.1 as u64,
+                    signer: self.signing_key.public_key().into(),
+                    signature: vec![],
+                };
+                request.signature = self.signing_key.sign(&request.encode_to_vec())?;
+                tracing::debug!(pubkey = address.to_string(), %at, "fetching radio history");
+                let response = match call_with_retry!(self
+                    .client
+                    .clone()
+                    .info_at_timestamp(request.clone()))
+                {
+                    Ok(info_res) => {
+                        let response = info_res.into_inner();
+                        response.verify(&self.config_pubkey)?;
+                        response
+                            .info
+                            .map(gateway_info::GatewayInfo::try_from)
+                            .transpose()?
+                    }
+                    Err(status) if status.code() == tonic::Code::NotFound => None,
+                    Err(status) => Err(status)?,
+                };
+                history
+                    .cache
+                    .insert(key, response.clone(), self.cache_ttl)
+                    .await;
+                response
+            }
+        };
+
+        match response {
+            Some(info) => Ok(Some(info)),
+            None => self.resolve_gateway_info(address).await,
+        }
+    }
+
     /// Returns all gateways if device_types is empty
     /// Otherwise, only selected device_types
     async fn stream_gateways_info(
@@ -26,6 +26,15 @@ pub struct Settings {
     pub hex_boosting_batch_size: u32,
     #[serde(with = "humantime_serde", default = "default_cache_ttl_in_secs")]
     pub cache_ttl: Duration,
+    /// Resolve gateway info as of a given time through the radio history of
+    /// the mobile config server. Gateway info as of a given time is the
+    /// current info if unset
+    #[serde(default)]
+    pub resolve_history: bool,
+    /// Gateway info is resolved through the history as of the start of the
+    /// bucket of this duration a time falls in. Default 10 minutes
+    #[serde(with = "humantime_serde", default = "default_history_bucket")]
+    pub history_bucket: Duration,
 }
 
 fn default_connect_timeout() -> Duration {
@@ -48,6 +57,10 @@ fn default_cache_ttl_in_secs() -> Duration {
     humantime::parse_duration("1 hour").unwrap()
 }
 
+fn default_history_bucket() -> Duration {
+    humantime::parse_duration("10 minutes").unwrap()
+}
+
 impl Settings {
     pub fn connect_epoch_client(&self) -> sub_dao::sub_dao_client::SubDaoClient<Channel> {
         let channel = connect_channel(self);
@@ -1,7 +1,7 @@
 use crate::{
     gateway_info::{self, db::get_updated_radios, DeviceType, GatewayInfo},
     key_cache::KeyCache,
-    telemetry, verify_public_key, GrpcResult, GrpcStreamResult,
+    mobile_radio_tracker, telemetry, verify_public_key, GrpcResult, GrpcStreamResult,
 };
 use chrono::{DateTime, TimeZone, Utc};
 use file_store::traits::{MsgVerify, TimestampEncode};
@@ -13,9 +13,9 @@ use futures::{
 use helium_crypto::{Keypair, PublicKey, PublicKeyBinary, Sign};
 use helium_proto::{
     services::mobile_config::{
-        self, GatewayInfoBatchReqV1, GatewayInfoReqV1, GatewayInfoResV1, GatewayInfoResV2,
-        GatewayInfoStreamReqV1, GatewayInfoStreamReqV2, GatewayInfoStreamResV1,
-        GatewayInfoStreamResV2, GatewayInfoV2,
+        self, GatewayChangesSinceReqV1, GatewayInfoAtTimestampReqV1, GatewayInfoBatchReqV1,
+        GatewayInfoReqV1, GatewayInfoResV1, GatewayInfoResV2, GatewayInfoStreamReqV1,
+        GatewayInfoStreamReqV2, GatewayInfoStreamResV1, GatewayInfoStreamResV2, GatewayInfoV2,
     },
     Message,
 };
@@ -344,6 +344,98 @@ impl mobile_config::Gateway for GatewayService {
 
         Ok(Response::new(GrpcStreamResult::new(rx)))
     }
+
+    async fn info_at_timestamp(
+        &self,
+        request: Request<GatewayInfoAtTimestampReqV1>,
+    ) -> GrpcResult<GatewayInfoResV2> {
+        let request = request.into_inner();
+        telemetry::count_request("gateway", "info-at-timestamp");
+        custom_tracing::record_b58("pub_key", &request.address);
+        custom_tracing::record_b58("signer", &request.signer);
+
+        let signer = verify_public_key(&request.signer)?;
+        self.verify_request_signature(&signer, &request)?;
+
+        let pubkey: PublicKeyBinary = request.address.clone().into();
+        let timestamp = Utc
+            .timestamp_opt(request.timestamp as i64, 0)
+            .single()
+            .ok_or(Status::invalid_argument("Invalid timestamp argument"))?;
+        tracing::debug!(
+            pubkey = pubkey.to_string(),
+            %timestamp,
+            "fetching gateway info at timestamp"
+        );
+
+        let info: GatewayInfo = mobile_radio_tracker::get_radio_as_of(
+            &self.mobile_config_db_pool,
+            &request.address,
+            timestamp,
+        )
+        .await
+        .map_err(|_| Status::internal("error fetching gateway info at timestamp"))?
+        .ok_or_else(|| Status::not_found(pubkey.to_string()))?
+        .try_into()
+        .map_err(|_| Status::internal("error parsing recorded gateway info"))?;
+
+        let info: GatewayInfoV2 = info
+            .try_into()
+            .map_err(|_| Status::internal("error serializing gateway info at timestamp"))?;
+
+        let mut res = GatewayInfoResV2 {
+            info: Some(info),
+            timestamp: Utc::now().encode_timestamp(),
+            signer: self.signing_key.public_key().into(),
+            signature: vec![],
+        };
+        res.signature = self.sign_response(&res.encode_to_vec())?;
+        Ok(Response::new(res))
+    }
+
+    type changes_sinceStream = GrpcStreamResult<GatewayInfoStreamResV2>;
+    async fn changes_since(
+        &self,
+        request: Request<GatewayChangesSinceReqV1>,
+    ) -> GrpcResult<Self::changes_sinceStream> {
+        let request = request.into_inner();
+        telemetry::count_request("gateway", "changes-since");
+        custom_tracing::record_b58("signer", &request.signer);
+
+        let signer = verify_public_key(&request.signer)?;
+        self.verify_request_signature(&signer, &request)?;
+
+        let since = Utc
+            .timestamp_opt(request.since as i64, 0)
+            .single()
+            .ok_or(Status::invalid_argument("Invalid since argument"))?;
+        tracing::debug!(%since, "fetching gateway changes");
+
+        let mobile_config_db_pool = self.mobile_config_db_pool.clone();
+        let signing_key = self.signing_key.clone();
+        let batch_size = request.batch_size;
+
+        let (tx, rx) = tokio::sync::mpsc::channel(100);
+
+        tokio::spawn(async move {
+            // Every recorded state after `since` is sent, in the order the
+            // gateways changed, with updated_at set to the time of the change
+            let changes = mobile_radio_tracker::get_changes_since(&mobile_config_db_pool, since)
+                .await?
+                .into_iter()
+                .filter_map(|change| match GatewayInfo::try_from(change.state) {
+                    Ok(info) => Some(info),
+                    Err(err) => {
+                        tracing::warn!(?err, "skipping unparsable recorded gateway info");
+                        None
+                    }
+                });
+            let stream = futures::stream::iter(changes);
+            stream_multi_gateways_info(stream, tx.clone(), signing_key.clone(), batch_size).await
+        });
+
+        Ok(Response::new(GrpcStreamResult::new(rx)))
+    }
 }
 
 fn handle_updated_at(
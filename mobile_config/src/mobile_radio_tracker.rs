@@ -1,15 +1,24 @@
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
 
 use chrono::{DateTime, Utc};
 use futures::{Stream, StreamExt, TryFutureExt, TryStreamExt};
-use sqlx::{Pool, Postgres, QueryBuilder};
+use serde_json::{json, Value};
+use sqlx::{PgExecutor, Pool, Postgres, QueryBuilder};
 use task_manager::ManagedTask;
 
+use crate::gateway_info::{
+    DeploymentInfo, DeviceType, DeviceTypeParseError, GatewayInfo, GatewayMetadata,
+};
+
 type EntityKey = Vec<u8>;
 
 #[derive(Debug, Clone, sqlx::FromRow)]
 struct MobileRadio {
     entity_key: EntityKey,
+    created_at: DateTime<Utc>,
     refreshed_at: DateTime<Utc>,
     location: Option<i64>,
     is_full_hotspot: Option<i32>,
@@ -101,6 +110,115 @@ impl TrackedMobileRadio {
     }
 }
 
+/// The tracked fields of a radio from the time they changed to these values
+/// until its next recorded change
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct MobileRadioState {
+    pub entity_key: EntityKey,
+    pub created_at: DateTime<Utc>,
+    pub changed_at: DateTime<Utc>,
+    pub location: Option<i64>,
+    pub is_full_hotspot: Option<i32>,
+    pub num_location_asserts: Option<i32>,
+    pub is_active: Option<i32>,
+    pub dc_onboarding_fee_paid: Option<i64>,
+    pub device_type: String,
+    pub deployment_info: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MobileRadioStateError {
+    #[error("invalid device type: {0}")]
+    DeviceType(#[from] DeviceTypeParseError),
+    #[error("invalid json field: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+impl TryFrom<MobileRadioState> for GatewayInfo {
+    type Error = MobileRadioStateError;
+
+    fn try_from(state: MobileRadioState) -> Result<Self, Self::Error> {
+        // device_type and deployment_info are recorded as the text of their
+        // jsonb columns
+        let device_type: DeviceType =
+            serde_json::from_str::<String>(&state.device_type)?.parse()?;
+        let deployment_info = state
+            .deployment_info
+            .as_deref()
+            .map(serde_json::from_str::<DeploymentInfo>)
+            .transpose()?;
+        Ok(Self {
+            address: state.entity_key.into(),
+            metadata: state.location.map(|location| GatewayMetadata {
+                location: location as u64,
+                deployment_info,
+            }),
+            device_type,
+            created_at: Some(state.created_at),
+            updated_at: Some(state.changed_at),
+            // A state is recorded as of the refresh that changed it
+            refreshed_at: Some(state.changed_at),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub from: Value,
+    pub to: Value,
+}
+
+/// A recorded change of a radio and how it differs from the state before
+#[derive(Debug, Clone, PartialEq)]
+pub struct MobileRadioChange {
+    pub state: MobileRadioState,
+    pub changes: Vec<FieldChange>,
+}
+
+impl MobileRadioState {
+    fn new(radio: &MobileRadio, changed_at: DateTime<Utc>) -> Self {
+        Self {
+            entity_key: radio.entity_key.clone(),
+            created_at: radio.created_at,
+            changed_at,
+            location: radio.location,
+            is_full_hotspot: radio.is_full_hotspot,
+            num_location_asserts: radio.num_location_asserts,
+            is_active: radio.is_active,
+            dc_onboarding_fee_paid: radio.dc_onboarding_fee_paid,
+            device_type: radio.device_type.clone(),
+            deployment_info: radio.deployment_info.clone(),
+        }
+    }
+
+    fn fields(&self) -> [(&'static str, Value); 7] {
+        [
+            ("location", json!(self.location)),
+            ("is_full_hotspot", json!(self.is_full_hotspot)),
+            ("num_location_asserts", json!(self.num_location_asserts)),
+            ("is_active", json!(self.is_active)),
+            ("dc_onboarding_fee_paid", json!(self.dc_onboarding_fee_paid)),
+            ("device_type", json!(self.device_type)),
+            ("deployment_info", json!(self.deployment_info)),
+        ]
+    }
+
+    /// Fields that differ from the previous state. Without a previous state
+    /// every set field is reported as changed from null.
+    pub fn diff(&self, previous: Option<&Self>) -> Vec<FieldChange> {
+        let previous: [Value; 7] = previous
+            .map(|previous| previous.fields().map(|(_, value)| value))
+            .unwrap_or_default();
+        self.fields()
+            .into_iter()
+            .zip(previous)
+            .filter(|((_, to), from)| from != to)
+            .map(|((field, to), from)| FieldChange { field, from, to })
+            .collect()
+    }
+}
+
 pub struct MobileRadioTracker {
     pool: Pool<Postgres>,
     metadata: Pool<Postgres>,
@@ -155,32 +273,54 @@ impl MobileRadioTracker {
 pub async fn track_changes(pool: &Pool<Postgres>, metadata: &Pool<Postgres>) -> anyhow::Result<()> {
     tracing::info!("looking for changes to radios");
     let tracked_radios = get_tracked_radios(pool).await?;
+    let radios_with_history = get_radios_with_history(pool).await?;
     let all_mobile_radios = get_all_mobile_radios(metadata);
 
-    let updates = identify_changes(all_mobile_radios, tracked_radios).await;
-    tracing::info!("updating in db: {}", updates.len());
+    let (updates, changes) =
+        identify_changes(all_mobile_radios, tracked_radios, &radios_with_history).await;
+    tracing::info!(
+        "updating in db: {}, recording changes: {}",
+        updates.len(),
+        changes.len()
+    );
 
-    update_tracked_radios(pool, updates).await?;
+    update_tracked_radios(pool, updates, changes).await?;
     tracing::info!("done");
 
     Ok(())
 }
 
+/// Updates the tracking of every radio and returns the states to record in
+/// the change history. A state is recorded for new and changed radios, and
+/// for tracked radios without any history yet, as of their last change.
 async fn identify_changes(
     all_mobile_radios: impl Stream<Item = MobileRadio>,
     tracked_radios: HashMap<EntityKey, TrackedMobileRadio>,
-) -> Vec<TrackedMobileRadio> {
-    all_mobile_radios
+    radios_with_history: &HashSet<EntityKey>,
+) -> (Vec<TrackedMobileRadio>, Vec<MobileRadioState>) {
+    let (tracked, changes): (Vec<_>, Vec<_>) = all_mobile_radios
         .scan(tracked_radios, |tracked, radio| {
             let tracked_radio_opt = tracked.remove(&radio.entity_key);
             async { Some((radio, tracked_radio_opt)) }
         })
         .map(|(radio, tracked_radio_opt)| match tracked_radio_opt {
-            Some(tracked_radio) => tracked_radio.update_from_radio(&radio),
-            None => TrackedMobileRadio::new(&radio),
+            Some(tracked_radio) => {
+                let changed = tracked_radio.hash != radio.hash();
+                let tracked_radio = tracked_radio.update_from_radio(&radio);
+                let change = (changed || !radios_with_history.contains(&radio.entity_key))
+                    .then(|| MobileRadioState::new(&radio, tracked_radio.last_changed_at));
+                (tracked_radio, change)
+            }
+            None => {
+                let tracked_radio = TrackedMobileRadio::new(&radio);
+                let change = MobileRadioState::new(&radio, tracked_radio.last_changed_at);
+                (tracked_radio, Some(change))
+            }
         })
-        .collect()
-        .await
+        .unzip()
+        .await;
+
+    (tracked, changes.into_iter().flatten().collect())
 }
 
 pub async fn get_tracked_radios(
@@ -212,6 +352,7 @@ fn get_all_mobile_radios(metadata: &Pool<Postgres>) -> impl Stream<Item = Mobile
             DISTINCT ON (kta.entity_key, mhi.asset)
             kta.entity_key,
             mhi.asset,
+            mhi.created_at,
             mhi.refreshed_at,
             mhi.location::bigint,
             mhi.is_full_hotspot::int,
@@ -238,9 +379,111 @@ fn get_all_mobile_radios(metadata: &Pool<Postgres>) -> impl Stream<Item = Mobile
     .boxed()
 }
 
+pub async fn get_radios_with_history(pool: &Pool<Postgres>) -> anyhow::Result<HashSet<EntityKey>> {
+    sqlx::query_scalar::<_, EntityKey>("SELECT DISTINCT entity_key FROM mobile_radio_changes")
+        .fetch(pool)
+        .try_collect()
+        .map_err(anyhow::Error::from)
+        .await
+}
+
+/// The state of a radio at `at`, from its latest change at or before then
+pub async fn get_radio_as_of(
+    db: impl PgExecutor<'_>,
+    entity_key: &[u8],
+    at: DateTime<Utc>,
+) -> anyhow::Result<Option<MobileRadioState>> {
+    sqlx::query_as::<_, MobileRadioState>(
+        r#"
+        SELECT * FROM mobile_radio_changes
+        WHERE entity_key = $1 AND changed_at <= $2
+        ORDER BY changed_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(entity_key)
+    .bind(at)
+    .fetch_optional(db)
+    .await
+    .map_err(anyhow::Error::from)
+}
+
+/// All recorded states of a radio, ordered by the time they changed
+pub async fn get_radio_history(
+    db: impl PgExecutor<'_>,
+    entity_key: &[u8],
+) -> anyhow::Result<Vec<MobileRadioState>> {
+    sqlx::query_as::<_, MobileRadioState>(
+        r#"
+        SELECT * FROM mobile_radio_changes
+        WHERE entity_key = $1
+        ORDER BY changed_at
+        "#,
+    )
+    .bind(entity_key)
+    .fetch_all(db)
+    .await
+    .map_err(anyhow::Error::from)
+}
+
+/// All changes of radios after `since`, ordered by the time they changed
+pub async fn get_changes_since(
+    pool: &Pool<Postgres>,
+    since: DateTime<Utc>,
+) -> anyhow::Result<Vec<MobileRadioChange>> {
+    let states = sqlx::query_as::<_, MobileRadioState>(
+        r#"
+        SELECT * FROM mobile_radio_changes
+        WHERE changed_at > $1
+        ORDER BY changed_at, entity_key
+        "#,
+    )
+    .bind(since)
+    .fetch_all(pool)
+    .await?;
+
+    let entity_keys: Vec<EntityKey> = states
+        .iter()
+        .map(|state| state.entity_key.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let previous: HashMap<EntityKey, MobileRadioState> = sqlx::query_as::<_, MobileRadioState>(
+        r#"
+        SELECT DISTINCT ON (entity_key) * FROM mobile_radio_changes
+        WHERE entity_key = ANY($1) AND changed_at <= $2
+        ORDER BY entity_key, changed_at DESC
+        "#,
+    )
+    .bind(entity_keys)
+    .bind(since)
+    .fetch(pool)
+    .map_ok(|state| (state.entity_key.clone(), state))
+    .try_collect()
+    .await?;
+
+    Ok(with_diffs(previous, states))
+}
+
+/// Pairs ordered states with the fields changed since the state before them
+fn with_diffs(
+    mut previous: HashMap<EntityKey, MobileRadioState>,
+    states: Vec<MobileRadioState>,
+) -> Vec<MobileRadioChange> {
+    states
+        .into_iter()
+        .map(|state| {
+            let changes = state.diff(previous.get(&state.entity_key));
+            previous.insert(state.entity_key.clone(), state.clone());
+            MobileRadioChange { state, changes }
+        })
+        .collect()
+}
+
 async fn update_tracked_radios(
     pool: &Pool<Postgres>,
     tracked_radios: Vec<TrackedMobileRadio>,
+    changes: Vec<MobileRadioState>,
 ) -> anyhow::Result<()> {
     let mut txn = pool.begin().await?;
 
@@ -269,6 +512,45 @@ async fn update_tracked_radios(
         .await?;
     }
 
+    const CHANGES_BATCH_SIZE: usize = (u16::MAX / 10) as usize;
+
+    for chunk in changes.chunks(CHANGES_BATCH_SIZE) {
+        QueryBuilder::new(
+            r#"
+            INSERT INTO mobile_radio_changes(entity_key, created_at, changed_at, location, is_full_hotspot,
+                num_location_asserts, is_active, dc_onboarding_fee_paid, device_type, deployment_info)
+            "#,
+        )
+        .push_values(chunk, |mut b, state| {
+            b.push_bind(&state.entity_key)
+                .push_bind(state.created_at)
+                .push_bind(state.changed_at)
+                .push_bind(state.location)
+                .push_bind(state.is_full_hotspot)
+                .push_bind(state.num_location_asserts)
+                .push_bind(state.is_active)
+                .push_bind(state.dc_onboarding_fee_paid)
+                .push_bind(&state.device_type)
+                .push_bind(&state.deployment_info);
+        })
+        .push(
+            r#"
+            ON CONFLICT (entity_key, changed_at) DO UPDATE SET
+                created_at = EXCLUDED.created_at,
+                location = EXCLUDED.location,
+                is_full_hotspot = EXCLUDED.is_full_hotspot,
+                num_location_asserts = EXCLUDED.num_location_asserts,
+                is_active = EXCLUDED.is_active,
+                dc_onboarding_fee_paid = EXCLUDED.dc_onboarding_fee_paid,
+                device_type = EXCLUDED.device_type,
+                deployment_info = EXCLUDED.deployment_info
+            "#,
+        )
+        .build()
+        .execute(&mut txn)
+        .await?;
+    }
+
     txn.commit().await?;
 
     Ok(())
@@ -285,11 +567,20 @@ mod tests {
     async fn records_tracking_for_new_radio() {
         let radio = mobile_radio(vec![1, 2, 3]);
 
-        let result = identify_changes(stream::iter(vec![radio.clone()]), HashMap::new()).await;
+        let (result, changes) = identify_changes(
+            stream::iter(vec![radio.clone()]),
+            HashMap::new(),
+            &HashSet::new(),
+        )
+        .await;
 
         assert_eq!(result[0].entity_key, radio.entity_key);
         assert_eq!(result[0].hash, radio.hash());
         assert_eq!(result[0].last_changed_at, radio.refreshed_at);
+        assert_eq!(
+            changes,
+            vec![MobileRadioState::new(&radio, radio.refreshed_at)]
+        );
     }
 
     #[tokio::test]
@@ -302,10 +593,40 @@ mod tests {
         let mut tracked_radios = HashMap::new();
         tracked_radios.insert(tracked_radio.entity_key.clone(), tracked_radio);
 
-        let result = identify_changes(stream::iter(vec![radio.clone()]), tracked_radios).await;
+        let radios_with_history = HashSet::from([radio.entity_key.clone()]);
+        let (result, changes) = identify_changes(
+            stream::iter(vec![radio.clone()]),
+            tracked_radios,
+            &radios_with_history,
+        )
+        .await;
 
         assert_eq!(1, result.len());
         assert_eq!(original_refreshed_at, result[0].last_changed_at);
+        assert!(changes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn records_history_of_tracked_radio_without_history() {
+        let mut radio = mobile_radio(vec![1, 2, 3]);
+        let tracked_radio = TrackedMobileRadio::new(&radio);
+        let original_refreshed_at = radio.refreshed_at;
+        radio.refreshed_at = Utc::now();
+
+        let mut tracked_radios = HashMap::new();
+        tracked_radios.insert(tracked_radio.entity_key.clone(), tracked_radio);
+
+        let (_, changes) = identify_changes(
+            stream::iter(vec![radio.clone()]),
+            tracked_radios,
+            &HashSet::new(),
+        )
+        .await;
+
+        assert_eq!(
+            changes,
+            vec![MobileRadioState::new(&radio, original_refreshed_at)]
+        );
     }
 
     #[tokio::test]
@@ -318,15 +639,87 @@ mod tests {
         let mut tracked_radios = HashMap::new();
         tracked_radios.insert(tracked_radio.entity_key.clone(), tracked_radio);
 
-        let result = identify_changes(stream::iter(vec![radio.clone()]), tracked_radios).await;
+        let radios_with_history = HashSet::from([radio.entity_key.clone()]);
+        let (result, changes) = identify_changes(
+            stream::iter(vec![radio.clone()]),
+            tracked_radios,
+            &radios_with_history,
+        )
+        .await;
 
         assert_eq!(radio.refreshed_at, result[0].last_changed_at);
         assert_eq!(radio.hash(), result[0].hash);
+        assert_eq!(
+            changes,
+            vec![MobileRadioState::new(&radio, radio.refreshed_at)]
+        );
+    }
+
+    #[test]
+    fn diffs_changes_against_previous_state() {
+        let radio = mobile_radio(vec![1, 2, 3]);
+        let first = MobileRadioState::new(&radio, radio.refreshed_at);
+        let second = MobileRadioState {
+            changed_at: radio.refreshed_at + chrono::Duration::minutes(30),
+            location: Some(2),
+            deployment_info: None,
+            ..first.clone()
+        };
+
+        let changes = with_diffs(HashMap::new(), vec![first.clone(), second.clone()]);
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].changes.len(), 7);
+        assert_eq!(
+            changes[1].changes,
+            vec![
+                FieldChange {
+                    field: "location",
+                    from: json!(1),
+                    to: json!(2),
+                },
+                FieldChange {
+                    field: "deployment_info",
+                    from: json!("deployment_info"),
+                    to: Value::Null,
+                },
+            ]
+        );
+
+        let previous = HashMap::from([(first.entity_key.clone(), first)]);
+        assert_eq!(with_diffs(previous, vec![second])[0].changes.len(), 2);
+    }
+
+    #[test]
+    fn gateway_info_from_recorded_state() {
+        let radio = MobileRadio {
+            device_type: "\"wifiIndoor\"".to_string(),
+            deployment_info: None,
+            ..mobile_radio(vec![1, 2, 3])
+        };
+        let state = MobileRadioState::new(&radio, radio.refreshed_at);
+
+        let info = GatewayInfo::try_from(state).unwrap();
+        assert!(matches!(info.device_type, DeviceType::WifiIndoor));
+        assert_eq!(info.metadata.map(|metadata| metadata.location), Some(1));
+        assert_eq!(info.created_at, Some(radio.created_at));
+        assert_eq!(info.updated_at, Some(radio.refreshed_at));
+        assert_eq!(info.refreshed_at, Some(radio.refreshed_at));
+
+        let state = MobileRadioState {
+            deployment_info: Some("{\"unknownInfoV0\": {}}".to_string()),
+            ..MobileRadioState::new(&radio, radio.refreshed_at)
+        };
+        assert!(GatewayInfo::try_from(state).is_err());
+
+        let state = MobileRadioState::new(&mobile_radio(vec![1, 2, 3]), radio.refreshed_at);
+        assert!(GatewayInfo::try_from(state).is_err());
     }
 
     fn mobile_radio(entity_key: EntityKey) -> MobileRadio {
         MobileRadio {
             entity_key,
+            created_at: Utc::now() - chrono::Duration::days(1),
             refreshed_at: Utc::now() - chrono::Duration::hours(1),
             location: Some(1),
             is_full_hotspot: Some(1),
@@ -11,6 +11,7 @@ use helium_proto::services::mobile_config::{
 use mobile_config::{
     gateway_service::GatewayService,
     key_cache::{CacheKeys, KeyCache},
+    mobile_radio_tracker::track_changes,
     KeyRole,
 };
 use prost::Message;
@@ -590,6 +591,102 @@ async fn gateway_info_v2(pool: PgPool) {
     assert_eq!(resp_err.code(), Code::NotFound);
 }
 
+#[sqlx::test]
+async fn gateway_info_at_timestamp(pool: PgPool) {
+    let admin_key = make_keypair();
+    let asset1_pubkey = make_keypair().public_key().clone();
+    let asset1_hex_idx = 631711281837647359_i64;
+    let asset1_new_hex_idx = 631711286145955327_i64;
+    let created_at = Utc::now() - Duration::hours(5);
+    let refreshed_at = Utc::now() - Duration::hours(3);
+    let moved_at = Utc::now() - Duration::hours(1);
+
+    create_db_tables(&pool).await;
+    add_db_record(
+        &pool,
+        "asset1",
+        asset1_hex_idx,
+        "\"wifiIndoor\"",
+        asset1_pubkey.clone().into(),
+        created_at,
+        Some(refreshed_at),
+        None,
+    )
+    .await;
+    track_changes(&pool, &pool).await.unwrap();
+    sqlx::query("UPDATE mobile_hotspot_infos SET location = $1, refreshed_at = $2")
+        .bind(asset1_new_hex_idx)
+        .bind(moved_at)
+        .execute(&pool)
+        .await
+        .unwrap();
+    track_changes(&pool, &pool).await.unwrap();
+
+    let (addr, _handle) = spawn_gateway_service(pool.clone(), admin_key.public_key().clone()).await;
+    let mut client = GatewayClient::connect(addr).await.unwrap();
+
+    let location_at = |info: proto::GatewayInfoV2| {
+        i64::from_str_radix(&info.metadata.unwrap().location, 16).unwrap()
+    };
+
+    let req = make_signed_info_at_timestamp_request(
+        &asset1_pubkey,
+        &admin_key,
+        (moved_at - Duration::minutes(1)).timestamp() as u64,
+    );
+    let info = client
+        .info_at_timestamp(req)
+        .await
+        .unwrap()
+        .into_inner()
+        .info
+        .unwrap();
+    assert_eq!(info.created_at, created_at.timestamp() as u64);
+    assert_eq!(info.updated_at, refreshed_at.timestamp() as u64);
+    assert_eq!(location_at(info), asset1_hex_idx);
+
+    let req = make_signed_info_at_timestamp_request(
+        &asset1_pubkey,
+        &admin_key,
+        Utc::now().timestamp() as u64,
+    );
+    let info = client
+        .info_at_timestamp(req)
+        .await
+        .unwrap()
+        .into_inner()
+        .info
+        .unwrap();
+    assert_eq!(info.updated_at, moved_at.timestamp() as u64);
+    assert_eq!(location_at(info), asset1_new_hex_idx);
+
+    // Before the recorded history
+    let req = make_signed_info_at_timestamp_request(
+        &asset1_pubkey,
+        &admin_key,
+        (refreshed_at - Duration::minutes(1)).timestamp() as u64,
+    );
+    let resp_err = client
+        .info_at_timestamp(req)
+        .await
+        .expect_err("testing expects error");
+    assert_eq!(resp_err.code(), Code::NotFound);
+
+    let mut req = proto::GatewayChangesSinceReqV1 {
+        since: (moved_at - Duration::minutes(1)).timestamp() as u64,
+        batch_size: 10,
+        signer: admin_key.public_key().to_vec(),
+        signature: vec![],
+    };
+    req.signature = admin_key.sign(&req.encode_to_vec()).unwrap();
+    let mut stream = client.changes_since(req).await.unwrap().into_inner();
+    let resp = stream.next().await.unwrap().unwrap();
+    assert_eq!(resp.gateways.len(), 1);
+    assert_eq!(resp.gateways[0].updated_at, moved_at.timestamp() as u64);
+    assert_eq!(location_at(resp.gateways[0].clone()), asset1_new_hex_idx);
+    assert!(stream.next().await.is_none());
+}
+
 #[sqlx::test]
 async fn gateway_info_stream_v2_updated_at_check(pool: PgPool) {
     let admin_key = make_keypair();
@@ -845,6 +942,21 @@ fn make_signed_info_request(address: &PublicKey, signer: &Keypair) -> proto::Gat
     req
 }
 
+fn make_signed_info_at_timestamp_request(
+    address: &PublicKey,
+    signer: &Keypair,
+    timestamp: u64,
+) -> proto::GatewayInfoAtTimestampReqV1 {
+    let mut req = proto::GatewayInfoAtTimestampReqV1 {
+        address: address.to_vec(),
+        timestamp,
+        signer: signer.public_key().to_vec(),
+        signature: vec![],
+    };
+    req.signature = signer.sign(&req.encode_to_vec()).unwrap();
+    req
+}
+
 fn make_signed_info_batch_request(
     addresses: &[PublicKey],
     signer: &Keypair,
@@ -1,6 +1,8 @@
 use chrono::Utc;
 use helium_crypto::PublicKeyBinary;
-use mobile_config::mobile_radio_tracker::{get_tracked_radios, track_changes};
+use mobile_config::mobile_radio_tracker::{
+    get_changes_since, get_radio_as_of, get_tracked_radios, track_changes,
+};
 use sqlx::PgPool;
 
 pub mod common;
@@ -62,3 +64,57 @@ async fn mobile_tracker_handle_entity_duplicates(pool: PgPool) {
         now.timestamp_millis()
     );
 }
+
+#[sqlx::test]
+async fn mobile_tracker_records_radio_history(pool: PgPool) {
+    let asset1_pubkey = make_keypair().public_key().clone();
+    let asset1_hex_idx = 631711281837647359_i64;
+    let asset1_new_hex_idx = 631711286145955327_i64;
+    create_db_tables(&pool).await;
+    let now = Utc::now();
+    let now_minus_hour = now - chrono::Duration::hours(1);
+    let pubkey_binary = PublicKeyBinary::from(asset1_pubkey.clone());
+
+    add_db_record(
+        &pool,
+        "asset1",
+        asset1_hex_idx,
+        "\"wifiIndoor\"",
+        asset1_pubkey.clone().into(),
+        now_minus_hour,
+        Some(now_minus_hour),
+        None,
+    )
+    .await;
+    track_changes(&pool, &pool).await.unwrap();
+
+    sqlx::query("UPDATE mobile_hotspot_infos SET location = $1, refreshed_at = $2")
+        .bind(asset1_new_hex_idx)
+        .bind(now)
+        .execute(&pool)
+        .await
+        .unwrap();
+    track_changes(&pool, &pool).await.unwrap();
+    // Nothing changed, nothing new is recorded
+    track_changes(&pool, &pool).await.unwrap();
+
+    let b58 = bs58::decode(pubkey_binary.to_string()).into_vec().unwrap();
+    let before = get_radio_as_of(&pool, &b58, now - chrono::Duration::minutes(1))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(before.location, Some(asset1_hex_idx));
+    let after = get_radio_as_of(&pool, &b58, now).await.unwrap().unwrap();
+    assert_eq!(after.location, Some(asset1_new_hex_idx));
+    assert!(
+        get_radio_as_of(&pool, &b58, now_minus_hour - chrono::Duration::minutes(1))
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let changes = get_changes_since(&pool, now_minus_hour).await.unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].changes.len(), 1);
+    assert_eq!(changes[0].changes[0].field, "location");
+}
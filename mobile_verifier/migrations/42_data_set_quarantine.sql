@@ -0,0 +1,5 @@
+ALTER TYPE data_set_status ADD VALUE IF NOT EXISTS 'quarantined';
+
+ALTER TABLE hex_assignment_data_set_status
+    ADD COLUMN IF NOT EXISTS checksum TEXT,
+    ADD COLUMN IF NOT EXISTS quarantine_reason TEXT;
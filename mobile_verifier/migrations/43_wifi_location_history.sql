@@ -0,0 +1,30 @@
+CREATE TABLE IF NOT EXISTS wifi_location_history (
+    hotspot_key TEXT NOT NULL,
+    truncated_timestamp TIMESTAMPTZ NOT NULL,
+    lat DOUBLE PRECISION NOT NULL,
+    lon DOUBLE PRECISION NOT NULL,
+    distance_to_asserted BIGINT NOT NULL,
+    PRIMARY KEY (hotspot_key, truncated_timestamp)
+);
+
+DO $$ BEGIN
+CREATE TYPE location_flag_reason AS enum (
+    'jumping',
+    'drifting'
+);
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+CREATE TABLE IF NOT EXISTS wifi_location_flags (
+    hotspot_key TEXT PRIMARY KEY,
+    reason location_flag_reason NOT NULL,
+    flagged_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    samples INTEGER NOT NULL,
+    mean_distance_to_asserted DOUBLE PRECISION NOT NULL,
+    max_distance_to_asserted BIGINT NOT NULL,
+    latest_distance_to_asserted BIGINT NOT NULL,
+    jumps INTEGER NOT NULL,
+    drift_m_per_day DOUBLE PRECISION NOT NULL
+);
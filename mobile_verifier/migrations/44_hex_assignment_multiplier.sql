@@ -0,0 +1 @@
+ALTER TABLE hexes ADD COLUMN IF NOT EXISTS assignment_multiplier NUMERIC;
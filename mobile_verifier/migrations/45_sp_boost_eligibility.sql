@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS sp_boost_eligibility (
+    period_start TIMESTAMPTZ NOT NULL,
+    hotspot_key TEXT NOT NULL,
+    cbsd_id TEXT,
+    status TEXT NOT NULL,
+    evidence TEXT
+);
+
+CREATE INDEX IF NOT EXISTS sp_boost_eligibility_period_hotspot_idx
+    ON sp_boost_eligibility (period_start, hotspot_key);
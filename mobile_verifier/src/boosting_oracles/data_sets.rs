@@ -6,6 +6,7 @@ use std::{
 };
 
 use chrono::{DateTime, Utc};
+use db_store::meta;
 use file_store::{
     file_sink::FileSinkClient,
     file_upload::FileUpload,
@@ -22,6 +23,7 @@ use lazy_static::lazy_static;
 use regex::Regex;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal_macros::dec;
+use sha2::{Digest, Sha256};
 use sqlx::{FromRow, PgPool, QueryBuilder};
 use task_manager::{ManagedTask, TaskManager};
 use tokio::{fs::File, io::AsyncWriteExt, time::Instant};
@@ -31,14 +33,16 @@ use crate::{
     Settings,
 };
 
+use super::validation::{self, DataSetValidationSettings};
+
 use hex_assignments::{
     assignment::HexAssignments, footfall::Footfall, landtype::Landtype,
-    service_provider_override::ServiceProviderOverride, urbanization::Urbanization, HexAssignment,
-    HexBoostData,
+    service_provider_override::ServiceProviderOverride, urbanization::Urbanization,
+    DataSetRegistry, HexAssignment, HexBoostData,
 };
 
 #[async_trait::async_trait]
-pub trait DataSet: HexAssignment + Send + Sync + 'static {
+pub trait DataSet: HexAssignment + Default + Send + Sync + 'static {
     const TYPE: DataSetType;
 
     fn timestamp(&self) -> Option<DateTime<Utc>>;
@@ -47,18 +51,30 @@ pub trait DataSet: HexAssignment + Send + Sync + 'static {
 
     fn is_ready(&self) -> bool;
 
-    async fn fetch_first_data_set(
+    fn disktree(&self) -> Option<&DiskTreeMap>;
+
+    /// Loads the latest processed data set if it differs from the one in use,
+    /// downloading it again if the file has since been deleted. Returns true
+    /// if a different data set was loaded.
+    async fn load_latest_processed_data_set(
         &mut self,
+        store: &FileStore,
         pool: &PgPool,
         data_set_directory: &Path,
-    ) -> anyhow::Result<()> {
-        let Some(first_data_set) = db::fetch_latest_processed_data_set(pool, Self::TYPE).await?
-        else {
-            return Ok(());
+    ) -> anyhow::Result<bool> {
+        let Some(latest) = db::fetch_latest_processed_data_set(pool, Self::TYPE).await? else {
+            return Ok(false);
         };
-        let path = get_data_set_path(data_set_directory, Self::TYPE, first_data_set.time_to_use);
-        self.update(Path::new(&path), first_data_set.time_to_use)?;
-        Ok(())
+        if self.timestamp() == Some(latest.time_to_use) {
+            return Ok(false);
+        }
+        let path = get_data_set_path(data_set_directory, Self::TYPE, latest.time_to_use);
+        if !tokio::fs::try_exists(&path).await? {
+            download_data_set(store, &latest.filename, &path).await?;
+        }
+        self.update(Path::new(&path), latest.time_to_use)?;
+        tracing::info!(data_set = latest.filename, "Loaded data set");
+        Ok(true)
     }
 
     async fn check_for_available_data_sets(
@@ -69,6 +85,10 @@ pub trait DataSet: HexAssignment + Send + Sync + 'static {
         tracing::info!("Checking for new {} data sets", Self::TYPE.to_prefix());
         let mut new_data_sets = store.list(Self::TYPE.to_prefix(), self.timestamp(), None);
         while let Some(new_data_set) = new_data_sets.next().await.transpose()? {
+            // Published checksums are fetched alongside their data set
+            if new_data_set.key.ends_with(CHECKSUM_SUFFIX) {
+                continue;
+            }
             db::insert_new_data_set(pool, &new_data_set.key, Self::TYPE, new_data_set.timestamp)
                 .await?;
         }
@@ -80,6 +100,7 @@ pub trait DataSet: HexAssignment + Send + Sync + 'static {
         store: &FileStore,
         pool: &PgPool,
         data_set_directory: &Path,
+        validation: &DataSetValidationSettings,
     ) -> anyhow::Result<Option<NewDataSet>> {
         self.check_for_available_data_sets(store, pool).await?;
 
@@ -97,7 +118,23 @@ pub trait DataSet: HexAssignment + Send + Sync + 'static {
         );
 
         if !latest_unprocessed_data_set.status.is_downloaded() {
-            download_data_set(store, &latest_unprocessed_data_set.filename, &path).await?;
+            let checksum =
+                download_data_set(store, &latest_unprocessed_data_set.filename, &path).await?;
+            if let Err(reason) = verify_checksum(
+                store,
+                &latest_unprocessed_data_set.filename,
+                &checksum,
+                validation.require_checksum,
+            )
+            .await
+            {
+                latest_unprocessed_data_set
+                    .quarantine(pool, &reason)
+                    .await?;
+                return Ok(None);
+            }
+            db::set_data_set_checksum(pool, &latest_unprocessed_data_set.filename, &checksum)
+                .await?;
             latest_unprocessed_data_set.mark_as_downloaded(pool).await?;
             tracing::info!(
                 data_set = latest_unprocessed_data_set.filename,
@@ -105,7 +142,32 @@ pub trait DataSet: HexAssignment + Send + Sync + 'static {
             );
         }
 
-        self.update(Path::new(&path), latest_unprocessed_data_set.time_to_use)?;
+        // Validate the new data set before it replaces the one in use
+        let mut candidate = Self::default();
+        if let Err(err) =
+            candidate.update(Path::new(&path), latest_unprocessed_data_set.time_to_use)
+        {
+            latest_unprocessed_data_set
+                .quarantine(pool, &format!("could not open data set: {err}"))
+                .await?;
+            return Ok(None);
+        }
+        let report = validation::validate_data_set(pool, &*self, &candidate, validation).await?;
+        let failures = report.failures(Self::TYPE, validation);
+        if !failures.is_empty() {
+            latest_unprocessed_data_set
+                .quarantine(pool, &failures.join("; "))
+                .await?;
+            return Ok(None);
+        }
+        tracing::info!(
+            data_set = latest_unprocessed_data_set.filename,
+            coverage = report.coverage(),
+            changed_share = report.changed_share(),
+            "Data set passed validation"
+        );
+
+        *self = candidate;
 
         Ok(Some(latest_unprocessed_data_set))
     }
@@ -128,6 +190,10 @@ impl DataSet for Footfall {
     fn is_ready(&self) -> bool {
         self.footfall.is_some()
     }
+
+    fn disktree(&self) -> Option<&DiskTreeMap> {
+        self.footfall.as_ref()
+    }
 }
 
 #[async_trait::async_trait]
@@ -147,6 +213,10 @@ impl DataSet for Landtype {
     fn is_ready(&self) -> bool {
         self.landtype.is_some()
     }
+
+    fn disktree(&self) -> Option<&DiskTreeMap> {
+        self.landtype.as_ref()
+    }
 }
 
 #[async_trait::async_trait]
@@ -166,6 +236,10 @@ impl DataSet for Urbanization {
     fn is_ready(&self) -> bool {
         self.urbanized.is_some()
     }
+
+    fn disktree(&self) -> Option<&DiskTreeMap> {
+        self.urbanized.as_ref()
+    }
 }
 
 #[async_trait::async_trait]
@@ -185,6 +259,10 @@ impl DataSet for ServiceProviderOverride {
     fn is_ready(&self) -> bool {
         self.service_provider_override.is_some()
     }
+
+    fn disktree(&self) -> Option<&DiskTreeMap> {
+        self.service_provider_override.as_ref()
+    }
 }
 
 pub fn is_hex_boost_data_ready<A, B, C, D>(h: &HexBoostData<A, B, C, D>) -> bool
@@ -200,6 +278,36 @@ where
         && h.service_provider_override.is_ready()
 }
 
+/// Builds the hex boost data with the additional data sets and assignment
+/// rule table from the settings, without any of the built-in data sets loaded
+pub fn hex_boost_data_from_settings(
+    settings: &Settings,
+) -> anyhow::Result<HexBoostData<Footfall, Landtype, Urbanization, ServiceProviderOverride>> {
+    Ok(HexBoostData::builder()
+        .footfall(Footfall::new(None))
+        .landtype(Landtype::new(None))
+        .urbanization(Urbanization::new(None))
+        .service_provider_override(ServiceProviderOverride::new(None))
+        .extra(DataSetRegistry::open(&settings.extra_data_sets)?)
+        .rules(settings.hex_assignment_rules.clone())
+        .build()?)
+}
+
+const HEX_ASSIGNMENT_RULES_KEY: &str = "hex_assignment_rules";
+
+/// Fingerprint of the assignment rule table and the additional data sets,
+/// including the contents of their files. Every hex is re-assigned when it
+/// changes.
+pub fn hex_assignment_rules_fingerprint(settings: &Settings) -> anyhow::Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(serde_json::to_vec(&settings.hex_assignment_rules)?);
+    for definition in &settings.extra_data_sets {
+        hasher.update(serde_json::to_vec(definition)?);
+        hasher.update(std::fs::read(&definition.path)?);
+    }
+    Ok(validation::encode_checksum(&hasher.finalize()))
+}
+
 pub struct DataSetDownloaderDaemon<A, B, C, D, T> {
     pool: PgPool,
     data_sets: HexBoostData<A, B, C, D>,
@@ -208,13 +316,24 @@ pub struct DataSetDownloaderDaemon<A, B, C, D, T> {
     data_set_directory: PathBuf,
     new_coverage_object_notification: NewCoverageObjectNotification,
     poll_duration: Duration,
+    validation: DataSetValidationSettings,
+    hex_assignment_rules_fingerprint: String,
 }
 
 #[derive(FromRow)]
 pub struct NewDataSet {
-    filename: String,
-    time_to_use: DateTime<Utc>,
-    status: DataSetStatus,
+    pub filename: String,
+    pub time_to_use: DateTime<Utc>,
+    pub status: DataSetStatus,
+}
+
+#[derive(FromRow)]
+pub struct DataSetHistoryEntry {
+    pub filename: String,
+    pub time_to_use: DateTime<Utc>,
+    pub status: DataSetStatus,
+    pub checksum: Option<String>,
+    pub quarantine_reason: Option<String>,
 }
 
 impl NewDataSet {
@@ -227,15 +346,23 @@ impl NewDataSet {
         db::set_data_set_status(pool, &self.filename, DataSetStatus::Processed).await?;
         Ok(())
     }
+
+    pub async fn quarantine(&self, pool: &PgPool, reason: &str) -> anyhow::Result<()> {
+        tracing::warn!(data_set = self.filename, reason, "Quarantining data set");
+        db::quarantine_data_set(pool, &self.filename, reason).await?;
+        Ok(())
+    }
 }
 
-#[derive(Copy, Clone, sqlx::Type)]
+#[derive(Copy, Clone, Debug, sqlx::Type)]
 #[sqlx(type_name = "data_set_status")]
 #[sqlx(rename_all = "lowercase")]
 pub enum DataSetStatus {
     Pending,
     Downloaded,
     Processed,
+    /// Failed validation or was rolled back, and is never used
+    Quarantined,
 }
 
 impl DataSetStatus {
@@ -298,16 +425,7 @@ impl
             )
             .await?;
 
-        let urbanization = Urbanization::new(None);
-        let footfall = Footfall::new(None);
-        let landtype = Landtype::new(None);
-        let service_provider_override = ServiceProviderOverride::new(None);
-        let hex_boost_data = HexBoostData::builder()
-            .footfall(footfall)
-            .landtype(landtype)
-            .urbanization(urbanization)
-            .service_provider_override(service_provider_override)
-            .build()?;
+        let hex_boost_data = hex_boost_data_from_settings(settings)?;
 
         let data_set_downloader = Self::new(
             pool,
@@ -317,6 +435,8 @@ impl
             settings.data_sets_directory.clone(),
             new_coverage_object_notification,
             settings.data_sets_poll_duration,
+            settings.data_set_validation.clone(),
+            hex_assignment_rules_fingerprint(settings)?,
         );
 
         Ok(TaskManager::builder()
@@ -334,6 +454,7 @@ where
     D: DataSet,
     T: DataSetProcessor,
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         pool: PgPool,
         data_sets: HexBoostData<A, B, C, D>,
@@ -342,6 +463,8 @@ where
         data_set_directory: PathBuf,
         new_coverage_object_notification: NewCoverageObjectNotification,
         poll_duration: Duration,
+        validation: DataSetValidationSettings,
+        hex_assignment_rules_fingerprint: String,
     ) -> Self {
         Self {
             pool,
@@ -351,42 +474,95 @@ where
             data_set_directory,
             new_coverage_object_notification,
             poll_duration,
+            validation,
+            hex_assignment_rules_fingerprint,
         }
     }
 
+    async fn hex_assignment_rules_changed(&self) -> anyhow::Result<bool> {
+        let stored = match meta::fetch::<String>(&self.pool, HEX_ASSIGNMENT_RULES_KEY).await {
+            Ok(stored) => Some(stored),
+            Err(db_store::Error::NotFound(_)) => None,
+            Err(err) => return Err(err.into()),
+        };
+        Ok(stored.as_ref() != Some(&self.hex_assignment_rules_fingerprint))
+    }
+
+    async fn set_all_oracle_boosting_assignments(&self) -> anyhow::Result<()> {
+        self.data_set_processor
+            .set_all_oracle_boosting_assignments(&self.pool, &self.data_sets)
+            .await?;
+        meta::store(
+            &self.pool,
+            HEX_ASSIGNMENT_RULES_KEY,
+            &self.hex_assignment_rules_fingerprint,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Loads the latest processed data sets that differ from those in use, i.e.
+    /// on startup or after a data set was rolled back
+    async fn load_latest_processed_data_sets(&mut self) -> anyhow::Result<bool> {
+        let (store, pool, dir) = (&self.store, &self.pool, &self.data_set_directory);
+        let urbanization = self
+            .data_sets
+            .urbanization
+            .load_latest_processed_data_set(store, pool, dir)
+            .await?;
+        let footfall = self
+            .data_sets
+            .footfall
+            .load_latest_processed_data_set(store, pool, dir)
+            .await?;
+        let landtype = self
+            .data_sets
+            .landtype
+            .load_latest_processed_data_set(store, pool, dir)
+            .await?;
+        let service_provider_override = self
+            .data_sets
+            .service_provider_override
+            .load_latest_processed_data_set(store, pool, dir)
+            .await?;
+        Ok(urbanization || footfall || landtype || service_provider_override)
+    }
+
     async fn check_for_new_data_sets(&mut self) -> anyhow::Result<()> {
+        let rolled_back = self.load_latest_processed_data_sets().await?;
+
+        let (store, pool, dir) = (&self.store, &self.pool, &self.data_set_directory);
         let new_urbanized = self
             .data_sets
             .urbanization
-            .fetch_next_available_data_set(&self.store, &self.pool, &self.data_set_directory)
+            .fetch_next_available_data_set(store, pool, dir, &self.validation)
             .await?;
         let new_footfall = self
             .data_sets
             .footfall
-            .fetch_next_available_data_set(&self.store, &self.pool, &self.data_set_directory)
+            .fetch_next_available_data_set(store, pool, dir, &self.validation)
             .await?;
         let new_landtype = self
             .data_sets
             .landtype
-            .fetch_next_available_data_set(&self.store, &self.pool, &self.data_set_directory)
+            .fetch_next_available_data_set(store, pool, dir, &self.validation)
             .await?;
         let new_service_provider_override = self
             .data_sets
             .service_provider_override
-            .fetch_next_available_data_set(&self.store, &self.pool, &self.data_set_directory)
+            .fetch_next_available_data_set(store, pool, dir, &self.validation)
             .await?;
 
-        // If all of the data sets are ready and there is at least one new one, re-process all
-        // hex assignments:
-        let new_data_set = new_urbanized.is_some()
+        // If all of the data sets are ready and there is at least one new or rolled back one,
+        // re-process all hex assignments:
+        let new_data_set = rolled_back
+            || new_urbanized.is_some()
             || new_footfall.is_some()
             || new_landtype.is_some()
             || new_service_provider_override.is_some();
         if is_hex_boost_data_ready(&self.data_sets) && new_data_set {
             tracing::info!("Processing new data sets");
-            self.data_set_processor
-                .set_all_oracle_boosting_assignments(&self.pool, &self.data_sets)
-                .await?;
+            self.set_all_oracle_boosting_assignments().await?;
         }
 
         // Mark the new data sets as processed and delete the old ones
@@ -433,28 +609,19 @@ where
 
     pub async fn run(mut self) -> anyhow::Result<()> {
         tracing::info!("Starting data set downloader task");
-        self.data_sets
-            .urbanization
-            .fetch_first_data_set(&self.pool, &self.data_set_directory)
-            .await?;
-        self.data_sets
-            .footfall
-            .fetch_first_data_set(&self.pool, &self.data_set_directory)
-            .await?;
-        self.data_sets
-            .landtype
-            .fetch_first_data_set(&self.pool, &self.data_set_directory)
-            .await?;
-        self.data_sets
-            .service_provider_override
-            .fetch_first_data_set(&self.pool, &self.data_set_directory)
-            .await?;
-        // Attempt to fill in any unassigned hexes. This is for the edge case in
-        // which we shutdown before a coverage object updates.
+        self.load_latest_processed_data_sets().await?;
+        // Re-assign every hex if the assignment rules or additional data sets
+        // changed, otherwise attempt to fill in any unassigned hexes. This is
+        // for the edge case in which we shutdown before a coverage object updates.
         if is_hex_boost_data_ready(&self.data_sets) {
-            self.data_set_processor
-                .set_unassigned_oracle_boosting_assignments(&self.pool, &self.data_sets)
-                .await?;
+            if self.hex_assignment_rules_changed().await? {
+                tracing::info!("Hex assignment rules changed, re-assigning all hexes");
+                self.set_all_oracle_boosting_assignments().await?;
+            } else {
+                self.data_set_processor
+                    .set_unassigned_oracle_boosting_assignments(&self.pool, &self.data_sets)
+                    .await?;
+            }
         }
 
         let mut wakeup = Instant::now() + self.poll_duration;
@@ -496,6 +663,8 @@ fn get_data_set_path(
     dir
 }
 
+const CHECKSUM_SUFFIX: &str = ".sha256";
+
 lazy_static! {
     static ref RE: Regex = Regex::new(r"([a-z,_]+).(\d+)(.res[0-9]{1,2}.h3tree)?").unwrap();
 }
@@ -523,13 +692,19 @@ async fn delete_old_data_sets(
     Ok(())
 }
 
-async fn download_data_set(
+/// Downloads and decompresses a data set, returning the sha256 checksum of
+/// the file as stored
+pub async fn download_data_set(
     store: &FileStore,
     in_file_name: &str,
     out_path: &Path,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<String> {
     tracing::info!("Downloading new data set: {}", out_path.to_string_lossy());
-    let stream = store.get_raw(in_file_name).await?;
+    let mut hasher = Sha256::new();
+    let stream = store
+        .get_raw(in_file_name)
+        .await?
+        .inspect_ok(|bytes| hasher.update(bytes));
     let mut bytes = tokio_util::codec::FramedRead::new(
         async_compression::tokio::bufread::GzipDecoder::new(tokio_util::io::StreamReader::new(
             stream,
@@ -540,10 +715,52 @@ async fn download_data_set(
     while let Some(bytes) = bytes.next().await.transpose()? {
         file.write_all(&bytes).await?;
     }
+    drop(bytes);
+    Ok(validation::encode_checksum(&hasher.finalize()))
+}
+
+/// Compares the checksum of a downloaded data set with the one published as
+/// `<filename>.sha256`, returning the reason to quarantine it on a mismatch.
+async fn verify_checksum(
+    store: &FileStore,
+    filename: &str,
+    checksum: &str,
+    require_checksum: bool,
+) -> Result<(), String> {
+    let published = match fetch_published_checksum(store, filename).await {
+        Ok(published) => published,
+        Err(err) if require_checksum => {
+            return Err(format!("no valid published checksum: {err}"));
+        }
+        Err(err) => {
+            tracing::warn!(
+                data_set = filename,
+                ?err,
+                "No published checksum for data set"
+            );
+            return Ok(());
+        }
+    };
+    if published != checksum {
+        return Err(format!(
+            "checksum {checksum} does not match published checksum {published}"
+        ));
+    }
     Ok(())
 }
 
-#[derive(Copy, Clone, sqlx::Type)]
+async fn fetch_published_checksum(store: &FileStore, filename: &str) -> anyhow::Result<String> {
+    let contents = store
+        .get_raw(format!("{filename}{CHECKSUM_SUFFIX}"))
+        .await?
+        .collect()
+        .await?
+        .into_bytes();
+    validation::parse_checksum(std::str::from_utf8(&contents)?)
+        .ok_or_else(|| anyhow::anyhow!("malformed checksum"))
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, sqlx::Type, clap::ValueEnum)]
 #[sqlx(type_name = "data_set_type")]
 #[sqlx(rename_all = "snake_case")]
 pub enum DataSetType {
@@ -571,6 +788,15 @@ impl DataSetType {
             Self::ServiceProviderOverride => "res12",
         }
     }
+
+    pub fn resolution(self) -> u8 {
+        match self {
+            Self::Urbanization => 10,
+            Self::Footfall => 10,
+            Self::Landtype => 10,
+            Self::ServiceProviderOverride => 12,
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -710,7 +936,7 @@ pub mod db {
         since: Option<DateTime<Utc>>,
     ) -> sqlx::Result<Option<NewDataSet>> {
         sqlx::query_as(
-            "SELECT filename, time_to_use, status FROM hex_assignment_data_set_status WHERE status != 'processed' AND data_set = $1 AND COALESCE(time_to_use > $2, TRUE) AND time_to_use <= $3 ORDER BY time_to_use DESC LIMIT 1"
+            "SELECT filename, time_to_use, status FROM hex_assignment_data_set_status WHERE status NOT IN ('processed', 'quarantined') AND data_set = $1 AND COALESCE(time_to_use > $2, TRUE) AND time_to_use <= $3 ORDER BY time_to_use DESC LIMIT 1"
         )
         .bind(data_set_type)
         .bind(since)
@@ -744,6 +970,60 @@ pub mod db {
         Ok(())
     }
 
+    /// Every data set of the given type, latest first
+    pub async fn fetch_data_set_history(
+        pool: &PgPool,
+        data_set_type: DataSetType,
+    ) -> sqlx::Result<Vec<DataSetHistoryEntry>> {
+        sqlx::query_as(
+            "SELECT filename, time_to_use, status, checksum, quarantine_reason FROM hex_assignment_data_set_status WHERE data_set = $1 ORDER BY time_to_use DESC"
+        )
+        .bind(data_set_type)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Processed data sets of the given type, latest first
+    pub async fn fetch_processed_data_sets(
+        pool: &PgPool,
+        data_set_type: DataSetType,
+    ) -> sqlx::Result<Vec<NewDataSet>> {
+        sqlx::query_as(
+            "SELECT filename, time_to_use, status FROM hex_assignment_data_set_status WHERE status = 'processed' AND data_set = $1 ORDER BY time_to_use DESC"
+        )
+        .bind(data_set_type)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn set_data_set_checksum(
+        pool: &PgPool,
+        filename: &str,
+        checksum: &str,
+    ) -> sqlx::Result<()> {
+        sqlx::query("UPDATE hex_assignment_data_set_status SET checksum = $1 WHERE filename = $2")
+            .bind(checksum)
+            .bind(filename)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn quarantine_data_set(
+        pool: &PgPool,
+        filename: &str,
+        reason: &str,
+    ) -> sqlx::Result<()> {
+        sqlx::query(
+            "UPDATE hex_assignment_data_set_status SET status = 'quarantined', quarantine_reason = $1 WHERE filename = $2",
+        )
+        .bind(reason)
+        .bind(filename)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn fetch_time_of_latest_processed_data_set(
         pool: &PgPool,
         data_set_type: DataSetType,
@@ -762,7 +1042,7 @@ pub mod db {
         period_end: DateTime<Utc>,
     ) -> sqlx::Result<bool> {
         Ok(sqlx::query_scalar(
-            "SELECT COUNT(*) > 0 FROM hex_assignment_data_set_status WHERE time_to_use <= $1 AND status != 'processed'",
+            "SELECT COUNT(*) > 0 FROM hex_assignment_data_set_status WHERE time_to_use <= $1 AND status NOT IN ('processed', 'quarantined')",
         )
         .bind(period_end)
         .fetch_one(pool)
@@ -780,6 +1060,7 @@ pub mod db {
                            OR footfall IS NULL
                            OR landtype IS NULL
                            OR service_provider_override IS NULL
+                           OR assignment_multiplier IS NULL
                 )
                 "#,
             )
@@ -804,7 +1085,8 @@ pub mod db {
                 urbanized IS NULL
                 OR footfall IS NULL
                 OR landtype IS NULL
-                OR service_provider_override IS NULL",
+                OR service_provider_override IS NULL
+                OR assignment_multiplier IS NULL",
         )
         .fetch(pool)
     }
@@ -873,14 +1155,14 @@ impl AssignedCoverageObjects {
     }
 
     pub async fn save(self, pool: &PgPool) -> anyhow::Result<()> {
-        const NUMBER_OF_FIELDS_IN_QUERY: u16 = 8;
+        const NUMBER_OF_FIELDS_IN_QUERY: u16 = 9;
         const ASSIGNMENTS_MAX_BATCH_ENTRIES: usize =
             (u16::MAX / NUMBER_OF_FIELDS_IN_QUERY) as usize;
 
         let assigned_hexes: Vec<_> = self.coverage_objs.into_values().flatten().collect();
         for assigned_hexes in assigned_hexes.chunks(ASSIGNMENTS_MAX_BATCH_ENTRIES) {
             QueryBuilder::new(
-                "INSERT INTO hexes (uuid, hex, signal_level, signal_power, footfall, landtype, urbanized, service_provider_override)",
+                "INSERT INTO hexes (uuid, hex, signal_level, signal_power, footfall, landtype, urbanized, service_provider_override, assignment_multiplier)",
             )
                 .push_values(assigned_hexes, |mut b, hex| {
                     b.push_bind(hex.uuid)
@@ -890,7 +1172,8 @@ impl AssignedCoverageObjects {
                         .push_bind(hex.assignments.footfall)
                         .push_bind(hex.assignments.landtype)
                         .push_bind(hex.assignments.urbanized)
-                        .push_bind(hex.assignments.service_provider_override);
+                        .push_bind(hex.assignments.service_provider_override)
+                        .push_bind(hex.assignments.assignment_multiplier);
                 })
                 .push(
                     r#"
@@ -898,7 +1181,8 @@ impl AssignedCoverageObjects {
                         footfall = EXCLUDED.footfall,
                         landtype = EXCLUDED.landtype,
                         urbanized = EXCLUDED.urbanized,
-                        service_provider_override = EXCLUDED.service_provider_override
+                        service_provider_override = EXCLUDED.service_provider_override,
+                        assignment_multiplier = EXCLUDED.assignment_multiplier
                     "#,
                 )
                 .build()
@@ -930,12 +1214,14 @@ impl UnassignedHex {
         >,
     ) -> anyhow::Result<AssignedHex> {
         let cell = hextree::Cell::try_from(self.hex)?;
-        let assignments = HexAssignments::builder(cell)
+        let mut assignments = HexAssignments::builder(cell)
             .footfall(&data_sets.footfall)
             .landtype(&data_sets.landtype)
             .urbanized(&data_sets.urbanization)
             .service_provider_override(&data_sets.service_provider_override)
             .build()?;
+        assignments.assignment_multiplier =
+            Some(data_sets.assignment_multiplier(cell, &assignments)?);
         Ok(AssignedHex {
             uuid: self.uuid,
             hex: self.hex,
@@ -1,2 +1,3 @@
 pub mod data_sets;
+pub mod validation;
 pub use data_sets::*;
@@ -0,0 +1,240 @@
+use std::pin::pin;
+
+use futures_util::TryStreamExt;
+use hextree::{disktree::DiskTreeMap, Cell};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use super::{DataSet, DataSetType};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DataSetValidationSettings {
+    /// Require every data set to be published with a `<filename>.sha256`
+    /// checksum. Otherwise checksums are only verified when present
+    pub require_checksum: bool,
+    /// Number of cells of a new data set checked for the expected resolution
+    pub resolution_sample_size: usize,
+    /// Minimum share of the currently active hexes a new data set must cover.
+    /// Not applied to service provider overrides, which only list the
+    /// overridden hexes
+    pub min_active_hex_coverage: f64,
+    /// Maximum share of the currently active hexes whose assignment a new
+    /// data set may change
+    pub max_changed_share: f64,
+}
+
+impl Default for DataSetValidationSettings {
+    fn default() -> Self {
+        Self {
+            require_checksum: false,
+            resolution_sample_size: 10_000,
+            min_active_hex_coverage: 0.9,
+            max_changed_share: 0.25,
+        }
+    }
+}
+
+/// Result of checking a new data set against the one currently in use
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct ValidationReport {
+    pub sampled_cells: usize,
+    pub wrong_resolution_cells: usize,
+    pub active_hexes: usize,
+    pub covered_hexes: usize,
+    pub changed_hexes: usize,
+}
+
+impl ValidationReport {
+    pub fn coverage(&self) -> f64 {
+        share(self.covered_hexes, self.active_hexes)
+    }
+
+    pub fn changed_share(&self) -> f64 {
+        share(self.changed_hexes, self.active_hexes)
+    }
+
+    /// Reasons the data set should not be activated, if any
+    pub fn failures(
+        &self,
+        data_set_type: DataSetType,
+        settings: &DataSetValidationSettings,
+    ) -> Vec<String> {
+        let mut failures = vec![];
+        if self.wrong_resolution_cells > 0 {
+            failures.push(format!(
+                "{} of {} sampled cells are not at resolution {}",
+                self.wrong_resolution_cells,
+                self.sampled_cells,
+                data_set_type.resolution()
+            ));
+        }
+        if data_set_type != DataSetType::ServiceProviderOverride
+            && self.active_hexes > 0
+            && self.coverage() < settings.min_active_hex_coverage
+        {
+            failures.push(format!(
+                "covers {:.4} of active hexes, minimum is {}",
+                self.coverage(),
+                settings.min_active_hex_coverage
+            ));
+        }
+        if self.changed_share() > settings.max_changed_share {
+            failures.push(format!(
+                "changes the assignment of {:.4} of active hexes, maximum is {}",
+                self.changed_share(),
+                settings.max_changed_share
+            ));
+        }
+        failures
+    }
+}
+
+fn share(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64
+    }
+}
+
+/// Counts how many of the first `sample_size` cells of the disktree are not
+/// at the expected resolution. Returns the number of sampled and wrong cells.
+pub fn sample_resolutions(
+    disktree: &DiskTreeMap,
+    resolution: u8,
+    sample_size: usize,
+) -> anyhow::Result<(usize, usize)> {
+    let mut sampled = 0;
+    let mut wrong = 0;
+    for entry in disktree.iter()?.take(sample_size) {
+        let (cell, _) = entry?;
+        sampled += 1;
+        if cell.res() != resolution {
+            wrong += 1;
+        }
+    }
+    Ok((sampled, wrong))
+}
+
+/// Validates a candidate data set against every active hex and, if one is
+/// loaded, the data set currently in use.
+pub async fn validate_data_set<D: DataSet>(
+    pool: &PgPool,
+    current: &D,
+    candidate: &D,
+    settings: &DataSetValidationSettings,
+) -> anyhow::Result<ValidationReport> {
+    let Some(disktree) = candidate.disktree() else {
+        anyhow::bail!("{} data set is not loaded", D::TYPE.to_prefix());
+    };
+    let (sampled_cells, wrong_resolution_cells) = sample_resolutions(
+        disktree,
+        D::TYPE.resolution(),
+        settings.resolution_sample_size,
+    )?;
+
+    let mut report = ValidationReport {
+        sampled_cells,
+        wrong_resolution_cells,
+        ..Default::default()
+    };
+
+    let mut active_hexes = pin!(db::fetch_active_hexes(pool));
+    while let Some(hex) = active_hexes.try_next().await? {
+        let cell = Cell::try_from(hex as u64)?;
+        report.active_hexes += 1;
+        if disktree.get(cell)?.is_some() {
+            report.covered_hexes += 1;
+        }
+        if current.is_ready() && current.assignment(cell)? != candidate.assignment(cell)? {
+            report.changed_hexes += 1;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Lowercase hex encoding of a sha256 digest
+pub fn encode_checksum(digest: &[u8]) -> String {
+    digest.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Parses a published checksum, either a bare digest or a line of `sha256sum`
+/// output
+pub fn parse_checksum(contents: &str) -> Option<String> {
+    contents
+        .split_whitespace()
+        .next()
+        .filter(|digest| digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(str::to_lowercase)
+}
+
+pub mod db {
+    use super::*;
+    use futures_util::Stream;
+
+    pub fn fetch_active_hexes(pool: &PgPool) -> impl Stream<Item = sqlx::Result<i64>> + '_ {
+        sqlx::query_scalar("SELECT DISTINCT hex FROM hexes").fetch(pool)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hextree::HexTreeMap;
+    use std::io::Write;
+
+    #[test]
+    fn failures_over_thresholds() {
+        let settings = DataSetValidationSettings::default();
+        let report = ValidationReport {
+            sampled_cells: 10,
+            wrong_resolution_cells: 0,
+            active_hexes: 100,
+            covered_hexes: 95,
+            changed_hexes: 10,
+        };
+        assert!(report.failures(DataSetType::Footfall, &settings).is_empty());
+
+        let report = ValidationReport {
+            covered_hexes: 50,
+            changed_hexes: 30,
+            ..report
+        };
+        assert_eq!(report.failures(DataSetType::Footfall, &settings).len(), 2);
+        // Service provider overrides only list overridden hexes
+        assert_eq!(
+            report
+                .failures(DataSetType::ServiceProviderOverride, &settings)
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn wrong_resolution_disktree() -> anyhow::Result<()> {
+        let res12 = Cell::from_raw(0x8c2681a3064d9ff)?;
+        let res10 = Cell::from_raw(0x8a1fb46622dffff)?;
+
+        let file = tempfile::NamedTempFile::new()?;
+        let hextree: HexTreeMap<u8> = [(res10, 1), (res12, 0)].into_iter().collect();
+        hextree.to_disktree(file.reopen()?, |w, v| w.write_all(&[*v]))?;
+        let disktree = DiskTreeMap::open(file.path())?;
+
+        assert_eq!(sample_resolutions(&disktree, 10, 10)?, (2, 1));
+        assert_eq!(sample_resolutions(&disktree, 10, 1)?.0, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn published_checksums() {
+        let digest = "9F86D081884C7D659A2FEAA0C55AD015A3BF4F1B2B0B822CD15D6C15B0F00A08";
+        assert_eq!(
+            parse_checksum(&format!("{digest}  footfall.1722895200000.gz\n")),
+            Some(digest.to_lowercase())
+        );
+        assert_eq!(parse_checksum("not a checksum"), None);
+        assert_eq!(encode_checksum(&[0, 171, 255]), "00abff");
+    }
+}
@@ -1,8 +1,8 @@
-use chrono::{DateTime, Utc};
+use coverage_point_calculator::{asserted_distance_to_trust_multiplier, RadioType};
 use helium_proto::services::poc_mobile::CellType as CellTypeProto;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 pub const CELLTYPE_NOVA_436H: &str = "2AG32MBS3100196N";
 pub const CELLTYPE_NOVA_430I: &str = "2AG32PBS3101S";
@@ -10,9 +10,10 @@ pub const CELLTYPE_NEUTRINO_430: &str = "2AG32PBS31010";
 pub const CELLTYPE_SERCCOMM_INDOOR: &str = "P27-SCE4255W";
 pub const CELLTYPE_SERCCOMM_OUTDOOR: &str = "P27-SCO4255PA10";
 
-#[derive(Debug, Eq, Hash, PartialEq, Copy, Clone, Serialize, sqlx::Type)]
+#[derive(Debug, Eq, Hash, PartialEq, Copy, Clone, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "cell_type")]
 #[sqlx(rename_all = "lowercase")]
+#[serde(rename_all(deserialize = "lowercase"))]
 pub enum CellType {
     Nova436H = 0,
     Nova430I = 1,
@@ -24,7 +25,8 @@ pub enum CellType {
     NovaGenericWifiOutdoor = 7,
 }
 
-#[derive(PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum CellTypeLabel {
     CellTypeLabelNone = 0,
     CBRS = 1,
@@ -55,30 +57,187 @@ impl CellType {
             Self::CellTypeNone => CellTypeLabel::CellTypeLabelNone,
         }
     }
+}
+
+/// Location weights of a radio model, replacing the [HIP-119] asserted
+/// distance trust multipliers for radios of that model.
+///
+/// [HIP-119]: https://github.com/helium/HIP/blob/main/0119-closing-gaming-loopholes-within-the-mobile-network.md
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct LocationWeights {
+    /// Weight for a validated location within `max_distance_to_asserted`
+    pub trusted: Decimal,
+    /// Weight for any other location
+    pub untrusted: Decimal,
+    /// Max distance in meters from the asserted location for a trusted location
+    pub max_distance_to_asserted: u32,
+}
+
+impl LocationWeights {
+    pub fn weight(&self, location_validated: bool, distance_to_asserted: Option<i64>) -> Decimal {
+        match (distance_to_asserted, location_validated) {
+            (Some(dist), true) if dist <= self.max_distance_to_asserted as i64 => self.trusted,
+            _ => self.untrusted,
+        }
+    }
+}
+
+/// A radio model as configured in the cell type registry.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RadioModel {
+    /// Prefix of the CBSD id that identifies a CBRS model. Wi-Fi radios
+    /// carry no id, so a Wi-Fi model has no prefix and covers every Wi-Fi
+    /// radio with the same indoor flag
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// Whether the model is a CBRS or a Wi-Fi radio
+    pub label: CellTypeLabel,
+    /// Whether the model is an indoor radio
+    pub indoor: bool,
+    /// Cell type to record for radios of the model, must carry the label of
+    /// the model
+    pub cell_type: CellType,
+    /// Location weights for radios of the model, defaults to the HIP-119
+    /// asserted distance trust multipliers
+    #[serde(default)]
+    pub location_weights: Option<LocationWeights>,
+}
+
+impl RadioModel {
+    fn cbrs(prefix: &str, indoor: bool, cell_type: CellType) -> Self {
+        Self {
+            prefix: Some(prefix.to_string()),
+            label: CellTypeLabel::CBRS,
+            indoor,
+            cell_type,
+            location_weights: None,
+        }
+    }
+
+    fn wifi(indoor: bool, cell_type: CellType) -> Self {
+        Self {
+            prefix: None,
+            label: CellTypeLabel::Wifi,
+            indoor,
+            cell_type,
+            location_weights: None,
+        }
+    }
+
+    pub fn radio_type(&self) -> RadioType {
+        match (self.label, self.indoor) {
+            (CellTypeLabel::Wifi, true) => RadioType::IndoorWifi,
+            (CellTypeLabel::Wifi, false) => RadioType::OutdoorWifi,
+            (_, true) => RadioType::IndoorCbrs,
+            (_, false) => RadioType::OutdoorCbrs,
+        }
+    }
 
-    pub fn location_weight(
+    /// Location trust multiplier of a radio of the model, from its location
+    /// weights if configured and the HIP-119 maximum asserted distance check
+    /// otherwise. Radios without a distance to their asserted location are
+    /// trusted unless the model says otherwise.
+    pub fn location_trust_multiplier(
         &self,
-        location_validation_timestamp: Option<DateTime<Utc>>,
-        distance: Option<i64>,
-        max_distance_to_asserted: u32,
+        location_validated: bool,
+        distance_to_asserted: Option<i64>,
     ) -> Decimal {
-        match (self, distance, location_validation_timestamp.is_some()) {
-            (Self::NovaGenericWifiIndoor | Self::NovaGenericWifiOutdoor, Some(dist), true)
-                if dist <= max_distance_to_asserted as i64 =>
-            {
-                dec!(1.0)
-            }
-            (Self::NovaGenericWifiIndoor | Self::NovaGenericWifiOutdoor, Some(dist), true)
-                if dist > max_distance_to_asserted as i64 =>
-            {
-                dec!(0.25)
-            }
-            (Self::NovaGenericWifiIndoor | Self::NovaGenericWifiOutdoor, _, _) => dec!(0.25),
-            _ => dec!(1.0),
+        match self.location_weights {
+            Some(weights) => weights.weight(location_validated, distance_to_asserted),
+            None => distance_to_asserted.map_or(dec!(1.0), |distance| {
+                asserted_distance_to_trust_multiplier(self.radio_type(), distance as u32)
+            }),
+        }
+    }
+
+    fn validate(&self) -> Result<(), InvalidRadioModel> {
+        let invalid = |reason| InvalidRadioModel {
+            prefix: self.prefix.clone(),
+            reason,
+        };
+        if self.cell_type.to_label() != self.label {
+            return Err(invalid("cell type does not match the label"));
+        }
+        match (self.label, &self.prefix) {
+            (CellTypeLabel::CBRS, None) => Err(invalid("cbrs models need a prefix")),
+            (CellTypeLabel::Wifi, Some(_)) => Err(invalid("wifi models have no prefix")),
+            (CellTypeLabel::CellTypeLabelNone, _) => Err(invalid("label must be cbrs or wifi")),
+            _ => Ok(()),
         }
     }
 }
 
+/// The built-in radio models, used for radios no configured model matches
+fn builtin_models() -> Vec<RadioModel> {
+    vec![
+        RadioModel::cbrs(CELLTYPE_NOVA_436H, false, CellType::Nova436H),
+        RadioModel::cbrs(CELLTYPE_NOVA_430I, true, CellType::Nova430I),
+        RadioModel::cbrs(CELLTYPE_NEUTRINO_430, true, CellType::Neutrino430),
+        RadioModel::cbrs(CELLTYPE_SERCCOMM_INDOOR, true, CellType::SercommIndoor),
+        RadioModel::cbrs(CELLTYPE_SERCCOMM_OUTDOOR, false, CellType::SercommOutdoor),
+        RadioModel::wifi(true, CellType::NovaGenericWifiIndoor),
+        RadioModel::wifi(false, CellType::NovaGenericWifiOutdoor),
+    ]
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("invalid radio model {prefix:?}: {reason}")]
+pub struct InvalidRadioModel {
+    prefix: Option<String>,
+    reason: &'static str,
+}
+
+/// Registry of known radio models.
+///
+/// CBRS radios are matched by the longest prefix of their CBSD id and Wi-Fi
+/// radios by their indoor flag. Configured models take precedence over the
+/// built-in models, which remain as a stable fallback.
+#[derive(Debug, Clone)]
+pub struct CellTypeRegistry {
+    models: Vec<RadioModel>,
+}
+
+impl Default for CellTypeRegistry {
+    fn default() -> Self {
+        Self {
+            models: builtin_models(),
+        }
+    }
+}
+
+impl CellTypeRegistry {
+    pub fn new(mut models: Vec<RadioModel>) -> Result<Self, InvalidRadioModel> {
+        for model in &models {
+            model.validate()?;
+        }
+        models.sort_by_key(|model| std::cmp::Reverse(model.prefix.as_ref().map(String::len)));
+        models.extend(builtin_models());
+        Ok(Self { models })
+    }
+
+    /// Model of a CBRS radio by its CBSD id, or of a Wi-Fi radio by its
+    /// indoor flag
+    pub fn model(&self, cbsd_id: Option<&str>, indoor: bool) -> Option<&RadioModel> {
+        match cbsd_id {
+            Some(cbsd_id) => self.models.iter().find(|model| {
+                model
+                    .prefix
+                    .as_ref()
+                    .is_some_and(|prefix| cbsd_id.starts_with(prefix))
+            }),
+            None => self
+                .models
+                .iter()
+                .find(|model| model.label == CellTypeLabel::Wifi && model.indoor == indoor),
+        }
+    }
+
+    pub fn cell_type(&self, cbsd_id: &str) -> Option<CellType> {
+        self.model(Some(cbsd_id), false)
+            .map(|model| model.cell_type)
+    }
+}
+
 impl From<CellType> for CellTypeProto {
     fn from(ct: CellType) -> CellTypeProto {
         match ct {
@@ -93,3 +252,117 @@ impl From<CellType> for CellTypeProto {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(prefix: &str, indoor: bool, cell_type: CellType) -> RadioModel {
+        RadioModel::cbrs(prefix, indoor, cell_type)
+    }
+
+    #[test]
+    fn registry_falls_back_to_builtin_models() {
+        let registry = CellTypeRegistry::default();
+        assert_eq!(
+            registry.cell_type("P27-SCE4255W120200039521"),
+            Some(CellType::SercommIndoor)
+        );
+        assert_eq!(registry.cell_type("UNKNOWN-1234"), None);
+        assert_eq!(
+            registry.model(None, true).map(|model| model.cell_type),
+            Some(CellType::NovaGenericWifiIndoor)
+        );
+        assert_eq!(
+            registry.model(None, false).map(|model| model.cell_type),
+            Some(CellType::NovaGenericWifiOutdoor)
+        );
+    }
+
+    #[test]
+    fn registry_matches_longest_prefix_first() {
+        let registry = CellTypeRegistry::new(vec![
+            model("ACME", true, CellType::SercommIndoor),
+            model("ACME-OUT", false, CellType::SercommOutdoor),
+        ])
+        .unwrap();
+        assert_eq!(
+            registry.cell_type("ACME-OUT-0001"),
+            Some(CellType::SercommOutdoor)
+        );
+        assert_eq!(
+            registry.cell_type("ACME-IN-0001"),
+            Some(CellType::SercommIndoor)
+        );
+        assert_eq!(
+            registry.cell_type("2AG32MBS3100196N1202000"),
+            Some(CellType::Nova436H)
+        );
+    }
+
+    #[test]
+    fn registry_rejects_inconsistent_models() {
+        // cbrs label with a wifi cell type
+        assert!(
+            CellTypeRegistry::new(vec![model("ACME", true, CellType::NovaGenericWifiIndoor)])
+                .is_err()
+        );
+        assert!(CellTypeRegistry::new(vec![model("ACME", true, CellType::CellTypeNone)]).is_err());
+        // wifi models are not matched by prefix
+        let mut wifi = RadioModel::wifi(true, CellType::NovaGenericWifiIndoor);
+        wifi.prefix = Some("ACME".to_string());
+        assert!(CellTypeRegistry::new(vec![wifi]).is_err());
+        // cbrs models need a prefix
+        let mut cbrs = model("ACME", true, CellType::SercommIndoor);
+        cbrs.prefix = None;
+        assert!(CellTypeRegistry::new(vec![cbrs]).is_err());
+    }
+
+    #[test]
+    fn registry_location_weights_are_keyed_by_model() {
+        let weights = LocationWeights {
+            trusted: dec!(1.0),
+            untrusted: dec!(0.5),
+            max_distance_to_asserted: 200,
+        };
+        let mut weighted = model("ACME-W", false, CellType::SercommOutdoor);
+        weighted.location_weights = Some(weights);
+        let mut wifi = RadioModel::wifi(false, CellType::NovaGenericWifiOutdoor);
+        wifi.location_weights = Some(weights);
+        let registry = CellTypeRegistry::new(vec![
+            weighted,
+            model("ACME-U", false, CellType::SercommOutdoor),
+            wifi,
+        ])
+        .unwrap();
+
+        let multiplier = |cbsd_id, indoor, validated, distance| {
+            registry
+                .model(cbsd_id, indoor)
+                .unwrap()
+                .location_trust_multiplier(validated, distance)
+        };
+        assert_eq!(
+            multiplier(Some("ACME-W-1"), false, true, Some(10)),
+            dec!(1.0)
+        );
+        assert_eq!(
+            multiplier(Some("ACME-W-1"), false, false, Some(10)),
+            dec!(0.5)
+        );
+        assert_eq!(
+            multiplier(Some("ACME-W-1"), false, true, Some(300)),
+            dec!(0.5)
+        );
+        assert_eq!(multiplier(Some("ACME-W-1"), false, true, None), dec!(0.5));
+        // same cell type, different model
+        assert_eq!(multiplier(Some("ACME-U-1"), false, false, None), dec!(1.0));
+
+        // the configured outdoor wifi model replaces the built-in one
+        assert_eq!(multiplier(None, false, true, Some(150)), dec!(1.0));
+        assert_eq!(multiplier(None, false, true, Some(250)), dec!(0.5));
+        // indoor wifi still uses the built-in HIP-119 multipliers
+        assert_eq!(multiplier(None, true, true, Some(250)), dec!(0.25));
+        assert_eq!(multiplier(None, true, true, Some(50)), dec!(1.0));
+    }
+}
@@ -0,0 +1,118 @@
+use anyhow::Result;
+use file_store::FileStore;
+use sqlx::PgPool;
+
+use crate::{
+    boosting_oracles::{
+        db, hex_boost_data_from_settings, is_hex_boost_data_ready, AssignedCoverageObjects,
+        DataSet, DataSetType,
+    },
+    Settings,
+};
+
+#[derive(Debug, clap::Args)]
+pub struct Cmd {
+    #[clap(subcommand)]
+    cmd: DataSetCmd,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum DataSetCmd {
+    /// List the data sets of a type with their status, checksum and, if
+    /// quarantined, the reason
+    List {
+        #[clap(long)]
+        r#type: DataSetType,
+    },
+    /// Quarantine the data set of a type currently in use, falling back to the
+    /// previous one, and re-assign every hex.
+    ///
+    /// A running verifier loads the previous data set on its next poll.
+    Rollback {
+        #[clap(long)]
+        r#type: DataSetType,
+        /// Why the data set is rolled back, recorded as the quarantine reason
+        #[clap(long)]
+        reason: String,
+    },
+}
+
+impl Cmd {
+    pub async fn run(self, settings: &Settings) -> Result<()> {
+        let pool = settings.database.connect(env!("CARGO_PKG_NAME")).await?;
+        match self.cmd {
+            DataSetCmd::List { r#type } => list(&pool, r#type).await,
+            DataSetCmd::Rollback { r#type, reason } => {
+                rollback(&pool, settings, r#type, &reason).await
+            }
+        }
+    }
+}
+
+async fn list(pool: &PgPool, data_set_type: DataSetType) -> Result<()> {
+    for entry in db::fetch_data_set_history(pool, data_set_type).await? {
+        println!(
+            "{} | {} | {:?} | {}",
+            entry.filename,
+            entry.time_to_use,
+            entry.status,
+            entry.checksum.as_deref().unwrap_or("-"),
+        );
+        if let Some(reason) = entry.quarantine_reason {
+            println!("    quarantined: {reason}");
+        }
+    }
+    Ok(())
+}
+
+async fn rollback(
+    pool: &PgPool,
+    settings: &Settings,
+    data_set_type: DataSetType,
+    reason: &str,
+) -> Result<()> {
+    let processed = db::fetch_processed_data_sets(pool, data_set_type).await?;
+    let [latest, previous, ..] = processed.as_slice() else {
+        anyhow::bail!(
+            "No earlier processed {} data set to roll back to",
+            data_set_type.to_prefix()
+        );
+    };
+    latest
+        .quarantine(pool, &format!("rolled back: {reason}"))
+        .await?;
+    println!("Rolled back {} to {}", latest.filename, previous.filename);
+
+    let store = FileStore::from_settings(&settings.data_sets).await?;
+    let directory = &settings.data_sets_directory;
+    let mut data_sets = hex_boost_data_from_settings(settings)?;
+    data_sets
+        .footfall
+        .load_latest_processed_data_set(&store, pool, directory)
+        .await?;
+    data_sets
+        .landtype
+        .load_latest_processed_data_set(&store, pool, directory)
+        .await?;
+    data_sets
+        .urbanization
+        .load_latest_processed_data_set(&store, pool, directory)
+        .await?;
+    data_sets
+        .service_provider_override
+        .load_latest_processed_data_set(&store, pool, directory)
+        .await?;
+    if !is_hex_boost_data_ready(&data_sets) {
+        anyhow::bail!(
+            "Not every data set type has a processed data set, hexes were not re-assigned"
+        );
+    }
+
+    let assigned_coverage_objs =
+        AssignedCoverageObjects::assign_hex_stream(db::fetch_all_hexes(pool), &data_sets).await?;
+    let coverage_objects = assigned_coverage_objs.coverage_objs.len();
+    assigned_coverage_objs.save(pool).await?;
+    println!("Re-assigned the hexes of {coverage_objects} coverage objects");
+
+    Ok(())
+}
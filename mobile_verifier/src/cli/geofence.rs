@@ -0,0 +1,64 @@
+use crate::{
+    geofence::{GeofenceFeature, Geofences},
+    Settings,
+};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use h3o::{CellIndex, LatLng};
+use serde::Serialize;
+
+/// Check which configured geofence regions contain a location at a given
+/// time
+#[derive(Debug, clap::Args)]
+pub struct Cmd {
+    #[clap(long, requires = "lon", conflicts_with = "hex")]
+    lat: Option<f64>,
+    #[clap(long, requires = "lat")]
+    lon: Option<f64>,
+    /// h3 cell, as a hex string
+    #[clap(long, required_unless_present = "lat")]
+    hex: Option<CellIndex>,
+    /// Defaults to now
+    #[clap(long)]
+    at: Option<DateTime<Utc>>,
+    /// Only show regions for the given feature
+    #[clap(long, value_enum)]
+    feature: Option<GeofenceFeature>,
+}
+
+#[derive(Serialize)]
+struct MatchedRegion<'a> {
+    name: &'a str,
+    feature: GeofenceFeature,
+    version: u32,
+    active_from: DateTime<Utc>,
+}
+
+impl Cmd {
+    pub async fn run(self, settings: &Settings) -> Result<()> {
+        let cell = match (self.hex, self.lat, self.lon) {
+            (Some(hex), _, _) => hex,
+            (None, Some(lat), Some(lon)) => {
+                LatLng::new(lat, lon)?.to_cell(h3o::Resolution::Fifteen)
+            }
+            _ => anyhow::bail!("either --hex or --lat and --lon are required"),
+        };
+        let at = self.at.unwrap_or_else(Utc::now);
+
+        let geofences = Geofences::from_settings(&settings.geofences)?;
+        let matched: Vec<_> = geofences
+            .matching(cell, at)
+            .into_iter()
+            .filter(|region| self.feature.map_or(true, |f| f == region.feature))
+            .map(|region| MatchedRegion {
+                name: &region.name,
+                feature: region.feature,
+                version: region.version,
+                active_from: region.active_from,
+            })
+            .collect();
+
+        println!("{}", serde_json::to_string_pretty(&matched)?);
+        Ok(())
+    }
+}
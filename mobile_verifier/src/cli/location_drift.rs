@@ -0,0 +1,65 @@
+use crate::{
+    heartbeats::location_history::{db, LocationFlagReason, LocationStats},
+    Settings,
+};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use helium_crypto::PublicKeyBinary;
+use serde::Serialize;
+
+/// Report Wi-Fi hotspots flagged for location jumps or drift, or the location
+/// statistics of a single hotspot
+#[derive(Debug, clap::Args)]
+pub struct Cmd {
+    /// Show the location statistics of this hotspot over the detection window
+    #[clap(long)]
+    hotspot: Option<PublicKeyBinary>,
+}
+
+#[derive(Serialize)]
+struct FlagReport {
+    hotspot_key: String,
+    reason: Option<LocationFlagReason>,
+    flagged_at: Option<DateTime<Utc>>,
+    stats: LocationStats,
+}
+
+impl Cmd {
+    pub async fn run(self, settings: &Settings) -> Result<()> {
+        let pool = settings.database.connect(env!("CARGO_PKG_NAME")).await?;
+
+        let reports = match self.hotspot {
+            Some(hotspot_key) => {
+                let since = Utc::now() - Duration::from_std(settings.location_drift.window)?;
+                let samples = db::fetch_hotspot_history(&pool, &hotspot_key, since).await?;
+                let stats = LocationStats::from_samples(&samples, &settings.location_drift);
+                let flagged_at = db::fetch_flags(&pool)
+                    .await?
+                    .into_iter()
+                    .find(|flag| flag.hotspot_key == hotspot_key)
+                    .map(|flag| flag.flagged_at);
+                vec![FlagReport {
+                    hotspot_key: hotspot_key.to_string(),
+                    reason: stats.flag(&settings.location_drift),
+                    flagged_at,
+                    stats,
+                }]
+            }
+            None => db::fetch_flags(&pool)
+                .await?
+                .into_iter()
+                .map(|flag| FlagReport {
+                    hotspot_key: flag.hotspot_key.to_string(),
+                    reason: Some(flag.reason),
+                    flagged_at: Some(flag.flagged_at),
+                    stats: flag.stats,
+                })
+                .collect(),
+        };
+
+        for report in reports {
+            println!("{}", serde_json::to_string(&report)?);
+        }
+        Ok(())
+    }
+}
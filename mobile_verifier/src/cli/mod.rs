@@ -1,3 +1,8 @@
+pub mod data_sets;
+pub mod geofence;
+pub mod location_drift;
+pub mod reward_dry_run;
+pub mod reward_explanation;
 pub mod reward_from_db;
 pub mod server;
 pub mod service_provider_promotions;
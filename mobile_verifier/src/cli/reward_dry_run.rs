@@ -0,0 +1,152 @@
+use crate::{
+    geofence::Geofences,
+    resolve_subdao_pubkey,
+    rewarder::dry_run::{self, BoostedHexesOverride, DryRunReport},
+    PriceInfo, Settings,
+};
+use anyhow::Result;
+use chrono::Duration;
+use coverage_point_calculator::SpeedtestTierTable;
+use helium_lib::token::Token;
+use hextree::Cell;
+use mobile_config::{
+    boosted_hex_info::BoostedHexInfo,
+    client::{
+        hex_boosting_client::HexBoostingClient, sub_dao_client::SubDaoEpochRewardInfoResolver,
+        CarrierServiceClient, SubDaoClient,
+    },
+};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::{num::NonZeroU32, path::PathBuf, sync::Arc};
+
+/// Reward an epoch from the real inputs without writing any rewards or
+/// modifying the database, optionally overriding reward parameters
+#[derive(Debug, clap::Args)]
+pub struct Cmd {
+    #[clap(long)]
+    reward_epoch: u64,
+    /// Override the epoch emissions, in bones
+    #[clap(long)]
+    epoch_emissions: Option<Decimal>,
+    /// The hnt price for the epoch, in bones. An epoch that has already been
+    /// rewarded was priced with the `price` of its reward manifest, an epoch
+    /// yet to be rewarded is priced with the latest price
+    #[clap(long)]
+    hnt_price: u64,
+    /// Override the boosted hexes with those in the given json file, a list
+    /// of `{"location": "<h3 hex>", "multiplier": <n>}`. Use an empty list
+    /// for no boosting
+    #[clap(long)]
+    boosted_hexes: Option<PathBuf>,
+    /// Override the speedtest tier thresholds with the profiles in the given
+    /// json file, in the same layout as the `speedtest_tiers` setting
+    #[clap(long)]
+    speedtest_tiers: Option<PathBuf>,
+    /// Write the report to the given file
+    #[clap(long)]
+    output: Option<PathBuf>,
+    /// Print the per radio difference from a previously written report
+    /// instead of the report itself
+    #[clap(long)]
+    compare: Option<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BoostedHexOverride {
+    location: String,
+    multiplier: NonZeroU32,
+}
+
+impl Cmd {
+    pub async fn run(self, settings: &Settings) -> Result<()> {
+        let sub_dao_rewards_client = SubDaoClient::from_settings(&settings.config_client)?;
+        let sub_dao = resolve_subdao_pubkey();
+
+        let mut reward_info = sub_dao_rewards_client
+            .resolve_info(&sub_dao.to_string(), self.reward_epoch)
+            .await?
+            .ok_or(anyhow::anyhow!(
+                "No reward info found for epoch {}",
+                self.reward_epoch
+            ))?;
+        if let Some(epoch_emissions) = self.epoch_emissions {
+            reward_info.epoch_emissions = epoch_emissions;
+        }
+
+        let price_info = PriceInfo::new(self.hnt_price, Token::Hnt.decimals());
+
+        let hex_boosting_client = match &self.boosted_hexes {
+            Some(path) => BoostedHexesOverride::Fixed(Arc::new(read_boosted_hexes(path)?)),
+            None => BoostedHexesOverride::Live(HexBoostingClient::from_settings(
+                &settings.config_client,
+            )?),
+        };
+        let speedtest_tiers: SpeedtestTierTable = match &self.speedtest_tiers {
+            Some(path) => serde_json::from_slice(&std::fs::read(path)?)?,
+            None => settings.speedtest_tiers.clone(),
+        };
+        let carrier_client = CarrierServiceClient::from_settings(&settings.config_client)?;
+        let pool = settings.database.connect(env!("CARGO_PKG_NAME")).await?;
+
+        tracing::info!(
+            "Dry run rewarding epoch {} from {} to {} with emissions: {} and hnt bone price: {}",
+            reward_info.epoch_day,
+            reward_info.epoch_period.start,
+            reward_info.epoch_period.end,
+            reward_info.epoch_emissions,
+            price_info.price_per_bone,
+        );
+        let report = dry_run::dry_run(
+            &pool,
+            &carrier_client,
+            &hex_boosting_client,
+            &reward_info,
+            price_info,
+            &speedtest_tiers,
+            &settings.unique_connections,
+            &Geofences::from_settings(&settings.geofences)?,
+        )
+        .await?;
+
+        if let Some(output) = &self.output {
+            std::fs::write(output, serde_json::to_vec_pretty(&report)?)?;
+        }
+        match &self.compare {
+            Some(previous) => {
+                let previous: DryRunReport = serde_json::from_slice(&std::fs::read(previous)?)?;
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&serde_json::json!({
+                        "total_rewards_before": previous.total_rewards,
+                        "total_rewards_after": report.total_rewards,
+                        "radios": report.diff(&previous),
+                    }))?
+                );
+            }
+            None => println!("{}", serde_json::to_string_pretty(&report)?),
+        }
+        Ok(())
+    }
+}
+
+fn read_boosted_hexes(path: &PathBuf) -> Result<Vec<BoostedHexInfo>> {
+    let overrides: Vec<BoostedHexOverride> = serde_json::from_slice(&std::fs::read(path)?)?;
+    overrides
+        .into_iter()
+        .map(|hex| {
+            let location = Cell::from_raw(u64::from_str_radix(&hex.location, 16)?)?;
+            // without a start time the first multiplier applies for the whole epoch
+            Ok(BoostedHexInfo {
+                location,
+                start_ts: None,
+                end_ts: None,
+                period_length: Duration::days(30),
+                multipliers: vec![hex.multiplier],
+                boosted_hex_pubkey: Default::default(),
+                boost_config_pubkey: Default::default(),
+                version: 0,
+            })
+        })
+        .collect()
+}
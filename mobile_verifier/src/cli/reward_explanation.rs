@@ -0,0 +1,93 @@
+use crate::{
+    reward_shares::explanation::{self, RadioRewardExplanation},
+    Settings,
+};
+use anyhow::Result;
+use file_store::{file_info::FileType, FileStore};
+use futures::TryStreamExt;
+use helium_crypto::PublicKeyBinary;
+use mobile_config::EpochInfo;
+use std::time::Duration;
+
+/// Look up the reward explanations written for a radio in a given epoch.
+///
+/// This is an offline lookup of the explanation files in the output bucket,
+/// not a query against a running verifier. Nothing is found for epochs
+/// rewarded without `reward_explanations` enabled, except for the service
+/// provider boosting eligibility which is saved to the database for every
+/// rewarded epoch.
+#[derive(Debug, clap::Args)]
+pub struct Cmd {
+    #[clap(long)]
+    reward_epoch: u64,
+    #[clap(long)]
+    hotspot_key: PublicKeyBinary,
+    /// Only show the explanation for the given cbrs radio
+    #[clap(long)]
+    cbsd_id: Option<String>,
+    /// How long after the end of the epoch to search for explanation files
+    #[clap(long, default_value = "2 days", value_parser = humantime::parse_duration)]
+    search_window: Duration,
+    /// Only show the service provider boosting status of the radio and the
+    /// evidence it was decided on, read from the database
+    #[clap(long)]
+    sp_boost_eligibility: bool,
+}
+
+impl Cmd {
+    pub async fn run(self, settings: &Settings) -> Result<()> {
+        let period = EpochInfo::from(self.reward_epoch).period;
+        if self.sp_boost_eligibility {
+            let pool = settings.database.connect(env!("CARGO_PKG_NAME")).await?;
+            let eligibility = explanation::db::get(
+                &pool,
+                period.start,
+                &self.hotspot_key,
+                self.cbsd_id.as_deref(),
+            )
+            .await?;
+            if eligibility.is_empty() {
+                anyhow::bail!(
+                    "no service provider boosting eligibility found for {} in epoch {}",
+                    self.hotspot_key,
+                    self.reward_epoch
+                );
+            }
+            println!("{}", serde_json::to_string_pretty(&eligibility)?);
+            return Ok(());
+        }
+
+        let store = FileStore::from_settings(&settings.output).await?;
+        let files = store
+            .list_all(
+                FileType::RadioRewardExplanation.to_str(),
+                period.end,
+                period.end + chrono::Duration::from_std(self.search_window)?,
+            )
+            .await?;
+
+        let mut explanations = vec![];
+        for file in files {
+            let mut records = store.stream_file(file).await?;
+            while let Some(record) = records.try_next().await? {
+                let explanation: RadioRewardExplanation = serde_json::from_slice(&record)?;
+                if explanation.start_period == period.start
+                    && explanation.hotspot_key == self.hotspot_key
+                    && (self.cbsd_id.is_none() || explanation.cbsd_id == self.cbsd_id)
+                {
+                    explanations.push(explanation);
+                }
+            }
+        }
+
+        if explanations.is_empty() {
+            anyhow::bail!(
+                "no reward explanation found for {} in epoch {}, were reward_explanations enabled?",
+                self.hotspot_key,
+                self.reward_epoch
+            );
+        }
+        println!("{}", serde_json::to_string_pretty(&explanations)?);
+        Ok(())
+    }
+}
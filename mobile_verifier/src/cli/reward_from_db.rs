@@ -54,9 +54,14 @@ impl Cmd {
         let pool = settings.database.connect(env!("CARGO_PKG_NAME")).await?;
 
         let heartbeats = HeartbeatReward::validated(&pool, &reward_info.epoch_period);
-        let speedtest_averages =
-            SpeedtestAverages::aggregate_epoch_averages(reward_info.epoch_period.end, &pool)
-                .await?;
+        let speedtest_averages = SpeedtestAverages::aggregate_epoch_averages(
+            reward_info.epoch_period.end,
+            &pool,
+            settings
+                .speedtest_tiers
+                .base_thresholds(reward_info.epoch_period.start),
+        )
+        .await?;
 
         let unique_connections =
             unique_connections::db::get(&pool, &reward_info.epoch_period).await?;
@@ -70,6 +75,7 @@ impl Cmd {
             &BannedRadios::default(),
             &unique_connections,
             &reward_info.epoch_period,
+            &settings.speedtest_tiers,
         )
         .await?;
 
@@ -1,11 +1,14 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use crate::{
     boosting_oracles::DataSetDownloaderDaemon,
     coverage::{new_coverage_object_notification_channel, CoverageDaemon},
     data_session::DataSessionIngestor,
-    geofence::Geofence,
-    heartbeats::{cbrs::CbrsHeartbeatDaemon, wifi::WifiHeartbeatDaemon},
+    geofence::{FeatureGeofence, Geofence, GeofenceFeature, Geofences},
+    heartbeats::{
+        cbrs::CbrsHeartbeatDaemon, location_history::LocationDriftDetector,
+        wifi::WifiHeartbeatDaemon,
+    },
     radio_threshold::RadioThresholdIngestor,
     rewarder::Rewarder,
     sp_boosted_rewards_bans::ServiceProviderBoostedRewardsBanIngestor,
@@ -101,6 +104,18 @@ impl Cmd {
             settings.usa_and_mexico_fencing_resolution()?,
         )?;
 
+        let geofences = Arc::new(Geofences::from_settings(&settings.geofences)?);
+        let usa_geofence = FeatureGeofence::new(
+            geofences.clone(),
+            GeofenceFeature::CbrsHeartbeats,
+            usa_geofence,
+        );
+        let usa_and_mexico_geofence = FeatureGeofence::new(
+            geofences,
+            GeofenceFeature::WifiHeartbeats,
+            usa_and_mexico_geofence,
+        );
+
         let (new_coverage_obj_notifier, new_coverage_obj_notification) =
             new_coverage_object_notification_channel();
 
@@ -207,6 +222,10 @@ impl Cmd {
                 .await?,
             )
             .add_task(DataSessionIngestor::create_managed_task(pool.clone(), settings).await?)
+            .add_task(LocationDriftDetector::new(
+                pool.clone(),
+                settings.location_drift.clone(),
+            ))
             .add_task(
                 ServiceProviderBoostedRewardsBanIngestor::create_managed_task(
                     pool.clone(),
@@ -1,21 +1,47 @@
-use crate::{service_provider, Settings};
+use crate::{
+    service_provider::{self, ServiceProviderEpochStatement},
+    Settings,
+};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
+use file_store::FileStore;
 use mobile_config::client::CarrierServiceClient;
+use std::path::PathBuf;
 
 #[derive(Debug, clap::Args)]
 pub struct Cmd {
     #[clap(long)]
     start: Option<DateTime<Utc>>,
+    /// Print the service provider reward statement of the epoch starting at
+    /// `start` from the output bucket instead of the promotions
+    #[clap(long, requires = "start")]
+    statement: bool,
+    /// Print the service provider reward statement in the given file
+    #[clap(long, conflicts_with = "statement")]
+    statement_file: Option<PathBuf>,
 }
 
 impl Cmd {
     pub async fn run(self, settings: &Settings) -> Result<()> {
+        if let Some(path) = &self.statement_file {
+            ServiceProviderEpochStatement::read(path)?.print();
+            return Ok(());
+        }
+
         let epoch_start = match self.start {
             Some(dt) => dt,
             None => Utc::now(),
         };
 
+        if self.statement {
+            let file_store = FileStore::from_settings(&settings.output).await?;
+            match ServiceProviderEpochStatement::fetch(&file_store, epoch_start).await? {
+                Some(statement) => statement.print(),
+                None => println!("No statement for epoch starting {epoch_start}"),
+            }
+            return Ok(());
+        }
+
         let carrier_client = CarrierServiceClient::from_settings(&settings.config_client)?;
         let promos = service_provider::get_promotions(&carrier_client, &epoch_start).await?;
 
@@ -1,10 +1,16 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::PathBuf,
+};
 
 use hextree::disktree::DiskTreeMap;
 
 use hex_assignments::{landtype::LandtypeValue, Assignment};
 
-use crate::Settings;
+use crate::{
+    coverage_map_export::{decode_hex_records, HexCoverageRecord},
+    Settings,
+};
 
 #[derive(Debug, clap::Args)]
 pub struct Cmd {
@@ -20,12 +26,18 @@ pub struct Cmd {
 #[derive(Debug, Clone, clap::ValueEnum)]
 enum DisktreeType {
     Landtype,
+    /// Ranked coverage map exported by the rewarder
+    CoverageMap,
 }
 
 impl Cmd {
     pub async fn run(self, _settings: &Settings) -> anyhow::Result<()> {
         let disktree = DiskTreeMap::open(&self.path)?;
 
+        if let DisktreeType::CoverageMap = self.r#type {
+            return verify_coverage_map(&disktree);
+        }
+
         let mut value_counts = HashMap::<u8, usize>::new();
         let mut idx: u128 = 0;
         let start = tokio::time::Instant::now();
@@ -42,6 +54,7 @@ impl Cmd {
 
         println!("REPORT {}", "=".repeat(50));
         match self.r#type {
+            DisktreeType::CoverageMap => unreachable!("coverage maps are verified separately"),
             DisktreeType::Landtype => {
                 for (key, count) in value_counts {
                     let landtype = LandtypeValue::try_from(key);
@@ -58,3 +71,57 @@ impl Cmd {
         Ok(())
     }
 }
+
+/// Checks that every radio category of each hex in an exported coverage map is
+/// ranked 1..=n without gaps, and reports how many radios were ranked at each
+/// position per category.
+fn verify_coverage_map(disktree: &DiskTreeMap) -> anyhow::Result<()> {
+    let mut hexes: usize = 0;
+    let mut invalid_hexes: usize = 0;
+    let mut rank_counts = BTreeMap::<(String, usize), usize>::new();
+    let mut radios = HashSet::new();
+
+    for x in disktree.iter()? {
+        let (cell, vals) = x?;
+        let records = decode_hex_records(vals)?;
+        hexes += 1;
+
+        let mut categories = BTreeMap::<&str, Vec<&HexCoverageRecord>>::new();
+        for record in &records {
+            categories
+                .entry(record.radio_category.as_str())
+                .or_default()
+                .push(record);
+        }
+        let ranks_valid = categories.values().all(|records| {
+            records
+                .iter()
+                .enumerate()
+                .all(|(idx, record)| record.rank == idx + 1)
+        });
+        if !ranks_valid {
+            invalid_hexes += 1;
+            println!("Invalid ranking in {cell:?}: {records:?}");
+        }
+
+        for record in records {
+            *rank_counts
+                .entry((record.radio_category, record.rank))
+                .or_insert(0) += 1;
+            radios.insert((record.hotspot_key, record.cbsd_id));
+        }
+    }
+
+    println!("REPORT {}", "=".repeat(50));
+    println!("| hexes                | {hexes:<12} |");
+    println!("| radios               | {:<12} |", radios.len());
+    println!("| invalid hexes        | {invalid_hexes:<12} |");
+    for ((category, rank), count) in rank_counts {
+        println!("| {category:<12} {rank:<7} | {count:<12} |");
+    }
+
+    if invalid_hexes > 0 {
+        anyhow::bail!("{invalid_hexes} hexes have an invalid ranking");
+    }
+    Ok(())
+}
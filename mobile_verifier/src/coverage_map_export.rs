@@ -0,0 +1,298 @@
+use std::{
+    collections::HashMap,
+    io::{Seek, Write},
+    ops::Range,
+    path::PathBuf,
+    sync::Arc,
+};
+
+use chrono::{DateTime, Utc};
+use coverage_map::{CoverageMap, RadioCategory, RankedCoverage};
+use file_store::{FileExporter, FileStore};
+use helium_crypto::PublicKeyBinary;
+use hextree::{Cell, HexTreeMap};
+use serde::{Deserialize, Serialize};
+
+use crate::Settings;
+
+pub const COVERAGE_MAP_PREFIX: &str = "coverage_map";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CoverageMapExportSettings {
+    /// Directory the exported coverage maps are written to
+    pub directory: PathBuf,
+    /// Also write the coverage map as a GeoJSON feature collection
+    #[serde(default)]
+    pub geojson: bool,
+    /// Upload the exported files to the output bucket
+    #[serde(default)]
+    pub upload: bool,
+}
+
+/// A radio's ranked coverage of a single hex, as exported. The disktree value
+/// of a hex is the JSON encoded list of records for that hex, ordered by radio
+/// category and then by rank. Radios are ranked within their category, so each
+/// category of a hex starts at rank 1.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HexCoverageRecord {
+    pub radio_category: String,
+    pub rank: usize,
+    pub hotspot_key: String,
+    pub cbsd_id: Option<String>,
+    pub signal_level: String,
+    pub footfall: String,
+    pub landtype: String,
+    pub urbanized: String,
+    pub service_provider_override: String,
+    pub boosted: Option<u32>,
+}
+
+pub fn radio_category_name(category: RadioCategory) -> &'static str {
+    match category {
+        RadioCategory::IndoorWifi => "indoor_wifi",
+        RadioCategory::IndoorCbrs => "indoor_cbrs",
+        RadioCategory::OutdoorWifi => "outdoor_wifi",
+        RadioCategory::OutdoorCbrs => "outdoor_cbrs",
+    }
+}
+
+impl HexCoverageRecord {
+    fn new(category: RadioCategory, coverage: &RankedCoverage) -> Self {
+        Self {
+            radio_category: radio_category_name(category).to_string(),
+            rank: coverage.rank,
+            hotspot_key: PublicKeyBinary::from(coverage.hotspot_key.clone()).to_string(),
+            cbsd_id: coverage.cbsd_id.clone(),
+            signal_level: format!("{:?}", coverage.signal_level).to_lowercase(),
+            footfall: coverage.assignments.footfall.to_string(),
+            landtype: coverage.assignments.landtype.to_string(),
+            urbanized: coverage.assignments.urbanized.to_string(),
+            service_provider_override: coverage.assignments.service_provider_override.to_string(),
+            boosted: coverage.boosted.map(|boost| boost.get()),
+        }
+    }
+}
+
+/// Groups the ranked coverage of the map by hex
+pub fn hex_records(coverage_map: &CoverageMap) -> HashMap<Cell, Vec<HexCoverageRecord>> {
+    let mut hexes = HashMap::<Cell, Vec<(RadioCategory, HexCoverageRecord)>>::new();
+    for (category, coverage) in coverage_map.ranked_coverage() {
+        hexes
+            .entry(coverage.hex)
+            .or_default()
+            .push((category, HexCoverageRecord::new(category, coverage)));
+    }
+    hexes
+        .into_iter()
+        .map(|(hex, mut records)| {
+            records.sort_by_key(|(category, record)| (*category, record.rank));
+            (hex, records.into_iter().map(|(_, record)| record).collect())
+        })
+        .collect()
+}
+
+pub fn decode_hex_records(value: &[u8]) -> serde_json::Result<Vec<HexCoverageRecord>> {
+    serde_json::from_slice(value)
+}
+
+pub fn write_disktree(
+    hexes: &HashMap<Cell, Vec<HexCoverageRecord>>,
+    writer: impl Write + Seek,
+) -> anyhow::Result<()> {
+    let hextree: HexTreeMap<Vec<u8>> = hexes
+        .iter()
+        .map(|(hex, records)| Ok((*hex, serde_json::to_vec(records)?)))
+        .collect::<serde_json::Result<_>>()?;
+    hextree.to_disktree(writer, |w, v| w.write_all(v))?;
+    Ok(())
+}
+
+pub fn write_geojson(
+    hexes: &HashMap<Cell, Vec<HexCoverageRecord>>,
+    writer: impl Write,
+) -> anyhow::Result<()> {
+    let mut features = Vec::with_capacity(hexes.len());
+    for (hex, records) in hexes {
+        let cell = h3o::CellIndex::try_from(hex.into_raw())?;
+        let mut ring: Vec<Vec<f64>> = cell
+            .boundary()
+            .iter()
+            .map(|vertex| vec![vertex.lng(), vertex.lat()])
+            .collect();
+        if let Some(first) = ring.first().cloned() {
+            ring.push(first);
+        }
+
+        let mut properties = geojson::JsonObject::new();
+        properties.insert("hex".to_string(), cell.to_string().into());
+        properties.insert("coverage".to_string(), serde_json::to_value(records)?);
+
+        features.push(geojson::Feature {
+            bbox: None,
+            geometry: Some(geojson::Geometry::new(geojson::Value::Polygon(vec![ring]))),
+            id: None,
+            properties: Some(properties),
+            foreign_members: None,
+        });
+    }
+
+    let collection = geojson::FeatureCollection {
+        bbox: None,
+        features,
+        foreign_members: None,
+    };
+    serde_json::to_writer(writer, &collection)?;
+    Ok(())
+}
+
+/// Writes the ranked coverage map of each rewarded epoch so that the per hex
+/// ranking can be audited
+pub struct CoverageMapExporter {
+    exporter: FileExporter,
+    geojson: bool,
+}
+
+impl CoverageMapExporter {
+    pub async fn from_settings(settings: &Settings) -> anyhow::Result<Option<Self>> {
+        let Some(export_settings) = settings.coverage_map_export.clone() else {
+            return Ok(None);
+        };
+        let file_store = if export_settings.upload {
+            Some(FileStore::from_settings(&settings.output).await?)
+        } else {
+            None
+        };
+        Ok(Some(Self {
+            exporter: FileExporter::new(export_settings.directory, file_store),
+            geojson: export_settings.geojson,
+        }))
+    }
+
+    /// Writes the coverage map for the epoch, returning the paths of the
+    /// written files.
+    pub async fn export(
+        &self,
+        coverage_map: &CoverageMap,
+        epoch: &Range<DateTime<Utc>>,
+    ) -> anyhow::Result<Vec<PathBuf>> {
+        let hexes = Arc::new(hex_records(coverage_map));
+        let base = format!("{COVERAGE_MAP_PREFIX}.{}", epoch.start.timestamp_millis());
+
+        let mut paths = vec![];
+        {
+            let hexes = hexes.clone();
+            let path = self
+                .exporter
+                .export(&format!("{base}.h3tree"), move |writer| {
+                    write_disktree(&hexes, writer)
+                })
+                .await?;
+            paths.push(path);
+        }
+        if self.geojson {
+            let path = self
+                .exporter
+                .export(&format!("{base}.geojson"), move |writer| {
+                    write_geojson(&hexes, writer)
+                })
+                .await?;
+            paths.push(path);
+        }
+
+        tracing::info!(?paths, "exported coverage map");
+        Ok(paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coverage_map::{CoverageMapBuilder, CoverageObject, SignalLevel, UnrankedCoverage};
+    use hex_assignments::{assignment::HexAssignments, Assignment};
+    use hextree::disktree::DiskTreeMap;
+    use std::num::NonZeroU32;
+
+    struct Boosted;
+
+    impl coverage_map::BoostedHexMap for Boosted {
+        fn get_current_multiplier(&self, _cell: Cell, _ts: DateTime<Utc>) -> Option<NonZeroU32> {
+            NonZeroU32::new(5)
+        }
+    }
+
+    fn coverage_object(hotspot_key: &str, indoor: bool, seniority_days: i64) -> CoverageObject {
+        let hotspot_key: PublicKeyBinary = hotspot_key.parse().unwrap();
+        CoverageObject {
+            indoor,
+            hotspot_key: hotspot_key.into(),
+            cbsd_id: None,
+            seniority_timestamp: DateTime::UNIX_EPOCH + chrono::Duration::days(seniority_days),
+            coverage: vec![UnrankedCoverage {
+                location: Cell::from_raw(0x8c2681a3064d9ff).unwrap(),
+                signal_power: 0,
+                signal_level: SignalLevel::High,
+                assignments: HexAssignments {
+                    footfall: Assignment::A,
+                    landtype: Assignment::B,
+                    urbanized: Assignment::A,
+                    service_provider_override: Assignment::C,
+                    assignment_multiplier: None,
+                },
+            }],
+        }
+    }
+
+    #[test]
+    fn disktree_round_trip() -> anyhow::Result<()> {
+        let mut builder = CoverageMapBuilder::default();
+        builder.insert_coverage_object(coverage_object(
+            "11sctWiP9r5wDJVuDe1Th4XSL2vaawaLLSQF8f8iokAoMAJHxqp",
+            true,
+            2,
+        ));
+        builder.insert_coverage_object(coverage_object(
+            "112NqN2WWMwtK29PMzRby62fDydBJfsCLkCAf392stdok48ovNT6",
+            true,
+            1,
+        ));
+        builder.insert_coverage_object(coverage_object(
+            "11eX55faMbqZB7jzN4p67m6w7ScPMH6ubnvCjCPLh72J49PaJEL",
+            false,
+            3,
+        ));
+        let hexes = hex_records(&builder.build(&Boosted, Utc::now()));
+
+        let file = tempfile::NamedTempFile::new()?;
+        write_disktree(&hexes, file.reopen()?)?;
+
+        let disktree = DiskTreeMap::open(file.path())?;
+        let (cell, value) = disktree
+            .get(Cell::from_raw(0x8c2681a3064d9ff)?)?
+            .expect("hex in disktree");
+        let records = decode_hex_records(value)?;
+        assert_eq!(records, hexes[&cell]);
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].radio_category, "indoor_wifi");
+        assert_eq!(records[0].rank, 1);
+        assert_eq!(
+            records[0].hotspot_key,
+            "112NqN2WWMwtK29PMzRby62fDydBJfsCLkCAf392stdok48ovNT6"
+        );
+        assert_eq!(records[0].landtype, "b");
+        assert_eq!(records[0].boosted, Some(5));
+        assert_eq!(records[1].radio_category, "indoor_wifi");
+        assert_eq!(records[1].rank, 2);
+        // Outdoor radios are ranked separately
+        assert_eq!(records[2].radio_category, "outdoor_wifi");
+        assert_eq!(records[2].rank, 1);
+
+        let mut geojson = vec![];
+        write_geojson(&hexes, &mut geojson)?;
+        let geojson: geojson::GeoJson = std::str::from_utf8(&geojson)?.parse()?;
+        let geojson::GeoJson::FeatureCollection(collection) = geojson else {
+            panic!("expected a feature collection");
+        };
+        assert_eq!(collection.features.len(), 1);
+        Ok(())
+    }
+}
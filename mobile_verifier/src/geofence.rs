@@ -1,7 +1,12 @@
 use base64::{engine::general_purpose, Engine as _};
-use h3o::{LatLng, Resolution};
+use chrono::{DateTime, Utc};
+use h3o::{
+    geom::{PolyfillConfig, ToCells},
+    CellIndex, LatLng, Resolution,
+};
 use hextree::{Cell, HexTreeSet};
-use std::{fs, io::Read, path, sync::Arc};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, io::Read, path, path::PathBuf, sync::Arc};
 
 use crate::heartbeats::Heartbeat;
 
@@ -44,6 +49,217 @@ impl GeofenceValidator for Geofence {
     }
 }
 
+/// The feature a geofence region applies to. CBRS and Wi-Fi heartbeats are
+/// fenced separately as they are supported in different regions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum GeofenceFeature {
+    CbrsHeartbeats,
+    WifiHeartbeats,
+    /// Regions for subscriber mapping. Subscriber location reports and
+    /// verified mapping events carry no location, so these regions are only
+    /// available to the geofence cli for now
+    SubscriberMapping,
+    /// Regions in which boosted hexes require unique connections (HIP-140)
+    /// rather than a radio threshold (HIP-84)
+    Boosting,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeofenceFormat {
+    /// GeoJSON file, polygons are polyfilled to h3 at the region's resolution
+    GeoJson,
+    /// Directory of base64 encoded, gzipped h3 index files
+    H3,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GeofenceSettings {
+    pub name: String,
+    pub feature: GeofenceFeature,
+    pub version: u32,
+    /// Time from which this version of the region is used
+    pub active_from: DateTime<Utc>,
+    pub format: GeofenceFormat,
+    pub path: PathBuf,
+    #[serde(default = "default_geofence_resolution")]
+    pub resolution: u8,
+}
+
+fn default_geofence_resolution() -> u8 {
+    7
+}
+
+/// A named, versioned geofence region for a single feature
+#[derive(Clone)]
+pub struct GeofenceRegion {
+    pub name: String,
+    pub feature: GeofenceFeature,
+    pub version: u32,
+    pub active_from: DateTime<Utc>,
+    resolution: Resolution,
+    cells: Arc<HexTreeSet>,
+}
+
+impl GeofenceRegion {
+    pub fn new(
+        name: String,
+        feature: GeofenceFeature,
+        version: u32,
+        active_from: DateTime<Utc>,
+        resolution: Resolution,
+        cells: HexTreeSet,
+    ) -> Self {
+        Self {
+            name,
+            feature,
+            version,
+            active_from,
+            resolution,
+            cells: Arc::new(cells),
+        }
+    }
+
+    pub fn from_settings(settings: &GeofenceSettings) -> anyhow::Result<Self> {
+        let resolution = Resolution::try_from(settings.resolution)?;
+        let cells = match settings.format {
+            GeofenceFormat::GeoJson => from_geojson_file(&settings.path, resolution)?,
+            GeofenceFormat::H3 => {
+                let paths = fs::read_dir(&settings.path)?
+                    .map(|entry| entry.map(|entry| entry.path()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                valid_mapping_regions(paths)?
+            }
+        };
+        Ok(Self::new(
+            settings.name.clone(),
+            settings.feature,
+            settings.version,
+            settings.active_from,
+            resolution,
+            cells,
+        ))
+    }
+
+    pub fn contains_cell(&self, cell: CellIndex) -> bool {
+        let cell = if cell.resolution() > self.resolution {
+            // Can't panic, the region's resolution is coarser than the cell's
+            cell.parent(self.resolution).unwrap()
+        } else {
+            cell
+        };
+        Cell::try_from(u64::from(cell)).is_ok_and(|cell| self.cells.contains(cell))
+    }
+
+    pub fn contains_latlng(&self, lat_lng: LatLng) -> bool {
+        self.contains_cell(lat_lng.to_cell(self.resolution))
+    }
+}
+
+/// All configured geofence regions, across features and versions
+#[derive(Clone, Default)]
+pub struct Geofences {
+    regions: Vec<GeofenceRegion>,
+}
+
+impl Geofences {
+    pub fn new(regions: Vec<GeofenceRegion>) -> Self {
+        Self { regions }
+    }
+
+    pub fn from_settings(settings: &[GeofenceSettings]) -> anyhow::Result<Self> {
+        Ok(Self::new(
+            settings
+                .iter()
+                .map(GeofenceRegion::from_settings)
+                .collect::<anyhow::Result<_>>()?,
+        ))
+    }
+
+    /// Regions in effect at the given time. For every region name and
+    /// feature only the latest version that is active is returned.
+    pub fn active_at(&self, at: DateTime<Utc>) -> impl Iterator<Item = &GeofenceRegion> {
+        let mut active: HashMap<(&str, GeofenceFeature), &GeofenceRegion> = HashMap::new();
+        for region in self.regions.iter().filter(|r| r.active_from <= at) {
+            active
+                .entry((region.name.as_str(), region.feature))
+                .and_modify(|current| {
+                    if region.version > current.version {
+                        *current = region;
+                    }
+                })
+                .or_insert(region);
+        }
+        active.into_values()
+    }
+
+    /// Whether any region of the feature is active at the given time
+    pub fn is_active(&self, feature: GeofenceFeature, at: DateTime<Utc>) -> bool {
+        self.active_at(at).any(|region| region.feature == feature)
+    }
+
+    /// Whether the cell falls within any region of the feature active at the
+    /// given time. Returns `None` if no region is active for the feature.
+    pub fn contains(
+        &self,
+        feature: GeofenceFeature,
+        cell: CellIndex,
+        at: DateTime<Utc>,
+    ) -> Option<bool> {
+        let mut regions = self
+            .active_at(at)
+            .filter(|r| r.feature == feature)
+            .peekable();
+        regions.peek()?;
+        Some(regions.any(|region| region.contains_cell(cell)))
+    }
+
+    /// Active regions containing the cell at the given time
+    pub fn matching(&self, cell: CellIndex, at: DateTime<Utc>) -> Vec<&GeofenceRegion> {
+        let mut regions: Vec<_> = self
+            .active_at(at)
+            .filter(|region| region.contains_cell(cell))
+            .collect();
+        regions.sort_by(|a, b| (a.feature as u8, &a.name).cmp(&(b.feature as u8, &b.name)));
+        regions
+    }
+}
+
+/// Validates heartbeats against the configured regions for a feature,
+/// falling back to a static geofence while none are active.
+#[derive(Clone)]
+pub struct FeatureGeofence {
+    geofences: Arc<Geofences>,
+    feature: GeofenceFeature,
+    fallback: Geofence,
+}
+
+impl FeatureGeofence {
+    pub fn new(geofences: Arc<Geofences>, feature: GeofenceFeature, fallback: Geofence) -> Self {
+        Self {
+            geofences,
+            feature,
+            fallback,
+        }
+    }
+}
+
+impl GeofenceValidator for FeatureGeofence {
+    fn in_valid_region(&self, heartbeat: &Heartbeat) -> bool {
+        let Ok(lat_lon) = LatLng::new(heartbeat.lat, heartbeat.lon) else {
+            return false;
+        };
+        self.geofences
+            .contains(
+                self.feature,
+                lat_lon.to_cell(Resolution::Fifteen),
+                heartbeat.timestamp,
+            )
+            .unwrap_or_else(|| self.fallback.in_valid_region(heartbeat))
+    }
+}
+
 pub fn valid_mapping_regions(encoded_files: Vec<std::path::PathBuf>) -> anyhow::Result<HexTreeSet> {
     let mut combined_regions: Vec<Cell> = Vec::new();
     for file in encoded_files {
@@ -54,6 +270,21 @@ pub fn valid_mapping_regions(encoded_files: Vec<std::path::PathBuf>) -> anyhow::
     Ok(region_set)
 }
 
+fn from_geojson_file<P: AsRef<path::Path>>(
+    file: P,
+    resolution: Resolution,
+) -> anyhow::Result<HexTreeSet> {
+    let geojson: geojson::GeoJson = fs::read_to_string(file.as_ref())?.parse()?;
+    let mut cells: Vec<Cell> = Vec::new();
+    for geometry in geojson::quick_collection(&geojson)? {
+        let geometry = h3o::geom::Geometry::from_degrees(geometry)?;
+        for cell in geometry.to_cells(PolyfillConfig::new(resolution)) {
+            cells.push(Cell::try_from(u64::from(cell))?);
+        }
+    }
+    Ok(cells.iter().collect())
+}
+
 fn from_base64_file<P: AsRef<path::Path>>(file: P) -> anyhow::Result<Vec<Cell>> {
     let mut file = fs::File::open(file.as_ref())?;
     let mut encoded_string = String::new();
@@ -74,3 +305,72 @@ fn from_base64_file<P: AsRef<path::Path>>(file: P) -> anyhow::Result<Vec<Cell>>
     }
     Ok(indexes)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    const SQUARE: &str = r#"{
+        "type": "Polygon",
+        "coordinates": [[
+            [-105.30, 40.00], [-105.20, 40.00], [-105.20, 40.05],
+            [-105.30, 40.05], [-105.30, 40.00]
+        ]]
+    }"#;
+
+    fn region(name: &str, version: u32, active_from: DateTime<Utc>) -> GeofenceRegion {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(file.path(), SQUARE).unwrap();
+        GeofenceRegion::from_settings(&GeofenceSettings {
+            name: name.to_string(),
+            feature: GeofenceFeature::WifiHeartbeats,
+            version,
+            active_from,
+            format: GeofenceFormat::GeoJson,
+            path: file.path().to_path_buf(),
+            resolution: 7,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn latest_active_version_is_used() {
+        let now = Utc::now();
+        let geofences = Geofences::new(vec![
+            region("boulder", 1, now - Duration::days(2)),
+            region("boulder", 2, now - Duration::days(1)),
+            region("boulder", 3, now + Duration::days(1)),
+        ]);
+
+        let versions: Vec<u32> = geofences.active_at(now).map(|r| r.version).collect();
+        assert_eq!(versions, vec![2]);
+        assert_eq!(geofences.active_at(now - Duration::days(3)).count(), 0);
+    }
+
+    #[test]
+    fn contains_polyfilled_cells() {
+        let now = Utc::now();
+        let geofences = Geofences::new(vec![region("longmont", 1, now - Duration::days(1))]);
+        let inside = LatLng::new(40.02, -105.25)
+            .unwrap()
+            .to_cell(Resolution::Twelve);
+        let outside = LatLng::new(41.0, -105.25)
+            .unwrap()
+            .to_cell(Resolution::Twelve);
+
+        assert_eq!(
+            geofences.contains(GeofenceFeature::WifiHeartbeats, inside, now),
+            Some(true)
+        );
+        assert_eq!(
+            geofences.contains(GeofenceFeature::WifiHeartbeats, outside, now),
+            Some(false)
+        );
+        assert_eq!(
+            geofences.contains(GeofenceFeature::CbrsHeartbeats, inside, now),
+            None
+        );
+        assert_eq!(geofences.matching(inside, now).len(), 1);
+    }
+}
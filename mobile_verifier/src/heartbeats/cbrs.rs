@@ -1,5 +1,6 @@
 use super::{process_validated_heartbeats, Heartbeat, ValidatedHeartbeat};
 use crate::{
+    cell_type::CellTypeRegistry,
     coverage::{CoverageClaimTimeCache, CoverageObjectCache},
     geofence::GeofenceValidator,
     heartbeats::LocationCache,
@@ -34,6 +35,7 @@ pub struct CbrsHeartbeatDaemon<GIR, GFV> {
     seniority_sink: FileSinkClient<proto::SeniorityUpdate>,
     geofence: GFV,
     cbrs_disable_time: DateTime<Utc>,
+    cell_types: CellTypeRegistry,
 }
 
 impl<GIR, GFV> CbrsHeartbeatDaemon<GIR, GFV>
@@ -71,6 +73,7 @@ where
             seniority_updates,
             geofence,
             settings.cbrs_disable_time,
+            settings.cell_type_registry()?,
         );
 
         Ok(TaskManager::builder()
@@ -89,6 +92,7 @@ where
         seniority_sink: FileSinkClient<proto::SeniorityUpdate>,
         geofence: GFV,
         cbrs_disable_time: DateTime<Utc>,
+        cell_types: CellTypeRegistry,
     ) -> Self {
         Self {
             pool,
@@ -99,6 +103,7 @@ where
             seniority_sink,
             geofence,
             cbrs_disable_time,
+            cell_types,
         }
     }
 
@@ -179,6 +184,7 @@ where
                 self.max_distance_to_coverage,
                 &epoch,
                 &self.geofence,
+                &self.cell_types,
             ),
             heartbeat_cache,
             coverage_claim_time_cache,
@@ -5,7 +5,7 @@ use helium_crypto::PublicKeyBinary;
 use retainer::Cache;
 use sqlx::PgPool;
 
-use super::Heartbeat;
+use super::{location_history, Heartbeat};
 
 #[derive(Debug, sqlx::FromRow, Copy, Clone, PartialEq)]
 pub struct LastLocation {
@@ -63,6 +63,7 @@ impl LastLocation {
 pub struct LocationCache {
     pool: PgPool,
     locations: Arc<Cache<PublicKeyBinary, Option<LastLocation>>>,
+    location_flags: Arc<Cache<PublicKeyBinary, bool>>,
 }
 
 impl LocationCache {
@@ -77,9 +78,27 @@ impl LocationCache {
         Self {
             pool: pool.clone(),
             locations,
+            location_flags: Arc::new(Cache::new()),
         }
     }
 
+    /// Whether the hotspot's location is currently flagged as jumping or
+    /// drifting by the location drift detector
+    pub async fn is_flagged(&self, hotspot: &PublicKeyBinary) -> anyhow::Result<bool> {
+        if let Some(flagged) = self.location_flags.get(hotspot).await {
+            return Ok(*flagged);
+        }
+        let flagged = location_history::db::is_flagged(&self.pool, hotspot).await?;
+        self.location_flags
+            .insert(
+                hotspot.clone(),
+                flagged,
+                std::time::Duration::from_secs(60 * 60),
+            )
+            .await;
+        Ok(flagged)
+    }
+
     pub async fn set(&self, hotspot: &PublicKeyBinary, last_location: LastLocation) {
         self.cache_last_location(hotspot, Some(last_location)).await;
     }
@@ -317,4 +336,25 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test]
+    async fn flagged_hotspots(pool: PgPool) -> anyhow::Result<()> {
+        let cache = LocationCache::new(&pool);
+        let flagged = PublicKeyBinary::from(vec![1]);
+        let unflagged = PublicKeyBinary::from(vec![2]);
+
+        location_history::db::upsert_flag(
+            &pool,
+            &flagged,
+            location_history::LocationFlagReason::Jumping,
+            &location_history::LocationStats::default(),
+            Utc::now(),
+        )
+        .await?;
+
+        assert!(cache.is_flagged(&flagged).await?);
+        assert!(!cache.is_flagged(&unflagged).await?);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,479 @@
+//! Persisted Wi-Fi heartbeat locations, used to flag hotspots whose location
+//! jumps between distant places or steadily drifts away from the asserted
+//! location.
+
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, Utc};
+use futures::{Stream, TryFutureExt, TryStreamExt};
+use h3o::LatLng;
+use helium_crypto::PublicKeyBinary;
+use humantime_serde::re::humantime;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Postgres, Transaction};
+use task_manager::ManagedTask;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LocationDriftSettings {
+    /// How often hotspots are checked for location drift
+    #[serde(with = "humantime_serde")]
+    pub interval: StdDuration,
+    /// Heartbeat locations considered when checking a hotspot
+    #[serde(with = "humantime_serde")]
+    pub window: StdDuration,
+    /// How long heartbeat locations are kept
+    #[serde(with = "humantime_serde")]
+    pub retention: StdDuration,
+    /// Distance in meters between consecutive heartbeat locations that counts
+    /// as a jump
+    pub jump_distance_m: f64,
+    /// Number of jumps within the window for a hotspot to be flagged
+    pub min_jumps: usize,
+    /// Minimum number of heartbeat locations to estimate drift
+    pub min_samples: usize,
+    /// Increase in distance to the asserted location, in meters per day, for
+    /// a hotspot to be flagged as drifting
+    pub drift_m_per_day: f64,
+    /// Minimum latest distance to the asserted location for a hotspot to be
+    /// flagged as drifting
+    pub min_drift_distance_m: i64,
+}
+
+impl Default for LocationDriftSettings {
+    fn default() -> Self {
+        Self {
+            interval: humantime::parse_duration("1 hour").unwrap(),
+            window: humantime::parse_duration("7 days").unwrap(),
+            retention: humantime::parse_duration("30 days").unwrap(),
+            jump_distance_m: 10_000.0,
+            min_jumps: 2,
+            min_samples: 24,
+            drift_m_per_day: 100.0,
+            min_drift_distance_m: 500,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, FromRow)]
+pub struct LocationSample {
+    pub truncated_timestamp: DateTime<Utc>,
+    pub lat: f64,
+    pub lon: f64,
+    pub distance_to_asserted: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "location_flag_reason")]
+#[sqlx(rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum LocationFlagReason {
+    /// Consecutive heartbeats from distant places
+    Jumping,
+    /// Distance to the asserted location steadily increasing
+    Drifting,
+}
+
+/// Distance to asserted statistics of a hotspot's heartbeat locations
+#[derive(Debug, Clone, Default, PartialEq, Serialize, FromRow)]
+pub struct LocationStats {
+    pub samples: i32,
+    pub mean_distance_to_asserted: f64,
+    pub max_distance_to_asserted: i64,
+    pub latest_distance_to_asserted: i64,
+    pub jumps: i32,
+    /// Least squares slope of the distance to the asserted location
+    pub drift_m_per_day: f64,
+}
+
+impl LocationStats {
+    /// Samples must be ordered by time
+    pub fn from_samples(samples: &[LocationSample], settings: &LocationDriftSettings) -> Self {
+        let Some(latest) = samples.last() else {
+            return Self::default();
+        };
+        let count = samples.len() as f64;
+        let mean_distance_to_asserted = samples
+            .iter()
+            .map(|sample| sample.distance_to_asserted as f64)
+            .sum::<f64>()
+            / count;
+        let jumps = samples
+            .windows(2)
+            .filter(|pair| distance_m(&pair[0], &pair[1]) > settings.jump_distance_m)
+            .count();
+
+        Self {
+            samples: samples.len() as i32,
+            mean_distance_to_asserted,
+            max_distance_to_asserted: samples
+                .iter()
+                .map(|sample| sample.distance_to_asserted)
+                .max()
+                .unwrap_or_default(),
+            latest_distance_to_asserted: latest.distance_to_asserted,
+            jumps: jumps as i32,
+            drift_m_per_day: drift_m_per_day(samples, mean_distance_to_asserted),
+        }
+    }
+
+    pub fn flag(&self, settings: &LocationDriftSettings) -> Option<LocationFlagReason> {
+        if self.jumps as usize >= settings.min_jumps {
+            Some(LocationFlagReason::Jumping)
+        } else if self.samples as usize >= settings.min_samples
+            && self.drift_m_per_day >= settings.drift_m_per_day
+            && self.latest_distance_to_asserted >= settings.min_drift_distance_m
+        {
+            Some(LocationFlagReason::Drifting)
+        } else {
+            None
+        }
+    }
+}
+
+fn distance_m(a: &LocationSample, b: &LocationSample) -> f64 {
+    match (LatLng::new(a.lat, a.lon), LatLng::new(b.lat, b.lon)) {
+        (Ok(a), Ok(b)) => a.distance_m(b),
+        _ => 0.0,
+    }
+}
+
+fn drift_m_per_day(samples: &[LocationSample], mean_distance: f64) -> f64 {
+    let Some(first) = samples.first() else {
+        return 0.0;
+    };
+    let days: Vec<f64> = samples
+        .iter()
+        .map(|sample| {
+            (sample.truncated_timestamp - first.truncated_timestamp).num_seconds() as f64 / 86_400.0
+        })
+        .collect();
+    let mean_days = days.iter().sum::<f64>() / days.len() as f64;
+
+    let (covariance, variance) =
+        days.iter()
+            .zip(samples)
+            .fold((0.0, 0.0), |(covariance, variance), (day, sample)| {
+                let dx = day - mean_days;
+                let dy = sample.distance_to_asserted as f64 - mean_distance;
+                (covariance + dx * dy, variance + dx * dx)
+            });
+    if variance == 0.0 {
+        0.0
+    } else {
+        covariance / variance
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct LocationFlag {
+    pub hotspot_key: PublicKeyBinary,
+    pub reason: LocationFlagReason,
+    pub flagged_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[sqlx(flatten)]
+    pub stats: LocationStats,
+}
+
+/// Periodically checks the location history of every Wi-Fi hotspot and
+/// updates its location flag
+pub struct LocationDriftDetector {
+    pool: PgPool,
+    settings: LocationDriftSettings,
+}
+
+impl ManagedTask for LocationDriftDetector {
+    fn start_task(
+        self: Box<Self>,
+        shutdown: triggered::Listener,
+    ) -> futures::prelude::future::LocalBoxFuture<'static, anyhow::Result<()>> {
+        let handle = tokio::spawn(self.run(shutdown));
+        Box::pin(
+            handle
+                .map_err(anyhow::Error::from)
+                .and_then(|result| async move { result }),
+        )
+    }
+}
+
+impl LocationDriftDetector {
+    pub fn new(pool: PgPool, settings: LocationDriftSettings) -> Self {
+        Self { pool, settings }
+    }
+
+    pub async fn run(self, shutdown: triggered::Listener) -> anyhow::Result<()> {
+        tracing::info!("Starting location drift detector");
+        let mut interval = tokio::time::interval(self.settings.interval);
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown.clone() => {
+                    tracing::info!("Location drift detector shutting down");
+                    break;
+                }
+                _ = interval.tick() => {
+                    if let Err(err) = self.detect(Utc::now()).await {
+                        tracing::error!(?err, "Failed to check hotspot locations");
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn detect(&self, now: DateTime<Utc>) -> anyhow::Result<()> {
+        let since = now - Duration::from_std(self.settings.window)?;
+        let mut history = std::pin::pin!(db::fetch_history(&self.pool, since));
+
+        let mut flagged = vec![];
+        let mut current: Option<(PublicKeyBinary, Vec<LocationSample>)> = None;
+        while let Some((hotspot_key, sample)) = history.try_next().await? {
+            match current.as_mut() {
+                Some((key, samples)) if *key == hotspot_key => samples.push(sample),
+                _ => {
+                    if let Some((key, samples)) = current.replace((hotspot_key, vec![sample])) {
+                        flagged.extend(self.check(key, &samples, now).await?);
+                    }
+                }
+            }
+        }
+        if let Some((key, samples)) = current {
+            flagged.extend(self.check(key, &samples, now).await?);
+        }
+
+        let cleared = db::clear_flags_except(&self.pool, &flagged).await?;
+        let pruned = db::delete_history_before(
+            &self.pool,
+            now - Duration::from_std(self.settings.retention)?,
+        )
+        .await?;
+        tracing::info!(
+            flagged = flagged.len(),
+            cleared,
+            pruned,
+            "Checked hotspot locations"
+        );
+        Ok(())
+    }
+
+    async fn check(
+        &self,
+        hotspot_key: PublicKeyBinary,
+        samples: &[LocationSample],
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Option<PublicKeyBinary>> {
+        let stats = LocationStats::from_samples(samples, &self.settings);
+        let Some(reason) = stats.flag(&self.settings) else {
+            return Ok(None);
+        };
+        db::upsert_flag(&self.pool, &hotspot_key, reason, &stats, now).await?;
+        Ok(Some(hotspot_key))
+    }
+}
+
+pub mod db {
+    use super::*;
+
+    pub async fn insert_sample(
+        exec: &mut Transaction<'_, Postgres>,
+        hotspot_key: &PublicKeyBinary,
+        sample: &LocationSample,
+    ) -> sqlx::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO wifi_location_history (hotspot_key, truncated_timestamp, lat, lon, distance_to_asserted)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (hotspot_key, truncated_timestamp) DO NOTHING
+            "#,
+        )
+        .bind(hotspot_key)
+        .bind(sample.truncated_timestamp)
+        .bind(sample.lat)
+        .bind(sample.lon)
+        .bind(sample.distance_to_asserted)
+        .execute(&mut *exec)
+        .await?;
+        Ok(())
+    }
+
+    /// Location history since the given time, ordered by hotspot and time
+    pub fn fetch_history(
+        pool: &PgPool,
+        since: DateTime<Utc>,
+    ) -> impl Stream<Item = sqlx::Result<(PublicKeyBinary, LocationSample)>> + '_ {
+        #[derive(FromRow)]
+        struct Row {
+            hotspot_key: PublicKeyBinary,
+            #[sqlx(flatten)]
+            sample: LocationSample,
+        }
+
+        sqlx::query_as::<_, Row>(
+            r#"
+            SELECT hotspot_key, truncated_timestamp, lat, lon, distance_to_asserted
+            FROM wifi_location_history
+            WHERE truncated_timestamp >= $1
+            ORDER BY hotspot_key, truncated_timestamp
+            "#,
+        )
+        .bind(since)
+        .fetch(pool)
+        .map_ok(|row| (row.hotspot_key, row.sample))
+    }
+
+    pub async fn fetch_hotspot_history(
+        pool: &PgPool,
+        hotspot_key: &PublicKeyBinary,
+        since: DateTime<Utc>,
+    ) -> sqlx::Result<Vec<LocationSample>> {
+        sqlx::query_as(
+            r#"
+            SELECT truncated_timestamp, lat, lon, distance_to_asserted
+            FROM wifi_location_history
+            WHERE hotspot_key = $1 AND truncated_timestamp >= $2
+            ORDER BY truncated_timestamp
+            "#,
+        )
+        .bind(hotspot_key)
+        .bind(since)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn upsert_flag(
+        pool: &PgPool,
+        hotspot_key: &PublicKeyBinary,
+        reason: LocationFlagReason,
+        stats: &LocationStats,
+        now: DateTime<Utc>,
+    ) -> sqlx::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO wifi_location_flags
+                (hotspot_key, reason, flagged_at, updated_at, samples, mean_distance_to_asserted,
+                 max_distance_to_asserted, latest_distance_to_asserted, jumps, drift_m_per_day)
+            VALUES ($1, $2, $3, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (hotspot_key) DO UPDATE SET
+                reason = EXCLUDED.reason,
+                updated_at = EXCLUDED.updated_at,
+                samples = EXCLUDED.samples,
+                mean_distance_to_asserted = EXCLUDED.mean_distance_to_asserted,
+                max_distance_to_asserted = EXCLUDED.max_distance_to_asserted,
+                latest_distance_to_asserted = EXCLUDED.latest_distance_to_asserted,
+                jumps = EXCLUDED.jumps,
+                drift_m_per_day = EXCLUDED.drift_m_per_day
+            "#,
+        )
+        .bind(hotspot_key)
+        .bind(reason)
+        .bind(now)
+        .bind(stats.samples)
+        .bind(stats.mean_distance_to_asserted)
+        .bind(stats.max_distance_to_asserted)
+        .bind(stats.latest_distance_to_asserted)
+        .bind(stats.jumps)
+        .bind(stats.drift_m_per_day)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes the flags of every hotspot not in the given list, returning
+    /// the number of cleared flags
+    pub async fn clear_flags_except(
+        pool: &PgPool,
+        flagged: &[PublicKeyBinary],
+    ) -> sqlx::Result<u64> {
+        let flagged: Vec<String> = flagged.iter().map(ToString::to_string).collect();
+        Ok(
+            sqlx::query("DELETE FROM wifi_location_flags WHERE NOT hotspot_key = ANY($1)")
+                .bind(flagged)
+                .execute(pool)
+                .await?
+                .rows_affected(),
+        )
+    }
+
+    pub async fn delete_history_before(pool: &PgPool, before: DateTime<Utc>) -> sqlx::Result<u64> {
+        Ok(
+            sqlx::query("DELETE FROM wifi_location_history WHERE truncated_timestamp < $1")
+                .bind(before)
+                .execute(pool)
+                .await?
+                .rows_affected(),
+        )
+    }
+
+    pub async fn fetch_flags(pool: &PgPool) -> sqlx::Result<Vec<LocationFlag>> {
+        sqlx::query_as("SELECT * FROM wifi_location_flags ORDER BY flagged_at")
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn is_flagged(pool: &PgPool, hotspot_key: &PublicKeyBinary) -> sqlx::Result<bool> {
+        sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM wifi_location_flags WHERE hotspot_key = $1)",
+        )
+        .bind(hotspot_key)
+        .fetch_one(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(hours: i64, lat: f64, lon: f64, distance_to_asserted: i64) -> LocationSample {
+        LocationSample {
+            truncated_timestamp: DateTime::UNIX_EPOCH + Duration::hours(hours),
+            lat,
+            lon,
+            distance_to_asserted,
+        }
+    }
+
+    #[test]
+    fn stationary_hotspot_is_not_flagged() {
+        let settings = LocationDriftSettings::default();
+        let samples: Vec<_> = (0..48)
+            .map(|hour| sample(hour, 40.0190, -105.2747, 30))
+            .collect();
+        let stats = LocationStats::from_samples(&samples, &settings);
+        assert_eq!(stats.jumps, 0);
+        assert_eq!(stats.drift_m_per_day, 0.0);
+        assert_eq!(stats.flag(&settings), None);
+    }
+
+    #[test]
+    fn jumping_hotspot_is_flagged() {
+        let settings = LocationDriftSettings::default();
+        // Boulder, Denver and back again
+        let samples = vec![
+            sample(0, 40.0190, -105.2747, 30),
+            sample(1, 39.7392, -104.9903, 38_000),
+            sample(2, 40.0190, -105.2747, 30),
+        ];
+        let stats = LocationStats::from_samples(&samples, &settings);
+        assert_eq!(stats.jumps, 2);
+        assert_eq!(stats.max_distance_to_asserted, 38_000);
+        assert_eq!(stats.flag(&settings), Some(LocationFlagReason::Jumping));
+    }
+
+    #[test]
+    fn drifting_hotspot_is_flagged() {
+        let settings = LocationDriftSettings::default();
+        // 200 meters further from the asserted location every day
+        let samples: Vec<_> = (0..72)
+            .map(|hour| sample(hour, 40.0190, -105.2747, hour * 200 / 24))
+            .collect();
+        let stats = LocationStats::from_samples(&samples, &settings);
+        assert!((stats.drift_m_per_day - 200.0).abs() < 5.0);
+        assert_eq!(stats.latest_distance_to_asserted, 591);
+        assert_eq!(stats.flag(&settings), Some(LocationFlagReason::Drifting));
+
+        // Too few samples to estimate drift
+        let stats = LocationStats::from_samples(&samples[60..], &settings);
+        assert_eq!(stats.flag(&settings), None);
+    }
+}
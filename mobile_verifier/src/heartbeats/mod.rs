@@ -1,9 +1,10 @@
 pub mod cbrs;
 pub mod last_location;
+pub mod location_history;
 pub mod wifi;
 
 use crate::{
-    cell_type::{CellType, CellTypeLabel},
+    cell_type::{CellType, CellTypeLabel, CellTypeRegistry},
     coverage::{self, CoverageClaimTimeCache, CoverageObjectCache, CoverageObjectMeta},
     geofence::GeofenceValidator,
     seniority::{Seniority, SeniorityUpdate},
@@ -384,6 +385,7 @@ impl ValidatedHeartbeat {
         max_distance_to_coverage: u32,
         epoch: &Range<DateTime<Utc>>,
         geofence: &impl GeofenceValidator,
+        cell_types: &CellTypeRegistry,
     ) -> anyhow::Result<Self> {
         let Some(coverage_object) = heartbeat.coverage_object else {
             return Ok(Self::new(
@@ -410,21 +412,11 @@ impl ValidatedHeartbeat {
             ));
         };
 
-        let cell_type = match heartbeat.hb_type {
-            HbType::Cbrs => match heartbeat.cbsd_id.as_ref() {
-                Some(cbsd_id) => match CellType::from_cbsd_id(cbsd_id) {
-                    Some(ty) => ty,
-                    _ => {
-                        return Ok(Self::new(
-                            heartbeat,
-                            CellType::CellTypeNone,
-                            dec!(0),
-                            None,
-                            Some(coverage_object.meta),
-                            proto::HeartbeatValidity::BadCbsdId,
-                        ));
-                    }
-                },
+        // CBRS radios are matched by their CBSD id, Wi-Fi radios by the
+        // indoor flag of their coverage object
+        let cbsd_id = match heartbeat.hb_type {
+            HbType::Cbrs => match heartbeat.cbsd_id.as_deref() {
+                Some(cbsd_id) => Some(cbsd_id),
                 None => {
                     return Ok(Self::new(
                         heartbeat,
@@ -436,14 +428,22 @@ impl ValidatedHeartbeat {
                     ));
                 }
             },
-            HbType::Wifi => {
-                if coverage_object.meta.indoor {
-                    CellType::NovaGenericWifiIndoor
-                } else {
-                    CellType::NovaGenericWifiOutdoor
-                }
-            }
+            HbType::Wifi => None,
         };
+        let Some(radio_model) = cell_types
+            .model(cbsd_id, coverage_object.meta.indoor)
+            .cloned()
+        else {
+            return Ok(Self::new(
+                heartbeat,
+                CellType::CellTypeNone,
+                dec!(0),
+                None,
+                Some(coverage_object.meta),
+                proto::HeartbeatValidity::BadCbsdId,
+            ));
+        };
+        let cell_type = radio_model.cell_type;
 
         if !heartbeat.operation_mode {
             return Ok(Self::new(
@@ -490,7 +490,7 @@ impl ValidatedHeartbeat {
         }
 
         match gateway_info_resolver
-            .resolve_gateway(&heartbeat.hotspot_key)
+            .resolve_gateway(&heartbeat.hotspot_key, heartbeat.timestamp)
             .await?
         {
             GatewayResolution::DataOnly => Ok(Self::new(
@@ -555,23 +555,31 @@ impl ValidatedHeartbeat {
                 let distance_to_asserted = asserted_latlng.distance_m(hb_latlng).round() as i64;
                 let max_distance = coverage_object.max_distance_m(hb_latlng).round() as u32;
 
+                if last_location_cache
+                    .is_flagged(&heartbeat.hotspot_key)
+                    .await?
+                {
+                    // Location jumping or drifting away from the asserted
+                    // location, see `location_history`
+                    return Ok(Self::new(
+                        heartbeat,
+                        cell_type,
+                        dec!(0),
+                        Some(distance_to_asserted),
+                        Some(coverage_object.meta),
+                        proto::HeartbeatValidity::LocationFlagged,
+                    ));
+                }
+
                 let location_trust_score_multiplier = if !is_valid {
                     dec!(0)
                 } else if max_distance >= max_distance_to_coverage {
                     // Furthest hex in Heartbeat exceeds allowed coverage distance
                     dec!(0)
                 } else {
-                    // HIP-119 maximum asserted distance check
-                    use coverage_point_calculator::{
-                        asserted_distance_to_trust_multiplier, RadioType,
-                    };
-                    let radio_type = match (heartbeat.hb_type, coverage_object.meta.indoor) {
-                        (HbType::Cbrs, true) => RadioType::IndoorCbrs,
-                        (HbType::Cbrs, false) => RadioType::OutdoorCbrs,
-                        (HbType::Wifi, true) => RadioType::IndoorWifi,
-                        (HbType::Wifi, false) => RadioType::OutdoorWifi,
-                    };
-                    asserted_distance_to_trust_multiplier(radio_type, distance_to_asserted as u32)
+                    // HIP-119 maximum asserted distance check, unless the
+                    // radio model has its own location weights
+                    radio_model.location_trust_multiplier(true, Some(distance_to_asserted))
                 };
 
                 Ok(Self::new(
@@ -583,14 +591,20 @@ impl ValidatedHeartbeat {
                     proto::HeartbeatValidity::Valid,
                 ))
             }
-            _ => Ok(Self::new(
-                heartbeat,
-                cell_type,
-                dec!(1.0),
-                None,
-                Some(coverage_object.meta),
-                proto::HeartbeatValidity::Valid,
-            )),
+            _ => {
+                let location_trust_score_multiplier = radio_model.location_trust_multiplier(
+                    heartbeat.location_validation_timestamp.is_some(),
+                    None,
+                );
+                Ok(Self::new(
+                    heartbeat,
+                    cell_type,
+                    location_trust_score_multiplier,
+                    None,
+                    Some(coverage_object.meta),
+                    proto::HeartbeatValidity::Valid,
+                ))
+            }
         }
     }
 
@@ -603,6 +617,7 @@ impl ValidatedHeartbeat {
         max_distance_to_coverage: u32,
         epoch: &'a Range<DateTime<Utc>>,
         geofence: &'a impl GeofenceValidator,
+        cell_types: &'a CellTypeRegistry,
     ) -> impl Stream<Item = anyhow::Result<Self>> + 'a {
         heartbeats.then(move |heartbeat| async move {
             Self::validate(
@@ -613,6 +628,7 @@ impl ValidatedHeartbeat {
                 max_distance_to_coverage,
                 epoch,
                 geofence,
+                cell_types,
             )
             .await
         })
@@ -624,7 +640,7 @@ impl ValidatedHeartbeat {
                 proto::Heartbeat {
                     cbsd_id: self.heartbeat.cbsd_id.clone().unwrap_or_default(),
                     pub_key: self.heartbeat.hotspot_key.as_ref().into(),
-                    cell_type: self.cell_type as i32,
+                    cell_type: proto::CellType::from(self.cell_type) as i32,
                     validity: self.validity as i32,
                     timestamp: self.heartbeat.timestamp.timestamp() as u64,
                     location_trust_score_multiplier: (self.location_trust_score_multiplier
@@ -693,6 +709,22 @@ impl ValidatedHeartbeat {
 
     async fn save_wifi_hb(self, exec: &mut Transaction<'_, Postgres>) -> anyhow::Result<()> {
         let truncated_timestamp = self.truncated_timestamp()?;
+        if let (Some(distance_to_asserted), Some(_)) = (
+            self.distance_to_asserted,
+            self.heartbeat.location_validation_timestamp,
+        ) {
+            location_history::db::insert_sample(
+                exec,
+                &self.heartbeat.hotspot_key,
+                &location_history::LocationSample {
+                    truncated_timestamp,
+                    lat: self.heartbeat.lat,
+                    lon: self.heartbeat.lon,
+                    distance_to_asserted,
+                },
+            )
+            .await?;
+        }
         sqlx::query(
             r#"
             INSERT INTO wifi_heartbeats (hotspot_key, cell_type, latest_timestamp, truncated_timestamp, coverage_object, location_trust_score_multiplier, distance_to_asserted, location_validation_timestamp, lat, lon)
@@ -1,5 +1,6 @@
 use super::{process_validated_heartbeats, Heartbeat, ValidatedHeartbeat};
 use crate::{
+    cell_type::CellTypeRegistry,
     coverage::{CoverageClaimTimeCache, CoverageObjectCache},
     geofence::GeofenceValidator,
     heartbeats::LocationCache,
@@ -32,6 +33,7 @@ pub struct WifiHeartbeatDaemon<GIR, GFV> {
     heartbeat_sink: FileSinkClient<proto::Heartbeat>,
     seniority_sink: FileSinkClient<proto::SeniorityUpdate>,
     geofence: GFV,
+    cell_types: CellTypeRegistry,
 }
 
 impl<GIR, GFV> WifiHeartbeatDaemon<GIR, GFV>
@@ -67,6 +69,7 @@ where
             valid_heartbeats,
             seniority_updates,
             geofence,
+            settings.cell_type_registry()?,
         );
 
         Ok(TaskManager::builder()
@@ -84,6 +87,7 @@ where
         heartbeat_sink: FileSinkClient<proto::Heartbeat>,
         seniority_sink: FileSinkClient<proto::SeniorityUpdate>,
         geofence: GFV,
+        cell_types: CellTypeRegistry,
     ) -> Self {
         Self {
             pool,
@@ -93,6 +97,7 @@ where
             heartbeat_sink,
             seniority_sink,
             geofence,
+            cell_types,
         }
     }
 
@@ -161,6 +166,7 @@ where
                 self.max_distance_to_coverage,
                 &epoch,
                 &self.geofence,
+                &self.cell_types,
             ),
             heartbeat_cache,
             coverage_claim_time_cache,
@@ -2,6 +2,7 @@ pub mod boosting_oracles;
 pub mod cell_type;
 pub mod cli;
 pub mod coverage;
+pub mod coverage_map_export;
 pub mod data_session;
 pub mod geofence;
 pub mod heartbeats;
@@ -22,6 +23,7 @@ pub mod unique_connections;
 pub use settings::Settings;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use helium_lib::keypair::Pubkey;
 use rust_decimal::Decimal;
 use std::error::Error;
@@ -43,9 +45,11 @@ impl GatewayResolution {
 pub trait GatewayResolver: Clone + Send + Sync + 'static {
     type Error: Error + Send + Sync + 'static;
 
+    /// Resolves the gateway as it was at `at`
     async fn resolve_gateway(
         &self,
         address: &helium_crypto::PublicKeyBinary,
+        at: DateTime<Utc>,
     ) -> Result<GatewayResolution, Self::Error>;
 }
 
@@ -56,11 +60,12 @@ impl GatewayResolver for mobile_config::GatewayClient {
     async fn resolve_gateway(
         &self,
         address: &helium_crypto::PublicKeyBinary,
+        at: DateTime<Utc>,
     ) -> Result<GatewayResolution, Self::Error> {
         use mobile_config::client::gateway_client::GatewayInfoResolver;
         use mobile_config::gateway_info::{DeviceType, GatewayInfo};
 
-        match self.resolve_gateway_info(address).await? {
+        match self.resolve_gateway_info_at(address, at).await? {
             None => Ok(GatewayResolution::GatewayNotFound),
             Some(GatewayInfo {
                 device_type: DeviceType::WifiDataOnly,
@@ -1,7 +1,10 @@
 use anyhow::Result;
 use clap::Parser;
 use mobile_verifier::{
-    cli::{reward_from_db, server, service_provider_promotions, verify_disktree},
+    cli::{
+        data_sets, geofence, location_drift, reward_dry_run, reward_explanation, reward_from_db,
+        server, service_provider_promotions, verify_disktree,
+    },
     Settings,
 };
 use std::path;
@@ -32,6 +35,13 @@ impl Cli {
 pub enum Cmd {
     Server(server::Cmd),
     RewardFromDb(reward_from_db::Cmd),
+    /// Reward an epoch without writing rewards or modifying the database.
+    ///
+    /// Reward parameters can be overridden and the result compared with a
+    /// previous run.
+    RewardDryRun(reward_dry_run::Cmd),
+    /// Show how a radio's proof of coverage reward was calculated.
+    RewardExplanation(reward_explanation::Cmd),
     /// Verify a Disktree file for HexBoosting.
     ///
     /// Go through every cell and ensure it's value can be turned into an Assignment.
@@ -39,6 +49,12 @@ pub enum Cmd {
     VerifyDisktree(verify_disktree::Cmd),
     /// Print active Service Provider Promotions
     ServiceProviderPromotions(service_provider_promotions::Cmd),
+    /// Show which geofence regions contain a location at a given time.
+    Geofence(geofence::Cmd),
+    /// List oracle boosting data sets or roll back to a previous one.
+    DataSets(data_sets::Cmd),
+    /// Report Wi-Fi hotspots whose heartbeat locations jump or drift.
+    LocationDrift(location_drift::Cmd),
 }
 
 impl Cmd {
@@ -46,8 +62,13 @@ impl Cmd {
         match self {
             Self::Server(cmd) => cmd.run(&settings).await,
             Self::RewardFromDb(cmd) => cmd.run(&settings).await,
+            Self::RewardDryRun(cmd) => cmd.run(&settings).await,
+            Self::RewardExplanation(cmd) => cmd.run(&settings).await,
             Self::VerifyDisktree(cmd) => cmd.run(&settings).await,
             Self::ServiceProviderPromotions(cmd) => cmd.run(&settings).await,
+            Self::Geofence(cmd) => cmd.run(&settings).await,
+            Self::DataSets(cmd) => cmd.run(&settings).await,
+            Self::LocationDrift(cmd) => cmd.run(&settings).await,
         }
     }
 }
@@ -25,8 +25,9 @@ use helium_proto::services::{
     },
 };
 use mobile_config::client::authorization_client::AuthorizationVerifier;
+use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, PgPool, Pool, Postgres, Row, Transaction};
-use std::{collections::HashSet, ops::Range};
+use std::{collections::HashMap, ops::Range};
 use task_manager::{ManagedTask, TaskManager};
 use tokio::sync::mpsc::Receiver;
 
@@ -353,36 +354,82 @@ pub async fn save(
 pub struct RadioThreshold {
     hotspot_pubkey: PublicKeyBinary,
     cbsd_id: Option<String>,
+    bytes_threshold: i64,
+    subscriber_threshold: i32,
+    threshold_timestamp: DateTime<Utc>,
+}
+
+/// The threshold a carrier reported a radio to have met
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportedThreshold {
+    pub bytes_threshold: u64,
+    pub subscriber_threshold: u32,
+    /// When the radio met the threshold
+    pub threshold_timestamp: DateTime<Utc>,
+}
+
+impl From<&RadioThreshold> for ReportedThreshold {
+    fn from(row: &RadioThreshold) -> Self {
+        Self {
+            bytes_threshold: row.bytes_threshold as u64,
+            subscriber_threshold: row.subscriber_threshold as u32,
+            threshold_timestamp: row.threshold_timestamp,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct VerifiedRadioThresholds {
-    gateways: HashSet<(PublicKeyBinary, Option<String>)>,
+    gateways: HashMap<(PublicKeyBinary, Option<String>), ReportedThreshold>,
+    /// Thresholds have to be met before this to be verified
+    met_before: Option<DateTime<Utc>>,
 }
 
 impl VerifiedRadioThresholds {
-    pub fn insert(&mut self, hotspot_key: PublicKeyBinary, cbsd_id: Option<String>) {
-        self.gateways.insert((hotspot_key, cbsd_id));
+    pub fn insert(
+        &mut self,
+        hotspot_key: PublicKeyBinary,
+        cbsd_id: Option<String>,
+        threshold: ReportedThreshold,
+    ) {
+        self.gateways.insert((hotspot_key, cbsd_id), threshold);
+    }
+
+    pub fn get(&self, key: PublicKeyBinary, cbsd_id: Option<String>) -> Option<&ReportedThreshold> {
+        self.gateways.get(&(key, cbsd_id))
     }
 
     pub fn is_verified(&self, key: PublicKeyBinary, cbsd_id: Option<String>) -> bool {
-        self.gateways.contains(&(key, cbsd_id))
+        self.get(key, cbsd_id).is_some()
+    }
+
+    pub fn met_before(&self) -> Option<DateTime<Utc>> {
+        self.met_before
     }
 }
 
+/// Thresholds met before the end of the reward period
 pub async fn verified_radio_thresholds(
     pool: &sqlx::Pool<Postgres>,
     reward_period: &Range<DateTime<Utc>>,
 ) -> Result<VerifiedRadioThresholds, sqlx::Error> {
     let mut rows = sqlx::query_as::<_, RadioThreshold>(
-        "SELECT hotspot_pubkey, cbsd_id
+        "SELECT hotspot_pubkey, cbsd_id, bytes_threshold, subscriber_threshold, threshold_timestamp
              FROM radio_threshold WHERE threshold_timestamp < $1",
     )
     .bind(reward_period.end)
     .fetch(pool);
-    let mut map = VerifiedRadioThresholds::default();
+    let mut map = VerifiedRadioThresholds {
+        met_before: Some(reward_period.end),
+        ..Default::default()
+    };
     while let Some(row) = rows.try_next().await? {
-        map.insert(row.hotspot_pubkey, row.cbsd_id.filter(|s| !s.is_empty()));
+        let threshold = ReportedThreshold::from(&row);
+        map.insert(
+            row.hotspot_pubkey,
+            row.cbsd_id.filter(|s| !s.is_empty()),
+            threshold,
+        );
     }
     Ok(map)
 }
@@ -2,20 +2,22 @@ use crate::{
     coverage::CoveredHexStream,
     data_session::HotspotMap,
     heartbeats::HeartbeatReward,
-    rewarder::boosted_hex_eligibility::BoostedHexEligibility,
+    rewarder::boosted_hex_eligibility::{BoostedHexEligibility, Eligibility, EligibilityEvidence},
     seniority::Seniority,
-    sp_boosted_rewards_bans::BannedRadios,
+    sp_boosted_rewards_bans::{BanEvidence, BannedRadios},
     speedtests_average::SpeedtestAverages,
     subscriber_location::SubscriberValidatedLocations,
     subscriber_verified_mapping_event::VerifiedSubscriberVerifiedMappingEventShares,
+    telemetry,
     unique_connections::{self, UniqueConnectionCounts},
     PriceInfo,
 };
 use chrono::{DateTime, Utc};
 use coverage_point_calculator::{
     BytesPs, LocationTrust, OracleBoostingStatus, RadioType, SPBoostedRewardEligibility, Speedtest,
-    SpeedtestTier,
+    SpeedtestTier, SpeedtestTierTable, SpeedtestTierThresholds,
 };
+use explanation::RadioRewardExplanation;
 use file_store::traits::TimestampEncode;
 use futures::{Stream, StreamExt};
 use helium_crypto::PublicKeyBinary;
@@ -26,9 +28,13 @@ use mobile_config::{boosted_hex_info::BoostedHexes, sub_dao_epoch_reward_info::E
 use radio_reward_v2::{RadioRewardV2Ext, ToProtoDecimal};
 use rust_decimal::prelude::*;
 use rust_decimal_macros::dec;
-use std::{collections::HashMap, ops::Range};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+};
 use uuid::Uuid;
 
+pub mod explanation;
 mod radio_reward_v2;
 
 /// Maximum amount of the total emissions pool allocated for data transfer
@@ -396,7 +402,10 @@ struct RadioInfo {
     seniority: Seniority,
     trust_scores: Vec<coverage_point_calculator::LocationTrust>,
     sp_boosted_reward_eligibility: SPBoostedRewardEligibility,
+    sp_boosted_reward_evidence: EligibilityEvidence,
+    poc_ban: Option<BanEvidence>,
     speedtests: Vec<coverage_point_calculator::Speedtest>,
+    speedtest_thresholds: SpeedtestTierThresholds,
     oracle_boosting_status: OracleBoostingStatus,
 }
 
@@ -417,9 +426,42 @@ impl CoverageShares {
         banned_radios: &BannedRadios,
         unique_connections: &UniqueConnectionCounts,
         reward_period: &Range<DateTime<Utc>>,
+        speedtest_tiers: &SpeedtestTierTable,
+    ) -> anyhow::Result<Self> {
+        Self::new_with_coverage_map(
+            &mut coverage_map::IncrementalCoverageMap::default(),
+            hex_streams,
+            heartbeats,
+            speedtest_averages,
+            boosted_hexes,
+            boosted_hex_eligibility,
+            banned_radios,
+            unique_connections,
+            reward_period,
+            speedtest_tiers,
+        )
+        .await
+    }
+
+    /// Updates `coverage_map` with the coverage of the radios rewarded in
+    /// `reward_period`, removing any radio that is no longer eligible, and
+    /// ranks the epoch from it.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new_with_coverage_map(
+        coverage_map: &mut coverage_map::IncrementalCoverageMap,
+        hex_streams: &impl CoveredHexStream,
+        heartbeats: impl Stream<Item = Result<HeartbeatReward, sqlx::Error>>,
+        speedtest_averages: &SpeedtestAverages,
+        boosted_hexes: &BoostedHexes,
+        boosted_hex_eligibility: &BoostedHexEligibility,
+        banned_radios: &BannedRadios,
+        unique_connections: &UniqueConnectionCounts,
+        reward_period: &Range<DateTime<Utc>>,
+        speedtest_tiers: &SpeedtestTierTable,
     ) -> anyhow::Result<Self> {
         let mut radio_infos: HashMap<RadioId, RadioInfo> = HashMap::new();
-        let mut coverage_map_builder = coverage_map::CoverageMapBuilder::default();
+        let mut mapped_radios = HashSet::new();
+        let mut unique_connections_flagged = 0;
 
         // The heartbearts query is written in a way that each radio is iterated a single time.
         let mut heartbeats = std::pin::pin!(heartbeats);
@@ -484,35 +526,49 @@ impl CoverageShares {
                 (false, Some(_)) => RadioType::OutdoorCbrs,
             };
 
+            if unique_connections.is_flagged(&pubkey, &radio_type) {
+                unique_connections_flagged += 1;
+                tracing::warn!(%pubkey, ?radio_type, "unique connections reported without data transfer");
+            }
+
+            let poc_ban = banned_radios.get(&pubkey, cbsd_id.as_deref()).cloned();
             let oracle_boosting_status =
                 if unique_connections::is_qualified(unique_connections, &pubkey, &radio_type) {
                     OracleBoostingStatus::Qualified
-                } else if banned_radios.contains(&pubkey, cbsd_id.as_deref()) {
+                } else if poc_ban.is_some() {
                     OracleBoostingStatus::Banned
                 } else {
                     OracleBoostingStatus::Eligible
                 };
 
-            let sp_boosted_reward_eligibility = boosted_hex_eligibility.eligibility(
+            let Eligibility {
+                status: sp_boosted_reward_eligibility,
+                evidence: sp_boosted_reward_evidence,
+            } = boosted_hex_eligibility.eligibility(
                 radio_type,
                 pubkey.clone(),
                 cbsd_id.clone(),
                 &covered_hexes,
             );
 
+            let speedtest_thresholds = *speedtest_tiers.thresholds(radio_type, reward_period.start);
+
             if eligible_for_coverage_map(
                 oracle_boosting_status,
                 &speedtests,
+                &speedtest_thresholds,
                 radio_type,
                 &trust_scores,
             ) {
-                coverage_map_builder.insert_coverage_object(coverage_map::CoverageObject {
+                let coverage_obj = coverage_map::CoverageObject {
                     indoor: is_indoor,
                     hotspot_key: pubkey.into(),
                     cbsd_id,
                     seniority_timestamp: seniority.seniority_ts,
                     coverage: covered_hexes,
-                });
+                };
+                mapped_radios.insert(coverage_obj.radio_key());
+                coverage_map.apply_coverage_object(coverage_obj);
             }
 
             radio_infos.insert(
@@ -523,13 +579,19 @@ impl CoverageShares {
                     seniority,
                     trust_scores,
                     sp_boosted_reward_eligibility,
+                    sp_boosted_reward_evidence,
+                    poc_ban,
                     speedtests,
+                    speedtest_thresholds,
                     oracle_boosting_status,
                 },
             );
         }
 
-        let coverage_map = coverage_map_builder.build(boosted_hexes, reward_period.start);
+        telemetry::unique_connections_flagged(unique_connections_flagged);
+
+        coverage_map.retain_radios(|radio| mapped_radios.contains(radio));
+        let coverage_map = coverage_map.snapshot(boosted_hexes, reward_period.start);
 
         Ok(Self {
             coverage_map,
@@ -537,6 +599,10 @@ impl CoverageShares {
         })
     }
 
+    pub fn coverage_map(&self) -> &coverage_map::CoverageMap {
+        &self.coverage_map
+    }
+
     fn coverage_points(
         &self,
         radio_id: &RadioId,
@@ -552,14 +618,16 @@ impl CoverageShares {
             ranked_coverage.to_vec()
         };
 
-        let coverage_points = coverage_point_calculator::CoveragePoints::new(
-            radio_info.radio_type,
-            radio_info.sp_boosted_reward_eligibility,
-            radio_info.speedtests.clone(),
-            radio_info.trust_scores.clone(),
-            hexes,
-            radio_info.oracle_boosting_status,
-        )?;
+        let coverage_points =
+            coverage_point_calculator::CoveragePoints::new_with_speedtest_thresholds(
+                radio_info.radio_type,
+                radio_info.sp_boosted_reward_eligibility,
+                radio_info.speedtests.clone(),
+                radio_info.trust_scores.clone(),
+                hexes,
+                radio_info.oracle_boosting_status,
+                &radio_info.speedtest_thresholds,
+            )?;
 
         Ok(coverage_points)
     }
@@ -571,12 +639,44 @@ impl CoverageShares {
     ) -> Option<(
         CalculatedPocRewardShares,
         impl Iterator<Item = (u64, proto::MobileRewardShare, proto::MobileRewardShare)> + '_,
+    )> {
+        let (rewards_per_share, rewards) =
+            self.into_rewards_with_explanations(reward_shares, reward_period)?;
+        Some((
+            rewards_per_share,
+            rewards.filter(|(poc_reward, ..)| *poc_reward > 0).map(
+                |(poc_reward, mobile_reward_v1, mobile_reward_v2, _explanation)| {
+                    (poc_reward, mobile_reward_v1, mobile_reward_v2)
+                },
+            ),
+        ))
+    }
+
+    /// Same as [`Self::into_rewards`] but also explains the reward of every
+    /// radio. Radios with a poc reward of 0 are included so their lack of
+    /// reward can be explained, their reward shares should not be written.
+    pub fn into_rewards_with_explanations(
+        self,
+        reward_shares: DataTransferAndPocAllocatedRewardBuckets,
+        reward_period: &Range<DateTime<Utc>>,
+    ) -> Option<(
+        CalculatedPocRewardShares,
+        impl Iterator<
+                Item = (
+                    u64,
+                    proto::MobileRewardShare,
+                    proto::MobileRewardShare,
+                    RadioRewardExplanation,
+                ),
+            > + '_,
     )> {
         struct ProcessedRadio {
             radio_id: RadioId,
             points: coverage_point_calculator::CoveragePoints,
             seniority: Seniority,
             coverage_obj_uuid: Uuid,
+            sp_boosted_reward_evidence: EligibilityEvidence,
+            poc_ban: Option<BanEvidence>,
         }
 
         let mut processed_radios = vec![];
@@ -598,6 +698,8 @@ impl CoverageShares {
                 points,
                 seniority: radio_info.seniority.clone(),
                 coverage_obj_uuid: radio_info.coverage_obj_uuid,
+                sp_boosted_reward_evidence: radio_info.sp_boosted_reward_evidence.clone(),
+                poc_ban: radio_info.poc_ban.clone(),
             });
         }
 
@@ -611,30 +713,36 @@ impl CoverageShares {
 
         Some((
             rewards_per_share,
-            processed_radios
-                .into_iter()
-                .map(move |radio| {
-                    let ProcessedRadio {
-                        radio_id,
-                        points,
-                        seniority,
-                        coverage_obj_uuid,
-                    } = radio;
-
-                    let poc_reward = rewards_per_share.poc_reward(&points);
-                    let (mobile_reward_v1, mobile_reward_v2) =
-                        coverage_point_to_mobile_reward_share(
-                            points,
-                            reward_period,
-                            &radio_id,
-                            poc_reward,
-                            rewards_per_share,
-                            seniority.seniority_ts,
-                            coverage_obj_uuid,
-                        );
-                    (poc_reward, mobile_reward_v1, mobile_reward_v2)
-                })
-                .filter(|(poc_reward, _mobile_reward_v1, _mobile_reward_v2)| *poc_reward > 0),
+            processed_radios.into_iter().map(move |radio| {
+                let ProcessedRadio {
+                    radio_id,
+                    points,
+                    seniority,
+                    coverage_obj_uuid,
+                    sp_boosted_reward_evidence,
+                    poc_ban,
+                } = radio;
+
+                let poc_reward = rewards_per_share.poc_reward(&points);
+                let explanation = RadioRewardExplanation::new(
+                    &points,
+                    reward_period,
+                    &radio_id,
+                    &rewards_per_share,
+                    sp_boosted_reward_evidence,
+                    poc_ban,
+                );
+                let (mobile_reward_v1, mobile_reward_v2) = coverage_point_to_mobile_reward_share(
+                    points,
+                    reward_period,
+                    &radio_id,
+                    poc_reward,
+                    rewards_per_share,
+                    seniority.seniority_ts,
+                    coverage_obj_uuid,
+                );
+                (poc_reward, mobile_reward_v1, mobile_reward_v2, explanation)
+            }),
         ))
     }
 
@@ -773,6 +881,7 @@ pub fn get_scheduled_tokens_for_oracles(total_emission_pool: Decimal) -> Decimal
 fn eligible_for_coverage_map(
     oracle_boosting_status: OracleBoostingStatus,
     speedtests: &[Speedtest],
+    speedtest_thresholds: &SpeedtestTierThresholds,
     radio_type: RadioType,
     trust_scores: &[LocationTrust],
 ) -> bool {
@@ -781,7 +890,7 @@ fn eligible_for_coverage_map(
     }
 
     let avg_speedtest = Speedtest::avg(speedtests);
-    if avg_speedtest.tier() == SpeedtestTier::Fail {
+    if avg_speedtest.tier_with(speedtest_thresholds) == SpeedtestTier::Fail {
         return false;
     }
 
@@ -804,6 +913,9 @@ mod test {
         data_session::{self, HotspotDataSession, HotspotReward},
         heartbeats::{HeartbeatReward, KeyType, OwnedKeyType},
         reward_shares,
+        rewarder::boosted_hex_eligibility::{
+            EligibilityRule, RadioThresholdEvidence, UniqueConnectionsEvidence,
+        },
         service_provider::{
             self, ServiceProviderDCSessions, ServiceProviderPromotions, ServiceProviderRewardInfos,
         },
@@ -830,12 +942,29 @@ mod test {
     const EMISSIONS_POOL_IN_BONES_24_HOURS: u64 = 82_191_780_821_917;
     const EMISSIONS_POOL_IN_BONES_1_HOUR: u64 = 3_424_657_534_247;
 
+    fn eligible_evidence() -> EligibilityEvidence {
+        EligibilityEvidence {
+            rule: EligibilityRule::UniqueConnections,
+            radio_threshold: RadioThresholdEvidence {
+                reported: None,
+                required_before: None,
+            },
+            unique_connections: UniqueConnectionsEvidence {
+                count: unique_connections::MINIMUM_UNIQUE_CONNECTIONS + 1,
+                required_more_than: unique_connections::MINIMUM_UNIQUE_CONNECTIONS,
+                radio_type_qualifies: true,
+                flagged: false,
+            },
+        }
+    }
+
     fn hex_assignments_mock() -> HexAssignments {
         HexAssignments {
             footfall: Assignment::A,
             urbanized: Assignment::A,
             landtype: Assignment::A,
             service_provider_override: Assignment::C,
+            assignment_multiplier: None,
         }
     }
 
@@ -845,6 +974,7 @@ mod test {
             urbanized: Assignment::C,
             landtype: Assignment::C,
             service_provider_override: Assignment::C,
+            assignment_multiplier: None,
         }
     }
 
@@ -1290,7 +1420,7 @@ mod test {
 
         let gw1_speedtests = vec![st1, st2];
 
-        let gw1_average = SpeedtestAverage::from(gw1_speedtests);
+        let gw1_average = SpeedtestAverage::new(gw1_speedtests, &SpeedtestTierThresholds::DEFAULT);
         let mut averages = HashMap::new();
         averages.insert(gw1.clone(), gw1_average);
 
@@ -1311,6 +1441,7 @@ mod test {
             &BannedRadios::default(),
             &UniqueConnectionCounts::default(),
             &rewards_info.epoch_period,
+            &SpeedtestTierTable::default(),
         )
         .await
         .unwrap()
@@ -1672,16 +1803,18 @@ mod test {
             acceptable_speedtest(gw11.clone(), timestamp),
         ];
 
-        let gw1_average = SpeedtestAverage::from(gw1_speedtests);
-        let gw2_average = SpeedtestAverage::from(gw2_speedtests);
-        let gw3_average = SpeedtestAverage::from(gw3_speedtests);
-        let gw4_average = SpeedtestAverage::from(gw4_speedtests);
-        let gw5_average = SpeedtestAverage::from(gw5_speedtests);
-        let gw6_average = SpeedtestAverage::from(gw6_speedtests);
-        let gw7_average = SpeedtestAverage::from(gw7_speedtests);
-        let gw9_average = SpeedtestAverage::from(gw9_speedtests);
-        let gw10_average = SpeedtestAverage::from(gw10_speedtests);
-        let gw11_average = SpeedtestAverage::from(gw11_speedtests);
+        let gw1_average = SpeedtestAverage::new(gw1_speedtests, &SpeedtestTierThresholds::DEFAULT);
+        let gw2_average = SpeedtestAverage::new(gw2_speedtests, &SpeedtestTierThresholds::DEFAULT);
+        let gw3_average = SpeedtestAverage::new(gw3_speedtests, &SpeedtestTierThresholds::DEFAULT);
+        let gw4_average = SpeedtestAverage::new(gw4_speedtests, &SpeedtestTierThresholds::DEFAULT);
+        let gw5_average = SpeedtestAverage::new(gw5_speedtests, &SpeedtestTierThresholds::DEFAULT);
+        let gw6_average = SpeedtestAverage::new(gw6_speedtests, &SpeedtestTierThresholds::DEFAULT);
+        let gw7_average = SpeedtestAverage::new(gw7_speedtests, &SpeedtestTierThresholds::DEFAULT);
+        let gw9_average = SpeedtestAverage::new(gw9_speedtests, &SpeedtestTierThresholds::DEFAULT);
+        let gw10_average =
+            SpeedtestAverage::new(gw10_speedtests, &SpeedtestTierThresholds::DEFAULT);
+        let gw11_average =
+            SpeedtestAverage::new(gw11_speedtests, &SpeedtestTierThresholds::DEFAULT);
         let mut averages = HashMap::new();
         averages.insert(gw1.clone(), gw1_average);
         averages.insert(gw2.clone(), gw2_average);
@@ -1716,6 +1849,7 @@ mod test {
             &BannedRadios::default(),
             &UniqueConnectionCounts::default(),
             &epoch,
+            &SpeedtestTierTable::default(),
         )
         .await
         .unwrap()
@@ -1865,8 +1999,8 @@ mod test {
             acceptable_speedtest(gw2.clone(), timestamp),
         ];
 
-        let gw1_average = SpeedtestAverage::from(gw1_speedtests);
-        let gw2_average = SpeedtestAverage::from(gw2_speedtests);
+        let gw1_average = SpeedtestAverage::new(gw1_speedtests, &SpeedtestTierThresholds::DEFAULT);
+        let gw2_average = SpeedtestAverage::new(gw2_speedtests, &SpeedtestTierThresholds::DEFAULT);
         let mut averages = HashMap::new();
         averages.insert(gw1.clone(), gw1_average);
         averages.insert(gw2.clone(), gw2_average);
@@ -1899,6 +2033,7 @@ mod test {
             &BannedRadios::default(),
             &UniqueConnectionCounts::default(),
             &rewards_info.epoch_period,
+            &SpeedtestTierTable::default(),
         )
         .await
         .unwrap()
@@ -2001,8 +2136,8 @@ mod test {
             acceptable_speedtest(gw2.clone(), timestamp),
         ];
 
-        let gw1_average = SpeedtestAverage::from(gw1_speedtests);
-        let gw2_average = SpeedtestAverage::from(gw2_speedtests);
+        let gw1_average = SpeedtestAverage::new(gw1_speedtests, &SpeedtestTierThresholds::DEFAULT);
+        let gw2_average = SpeedtestAverage::new(gw2_speedtests, &SpeedtestTierThresholds::DEFAULT);
         let mut averages = HashMap::new();
         averages.insert(gw1.clone(), gw1_average);
         averages.insert(gw2.clone(), gw2_average);
@@ -2035,6 +2170,7 @@ mod test {
             &BannedRadios::default(),
             &UniqueConnectionCounts::default(),
             &rewards_info.epoch_period,
+            &SpeedtestTierTable::default(),
         )
         .await
         .unwrap()
@@ -2139,8 +2275,8 @@ mod test {
             acceptable_speedtest(gw2.clone(), timestamp),
         ];
 
-        let gw1_average = SpeedtestAverage::from(gw1_speedtests);
-        let gw2_average = SpeedtestAverage::from(gw2_speedtests);
+        let gw1_average = SpeedtestAverage::new(gw1_speedtests, &SpeedtestTierThresholds::DEFAULT);
+        let gw2_average = SpeedtestAverage::new(gw2_speedtests, &SpeedtestTierThresholds::DEFAULT);
         let mut averages = HashMap::new();
         averages.insert(gw1.clone(), gw1_average);
         averages.insert(gw2.clone(), gw2_average);
@@ -2172,6 +2308,7 @@ mod test {
             &BannedRadios::default(),
             &UniqueConnectionCounts::default(),
             &rewards_info.epoch_period,
+            &SpeedtestTierTable::default(),
         )
         .await
         .unwrap()
@@ -2269,8 +2406,8 @@ mod test {
             acceptable_speedtest(gw2.clone(), timestamp),
         ];
 
-        let gw1_average = SpeedtestAverage::from(gw1_speedtests);
-        let gw2_average = SpeedtestAverage::from(gw2_speedtests);
+        let gw1_average = SpeedtestAverage::new(gw1_speedtests, &SpeedtestTierThresholds::DEFAULT);
+        let gw2_average = SpeedtestAverage::new(gw2_speedtests, &SpeedtestTierThresholds::DEFAULT);
         let mut averages = HashMap::new();
         averages.insert(gw1.clone(), gw1_average);
         averages.insert(gw2.clone(), gw2_average);
@@ -2304,6 +2441,7 @@ mod test {
             &BannedRadios::default(),
             &unique_connection_counts,
             &epoch,
+            &SpeedtestTierTable::default(),
         )
         .await
         .unwrap()
@@ -2400,6 +2538,8 @@ mod test {
                     update_reason: 0,
                 },
                 sp_boosted_reward_eligibility: SPBoostedRewardEligibility::Eligible,
+                sp_boosted_reward_evidence: eligible_evidence(),
+                poc_ban: None,
                 speedtests: vec![
                     coverage_point_calculator::Speedtest {
                         upload_speed: coverage_point_calculator::BytesPs::new(100_000_000),
@@ -2434,6 +2574,8 @@ mod test {
                     update_reason: 0,
                 },
                 sp_boosted_reward_eligibility: SPBoostedRewardEligibility::Eligible,
+                sp_boosted_reward_evidence: eligible_evidence(),
+                poc_ban: None,
                 speedtests: vec![],
                 oracle_boosting_status: OracleBoostingStatus::Eligible,
             },
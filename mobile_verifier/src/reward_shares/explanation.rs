@@ -0,0 +1,598 @@
+//! Per radio explanation of the proof of coverage reward.
+//!
+//! The `RadioReward` protos only carry a subset of the values that go into a
+//! radio's reward. A [`RadioRewardExplanation`] records every multiplier from
+//! [`coverage_point_calculator::CoveragePoints`] along with the HIP rule that
+//! produced it so that a radio's reward can be explained after the fact
+//! without access to the database.
+//!
+//! Explanations are written as json records to their own file_store prefix,
+//! [`FileType::RadioRewardExplanation`]. The record layout is versioned by
+//! [`EXPLANATION_VERSION`] which must be bumped on any breaking change.
+//!
+//! The service provider boosting part of every explanation, a
+//! [`SpBoostEligibility`], is also saved to the database for every epoch
+//! whether or not the explanation files are written.
+
+use super::{CalculatedPocRewardShares, RadioId};
+use crate::{
+    rewarder::boosted_hex_eligibility::EligibilityEvidence, sp_boosted_rewards_bans::BanEvidence,
+};
+use chrono::{DateTime, Utc};
+use coverage_point_calculator::{
+    location, CoveragePoints, CoveredHex, HexPoints, OracleBoostingStatus, SpBoostedHexStatus,
+    MAX_AVERAGE_DISTANCE, MIN_WIFI_TRUST_MULTIPLIER,
+};
+use file_store::{
+    file_info::FileType,
+    traits::{FileSinkWriteExt, MsgBytes},
+};
+use helium_crypto::PublicKeyBinary;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+pub const EXPLANATION_VERSION: u32 = 1;
+
+const HIP_74: &str = "HIP-74";
+const HIP_84: &str = "HIP-84";
+const HIP_93: &str = "HIP-93";
+const HIP_98: &str = "HIP-98";
+const HIP_103: &str = "HIP-103";
+const HIP_105: &str = "HIP-105";
+const HIP_119: &str = "HIP-119";
+const HIP_131: &str = "HIP-131";
+const HIP_134: &str = "HIP-134";
+const HIP_140: &str = "HIP-140";
+
+/// The explanation of a single radio's poc reward for an epoch.
+///
+/// Unlike the other mobile_verifier outputs this is not a helium-proto
+/// message. The explanation is an operator debugging aid read back by the
+/// `reward-explanation` cli rather than a record consumed by other oracles,
+/// and it changes whenever a reward rule does; keeping it as serde json lets
+/// it evolve with [`EXPLANATION_VERSION`] instead of a proto release. It is
+/// written as an [`EncodedRadioRewardExplanation`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RadioRewardExplanation {
+    pub version: u32,
+    pub start_period: DateTime<Utc>,
+    pub end_period: DateTime<Utc>,
+    pub hotspot_key: PublicKeyBinary,
+    pub cbsd_id: Option<String>,
+    pub radio_type: String,
+    /// Total poc reward in bones, `base_poc_reward + boosted_poc_reward`
+    pub poc_reward: u64,
+    pub base_poc_reward: u64,
+    pub boosted_poc_reward: u64,
+    pub base_reward_shares: Decimal,
+    pub boosted_reward_shares: Decimal,
+    pub coverage_points: PointsExplanation,
+    pub location_trust: MultiplierExplanation,
+    pub location_trust_scores: Vec<LocationTrustExplanation>,
+    pub speedtest: MultiplierExplanation,
+    pub speedtest_average: SpeedtestExplanation,
+    pub speedtest_count: usize,
+    pub sp_boosted_hex_status: StatusExplanation,
+    /// Not recorded by earlier explanations
+    #[serde(default)]
+    pub sp_boosted_hex_evidence: Option<SpBoostedHexEvidence>,
+    pub oracle_boosted_hex_status: StatusExplanation,
+    /// Not recorded by earlier explanations
+    #[serde(default)]
+    pub oracle_boosted_hex_evidence: Option<OracleBoostedHexEvidence>,
+    pub covered_hexes: Vec<CoveredHexExplanation>,
+}
+
+/// A multiplier applied to a radio or hex, the HIP which defines it and a
+/// human readable reason for its value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MultiplierExplanation {
+    pub value: Decimal,
+    pub rule: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusExplanation {
+    pub status: String,
+    pub rule: String,
+    pub reason: String,
+}
+
+/// What the service provider boosting status of a radio was decided on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpBoostedHexEvidence {
+    #[serde(flatten)]
+    pub eligibility: EligibilityEvidence,
+    pub location_trust: LocationTrustEvidence,
+}
+
+/// What the oracle boosting status of a radio was decided on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OracleBoostedHexEvidence {
+    /// The PoC ban report the radio was banned by in the reward period, a
+    /// radio that does not qualify by unique connections is then `Banned`
+    pub poc_ban: Option<BanEvidence>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocationTrustEvidence {
+    pub multiplier: Decimal,
+    /// Wifi radios need a multiplier of at least this
+    pub required_multiplier: Decimal,
+    /// Unset for wifi radios without trust scores
+    pub average_distance: Option<Decimal>,
+    pub max_average_distance: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PointsExplanation {
+    pub modeled: Decimal,
+    pub base: Decimal,
+    pub boosted: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocationTrustExplanation {
+    pub meters_to_asserted: u32,
+    pub trust_score: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeedtestExplanation {
+    pub upload_speed_bps: u64,
+    pub download_speed_bps: u64,
+    pub latency_millis: u32,
+    pub tier: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoveredHexExplanation {
+    /// h3 cell index in hex
+    pub hex: String,
+    pub points: PointsExplanation,
+    pub rank: MultiplierExplanation,
+    pub assignment: MultiplierExplanation,
+    pub boosted: Option<MultiplierExplanation>,
+}
+
+impl RadioRewardExplanation {
+    pub fn new(
+        points: &CoveragePoints,
+        reward_period: &Range<DateTime<Utc>>,
+        radio_id: &RadioId,
+        rewards_per_share: &CalculatedPocRewardShares,
+        sp_boosted_reward_evidence: EligibilityEvidence,
+        poc_ban: Option<BanEvidence>,
+    ) -> Self {
+        let (hotspot_key, cbsd_id) = radio_id.clone();
+        let base_poc_reward = rewards_per_share.base_poc_reward(points);
+        let boosted_poc_reward = rewards_per_share.boosted_poc_reward(points);
+
+        Self {
+            version: EXPLANATION_VERSION,
+            start_period: reward_period.start,
+            end_period: reward_period.end,
+            hotspot_key,
+            cbsd_id,
+            radio_type: format!("{:?}", points.radio_type),
+            poc_reward: base_poc_reward + boosted_poc_reward,
+            base_poc_reward,
+            boosted_poc_reward,
+            base_reward_shares: points.total_base_shares(),
+            boosted_reward_shares: points.total_boosted_shares(),
+            coverage_points: PointsExplanation::from(&points.coverage_points),
+            location_trust: explain_location_trust(points),
+            location_trust_scores: points
+                .location_trust_scores
+                .iter()
+                .map(|lt| LocationTrustExplanation {
+                    meters_to_asserted: lt.meters_to_asserted,
+                    trust_score: lt.trust_score,
+                })
+                .collect(),
+            speedtest: explain_speedtest(points),
+            speedtest_average: SpeedtestExplanation {
+                upload_speed_bps: points.speedtest_avg.upload_speed.as_bps(),
+                download_speed_bps: points.speedtest_avg.download_speed.as_bps(),
+                latency_millis: points.speedtest_avg.latency_millis,
+                tier: format!("{:?}", points.speedtest_tier),
+            },
+            speedtest_count: points.speedtests.len(),
+            sp_boosted_hex_status: explain_sp_boosted_hex_status(points.sp_boosted_hex_eligibility),
+            sp_boosted_hex_evidence: Some(SpBoostedHexEvidence {
+                eligibility: sp_boosted_reward_evidence,
+                location_trust: location_trust_evidence(points),
+            }),
+            oracle_boosted_hex_status: explain_oracle_boosted_hex_status(
+                points.oracle_boosted_hex_eligibility,
+            ),
+            oracle_boosted_hex_evidence: Some(OracleBoostedHexEvidence { poc_ban }),
+            covered_hexes: points
+                .covered_hexes
+                .iter()
+                .map(|hex| explain_covered_hex(points, hex))
+                .collect(),
+        }
+    }
+}
+
+impl From<&HexPoints> for PointsExplanation {
+    fn from(points: &HexPoints) -> Self {
+        Self {
+            modeled: points.modeled,
+            base: points.base,
+            boosted: points.boosted,
+        }
+    }
+}
+
+fn explain_location_trust(points: &CoveragePoints) -> MultiplierExplanation {
+    let reason = if points.radio_type.is_cbrs() {
+        "cbrs radio locations are always trusted".to_string()
+    } else if points.location_trust_scores.is_empty() {
+        "no location trust scores".to_string()
+    } else {
+        let count = points.location_trust_scores.len();
+        let average_distance = points
+            .location_trust_scores
+            .iter()
+            .map(|lt| Decimal::from(lt.meters_to_asserted))
+            .sum::<Decimal>()
+            / Decimal::from(count);
+        format!(
+            "average of {count} trust scores, average distance to asserted {}m",
+            average_distance.round_dp(2)
+        )
+    };
+    MultiplierExplanation {
+        value: points.location_trust_multiplier,
+        rule: format!("{HIP_98}, {HIP_119}"),
+        reason,
+    }
+}
+
+fn location_trust_evidence(points: &CoveragePoints) -> LocationTrustEvidence {
+    let average_distance = (points.radio_type.is_cbrs()
+        || !points.location_trust_scores.is_empty())
+    .then(|| location::average_distance(points.radio_type, &points.location_trust_scores));
+    LocationTrustEvidence {
+        multiplier: points.location_trust_multiplier,
+        required_multiplier: MIN_WIFI_TRUST_MULTIPLIER,
+        average_distance,
+        max_average_distance: MAX_AVERAGE_DISTANCE,
+    }
+}
+
+fn explain_speedtest(points: &CoveragePoints) -> MultiplierExplanation {
+    let avg = &points.speedtest_avg;
+    MultiplierExplanation {
+        value: points.speedtest_multiplier,
+        rule: format!("{HIP_74}, {HIP_98}"),
+        reason: format!(
+            "{:?} tier from the average of {} speedtests: {} bps down, {} bps up, {}ms latency",
+            points.speedtest_tier,
+            points.speedtests.len(),
+            avg.download_speed.as_bps(),
+            avg.upload_speed.as_bps(),
+            avg.latency_millis
+        ),
+    }
+}
+
+fn explain_sp_boosted_hex_status(status: SpBoostedHexStatus) -> StatusExplanation {
+    let (rule, reason) = match status {
+        SpBoostedHexStatus::Eligible => (HIP_84, "eligible for service provider boosting".into()),
+        SpBoostedHexStatus::WifiLocationScoreBelowThreshold(multiplier) => (
+            HIP_93,
+            format!(
+                "wifi location trust multiplier {multiplier} is below {MIN_WIFI_TRUST_MULTIPLIER}"
+            ),
+        ),
+        SpBoostedHexStatus::AverageAssertedDistanceOverLimit(distance) => (
+            HIP_119,
+            format!(
+                "average distance to asserted {}m is over {MAX_AVERAGE_DISTANCE}m",
+                distance.round_dp(2)
+            ),
+        ),
+        SpBoostedHexStatus::RadioThresholdNotMet => (
+            HIP_84,
+            "radio has not met the minimum data and subscriber threshold".into(),
+        ),
+        SpBoostedHexStatus::NotEnoughConnections => (
+            HIP_140,
+            "radio has not served enough unique connections".into(),
+        ),
+    };
+    StatusExplanation {
+        status: status_name(&status),
+        rule: rule.to_string(),
+        reason,
+    }
+}
+
+fn explain_oracle_boosted_hex_status(status: OracleBoostingStatus) -> StatusExplanation {
+    let (rule, reason) = match status {
+        OracleBoostingStatus::Eligible => (HIP_103, "hex assignment multipliers apply"),
+        OracleBoostingStatus::Banned => (HIP_131, "radio is banned, assignment multipliers are 0"),
+        OracleBoostingStatus::Qualified => (
+            HIP_134,
+            "radio serves enough unique connections, assignment multipliers are 1",
+        ),
+    };
+    StatusExplanation {
+        status: status_name(&status),
+        rule: rule.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+fn explain_covered_hex(points: &CoveragePoints, hex: &CoveredHex) -> CoveredHexExplanation {
+    let assignments = &hex.assignments;
+    let assignment_reason = match points.oracle_boosted_hex_eligibility {
+        OracleBoostingStatus::Eligible => format!(
+            "footfall {}, landtype {}, urbanized {}, service provider override {}",
+            assignments.footfall,
+            assignments.landtype,
+            assignments.urbanized,
+            assignments.service_provider_override
+        ),
+        status => format!("oracle boosting {}", status_name(&status).to_lowercase()),
+    };
+    let assignment_rule = match points.oracle_boosted_hex_eligibility {
+        OracleBoostingStatus::Eligible => HIP_103,
+        OracleBoostingStatus::Banned => HIP_131,
+        OracleBoostingStatus::Qualified => HIP_134,
+    };
+
+    CoveredHexExplanation {
+        hex: format!("{:x}", hex.hex.into_raw()),
+        points: PointsExplanation::from(&hex.points),
+        rank: MultiplierExplanation {
+            value: hex.rank_multiplier,
+            rule: HIP_105.to_string(),
+            reason: format!("rank {} for {:?}", hex.rank, points.radio_type),
+        },
+        assignment: MultiplierExplanation {
+            value: hex.assignment_multiplier,
+            rule: assignment_rule.to_string(),
+            reason: assignment_reason,
+        },
+        boosted: hex
+            .boosted_multiplier
+            .filter(|boost| *boost > dec!(1))
+            .map(|boost| MultiplierExplanation {
+                value: boost,
+                rule: HIP_84.to_string(),
+                reason: "hex boosted by a service provider".to_string(),
+            }),
+    }
+}
+
+fn status_name(status: &impl std::fmt::Debug) -> String {
+    // drop any values carried by the variant, they are part of the reason
+    let name = format!("{status:?}");
+    match name.split_once('(') {
+        Some((name, _)) => name.to_string(),
+        None => name,
+    }
+}
+
+/// A [`RadioRewardExplanation`] encoded as json. Encoding happens before the
+/// explanation is handed to the file sink, so a failure is returned to the
+/// rewarder rather than raised from [`MsgBytes::as_bytes`].
+#[derive(Debug, Clone)]
+pub struct EncodedRadioRewardExplanation(bytes::Bytes);
+
+impl TryFrom<&RadioRewardExplanation> for EncodedRadioRewardExplanation {
+    type Error = serde_json::Error;
+
+    fn try_from(explanation: &RadioRewardExplanation) -> Result<Self, Self::Error> {
+        Ok(Self(serde_json::to_vec(explanation)?.into()))
+    }
+}
+
+impl MsgBytes for EncodedRadioRewardExplanation {
+    fn as_bytes(&self) -> bytes::Bytes {
+        self.0.clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl FileSinkWriteExt for EncodedRadioRewardExplanation {
+    const FILE_PREFIX: &'static str = FileType::RadioRewardExplanation.to_str();
+    const METRIC_SUFFIX: &'static str = "radio_reward_explanation";
+}
+
+/// The service provider boosting status of a radio for an epoch and the
+/// evidence it was decided on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpBoostEligibility {
+    pub hotspot_key: PublicKeyBinary,
+    pub cbsd_id: Option<String>,
+    pub status: StatusExplanation,
+    pub evidence: Option<SpBoostedHexEvidence>,
+}
+
+impl From<&RadioRewardExplanation> for SpBoostEligibility {
+    fn from(explanation: &RadioRewardExplanation) -> Self {
+        Self {
+            hotspot_key: explanation.hotspot_key.clone(),
+            cbsd_id: explanation.cbsd_id.clone(),
+            status: explanation.sp_boosted_hex_status.clone(),
+            evidence: explanation.sp_boosted_hex_evidence.clone(),
+        }
+    }
+}
+
+pub mod db {
+    use super::*;
+    use futures::TryStreamExt;
+    use sqlx::{PgPool, Postgres, QueryBuilder, Row, Transaction};
+
+    /// Replaces the eligibility saved for the epoch starting at
+    /// `period_start`, the epoch may be rewarded again after a failure
+    pub async fn save(
+        txn: &mut Transaction<'_, Postgres>,
+        period_start: DateTime<Utc>,
+        eligibilities: &[SpBoostEligibility],
+    ) -> anyhow::Result<()> {
+        const BATCH_SIZE: usize = (u16::MAX / 5) as usize;
+
+        sqlx::query("DELETE FROM sp_boost_eligibility WHERE period_start = $1")
+            .bind(period_start)
+            .execute(&mut *txn)
+            .await?;
+
+        let rows = eligibilities
+            .iter()
+            .map(|eligibility| {
+                Ok((
+                    eligibility,
+                    serde_json::to_string(&eligibility.status)?,
+                    eligibility
+                        .evidence
+                        .as_ref()
+                        .map(serde_json::to_string)
+                        .transpose()?,
+                ))
+            })
+            .collect::<Result<Vec<_>, serde_json::Error>>()?;
+
+        for chunk in rows.chunks(BATCH_SIZE) {
+            QueryBuilder::new(
+                r#"
+                INSERT INTO sp_boost_eligibility
+                (period_start, hotspot_key, cbsd_id, status, evidence)
+                "#,
+            )
+            .push_values(chunk, |mut b, (eligibility, status, evidence)| {
+                b.push_bind(period_start)
+                    .push_bind(eligibility.hotspot_key.to_string())
+                    .push_bind(eligibility.cbsd_id.clone())
+                    .push_bind(status.clone())
+                    .push_bind(evidence.clone());
+            })
+            .build()
+            .execute(&mut *txn)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn get(
+        pool: &PgPool,
+        period_start: DateTime<Utc>,
+        hotspot_key: &PublicKeyBinary,
+        cbsd_id: Option<&str>,
+    ) -> anyhow::Result<Vec<SpBoostEligibility>> {
+        sqlx::query(
+            r#"
+                SELECT hotspot_key, cbsd_id, status, evidence
+                FROM sp_boost_eligibility
+                WHERE period_start = $1
+                    AND hotspot_key = $2
+                    AND ($3::text IS NULL OR cbsd_id = $3)
+                ORDER BY cbsd_id
+            "#,
+        )
+        .bind(period_start)
+        .bind(hotspot_key.to_string())
+        .bind(cbsd_id)
+        .fetch(pool)
+        .map_err(anyhow::Error::from)
+        .and_then(|row| async move {
+            let evidence: Option<String> = row.get("evidence");
+            Ok(SpBoostEligibility {
+                hotspot_key: row.get::<String, _>("hotspot_key").parse()?,
+                cbsd_id: row.get("cbsd_id"),
+                status: serde_json::from_str(row.get("status"))?,
+                evidence: evidence.as_deref().map(serde_json::from_str).transpose()?,
+            })
+        })
+        .try_collect()
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rewarder::boosted_hex_eligibility::{BoostedHexEligibility, EligibilityRule};
+    use chrono::Duration;
+    use coverage_point_calculator::{
+        LocationTrust, RadioType, SPBoostedRewardEligibility, Speedtest, SpeedtestTier,
+    };
+
+    #[test]
+    fn explain_radio_reward() {
+        let points = CoveragePoints::new(
+            RadioType::IndoorWifi,
+            SPBoostedRewardEligibility::NotEnoughConnections,
+            Speedtest::mock(SpeedtestTier::Acceptable),
+            vec![LocationTrust {
+                meters_to_asserted: 10,
+                trust_score: dec!(1),
+            }],
+            vec![],
+            OracleBoostingStatus::Qualified,
+        )
+        .unwrap();
+        let now = Utc::now();
+        let radio_id: RadioId = (PublicKeyBinary::from(vec![1]), None);
+        let eligibility = BoostedHexEligibility::default().eligibility(
+            RadioType::IndoorWifi,
+            radio_id.0.clone(),
+            None,
+            &[],
+        );
+        let ban = BanEvidence {
+            received_timestamp: now - Duration::days(2),
+            until: now + Duration::days(5),
+        };
+
+        let explanation = RadioRewardExplanation::new(
+            &points,
+            &(now - Duration::days(1)..now),
+            &radio_id,
+            &CalculatedPocRewardShares::default(),
+            eligibility.evidence,
+            Some(ban.clone()),
+        );
+
+        assert_eq!(EXPLANATION_VERSION, explanation.version);
+        assert_eq!(dec!(0.75), explanation.speedtest.value);
+        assert_eq!(
+            "NotEnoughConnections",
+            explanation.sp_boosted_hex_status.status
+        );
+        assert_eq!(HIP_140, explanation.sp_boosted_hex_status.rule);
+        assert_eq!(HIP_134, explanation.oracle_boosted_hex_status.rule);
+
+        let evidence = explanation.sp_boosted_hex_evidence.clone().unwrap();
+        assert_eq!(EligibilityRule::RadioThreshold, evidence.eligibility.rule);
+        assert_eq!(None, evidence.eligibility.radio_threshold.reported);
+        assert_eq!(Some(dec!(10)), evidence.location_trust.average_distance);
+        assert_eq!(dec!(0.75), evidence.location_trust.required_multiplier);
+        assert_eq!(
+            Some(ban),
+            explanation
+                .oracle_boosted_hex_evidence
+                .clone()
+                .unwrap()
+                .poc_ban
+        );
+
+        let decoded: RadioRewardExplanation = serde_json::from_slice(
+            &EncodedRadioRewardExplanation::try_from(&explanation)
+                .unwrap()
+                .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(explanation, decoded);
+    }
+}
@@ -1,20 +1,31 @@
 use crate::{
     boosting_oracles::db::check_for_unprocessed_data_sets,
-    coverage, data_session,
+    coverage,
+    coverage_map_export::CoverageMapExporter,
+    data_session,
+    geofence::Geofences,
     heartbeats::{self, HeartbeatReward},
     radio_threshold, resolve_subdao_pubkey,
     reward_shares::{
-        self, CalculatedPocRewardShares, CoverageShares, DataTransferAndPocAllocatedRewardBuckets,
+        self,
+        explanation::{EncodedRadioRewardExplanation, SpBoostEligibility},
+        CalculatedPocRewardShares, CoverageShares, DataTransferAndPocAllocatedRewardBuckets,
         MapperShares, TransferRewards,
     },
-    service_provider::{self, ServiceProviderDCSessions, ServiceProviderPromotions},
+    service_provider::{
+        self, ServiceProviderDCSessions, ServiceProviderEpochStatement, ServiceProviderPromotions,
+        ServiceProviderStatementWriter,
+    },
     sp_boosted_rewards_bans, speedtests,
     speedtests_average::SpeedtestAverages,
-    subscriber_location, subscriber_verified_mapping_event, telemetry, unique_connections,
+    subscriber_location, subscriber_verified_mapping_event, telemetry,
+    unique_connections::{self, UniqueConnectionCounts, UniqueConnectionsTable},
     PriceInfo, Settings,
 };
 use anyhow::bail;
 use chrono::{DateTime, TimeZone, Utc};
+use coverage_map::IncrementalCoverageMap;
+use coverage_point_calculator::SpeedtestTierTable;
 use db_store::meta;
 use file_store::{
     file_sink::FileSinkClient,
@@ -55,6 +66,7 @@ use tokio::time::sleep;
 
 pub mod boosted_hex_eligibility;
 mod db;
+pub mod dry_run;
 
 const REWARDS_NOT_CURRENT_DELAY_PERIOD: i64 = 5;
 
@@ -70,6 +82,13 @@ pub struct Rewarder<A, B, C> {
     reward_manifests: FileSinkClient<RewardManifest>,
     price_tracker: PriceTracker,
     speedtest_averages: FileSinkClient<proto::SpeedtestAvg>,
+    reward_explanations: Option<FileSinkClient<EncodedRadioRewardExplanation>>,
+    speedtest_tiers: SpeedtestTierTable,
+    unique_connection_rules: UniqueConnectionsTable,
+    geofences: Geofences,
+    coverage_map_export: Option<CoverageMapExporter>,
+    service_provider_statements: Option<ServiceProviderStatementWriter>,
+    coverage_map: IncrementalCoverageMap,
 }
 
 impl<A, B, C> Rewarder<A, B, C>
@@ -100,13 +119,28 @@ where
 
         let (reward_manifests, reward_manifests_server) = RewardManifest::file_sink(
             settings.store_base_path(),
-            file_upload,
+            file_upload.clone(),
             FileSinkCommitStrategy::Manual,
             FileSinkRollTime::Default,
             env!("CARGO_PKG_NAME"),
         )
         .await?;
 
+        let (reward_explanations, reward_explanations_server) = if settings.reward_explanations {
+            let (reward_explanations, reward_explanations_server) =
+                EncodedRadioRewardExplanation::file_sink(
+                    settings.store_base_path(),
+                    file_upload,
+                    FileSinkCommitStrategy::Manual,
+                    FileSinkRollTime::Default,
+                    env!("CARGO_PKG_NAME"),
+                )
+                .await?;
+            (Some(reward_explanations), Some(reward_explanations_server))
+        } else {
+            (None, None)
+        };
+
         let rewarder = Rewarder::new(
             pool.clone(),
             carrier_service_verifier,
@@ -118,14 +152,22 @@ where
             reward_manifests,
             price_tracker,
             speedtests_avg,
+            reward_explanations,
+            settings.speedtest_tiers.clone(),
+            settings.unique_connections.clone(),
+            Geofences::from_settings(&settings.geofences)?,
+            CoverageMapExporter::from_settings(settings).await?,
+            ServiceProviderStatementWriter::from_settings(settings).await?,
         )?;
 
-        Ok(TaskManager::builder()
+        let mut task_manager = TaskManager::builder()
             .add_task(price_daemon)
             .add_task(mobile_rewards_server)
-            .add_task(reward_manifests_server)
-            .add_task(rewarder)
-            .build())
+            .add_task(reward_manifests_server);
+        if let Some(reward_explanations_server) = reward_explanations_server {
+            task_manager = task_manager.add_task(reward_explanations_server);
+        }
+        Ok(task_manager.add_task(rewarder).build())
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -140,6 +182,12 @@ where
         reward_manifests: FileSinkClient<RewardManifest>,
         price_tracker: PriceTracker,
         speedtest_averages: FileSinkClient<proto::SpeedtestAvg>,
+        reward_explanations: Option<FileSinkClient<EncodedRadioRewardExplanation>>,
+        speedtest_tiers: SpeedtestTierTable,
+        unique_connection_rules: UniqueConnectionsTable,
+        geofences: Geofences,
+        coverage_map_export: Option<CoverageMapExporter>,
+        service_provider_statements: Option<ServiceProviderStatementWriter>,
     ) -> anyhow::Result<Self> {
         // get the subdao address
         let sub_dao = resolve_subdao_pubkey();
@@ -157,10 +205,17 @@ where
             reward_manifests,
             price_tracker,
             speedtest_averages,
+            reward_explanations,
+            speedtest_tiers,
+            unique_connection_rules,
+            geofences,
+            coverage_map_export,
+            service_provider_statements,
+            coverage_map: IncrementalCoverageMap::default(),
         })
     }
 
-    pub async fn run(self, shutdown: triggered::Listener) -> anyhow::Result<()> {
+    pub async fn run(mut self, shutdown: triggered::Listener) -> anyhow::Result<()> {
         tracing::info!("Starting rewarder");
 
         loop {
@@ -251,7 +306,7 @@ where
         Ok(true)
     }
 
-    pub async fn reward(&self, next_reward_epoch: u64) -> anyhow::Result<()> {
+    pub async fn reward(&mut self, next_reward_epoch: u64) -> anyhow::Result<()> {
         tracing::info!(
             "Resolving reward info for epoch: {}, subdao: {}",
             next_reward_epoch,
@@ -291,6 +346,12 @@ where
             &self.speedtest_averages,
             &reward_info,
             price_info.clone(),
+            &self.speedtest_tiers,
+            &self.unique_connection_rules,
+            &self.geofences,
+            &mut self.coverage_map,
+            self.reward_explanations.as_ref(),
+            self.coverage_map_export.as_ref(),
         )
         .await?;
 
@@ -307,7 +368,7 @@ where
         let sp_promotions =
             service_provider::get_promotions(&self.carrier_client, &reward_info.epoch_period.start)
                 .await?;
-        reward_service_providers(
+        let sp_statement = reward_service_providers(
             dc_sessions,
             sp_promotions.clone(),
             &self.mobile_rewards,
@@ -315,11 +376,21 @@ where
             price_info.price_per_bone,
         )
         .await?;
+        if let Some(service_provider_statements) = &self.service_provider_statements {
+            // The statement is only a report, it must not hold up the rewards
+            if let Err(err) = service_provider_statements.write(&sp_statement).await {
+                tracing::error!(?err, "failed to write service provider statement");
+                telemetry::service_provider_statement_error();
+            }
+        }
 
         // process rewards for oracles
         reward_oracles(&self.mobile_rewards, &reward_info).await?;
 
         self.speedtest_averages.commit().await?;
+        if let Some(reward_explanations) = &self.reward_explanations {
+            reward_explanations.commit().await?;
+        }
         let written_files = self.mobile_rewards.commit().await?.await??;
 
         let mut transaction = self.pool.begin().await?;
@@ -336,7 +407,13 @@ where
             .await?;
         subscriber_verified_mapping_event::clear(&mut transaction, &reward_info.epoch_period.start)
             .await?;
-        unique_connections::db::clear(&mut transaction, &reward_info.epoch_period.start).await?;
+        unique_connections::db::clear(
+            &mut transaction,
+            &self
+                .unique_connection_rules
+                .retain_after(&reward_info.epoch_period),
+        )
+        .await?;
         // subscriber_location::clear_location_shares(&mut transaction, &reward_period.end).await?;
 
         save_next_reward_epoch(&mut transaction, reward_info.epoch_day + 1).await?;
@@ -394,6 +471,7 @@ where
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn reward_poc_and_dc(
     pool: &Pool<Postgres>,
     hex_service_client: &impl HexBoostingInfoResolver<Error = ClientError>,
@@ -401,6 +479,12 @@ pub async fn reward_poc_and_dc(
     speedtest_avg_sink: &FileSinkClient<proto::SpeedtestAvg>,
     reward_info: &EpochRewardInfo,
     price_info: PriceInfo,
+    speedtest_tiers: &SpeedtestTierTable,
+    unique_connection_rules: &UniqueConnectionsTable,
+    geofences: &Geofences,
+    coverage_map: &mut IncrementalCoverageMap,
+    reward_explanations: Option<&FileSinkClient<EncodedRadioRewardExplanation>>,
+    coverage_map_export: Option<&CoverageMapExporter>,
 ) -> anyhow::Result<CalculatedPocRewardShares> {
     let mut reward_shares =
         DataTransferAndPocAllocatedRewardBuckets::new(reward_info.epoch_emissions);
@@ -438,6 +522,12 @@ pub async fn reward_poc_and_dc(
         speedtest_avg_sink,
         reward_info,
         reward_shares,
+        speedtest_tiers,
+        unique_connection_rules,
+        geofences,
+        coverage_map,
+        reward_explanations,
+        coverage_map_export,
     )
     .await?;
 
@@ -457,6 +547,7 @@ pub async fn reward_poc_and_dc(
     Ok(calculated_poc_reward_shares)
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn reward_poc(
     pool: &Pool<Postgres>,
     hex_service_client: &impl HexBoostingInfoResolver<Error = ClientError>,
@@ -464,21 +555,34 @@ async fn reward_poc(
     speedtest_avg_sink: &FileSinkClient<proto::SpeedtestAvg>,
     reward_info: &EpochRewardInfo,
     reward_shares: DataTransferAndPocAllocatedRewardBuckets,
+    speedtest_tiers: &SpeedtestTierTable,
+    unique_connection_rules: &UniqueConnectionsTable,
+    geofences: &Geofences,
+    coverage_map: &mut IncrementalCoverageMap,
+    reward_explanations: Option<&FileSinkClient<EncodedRadioRewardExplanation>>,
+    coverage_map_export: Option<&CoverageMapExporter>,
 ) -> anyhow::Result<(Decimal, CalculatedPocRewardShares)> {
     let heartbeats = HeartbeatReward::validated(pool, &reward_info.epoch_period);
-    let speedtest_averages =
-        SpeedtestAverages::aggregate_epoch_averages(reward_info.epoch_period.end, pool).await?;
+    let speedtest_averages = SpeedtestAverages::aggregate_epoch_averages(
+        reward_info.epoch_period.end,
+        pool,
+        speedtest_tiers.base_thresholds(reward_info.epoch_period.start),
+    )
+    .await?;
 
     speedtest_averages.write_all(speedtest_avg_sink).await?;
 
     let boosted_hexes = BoostedHexes::get_all(hex_service_client).await?;
 
-    let unique_connections = unique_connections::db::get(pool, &reward_info.epoch_period).await?;
+    let unique_connections =
+        unique_connections_for_epoch(pool, unique_connection_rules, &reward_info.epoch_period)
+            .await?;
 
     let boosted_hex_eligibility = BoostedHexEligibility::new(
         radio_threshold::verified_radio_thresholds(pool, &reward_info.epoch_period).await?,
         unique_connections.clone(),
-    );
+    )
+    .with_boosting_regions(geofences.clone(), reward_info.epoch_period.start);
 
     let poc_banned_radios = sp_boosted_rewards_bans::db::get_banned_radios(
         pool,
@@ -487,7 +591,8 @@ async fn reward_poc(
     )
     .await?;
 
-    let coverage_shares = CoverageShares::new(
+    let coverage_shares = CoverageShares::new_with_coverage_map(
+        coverage_map,
         pool,
         heartbeats,
         &speedtest_averages,
@@ -496,20 +601,41 @@ async fn reward_poc(
         &poc_banned_radios,
         &unique_connections,
         &reward_info.epoch_period,
+        speedtest_tiers,
     )
     .await?;
 
+    if let Some(coverage_map_export) = coverage_map_export {
+        // The export is only for auditing, it must not hold up the rewards
+        if let Err(err) = coverage_map_export
+            .export(coverage_shares.coverage_map(), &reward_info.epoch_period)
+            .await
+        {
+            tracing::error!(?err, "failed to export coverage map");
+            telemetry::coverage_map_export_error();
+        }
+    }
+
     let total_poc_rewards = reward_shares.total_poc();
 
     let (unallocated_poc_amount, calculated_poc_rewards_per_share) =
         if let Some((calculated_poc_rewards_per_share, mobile_reward_shares)) =
-            coverage_shares.into_rewards(reward_shares, &reward_info.epoch_period)
+            coverage_shares.into_rewards_with_explanations(reward_shares, &reward_info.epoch_period)
         {
             // handle poc reward outputs
             let mut allocated_poc_rewards = 0_u64;
-            for (poc_reward_amount, mobile_reward_share_v1, mobile_reward_share_v2) in
+            let mut sp_boost_eligibilities = vec![];
+            for (poc_reward_amount, mobile_reward_share_v1, mobile_reward_share_v2, explanation) in
                 mobile_reward_shares
             {
+                sp_boost_eligibilities.push(SpBoostEligibility::from(&explanation));
+                if let Some(reward_explanations) = reward_explanations {
+                    let encoded = EncodedRadioRewardExplanation::try_from(&explanation)?;
+                    reward_explanations.write(encoded, []).await?;
+                }
+                if poc_reward_amount == 0 {
+                    continue;
+                }
                 allocated_poc_rewards += poc_reward_amount;
                 mobile_rewards
                     .write(mobile_reward_share_v1, [])
@@ -522,6 +648,16 @@ async fn reward_poc(
                     // Await the returned one shot ot ensure that we wrote the file
                     .await??;
             }
+            // Saved regardless of reward_explanations so that the eligibility
+            // of any rewarded epoch can be looked up
+            let mut transaction = pool.begin().await?;
+            reward_shares::explanation::db::save(
+                &mut transaction,
+                reward_info.epoch_period.start,
+                &sp_boost_eligibilities,
+            )
+            .await?;
+            transaction.commit().await?;
             // calculate any unallocated poc reward
             (
                 total_poc_rewards - Decimal::from(allocated_poc_rewards),
@@ -640,13 +776,34 @@ pub async fn reward_oracles(
     Ok(())
 }
 
+/// Unique connection counts judged by the rule effective at the start of the
+/// epoch, with hotspots claiming connections without data transfer recorded
+async fn unique_connections_for_epoch(
+    pool: &Pool<Postgres>,
+    unique_connection_rules: &UniqueConnectionsTable,
+    epoch: &Range<DateTime<Utc>>,
+) -> anyhow::Result<UniqueConnectionCounts> {
+    let rule = unique_connection_rules.rule(epoch.start);
+    let require_data_transfer = rule.require_data_transfer;
+    let counts = unique_connections::db::get(pool, &rule.report_range(epoch)).await?;
+    let mut unique_connections = UniqueConnectionCounts::new(counts, rule);
+
+    if require_data_transfer {
+        let data_transfer =
+            data_session::aggregate_hotspot_data_sessions_to_dc(pool, epoch).await?;
+        unique_connections.flag_without_data_transfer(&data_transfer);
+    }
+
+    Ok(unique_connections)
+}
+
 pub async fn reward_service_providers(
     dc_sessions: ServiceProviderDCSessions,
     sp_promotions: ServiceProviderPromotions,
     mobile_rewards: &FileSinkClient<proto::MobileRewardShare>,
     reward_info: &EpochRewardInfo,
     hnt_bone_price: Decimal,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<ServiceProviderEpochStatement> {
     use service_provider::ServiceProviderRewardInfos;
 
     let total_sp_rewards = service_provider::get_scheduled_tokens(reward_info.epoch_emissions);
@@ -677,7 +834,7 @@ pub async fn reward_service_providers(
         reward_info,
     )
     .await?;
-    Ok(())
+    Ok(sps.statement())
 }
 
 async fn write_unallocated_reward(
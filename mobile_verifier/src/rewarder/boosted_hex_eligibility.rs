@@ -1,17 +1,68 @@
+use chrono::{DateTime, Utc};
 use coverage_map::UnrankedCoverage;
 use coverage_point_calculator::{RadioType, SPBoostedRewardEligibility};
+use h3o::CellIndex;
 use helium_crypto::PublicKeyBinary;
 use hex_assignments::Assignment;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    radio_threshold::VerifiedRadioThresholds,
-    unique_connections::{self, UniqueConnectionCounts},
+    geofence::{GeofenceFeature, Geofences},
+    radio_threshold::{ReportedThreshold, VerifiedRadioThresholds},
+    unique_connections::{self, QualifyingRadioType, UniqueConnectionCounts},
 };
 
-#[derive(Debug, Default)]
+#[derive(Default)]
 pub struct BoostedHexEligibility {
     radio_thresholds: VerifiedRadioThresholds,
     unique_connections: UniqueConnectionCounts,
+    boosting_regions: Option<(Geofences, DateTime<Utc>)>,
+}
+
+/// A radio's eligibility for service provider boosted rewards and what it
+/// was decided on
+#[derive(Debug, Clone, PartialEq)]
+pub struct Eligibility {
+    pub status: SPBoostedRewardEligibility,
+    pub evidence: EligibilityEvidence,
+}
+
+/// Both requirements are recorded for every radio, `rule` is the one its
+/// eligibility was decided by
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EligibilityEvidence {
+    pub rule: EligibilityRule,
+    pub radio_threshold: RadioThresholdEvidence,
+    pub unique_connections: UniqueConnectionsEvidence,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EligibilityRule {
+    /// hip-140: radios covering a hex in a boosting region, or while no
+    /// boosting region is active, in the United States
+    UniqueConnections,
+    /// hip-84: radios outside of the United States
+    RadioThreshold,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RadioThresholdEvidence {
+    /// The threshold reported by the carrier, if the radio met it in time
+    pub reported: Option<ReportedThreshold>,
+    /// The threshold has to be met before this
+    pub required_before: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UniqueConnectionsEvidence {
+    pub count: u64,
+    /// A radio needs more unique connections than this
+    pub required_more_than: u64,
+    /// Whether radios of this type can qualify by unique connections
+    pub radio_type_qualifies: bool,
+    /// The radio reported unique connections without transferring data
+    pub flagged: bool,
 }
 
 impl BoostedHexEligibility {
@@ -22,20 +73,43 @@ impl BoostedHexEligibility {
         Self {
             radio_thresholds,
             unique_connections,
+            boosting_regions: None,
         }
     }
 
+    /// Decide the rule by the boosting geofence regions active at the given
+    /// time instead of the urbanization data set
+    pub fn with_boosting_regions(mut self, geofences: Geofences, at: DateTime<Utc>) -> Self {
+        self.boosting_regions = Some((geofences, at));
+        self
+    }
+
     pub fn eligibility(
         &self,
         radio_type: RadioType,
         key: PublicKeyBinary,
         cbsd_id_opt: Option<String>,
         covered_hexes: &[UnrankedCoverage],
-    ) -> SPBoostedRewardEligibility {
-        if Self::in_united_states(covered_hexes) {
-            self.check_unique_connections(&key, &radio_type)
+    ) -> Eligibility {
+        let (rule, status) = if self.in_boosting_region(covered_hexes) {
+            (
+                EligibilityRule::UniqueConnections,
+                self.check_unique_connections(&key, &radio_type),
+            )
         } else {
-            self.check_radio_thresholds(key, cbsd_id_opt)
+            (
+                EligibilityRule::RadioThreshold,
+                self.check_radio_thresholds(key.clone(), cbsd_id_opt.clone()),
+            )
+        };
+
+        Eligibility {
+            status,
+            evidence: EligibilityEvidence {
+                rule,
+                radio_threshold: self.radio_threshold_evidence(key.clone(), cbsd_id_opt),
+                unique_connections: self.unique_connections_evidence(&key, radio_type),
+            },
         }
     }
 
@@ -63,10 +137,52 @@ impl BoostedHexEligibility {
         }
     }
 
-    fn in_united_states(covered_hexes: &[UnrankedCoverage]) -> bool {
-        covered_hexes
-            .iter()
-            .any(|uc| uc.assignments.urbanized != Assignment::C)
+    fn radio_threshold_evidence(
+        &self,
+        key: PublicKeyBinary,
+        cbsd_id_opt: Option<String>,
+    ) -> RadioThresholdEvidence {
+        RadioThresholdEvidence {
+            reported: self.radio_thresholds.get(key, cbsd_id_opt).copied(),
+            required_before: self.radio_thresholds.met_before(),
+        }
+    }
+
+    fn unique_connections_evidence(
+        &self,
+        key: &PublicKeyBinary,
+        radio_type: RadioType,
+    ) -> UniqueConnectionsEvidence {
+        let rule = self.unique_connections.rule();
+        UniqueConnectionsEvidence {
+            count: self
+                .unique_connections
+                .get(key)
+                .copied()
+                .unwrap_or_default(),
+            required_more_than: rule.minimum_unique_connections,
+            radio_type_qualifies: rule
+                .radio_types
+                .contains(&QualifyingRadioType::from(radio_type)),
+            flagged: self.unique_connections.is_flagged(key, &radio_type),
+        }
+    }
+
+    fn in_boosting_region(&self, covered_hexes: &[UnrankedCoverage]) -> bool {
+        match &self.boosting_regions {
+            Some((geofences, at)) if geofences.is_active(GeofenceFeature::Boosting, *at) => {
+                covered_hexes.iter().any(|uc| {
+                    CellIndex::try_from(uc.location.into_raw()).is_ok_and(|cell| {
+                        geofences.contains(GeofenceFeature::Boosting, cell, *at) == Some(true)
+                    })
+                })
+            }
+            // Without a boosting region, any hex outside of the
+            // urbanization data set is outside of the United States
+            _ => covered_hexes
+                .iter()
+                .any(|uc| uc.assignments.urbanized != Assignment::C),
+        }
     }
 }
 
@@ -79,6 +195,7 @@ mod tests {
     use unique_connections::MINIMUM_UNIQUE_CONNECTIONS;
 
     use super::*;
+    use crate::geofence::GeofenceRegion;
 
     #[test]
     fn eligible_in_united_states() {
@@ -101,7 +218,7 @@ mod tests {
             &covered_hexes,
         );
 
-        assert_eq!(SPBoostedRewardEligibility::Eligible, eligibility);
+        assert_eq!(SPBoostedRewardEligibility::Eligible, eligibility.status);
     }
 
     #[test]
@@ -112,7 +229,12 @@ mod tests {
 
         let unique_connections = UniqueConnectionCounts::default();
         let mut verified_thresholds = VerifiedRadioThresholds::default();
-        verified_thresholds.insert(pub_key.clone(), None);
+        let threshold = ReportedThreshold {
+            bytes_threshold: 1_000_000,
+            subscriber_threshold: 3,
+            threshold_timestamp: Utc::now(),
+        };
+        verified_thresholds.insert(pub_key.clone(), None, threshold);
 
         let boosted_hex_eligibility =
             BoostedHexEligibility::new(verified_thresholds, unique_connections);
@@ -126,7 +248,12 @@ mod tests {
             &covered_hexes,
         );
 
-        assert_eq!(SPBoostedRewardEligibility::Eligible, eligibility);
+        assert_eq!(SPBoostedRewardEligibility::Eligible, eligibility.status);
+        assert_eq!(EligibilityRule::RadioThreshold, eligibility.evidence.rule);
+        assert_eq!(
+            Some(threshold),
+            eligibility.evidence.radio_threshold.reported
+        );
     }
 
     #[test]
@@ -152,8 +279,10 @@ mod tests {
 
         assert_eq!(
             SPBoostedRewardEligibility::RadioThresholdNotMet,
-            eligibility
+            eligibility.status
         );
+        assert_eq!(EligibilityRule::RadioThreshold, eligibility.evidence.rule);
+        assert_eq!(None, eligibility.evidence.radio_threshold.reported);
     }
 
     #[test]
@@ -179,8 +308,84 @@ mod tests {
 
         assert_eq!(
             SPBoostedRewardEligibility::NotEnoughConnections,
-            eligibility
+            eligibility.status
+        );
+        assert_eq!(
+            UniqueConnectionsEvidence {
+                count: MINIMUM_UNIQUE_CONNECTIONS,
+                required_more_than: MINIMUM_UNIQUE_CONNECTIONS,
+                radio_type_qualifies: true,
+                flagged: false,
+            },
+            eligibility.evidence.unique_connections
+        );
+    }
+
+    #[test]
+    fn boosting_region_decides_the_rule() {
+        let keypair = generate_keypair();
+        let pub_key: PublicKeyBinary = keypair.public_key().to_vec().into();
+
+        let mut unique_connections = UniqueConnectionCounts::default();
+        unique_connections.insert(pub_key.clone(), MINIMUM_UNIQUE_CONNECTIONS + 1);
+
+        let now = Utc::now();
+        let covered = unranked_coverage(Assignment::C);
+        let region_cell = CellIndex::try_from(covered.location.into_raw())
+            .unwrap()
+            .parent(h3o::Resolution::Seven)
+            .unwrap();
+        let region = |cell: CellIndex| {
+            GeofenceRegion::new(
+                "boosting".to_string(),
+                GeofenceFeature::Boosting,
+                1,
+                now - chrono::Duration::days(1),
+                h3o::Resolution::Seven,
+                [Cell::try_from(u64::from(cell)).unwrap()].iter().collect(),
+            )
+        };
+
+        // outside of the united states by the urbanization data set, but
+        // within the boosting region
+        let eligibility = BoostedHexEligibility::new(
+            VerifiedRadioThresholds::default(),
+            unique_connections.clone(),
+        )
+        .with_boosting_regions(Geofences::new(vec![region(region_cell)]), now)
+        .eligibility(
+            RadioType::OutdoorWifi,
+            pub_key.clone(),
+            None,
+            &[covered.clone()],
+        );
+        assert_eq!(SPBoostedRewardEligibility::Eligible, eligibility.status);
+        assert_eq!(
+            EligibilityRule::UniqueConnections,
+            eligibility.evidence.rule
+        );
+
+        // within the united states by the urbanization data set, but outside
+        // of the boosting region
+        let elsewhere = region_cell
+            .grid_disk::<Vec<_>>(2)
+            .into_iter()
+            .last()
+            .unwrap();
+        let eligibility =
+            BoostedHexEligibility::new(VerifiedRadioThresholds::default(), unique_connections)
+                .with_boosting_regions(Geofences::new(vec![region(elsewhere)]), now)
+                .eligibility(
+                    RadioType::OutdoorWifi,
+                    pub_key,
+                    None,
+                    &[unranked_coverage(Assignment::A)],
+                );
+        assert_eq!(
+            SPBoostedRewardEligibility::RadioThresholdNotMet,
+            eligibility.status
         );
+        assert_eq!(EligibilityRule::RadioThreshold, eligibility.evidence.rule);
     }
 
     fn generate_keypair() -> Keypair {
@@ -197,6 +402,7 @@ mod tests {
                 landtype: Assignment::A,
                 urbanized,
                 service_provider_override: Assignment::C,
+                assignment_multiplier: None,
             },
         }
     }
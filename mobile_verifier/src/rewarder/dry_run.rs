@@ -0,0 +1,315 @@
+//! Reward an epoch without side effects.
+//!
+//! A dry run executes the same reward pipeline as [`super::Rewarder::reward`]
+//! against the real inputs in the database and config service, but all reward
+//! shares are collected in memory rather than written to a file sink, and no
+//! tables are cleared nor the next reward epoch advanced.
+//!
+//! The collected shares are summarised into a [`DryRunReport`] which can be
+//! saved and diffed against the report of another run, eg one with overridden
+//! emissions, boosted hexes or speedtest tier thresholds.
+
+use super::{reward_mappers, reward_oracles, reward_poc_and_dc, reward_service_providers};
+use crate::{
+    geofence::Geofences, service_provider, unique_connections::UniqueConnectionsTable, PriceInfo,
+};
+use chrono::{DateTime, Utc};
+use coverage_map::IncrementalCoverageMap;
+use coverage_point_calculator::SpeedtestTierTable;
+use file_store::file_sink::{FileSinkClient, Message};
+use futures::stream::{self, StreamExt};
+use helium_crypto::PublicKeyBinary;
+use helium_proto::{
+    services::poc_mobile::{
+        self as proto, mobile_reward_share::Reward as ProtoReward, UnallocatedRewardType,
+    },
+    ServiceProvider,
+};
+use mobile_config::{
+    boosted_hex_info::{BoostedHexInfo, BoostedHexInfoStream},
+    client::{
+        carrier_service_client::CarrierServiceVerifier,
+        hex_boosting_client::HexBoostingInfoResolver, ClientError,
+    },
+    sub_dao_epoch_reward_info::EpochRewardInfo,
+};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use std::{collections::BTreeMap, sync::Arc};
+use tokio::task::JoinHandle;
+
+const DRY_RUN_SINK_BUFFER: usize = 1_000;
+
+/// Resolves boosted hexes either from the config service or from a fixed set
+/// of overrides
+#[derive(Clone)]
+pub enum BoostedHexesOverride<B> {
+    Live(B),
+    Fixed(Arc<Vec<BoostedHexInfo>>),
+}
+
+#[async_trait::async_trait]
+impl<B> HexBoostingInfoResolver for BoostedHexesOverride<B>
+where
+    B: HexBoostingInfoResolver<Error = ClientError>,
+{
+    type Error = ClientError;
+
+    async fn stream_boosted_hexes_info(&mut self) -> Result<BoostedHexInfoStream, Self::Error> {
+        match self {
+            Self::Live(client) => client.stream_boosted_hexes_info().await,
+            Self::Fixed(hexes) => Ok(stream::iter(hexes.as_ref().clone()).boxed()),
+        }
+    }
+
+    async fn stream_modified_boosted_hexes_info(
+        &mut self,
+        timestamp: DateTime<Utc>,
+    ) -> Result<BoostedHexInfoStream, Self::Error> {
+        match self {
+            Self::Live(client) => client.stream_modified_boosted_hexes_info(timestamp).await,
+            Self::Fixed(hexes) => Ok(stream::iter(hexes.as_ref().clone()).boxed()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RadioRewardTotals {
+    pub poc_reward: u64,
+    pub dc_transfer_reward: u64,
+}
+
+impl RadioRewardTotals {
+    pub fn total(&self) -> u64 {
+        self.poc_reward + self.dc_transfer_reward
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct DryRunReport {
+    pub epoch: u64,
+    pub epoch_start: DateTime<Utc>,
+    pub epoch_end: DateTime<Utc>,
+    pub epoch_emissions: Decimal,
+    pub price_in_bones: u64,
+    /// keyed by hotspot key
+    pub radios: BTreeMap<PublicKeyBinary, RadioRewardTotals>,
+    /// keyed by service provider name, including promotion rewards
+    pub service_providers: BTreeMap<String, u64>,
+    pub subscribers: u64,
+    /// keyed by unallocated reward type name
+    pub unallocated: BTreeMap<String, u64>,
+    pub total_rewards: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RadioRewardDiff {
+    pub hotspot_key: PublicKeyBinary,
+    pub before: u64,
+    pub after: u64,
+    pub delta: i128,
+}
+
+impl DryRunReport {
+    fn new(reward_info: &EpochRewardInfo, price_info: &PriceInfo) -> Self {
+        Self {
+            epoch: reward_info.epoch_day,
+            epoch_start: reward_info.epoch_period.start,
+            epoch_end: reward_info.epoch_period.end,
+            epoch_emissions: reward_info.epoch_emissions,
+            price_in_bones: price_info.price_in_bones,
+            ..Default::default()
+        }
+    }
+
+    fn add_share(&mut self, share: proto::MobileRewardShare) {
+        let amount = match share.reward {
+            Some(ProtoReward::RadioReward(reward)) => {
+                self.radio(reward.hotspot_key).poc_reward += reward.poc_reward;
+                reward.poc_reward
+            }
+            Some(ProtoReward::GatewayReward(reward)) => {
+                self.radio(reward.hotspot_key).dc_transfer_reward += reward.dc_transfer_reward;
+                reward.dc_transfer_reward
+            }
+            Some(ProtoReward::ServiceProviderReward(reward)) => {
+                *self
+                    .service_providers
+                    .entry(service_provider_name(reward.service_provider_id))
+                    .or_default() += reward.amount;
+                reward.amount
+            }
+            Some(ProtoReward::PromotionReward(reward)) => {
+                let amount = reward.service_provider_amount + reward.matched_amount;
+                *self
+                    .service_providers
+                    .entry(format!("promotion:{}", reward.entity))
+                    .or_default() += amount;
+                amount
+            }
+            Some(ProtoReward::SubscriberReward(reward)) => {
+                let amount = reward.discovery_location_amount + reward.verification_mapping_amount;
+                self.subscribers += amount;
+                amount
+            }
+            Some(ProtoReward::UnallocatedReward(reward)) => {
+                let reward_type = UnallocatedRewardType::try_from(reward.reward_type)
+                    .map(|t| t.as_str_name().to_string())
+                    .unwrap_or_else(|_| reward.reward_type.to_string());
+                *self.unallocated.entry(reward_type).or_default() += reward.amount;
+                reward.amount
+            }
+            // radio reward v2 duplicates the v1 poc reward
+            _ => 0,
+        };
+        self.total_rewards += amount;
+    }
+
+    fn radio(&mut self, hotspot_key: Vec<u8>) -> &mut RadioRewardTotals {
+        self.radios
+            .entry(PublicKeyBinary::from(hotspot_key))
+            .or_default()
+    }
+
+    /// Per radio change in total rewards from `previous` to `self`, excluding
+    /// radios whose rewards are unchanged
+    pub fn diff(&self, previous: &DryRunReport) -> Vec<RadioRewardDiff> {
+        let mut keys: Vec<&PublicKeyBinary> = self.radios.keys().collect();
+        keys.extend(previous.radios.keys());
+        keys.sort();
+        keys.dedup();
+        keys.into_iter()
+            .filter_map(|key| {
+                let before = previous.radios.get(key).map_or(0, RadioRewardTotals::total);
+                let after = self.radios.get(key).map_or(0, RadioRewardTotals::total);
+                (before != after).then(|| RadioRewardDiff {
+                    hotspot_key: key.clone(),
+                    before,
+                    after,
+                    delta: after as i128 - before as i128,
+                })
+            })
+            .collect()
+    }
+}
+
+fn service_provider_name(service_provider_id: i32) -> String {
+    ServiceProvider::try_from(service_provider_id)
+        .map(|sp| sp.as_str_name().to_string())
+        .unwrap_or_else(|_| service_provider_id.to_string())
+}
+
+/// Run the full reward pipeline for the epoch described by `reward_info`
+/// collecting the resulting shares in memory
+pub async fn dry_run(
+    pool: &Pool<Postgres>,
+    carrier_client: &impl CarrierServiceVerifier<Error = ClientError>,
+    hex_service_client: &impl HexBoostingInfoResolver<Error = ClientError>,
+    reward_info: &EpochRewardInfo,
+    price_info: PriceInfo,
+    speedtest_tiers: &SpeedtestTierTable,
+    unique_connection_rules: &UniqueConnectionsTable,
+    geofences: &Geofences,
+) -> anyhow::Result<DryRunReport> {
+    let mut report = DryRunReport::new(reward_info, &price_info);
+    let (mobile_rewards, mobile_rewards_handle) = memory_sink::<proto::MobileRewardShare>();
+    let (speedtest_avgs, speedtest_avgs_handle) = memory_sink::<proto::SpeedtestAvg>();
+
+    reward_poc_and_dc(
+        pool,
+        hex_service_client,
+        &mobile_rewards,
+        &speedtest_avgs,
+        reward_info,
+        price_info.clone(),
+        speedtest_tiers,
+        unique_connection_rules,
+        geofences,
+        &mut IncrementalCoverageMap::default(),
+        None,
+        None,
+    )
+    .await?;
+
+    reward_mappers(pool, &mobile_rewards, reward_info).await?;
+
+    let dc_sessions =
+        service_provider::get_dc_sessions(pool, carrier_client, &reward_info.epoch_period).await?;
+    let sp_promotions =
+        service_provider::get_promotions(carrier_client, &reward_info.epoch_period.start).await?;
+    reward_service_providers(
+        dc_sessions,
+        sp_promotions,
+        &mobile_rewards,
+        reward_info,
+        price_info.price_per_bone,
+    )
+    .await?;
+
+    reward_oracles(&mobile_rewards, reward_info).await?;
+
+    // closing the sinks ends the collecting tasks
+    drop(mobile_rewards);
+    drop(speedtest_avgs);
+    speedtest_avgs_handle.await?;
+    for share in mobile_rewards_handle.await? {
+        report.add_share(share);
+    }
+    Ok(report)
+}
+
+/// A file sink client whose messages are acknowledged and collected in memory
+/// rather than written out. The collected messages are returned once every
+/// clone of the client has been dropped
+fn memory_sink<T: Send + 'static>() -> (FileSinkClient<T>, JoinHandle<Vec<T>>) {
+    let (sender, mut receiver) = tokio::sync::mpsc::channel(DRY_RUN_SINK_BUFFER);
+    let handle = tokio::spawn(async move {
+        let mut messages = vec![];
+        while let Some(msg) = receiver.recv().await {
+            match msg {
+                Message::Data(on_write_tx, item) => {
+                    let _ = on_write_tx.send(Ok(()));
+                    messages.push(item);
+                }
+                Message::Commit(on_commit_tx) | Message::Rollback(on_commit_tx) => {
+                    let _ = on_commit_tx.send(Ok(vec![]));
+                }
+            }
+        }
+        messages
+    });
+    (FileSinkClient::new(sender, "dry_run"), handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_reports() {
+        let unchanged = PublicKeyBinary::from(vec![1]);
+        let changed = PublicKeyBinary::from(vec![2]);
+        let removed = PublicKeyBinary::from(vec![3]);
+        let totals = |poc_reward| RadioRewardTotals {
+            poc_reward,
+            dc_transfer_reward: 0,
+        };
+
+        let mut previous = DryRunReport::default();
+        previous.radios.insert(unchanged.clone(), totals(10));
+        previous.radios.insert(changed.clone(), totals(10));
+        previous.radios.insert(removed.clone(), totals(5));
+
+        let mut current = DryRunReport::default();
+        current.radios.insert(unchanged, totals(10));
+        current.radios.insert(changed.clone(), totals(15));
+
+        let diff = current.diff(&previous);
+        assert_eq!(2, diff.len());
+        assert_eq!(changed, diff[0].hotspot_key);
+        assert_eq!(5, diff[0].delta);
+        assert_eq!(removed, diff[1].hotspot_key);
+        assert_eq!(-5, diff[1].delta);
+    }
+}
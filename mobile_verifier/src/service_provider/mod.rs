@@ -2,10 +2,14 @@ pub use dc_sessions::{get_dc_sessions, ServiceProviderDCSessions};
 pub use promotions::{get_promotions, ServiceProviderPromotions};
 pub use reward::ServiceProviderRewardInfos;
 use rust_decimal::Decimal;
+pub use statement::{
+    ServiceProviderEpochStatement, ServiceProviderStatementSettings, ServiceProviderStatementWriter,
+};
 
 mod dc_sessions;
 mod promotions;
 mod reward;
+pub mod statement;
 
 // This type is used in lieu of the helium_proto::ServiceProvider enum so we can
 // handle more than a single value without adding a hard deploy dependency to
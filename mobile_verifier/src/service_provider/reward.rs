@@ -4,7 +4,11 @@ use mobile_config::sub_dao_epoch_reward_info::EpochRewardInfo;
 use rust_decimal::{Decimal, RoundingStrategy};
 use rust_decimal_macros::dec;
 
-use super::{dc_sessions::ServiceProviderDCSessions, promotions::ServiceProviderPromotions};
+use super::{
+    dc_sessions::ServiceProviderDCSessions,
+    promotions::ServiceProviderPromotions,
+    statement::{PromotionStatement, ServiceProviderEpochStatement, ServiceProviderStatement},
+};
 
 mod proto {
     pub use helium_proto::{
@@ -20,6 +24,8 @@ mod proto {
 pub struct ServiceProviderRewardInfos {
     coll: Vec<RewardInfo>,
     total_sp_allocation: Decimal,
+    used_allocation: Decimal,
+    hnt_bone_price: Decimal,
     reward_info: EpochRewardInfo,
 }
 
@@ -30,6 +36,8 @@ struct RewardInfo {
     // proto::ServiceProvider enum repr
     sp_id: i32,
 
+    // Total DC transferred for reward epoch
+    dc: Decimal,
     // Total DC transferred for reward epoch in Bones
     bones: Decimal,
     // % of total allocated rewards for data transfer
@@ -58,12 +66,6 @@ impl ServiceProviderRewardInfos {
     ) -> Self {
         let all_transfer = dc_sessions.all_transfer(); // DC
 
-        let mut me = Self {
-            coll: vec![],
-            total_sp_allocation,
-            reward_info,
-        };
-
         // After this point, we enter percentage land. This number is the basis
         // for all percentages, our 100%. If the DC transferred in Bones is
         // greater than the amount of Bones allocated for rewarding, we use the
@@ -74,12 +76,21 @@ impl ServiceProviderRewardInfos {
         let used_allocation =
             total_sp_allocation.max(dc_to_hnt_bones(all_transfer, hnt_bone_price));
 
+        let mut me = Self {
+            coll: vec![],
+            total_sp_allocation,
+            used_allocation,
+            hnt_bone_price,
+            reward_info,
+        };
+
         for (service_provider, dc_transfer) in dc_sessions.iter() {
             let promo_fund_perc = promotions.get_fund_percent(service_provider);
             let promos = promotions.get_active_promotions(service_provider);
 
             me.coll.push(RewardInfo::new(
                 service_provider,
+                dc_transfer,
                 dc_to_hnt_bones(dc_transfer, hnt_bone_price),
                 promo_fund_perc,
                 used_allocation,
@@ -101,11 +112,37 @@ impl ServiceProviderRewardInfos {
             .filter(|(amount, _r)| *amount > 0)
             .collect::<Vec<_>>()
     }
+
+    /// Breakdown of how each service provider's reward for the epoch was
+    /// worked out. Amounts are those of `iter_rewards`.
+    pub fn statement(&self) -> ServiceProviderEpochStatement {
+        let service_providers: Vec<_> = self
+            .coll
+            .iter()
+            .map(|sp| sp.statement(self.total_sp_allocation, &self.reward_info))
+            .collect();
+        let rewarded: u64 = service_providers.iter().map(|sp| sp.total_reward()).sum();
+
+        ServiceProviderEpochStatement {
+            epoch: self.reward_info.epoch_day,
+            epoch_start: self.reward_info.epoch_period.start,
+            epoch_end: self.reward_info.epoch_period.end,
+            total_sp_allocation: self.total_sp_allocation.to_u64_floored(),
+            used_allocation: self.used_allocation,
+            hnt_bone_price: self.hnt_bone_price,
+            unallocated: self
+                .total_sp_allocation
+                .to_u64_floored()
+                .saturating_sub(rewarded),
+            service_providers,
+        }
+    }
 }
 
 impl RewardInfo {
     fn new(
         sp_id: i32,
+        dc_transfer: Decimal,
         bones_transfer: Decimal, // Bones
         promo_fund_perc: Decimal,
         total_sp_allocation: Decimal, // Bones
@@ -121,6 +158,7 @@ impl RewardInfo {
 
         Self {
             sp_id,
+            dc: dc_transfer,
             bones: bones_transfer,
             allocated_promo_perc: promo_fund_perc,
 
@@ -133,6 +171,43 @@ impl RewardInfo {
         }
     }
 
+    fn statement(
+        &self,
+        total_allocation: Decimal,
+        reward_info: &EpochRewardInfo,
+    ) -> ServiceProviderStatement {
+        let promotions = self
+            .promotions
+            .iter()
+            .zip(self.promo_rewards(total_allocation, reward_info))
+            .map(|(promotion, (_, share))| match share.reward {
+                Some(proto::Reward::PromotionReward(reward)) => PromotionStatement {
+                    entity: promotion.entity.clone(),
+                    shares: promotion.shares,
+                    service_provider_amount: reward.service_provider_amount,
+                    matched_amount: reward.matched_amount,
+                },
+                _ => unreachable!("promo_rewards only returns promotion rewards"),
+            })
+            .collect();
+
+        ServiceProviderStatement {
+            service_provider_id: self.sp_id,
+            service_provider: helium_proto::ServiceProvider::try_from(self.sp_id)
+                .map(|sp| sp.as_str_name().to_string())
+                .unwrap_or_else(|_| self.sp_id.to_string()),
+            dc_transferred: self.dc.to_u64_floored(),
+            bones: self.bones,
+            data_perc: self.data_perc,
+            allocated_promo_perc: self.allocated_promo_perc,
+            realized_promo_perc: self.realized_promo_perc,
+            realized_data_perc: self.realized_data_perc,
+            matched_promo_perc: self.matched_promo_perc,
+            carrier_reward: self.carrier_reward(total_allocation, reward_info).0,
+            promotions,
+        }
+    }
+
     pub fn iter_rewards(
         &self,
         total_allocation: Decimal,
@@ -338,6 +413,42 @@ mod tests {
         assert_eq!(sp_2.amount, 3);
     }
 
+    #[test]
+    fn statement_matches_rewards() {
+        let reward_info = default_rewards_info(82_191_780_821_917, Duration::hours(24));
+        let sp_infos = ServiceProviderRewardInfos::new(
+            ServiceProviderDCSessions::from([(0, dec!(12)), (1, dec!(6))]),
+            ServiceProviderPromotions::from(vec![make_test_promotion(0, "promo-0", 5000, 1)]),
+            dec!(100),
+            dec!(0.00001),
+            reward_info,
+        );
+
+        let statement = sp_infos.statement();
+        let rewarded: u64 = sp_infos
+            .iter_rewards()
+            .iter()
+            .map(|(amount, _)| amount)
+            .sum();
+        assert_eq!(statement.total_sp_allocation, 100);
+        assert_eq!(statement.unallocated, 100 - rewarded);
+
+        let sp_0 = &statement.service_providers[0];
+        assert_eq!(sp_0.dc_transferred, 12);
+        assert_eq!(sp_0.allocated_promo_perc, dec!(0.5));
+        assert_eq!(sp_0.carrier_reward, 6);
+        assert_eq!(sp_0.promotions.len(), 1);
+        assert_eq!(sp_0.promotions[0].entity, "promo-0");
+        assert_eq!(sp_0.promotions[0].service_provider_amount, 6);
+        assert_eq!(sp_0.promotions[0].matched_amount, 6);
+
+        let sp_1 = &statement.service_providers[1];
+        assert_eq!(sp_1.realized_promo_perc, dec!(0));
+        assert_eq!(sp_1.carrier_reward, 6);
+        assert!(sp_1.promotions.is_empty());
+        assert_eq!(sp_0.total_reward() + sp_1.total_reward(), rewarded);
+    }
+
     #[test]
     fn unallocated_reward_scaling_2() {
         let reward_info = default_rewards_info(82_191_780_821_917, Duration::hours(24));
@@ -0,0 +1,197 @@
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Duration, Utc};
+use file_store::{FileExporter, FileStore};
+use futures::TryStreamExt;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::Settings;
+
+use super::ServiceProviderId;
+
+pub const STATEMENT_PREFIX: &str = "service_provider_statement";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServiceProviderStatementSettings {
+    /// Directory the statements are written to
+    pub directory: PathBuf,
+    /// Upload the statements to the output bucket
+    #[serde(default)]
+    pub upload: bool,
+}
+
+/// How the service provider rewards of an epoch were worked out. All amounts
+/// are in bones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServiceProviderEpochStatement {
+    pub epoch: u64,
+    pub epoch_start: DateTime<Utc>,
+    pub epoch_end: DateTime<Utc>,
+    /// Bones scheduled for service provider rewards
+    pub total_sp_allocation: u64,
+    /// Basis of all percentages, the greater of the allocation and the bones
+    /// value of all DC transferred
+    pub used_allocation: Decimal,
+    pub hnt_bone_price: Decimal,
+    /// Bones of the allocation not rewarded to any service provider
+    pub unallocated: u64,
+    pub service_providers: Vec<ServiceProviderStatement>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServiceProviderStatement {
+    pub service_provider_id: ServiceProviderId,
+    pub service_provider: String,
+    pub dc_transferred: u64,
+    /// Bones value of the DC transferred
+    pub bones: Decimal,
+    /// Share of the allocation earned by data transfer
+    pub data_perc: Decimal,
+    /// Share of the data transfer reward the service provider escrows for
+    /// promotions
+    pub allocated_promo_perc: Decimal,
+    /// Share of the allocation going to promotions, zero without active
+    /// promotions
+    pub realized_promo_perc: Decimal,
+    /// Share of the allocation rewarded to the service provider
+    pub realized_data_perc: Decimal,
+    /// Share of the allocation matching the promotions, taken from the
+    /// unallocated remainder
+    pub matched_promo_perc: Decimal,
+    pub carrier_reward: u64,
+    pub promotions: Vec<PromotionStatement>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromotionStatement {
+    pub entity: String,
+    pub shares: u32,
+    pub service_provider_amount: u64,
+    pub matched_amount: u64,
+}
+
+impl ServiceProviderStatement {
+    pub fn total_reward(&self) -> u64 {
+        self.carrier_reward
+            + self
+                .promotions
+                .iter()
+                .map(|promo| promo.service_provider_amount + promo.matched_amount)
+                .sum::<u64>()
+    }
+}
+
+impl ServiceProviderEpochStatement {
+    pub fn file_name(&self) -> String {
+        format!(
+            "{STATEMENT_PREFIX}.{}.json",
+            self.epoch_start.timestamp_millis()
+        )
+    }
+
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+    }
+
+    /// Fetches the statement of the epoch starting at `epoch_start` from the
+    /// bucket
+    pub async fn fetch(
+        file_store: &FileStore,
+        epoch_start: DateTime<Utc>,
+    ) -> anyhow::Result<Option<Self>> {
+        let key = format!("{STATEMENT_PREFIX}.{}.json", epoch_start.timestamp_millis());
+        let infos = file_store
+            .list_all(
+                STATEMENT_PREFIX,
+                epoch_start - Duration::milliseconds(1),
+                epoch_start + Duration::milliseconds(1),
+            )
+            .await?;
+        let Some(info) = infos.into_iter().find(|info| info.key == key) else {
+            return Ok(None);
+        };
+
+        let bytes: Vec<u8> = file_store
+            .get_raw(info.key)
+            .await?
+            .map_ok(|bytes| bytes.to_vec())
+            .try_concat()
+            .await?;
+        Ok(Some(serde_json::from_slice(&bytes)?))
+    }
+
+    pub fn print(&self) {
+        println!(
+            "Service provider statement for epoch {} ({} -> {})",
+            self.epoch, self.epoch_start, self.epoch_end
+        );
+        println!("  allocation: {} bones", self.total_sp_allocation);
+        println!("  used allocation: {} bones", self.used_allocation);
+        println!("  hnt bone price: {}", self.hnt_bone_price);
+        for sp in &self.service_providers {
+            println!("  Service Provider: {}", sp.service_provider);
+            println!("    dc transferred: {}", sp.dc_transferred);
+            println!("    bones: {}", sp.bones);
+            println!("    data perc: {}", sp.data_perc);
+            println!("    allocated promo perc: {}", sp.allocated_promo_perc);
+            println!("    realized promo perc: {}", sp.realized_promo_perc);
+            println!("    realized data perc: {}", sp.realized_data_perc);
+            println!("    matched promo perc: {}", sp.matched_promo_perc);
+            println!("    carrier reward: {}", sp.carrier_reward);
+            println!("    Promotions: ({})", sp.promotions.len());
+            for promo in &sp.promotions {
+                println!("      name: {}", promo.entity);
+                println!("      shares: {}", promo.shares);
+                println!(
+                    "      service provider amount: {}",
+                    promo.service_provider_amount
+                );
+                println!("      matched amount: {}", promo.matched_amount);
+            }
+            println!("    total reward: {}", sp.total_reward());
+        }
+        println!("  unallocated: {}", self.unallocated);
+    }
+}
+
+/// Writes the service provider statement of each rewarded epoch
+pub struct ServiceProviderStatementWriter {
+    exporter: FileExporter,
+}
+
+impl ServiceProviderStatementWriter {
+    pub async fn from_settings(settings: &Settings) -> anyhow::Result<Option<Self>> {
+        let Some(statement_settings) = settings.service_provider_statements.clone() else {
+            return Ok(None);
+        };
+        let file_store = if statement_settings.upload {
+            Some(FileStore::from_settings(&settings.output).await?)
+        } else {
+            None
+        };
+        Ok(Some(Self {
+            exporter: FileExporter::new(statement_settings.directory, file_store),
+        }))
+    }
+
+    pub async fn write(
+        &self,
+        statement: &ServiceProviderEpochStatement,
+    ) -> anyhow::Result<PathBuf> {
+        let path = {
+            let statement = statement.clone();
+            self.exporter
+                .export(&statement.file_name(), move |writer| {
+                    Ok(serde_json::to_writer_pretty(writer, &statement)?)
+                })
+                .await?
+        };
+        tracing::info!(?path, "wrote service provider statement");
+        Ok(path)
+    }
+}
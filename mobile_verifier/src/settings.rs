@@ -1,5 +1,16 @@
+use crate::{
+    boosting_oracles::validation::DataSetValidationSettings,
+    cell_type::{CellTypeRegistry, InvalidRadioModel, RadioModel},
+    coverage_map_export::CoverageMapExportSettings,
+    geofence::GeofenceSettings,
+    heartbeats::location_history::LocationDriftSettings,
+    service_provider::ServiceProviderStatementSettings,
+    unique_connections::UniqueConnectionsTable,
+};
 use chrono::{DateTime, Utc};
 use config::{Config, ConfigError, Environment, File};
+use coverage_point_calculator::SpeedtestTierTable;
+use hex_assignments::{AssignmentRuleTable, DataSetDefinition};
 use humantime_serde::re::humantime;
 use serde::Deserialize;
 use std::{
@@ -43,6 +54,18 @@ pub struct Settings {
     /// Poll duration for new data sets
     #[serde(with = "humantime_serde", default = "default_data_sets_poll_duration")]
     pub data_sets_poll_duration: Duration,
+    /// Thresholds a new data set must pass before it is used, otherwise it is
+    /// quarantined
+    #[serde(default)]
+    pub data_set_validation: DataSetValidationSettings,
+    /// Oracle boosting data sets in addition to footfall, landtype,
+    /// urbanization and service provider override
+    #[serde(default)]
+    pub extra_data_sets: Vec<DataSetDefinition>,
+    /// Rules combining the data set assignments of a hex into its boosting
+    /// multiplier. (Default is the HIP-103 table)
+    #[serde(default)]
+    pub hex_assignment_rules: AssignmentRuleTable,
     // Geofencing settings
     pub usa_and_mexico_geofence_regions: String,
     #[serde(default = "default_fencing_resolution")]
@@ -52,6 +75,41 @@ pub struct Settings {
     pub usa_fencing_resolution: u8,
     #[serde(default = "default_cbrs_disable_time")]
     pub cbrs_disable_time: DateTime<Utc>,
+    /// Write an explanation of every radio's proof of coverage reward to the
+    /// output bucket alongside the rewards. (Default is false)
+    #[serde(default)]
+    pub reward_explanations: bool,
+    /// Speedtest tier thresholds by radio type and effective time. Epochs
+    /// before the first profile use the HIP-98 thresholds
+    #[serde(default)]
+    pub speedtest_tiers: SpeedtestTierTable,
+    /// Additional CBRS and Wi-Fi radio models. Radios no configured model
+    /// matches fall back to the built-in ones
+    #[serde(default)]
+    pub radio_models: Vec<RadioModel>,
+    /// Named and versioned geofence regions per feature. While no region is
+    /// active for heartbeats the usa and usa_and_mexico regions are used, and
+    /// while no boosting region is active the urbanization data set is
+    #[serde(default)]
+    pub geofences: Vec<GeofenceSettings>,
+    /// Export the ranked coverage map of every rewarded epoch. (Default is
+    /// disabled)
+    #[serde(default)]
+    pub coverage_map_export: Option<CoverageMapExportSettings>,
+    /// Detection of Wi-Fi hotspots whose heartbeat locations jump between
+    /// distant places or drift away from the asserted location
+    #[serde(default)]
+    pub location_drift: LocationDriftSettings,
+    /// Write a statement of how the service provider rewards of every
+    /// rewarded epoch were worked out. (Default is disabled)
+    #[serde(default)]
+    pub service_provider_statements: Option<ServiceProviderStatementSettings>,
+    /// HIP-134 unique connection qualification rules by effective date.
+    /// Before the first rule takes effect wifi radios with more than 25
+    /// unique connections reported during the epoch qualify if they
+    /// transferred data
+    #[serde(default)]
+    pub unique_connections: UniqueConnectionsTable,
 }
 
 fn default_cbrs_disable_time() -> DateTime<Utc> {
@@ -112,6 +170,10 @@ impl Settings {
             .and_then(|config| config.try_deserialize())
     }
 
+    pub fn cell_type_registry(&self) -> Result<CellTypeRegistry, InvalidRadioModel> {
+        CellTypeRegistry::new(self.radio_models.clone())
+    }
+
     pub fn usa_region_paths(&self) -> anyhow::Result<Vec<std::path::PathBuf>> {
         let paths = std::fs::read_dir(&self.usa_geofence_regions)?;
         Ok(paths
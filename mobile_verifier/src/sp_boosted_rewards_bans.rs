@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
 
 use chrono::{DateTime, TimeZone, Utc};
 use file_store::{
@@ -26,6 +26,7 @@ use helium_proto::services::{
     },
 };
 use mobile_config::client::authorization_client::AuthorizationVerifier;
+use serde::{Deserialize, Serialize};
 use sqlx::{PgPool, Postgres, Transaction};
 use task_manager::{ManagedTask, TaskManager};
 use tokio::sync::mpsc::Receiver;
@@ -96,27 +97,40 @@ impl TryFrom<ServiceProviderBoostedRewardsBannedRadioIngestReportV1> for BannedR
     }
 }
 
+/// The ban report a radio is banned by. Ban reports have no id of their
+/// own, a report is identified by the banned radio and when it was received.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BanEvidence {
+    pub received_timestamp: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+}
+
+/// Radios banned at a point in time with the latest ban report of each
 #[derive(Debug, Default)]
 pub struct BannedRadios {
-    wifi: HashSet<PublicKeyBinary>,
-    cbrs: HashSet<String>,
+    wifi: HashMap<PublicKeyBinary, BanEvidence>,
+    cbrs: HashMap<String, BanEvidence>,
 }
 
 impl BannedRadios {
-    pub fn insert_wifi(&mut self, pubkey: PublicKeyBinary) {
-        self.wifi.insert(pubkey);
+    pub fn insert_wifi(&mut self, pubkey: PublicKeyBinary, ban: BanEvidence) {
+        self.wifi.insert(pubkey, ban);
     }
 
-    pub fn insert_cbrs(&mut self, cbsd_id: String) {
-        self.cbrs.insert(cbsd_id);
+    pub fn insert_cbrs(&mut self, cbsd_id: String, ban: BanEvidence) {
+        self.cbrs.insert(cbsd_id, ban);
     }
 
-    pub fn contains(&self, pubkey: &PublicKeyBinary, cbsd_id_opt: Option<&str>) -> bool {
+    pub fn get(&self, pubkey: &PublicKeyBinary, cbsd_id_opt: Option<&str>) -> Option<&BanEvidence> {
         match cbsd_id_opt {
-            Some(cbsd_id) => self.cbrs.contains(cbsd_id),
-            None => self.wifi.contains(pubkey),
+            Some(cbsd_id) => self.cbrs.get(cbsd_id),
+            None => self.wifi.get(pubkey),
         }
     }
+
+    pub fn contains(&self, pubkey: &PublicKeyBinary, cbsd_id_opt: Option<&str>) -> bool {
+        self.get(pubkey, cbsd_id_opt).is_some()
+    }
 }
 
 pub struct ServiceProviderBoostedRewardsBanIngestor<AV> {
@@ -325,12 +339,14 @@ pub mod db {
     ) -> anyhow::Result<BannedRadios> {
         sqlx::query(
             r#"
-                SELECT distinct radio_type, radio_key
+                SELECT DISTINCT ON (radio_type, radio_key)
+                    radio_type, radio_key, received_timestamp, until
                 FROM sp_boosted_rewards_bans
                 WHERE ban_type = $1
                     AND received_timestamp <= $2
                     AND until > $2 
                     AND COALESCE(invalidated_at > $2, TRUE)
+                ORDER BY radio_type, radio_key, received_timestamp DESC
             "#,
         )
         .bind(ban_type.as_str_name())
@@ -340,9 +356,13 @@ pub mod db {
         .try_fold(BannedRadios::default(), |mut set, row| async move {
             let radio_type = row.get::<HbType, &str>("radio_type");
             let radio_key = row.get::<String, &str>("radio_key");
+            let ban = BanEvidence {
+                received_timestamp: row.get("received_timestamp"),
+                until: row.get("until"),
+            };
             match radio_type {
-                HbType::Wifi => set.insert_wifi(PublicKeyBinary::from_str(&radio_key)?),
-                HbType::Cbrs => set.insert_cbrs(radio_key),
+                HbType::Wifi => set.insert_wifi(PublicKeyBinary::from_str(&radio_key)?, ban),
+                HbType::Cbrs => set.insert_cbrs(radio_key, ban),
             };
 
             Ok(set)
@@ -3,6 +3,7 @@ use crate::{
     Settings,
 };
 use chrono::{DateTime, Utc};
+use coverage_point_calculator::SpeedtestTierTable;
 use file_store::{
     file_info_poller::{FileInfoStream, LookbackBehavior},
     file_sink::FileSinkClient,
@@ -60,6 +61,7 @@ pub struct SpeedtestDaemon<GIR> {
     speedtests: Receiver<FileInfoStream<CellSpeedtestIngestReport>>,
     speedtest_avg_file_sink: FileSinkClient<SpeedtestAvgProto>,
     verified_speedtest_file_sink: FileSinkClient<VerifiedSpeedtestProto>,
+    speedtest_tiers: SpeedtestTierTable,
 }
 
 impl<GIR> SpeedtestDaemon<GIR>
@@ -98,6 +100,7 @@ where
             speedtests,
             speedtests_avg,
             speedtests_validity,
+            settings.speedtest_tiers.clone(),
         );
 
         Ok(TaskManager::builder()
@@ -113,6 +116,7 @@ where
         speedtests: Receiver<FileInfoStream<CellSpeedtestIngestReport>>,
         speedtest_avg_file_sink: FileSinkClient<SpeedtestAvgProto>,
         verified_speedtest_file_sink: FileSinkClient<VerifiedSpeedtestProto>,
+        speedtest_tiers: SpeedtestTierTable,
     ) -> Self {
         Self {
             pool,
@@ -120,6 +124,7 @@ where
             speedtests,
             speedtest_avg_file_sink,
             verified_speedtest_file_sink,
+            speedtest_tiers,
         }
     }
 
@@ -160,7 +165,10 @@ where
                     &mut transaction,
                 )
                 .await?;
-                let average = SpeedtestAverage::from(latest_speedtests);
+                let thresholds = self
+                    .speedtest_tiers
+                    .base_thresholds(speedtest_report.report.timestamp);
+                let average = SpeedtestAverage::new(latest_speedtests, thresholds);
                 average.write(&self.speedtest_avg_file_sink).await?;
             }
             // write out paper trail of speedtest validity
@@ -181,7 +189,7 @@ where
 
         match self
             .gateway_info_resolver
-            .resolve_gateway_info(&pubkey)
+            .resolve_gateway_info_at(&pubkey, speedtest.report.timestamp)
             .await?
         {
             Some(gw_info) if gw_info.is_data_only() => {
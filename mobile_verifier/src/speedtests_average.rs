@@ -1,5 +1,6 @@
 use crate::speedtests::{self, Speedtest};
 use chrono::{DateTime, Utc};
+use coverage_point_calculator::{BytesPs, SpeedtestTierThresholds};
 use file_store::{
     file_sink::FileSinkClient,
     traits::{MsgTimestamp, TimestampEncode},
@@ -11,9 +12,6 @@ use rust_decimal_macros::dec;
 use std::collections::HashMap;
 
 pub const SPEEDTEST_LAPSE: i64 = 48;
-const MIN_DOWNLOAD: u64 = mbps(30);
-const MIN_UPLOAD: u64 = mbps(2);
-const MAX_LATENCY: u32 = 100;
 pub const MIN_REQUIRED_SAMPLES: usize = 2;
 
 pub type EpochAverages = HashMap<PublicKeyBinary, SpeedtestAverage>;
@@ -30,8 +28,9 @@ pub struct SpeedtestAverage {
     pub speedtests: Vec<Speedtest>,
 }
 
-impl From<Vec<Speedtest>> for SpeedtestAverage {
-    fn from(speedtests: Vec<Speedtest>) -> Self {
+impl SpeedtestAverage {
+    /// Average of `speedtests`, tiered and validated against `thresholds`
+    pub fn new(speedtests: Vec<Speedtest>, thresholds: &SpeedtestTierThresholds) -> Self {
         let mut id = vec![]; // eww!
         let mut window_size = 0;
         let mut sum_upload = 0;
@@ -55,12 +54,14 @@ impl From<Vec<Speedtest>> for SpeedtestAverage {
                 upload_speed_avg_bps,
                 download_speed_avg_bps,
                 latency_avg_ms,
+                thresholds,
             );
             let tier = SpeedtestTier::new(
                 window_size as usize,
                 upload_speed_avg_bps,
                 download_speed_avg_bps,
                 latency_avg_ms,
+                thresholds,
             );
             let reward_multiplier = tier.into_multiplier();
             SpeedtestAverage {
@@ -140,12 +141,14 @@ impl SpeedtestTier {
         upload_speed_avg_bps: u64,
         download_speed_avg_bps: u64,
         latency_avg_ms: u32,
+        thresholds: &SpeedtestTierThresholds,
     ) -> SpeedtestTier {
         calculate_tier(
             window_size,
             upload_speed_avg_bps,
             download_speed_avg_bps,
             latency_avg_ms,
+            thresholds,
         )
     }
 
@@ -158,46 +161,17 @@ impl SpeedtestTier {
             Self::Failed => dec!(0.0),
         }
     }
+}
 
-    fn from_download_speed(download_speed: u64) -> Self {
-        if download_speed >= mbps(100) {
-            Self::Good
-        } else if download_speed >= mbps(75) {
-            Self::Acceptable
-        } else if download_speed >= mbps(50) {
-            Self::Degraded
-        } else if download_speed >= mbps(30) {
-            Self::Poor
-        } else {
-            Self::Failed
-        }
-    }
-
-    fn from_upload_speed(upload_speed: u64) -> Self {
-        if upload_speed >= mbps(10) {
-            Self::Good
-        } else if upload_speed >= mbps(8) {
-            Self::Acceptable
-        } else if upload_speed >= mbps(5) {
-            Self::Degraded
-        } else if upload_speed >= mbps(2) {
-            Self::Poor
-        } else {
-            Self::Failed
-        }
-    }
-
-    fn from_latency(latency: u32) -> Self {
-        if latency < 50 {
-            Self::Good
-        } else if latency < 60 {
-            Self::Acceptable
-        } else if latency < 75 {
-            Self::Degraded
-        } else if latency < 100 {
-            Self::Poor
-        } else {
-            Self::Failed
+impl From<coverage_point_calculator::SpeedtestTier> for SpeedtestTier {
+    fn from(tier: coverage_point_calculator::SpeedtestTier) -> Self {
+        use coverage_point_calculator::SpeedtestTier as Tier;
+        match tier {
+            Tier::Good => Self::Good,
+            Tier::Acceptable => Self::Acceptable,
+            Tier::Degraded => Self::Degraded,
+            Tier::Poor => Self::Poor,
+            Tier::Fail => Self::Failed,
         }
     }
 }
@@ -226,12 +200,13 @@ impl SpeedtestAverages {
     pub async fn aggregate_epoch_averages(
         epoch_end: DateTime<Utc>,
         pool: &sqlx::Pool<sqlx::Postgres>,
+        thresholds: &SpeedtestTierThresholds,
     ) -> Result<SpeedtestAverages, sqlx::Error> {
         let averages: EpochAverages = speedtests::aggregate_epoch_speedtests(epoch_end, pool)
             .await?
             .into_iter()
             .map(|(pub_key, speedtests)| {
-                let average = SpeedtestAverage::from(speedtests);
+                let average = SpeedtestAverage::new(speedtests, thresholds);
                 (pub_key, average)
             })
             .collect();
@@ -240,19 +215,30 @@ impl SpeedtestAverages {
     }
 }
 
+/// Tier of a speedtest average according to `thresholds`, usually the
+/// [SpeedtestTierTable::base_thresholds] in effect for the epoch. The tier
+/// used for rewards depends on the radio type and is calculated by
+/// `coverage_point_calculator` from the epoch's [SpeedtestTierTable].
+///
+/// [SpeedtestTierTable]: coverage_point_calculator::SpeedtestTierTable
+/// [SpeedtestTierTable::base_thresholds]: coverage_point_calculator::SpeedtestTierTable::base_thresholds
 pub fn calculate_tier(
     window_size: usize,
     upload_speed_avg_bps: u64,
     download_speed_avg_bps: u64,
     latency_avg_ms: u32,
+    thresholds: &SpeedtestTierThresholds,
 ) -> SpeedtestTier {
     if window_size < MIN_REQUIRED_SAMPLES {
-        SpeedtestTier::Failed
-    } else {
-        SpeedtestTier::from_download_speed(download_speed_avg_bps)
-            .min(SpeedtestTier::from_upload_speed(upload_speed_avg_bps))
-            .min(SpeedtestTier::from_latency(latency_avg_ms))
+        return SpeedtestTier::Failed;
     }
+    let average = coverage_point_calculator::Speedtest {
+        upload_speed: BytesPs::new(upload_speed_avg_bps),
+        download_speed: BytesPs::new(download_speed_avg_bps),
+        latency_millis: latency_avg_ms,
+        timestamp: Utc::now(),
+    };
+    average.tier_with(thresholds).into()
 }
 
 pub fn validity(
@@ -260,17 +246,19 @@ pub fn validity(
     upload_speed_avg_bps: u64,
     download_speed_avg_bps: u64,
     latency_avg_ms: u32,
+    thresholds: &SpeedtestTierThresholds,
 ) -> proto::SpeedtestAvgValidity {
+    // Anything below the Poor tier is not a valid speedtest
     if window_size < MIN_REQUIRED_SAMPLES {
         return proto::SpeedtestAvgValidity::TooFewSamples;
     }
-    if download_speed_avg_bps < MIN_DOWNLOAD {
+    if download_speed_avg_bps < mbps(thresholds.poor.min_download_mbps) {
         return proto::SpeedtestAvgValidity::SlowDownloadSpeed;
     }
-    if upload_speed_avg_bps < MIN_UPLOAD {
+    if upload_speed_avg_bps < mbps(thresholds.poor.min_upload_mbps) {
         return proto::SpeedtestAvgValidity::SlowUploadSpeed;
     }
-    if latency_avg_ms > MAX_LATENCY {
+    if latency_avg_ms > thresholds.poor.max_latency_millis + 1 {
         return proto::SpeedtestAvgValidity::HighLatency;
     }
     proto::SpeedtestAvgValidity::Valid
@@ -292,10 +280,15 @@ mod test {
                 self.upload_speed_avg_bps,
                 self.download_speed_avg_bps,
                 self.latency_avg_ms,
+                &SpeedtestTierThresholds::DEFAULT,
             )
         }
     }
 
+    fn average(speedtests: Vec<Speedtest>) -> SpeedtestAverage {
+        SpeedtestAverage::new(speedtests, &SpeedtestTierThresholds::DEFAULT)
+    }
+
     fn bytes_per_s(mbps: u64) -> u64 {
         mbps * 125000
     }
@@ -311,7 +304,7 @@ mod test {
     #[test]
     fn validate_good_tier() {
         assert_eq!(
-            SpeedtestAverage::from(vec![speedtest(10, 100, 49), speedtest(10, 100, 50)]).tier(),
+            average(vec![speedtest(10, 100, 49), speedtest(10, 100, 50)]).tier(),
             SpeedtestTier::Good
         );
     }
@@ -319,20 +312,20 @@ mod test {
     #[test]
     fn validate_acceptable_tier() {
         assert_eq!(
-            SpeedtestAverage::from(vec![speedtest(9, 100, 49), speedtest(10, 100, 50)]).tier(),
+            average(vec![speedtest(9, 100, 49), speedtest(10, 100, 50)]).tier(),
             SpeedtestTier::Acceptable
         );
         assert_eq!(
-            SpeedtestAverage::from(vec![speedtest(10, 99, 49), speedtest(10, 100, 50)]).tier(),
+            average(vec![speedtest(10, 99, 49), speedtest(10, 100, 50)]).tier(),
             SpeedtestTier::Acceptable
         );
         assert_eq!(
-            SpeedtestAverage::from(vec![speedtest(10, 100, 50), speedtest(10, 100, 50)]).tier(),
+            average(vec![speedtest(10, 100, 50), speedtest(10, 100, 50)]).tier(),
             SpeedtestTier::Acceptable
         );
 
         assert_eq!(
-            SpeedtestAverage::from(vec![speedtest(8, 75, 59), speedtest(8, 100, 60)]).tier(),
+            average(vec![speedtest(8, 75, 59), speedtest(8, 100, 60)]).tier(),
             SpeedtestTier::Acceptable
         );
     }
@@ -340,20 +333,20 @@ mod test {
     #[test]
     fn validate_degraded_tier() {
         assert_eq!(
-            SpeedtestAverage::from(vec![speedtest(7, 75, 59), speedtest(8, 75, 60)]).tier(),
+            average(vec![speedtest(7, 75, 59), speedtest(8, 75, 60)]).tier(),
             SpeedtestTier::Degraded
         );
         assert_eq!(
-            SpeedtestAverage::from(vec![speedtest(8, 74, 59), speedtest(8, 75, 60)]).tier(),
+            average(vec![speedtest(8, 74, 59), speedtest(8, 75, 60)]).tier(),
             SpeedtestTier::Degraded
         );
         assert_eq!(
-            SpeedtestAverage::from(vec![speedtest(8, 75, 60), speedtest(8, 75, 60)]).tier(),
+            average(vec![speedtest(8, 75, 60), speedtest(8, 75, 60)]).tier(),
             SpeedtestTier::Degraded
         );
 
         assert_eq!(
-            SpeedtestAverage::from(vec![speedtest(5, 50, 74), speedtest(5, 50, 75)]).tier(),
+            average(vec![speedtest(5, 50, 74), speedtest(5, 50, 75)]).tier(),
             SpeedtestTier::Degraded
         );
     }
@@ -361,20 +354,20 @@ mod test {
     #[test]
     fn validate_poor_tier() {
         assert_eq!(
-            SpeedtestAverage::from(vec![speedtest(4, 50, 74), speedtest(5, 50, 75)]).tier(),
+            average(vec![speedtest(4, 50, 74), speedtest(5, 50, 75)]).tier(),
             SpeedtestTier::Poor
         );
         assert_eq!(
-            SpeedtestAverage::from(vec![speedtest(5, 49, 74), speedtest(5, 50, 75)]).tier(),
+            average(vec![speedtest(5, 49, 74), speedtest(5, 50, 75)]).tier(),
             SpeedtestTier::Poor
         );
         assert_eq!(
-            SpeedtestAverage::from(vec![speedtest(5, 50, 75), speedtest(5, 50, 75)]).tier(),
+            average(vec![speedtest(5, 50, 75), speedtest(5, 50, 75)]).tier(),
             SpeedtestTier::Poor
         );
 
         assert_eq!(
-            SpeedtestAverage::from(vec![speedtest(2, 30, 99), speedtest(2, 30, 100)]).tier(),
+            average(vec![speedtest(2, 30, 99), speedtest(2, 30, 100)]).tier(),
             SpeedtestTier::Poor
         );
     }
@@ -382,24 +375,43 @@ mod test {
     #[test]
     fn validate_failed_tier() {
         assert_eq!(
-            SpeedtestAverage::from(vec![speedtest(1, 30, 99), speedtest(2, 30, 100)]).tier(),
+            average(vec![speedtest(1, 30, 99), speedtest(2, 30, 100)]).tier(),
             SpeedtestTier::Failed
         );
         assert_eq!(
-            SpeedtestAverage::from(vec![speedtest(2, 29, 99), speedtest(2, 30, 100)]).tier(),
+            average(vec![speedtest(2, 29, 99), speedtest(2, 30, 100)]).tier(),
             SpeedtestTier::Failed
         );
         assert_eq!(
-            SpeedtestAverage::from(vec![speedtest(2, 30, 100), speedtest(2, 30, 100)]).tier(),
+            average(vec![speedtest(2, 30, 100), speedtest(2, 30, 100)]).tier(),
             SpeedtestTier::Failed
         );
 
         assert_eq!(
-            SpeedtestAverage::from(vec![speedtest(2, 30, 99)]).tier(),
+            average(vec![speedtest(2, 30, 99)]).tier(),
             SpeedtestTier::Failed
         );
     }
 
+    #[test]
+    fn validate_with_epoch_thresholds() {
+        let mut thresholds = SpeedtestTierThresholds::DEFAULT;
+        thresholds.poor.min_download_mbps = 50;
+        let strict = SpeedtestAverage::new(
+            vec![speedtest(2, 30, 99), speedtest(2, 30, 99)],
+            &thresholds,
+        );
+        assert_eq!(
+            strict.validity,
+            proto::SpeedtestAvgValidity::SlowDownloadSpeed
+        );
+        assert_eq!(strict.reward_multiplier, Decimal::ZERO);
+        assert_eq!(
+            average(vec![speedtest(2, 30, 99), speedtest(2, 30, 99)]).validity,
+            proto::SpeedtestAvgValidity::Valid
+        );
+    }
+
     fn speedtest(upload: u64, download: u64, latency: u32) -> Speedtest {
         let pubkey: PublicKeyBinary = "112NqN2WWMwtK29PMzRby62fDydBJfsCLkCAf392stdok48ovNT6"
             .parse()
@@ -5,6 +5,9 @@ use sqlx::{Pool, Postgres};
 
 const LAST_REWARDED_END_TIME: &str = "last_rewarded_end_time";
 const DATA_TRANSFER_REWARDS_SCALE: &str = "data_transfer_rewards_scale";
+const UNIQUE_CONNECTIONS_FLAGGED: &str = "unique_connections_flagged";
+const COVERAGE_MAP_EXPORT_ERRORS: &str = "coverage_map_export_errors";
+const SERVICE_PROVIDER_STATEMENT_ERRORS: &str = "service_provider_statement_errors";
 
 pub async fn initialize(db: &Pool<Postgres>) -> anyhow::Result<()> {
     let next_reward_epoch = rewarder::next_reward_epoch(db).await?;
@@ -20,3 +23,15 @@ pub fn last_rewarded_end_time(timestamp: DateTime<Utc>) {
 pub fn data_transfer_rewards_scale(scale: f64) {
     metrics::gauge!(DATA_TRANSFER_REWARDS_SCALE).set(scale);
 }
+
+pub fn unique_connections_flagged(count: usize) {
+    metrics::gauge!(UNIQUE_CONNECTIONS_FLAGGED).set(count as f64);
+}
+
+pub fn coverage_map_export_error() {
+    metrics::counter!(COVERAGE_MAP_EXPORT_ERRORS).increment(1);
+}
+
+pub fn service_provider_statement_error() {
+    metrics::counter!(SERVICE_PROVIDER_STATEMENT_ERRORS).increment(1);
+}
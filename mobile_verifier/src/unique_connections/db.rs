@@ -1,4 +1,4 @@
-use std::ops::Range;
+use std::{collections::HashMap, ops::Range};
 
 use chrono::{DateTime, Utc};
 use file_store::unique_connections::UniqueConnectionsIngestReport;
@@ -6,12 +6,12 @@ use futures::TryStreamExt;
 use helium_crypto::PublicKeyBinary;
 use sqlx::{FromRow, PgPool, Postgres, QueryBuilder, Transaction};
 
-use super::UniqueConnectionCounts;
-
+/// Latest unique connection count of each radio from reports received in
+/// the range
 pub async fn get(
     db: &PgPool,
     reward_period: &Range<DateTime<Utc>>,
-) -> anyhow::Result<UniqueConnectionCounts> {
+) -> anyhow::Result<HashMap<PublicKeyBinary, u64>> {
     #[derive(FromRow)]
     struct UniqueConnections {
         hotspot_pubkey: PublicKeyBinary,
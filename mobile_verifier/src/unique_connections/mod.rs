@@ -1,22 +1,302 @@
 pub mod db;
 pub mod ingestor;
 
+use chrono::{DateTime, Utc};
 use coverage_point_calculator::RadioType;
 use helium_crypto::PublicKeyBinary;
-use std::collections::HashMap;
+use serde::Deserialize;
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+    time::Duration,
+};
 
-pub type UniqueConnectionCounts = HashMap<PublicKeyBinary, u64>;
+use crate::data_session::HotspotMap;
 
 // hip-134:
 // https://github.com/helium/HIP/blob/main/0134-reward-mobile-carrier-offload-hotspots.md
 // A Hotspot serving >25 unique connections, as defined by the Carrier utlizing the hotspots for Carrier Offload, on a seven day rolling average.
 pub const MINIMUM_UNIQUE_CONNECTIONS: u64 = 25;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QualifyingRadioType {
+    IndoorWifi,
+    OutdoorWifi,
+    IndoorCbrs,
+    OutdoorCbrs,
+}
+
+impl From<RadioType> for QualifyingRadioType {
+    fn from(radio_type: RadioType) -> Self {
+        match radio_type {
+            RadioType::IndoorWifi => Self::IndoorWifi,
+            RadioType::OutdoorWifi => Self::OutdoorWifi,
+            RadioType::IndoorCbrs => Self::IndoorCbrs,
+            RadioType::OutdoorCbrs => Self::OutdoorCbrs,
+        }
+    }
+}
+
+/// How unique connection reports qualify a radio for the oracle boost, from
+/// `effective_from` until the next rule takes effect
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct UniqueConnectionsRule {
+    pub effective_from: DateTime<Utc>,
+    /// A radio qualifies with more unique connections than this
+    #[serde(default = "default_minimum_unique_connections")]
+    pub minimum_unique_connections: u64,
+    #[serde(default = "default_radio_types")]
+    pub radio_types: Vec<QualifyingRadioType>,
+    /// Reports received within this long before the end of the epoch are
+    /// used, the latest per radio. Only reports received during the epoch are
+    /// used if unset
+    #[serde(default, with = "humantime_serde")]
+    pub window: Option<Duration>,
+    /// Flag radios reporting unique connections without any data transferred
+    /// in the epoch rather than qualifying them. (Default is true)
+    #[serde(default = "default_require_data_transfer")]
+    pub require_data_transfer: bool,
+}
+
+fn default_minimum_unique_connections() -> u64 {
+    MINIMUM_UNIQUE_CONNECTIONS
+}
+
+fn default_radio_types() -> Vec<QualifyingRadioType> {
+    vec![
+        QualifyingRadioType::IndoorWifi,
+        QualifyingRadioType::OutdoorWifi,
+    ]
+}
+
+fn default_require_data_transfer() -> bool {
+    true
+}
+
+impl Default for UniqueConnectionsRule {
+    /// The rule before any configured rule takes effect
+    fn default() -> Self {
+        Self {
+            effective_from: DateTime::UNIX_EPOCH,
+            minimum_unique_connections: MINIMUM_UNIQUE_CONNECTIONS,
+            radio_types: default_radio_types(),
+            window: None,
+            require_data_transfer: default_require_data_transfer(),
+        }
+    }
+}
+
+impl UniqueConnectionsRule {
+    /// Range of received timestamps of the reports used for the epoch
+    pub fn report_range(&self, epoch: &Range<DateTime<Utc>>) -> Range<DateTime<Utc>> {
+        match self
+            .window
+            .and_then(|window| chrono::Duration::from_std(window).ok())
+        {
+            Some(window) => (epoch.end - window).min(epoch.start)..epoch.end,
+            None => epoch.clone(),
+        }
+    }
+}
+
+/// All unique connection rules over time
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct UniqueConnectionsTable {
+    rules: Vec<UniqueConnectionsRule>,
+}
+
+impl UniqueConnectionsTable {
+    pub fn new(rules: Vec<UniqueConnectionsRule>) -> Self {
+        Self { rules }
+    }
+
+    /// The latest rule effective at `at`
+    pub fn rule(&self, at: DateTime<Utc>) -> UniqueConnectionsRule {
+        self.rules
+            .iter()
+            .filter(|rule| rule.effective_from <= at)
+            .max_by_key(|rule| rule.effective_from)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Reports received before this are not needed by any rule effective
+    /// after the epoch
+    pub fn retain_after(&self, epoch: &Range<DateTime<Utc>>) -> DateTime<Utc> {
+        let next_epoch = epoch.end..epoch.end + (epoch.end - epoch.start);
+        self.rules
+            .iter()
+            .cloned()
+            .chain(std::iter::once(UniqueConnectionsRule::default()))
+            .map(|rule| rule.report_range(&next_epoch).start)
+            .min()
+            .unwrap_or(epoch.end)
+            .min(epoch.start)
+    }
+}
+
+/// The unique connection counts of an epoch and the rule they are judged by
+#[derive(Debug, Clone, Default)]
+pub struct UniqueConnectionCounts {
+    counts: HashMap<PublicKeyBinary, u64>,
+    rule: UniqueConnectionsRule,
+    without_data_transfer: HashSet<PublicKeyBinary>,
+}
+
+impl UniqueConnectionCounts {
+    pub fn new(counts: HashMap<PublicKeyBinary, u64>, rule: UniqueConnectionsRule) -> Self {
+        Self {
+            counts,
+            rule,
+            without_data_transfer: HashSet::new(),
+        }
+    }
+
+    pub fn insert(&mut self, pubkey: PublicKeyBinary, unique_connections: u64) {
+        self.counts.insert(pubkey, unique_connections);
+    }
+
+    pub fn get(&self, pubkey: &PublicKeyBinary) -> Option<&u64> {
+        self.counts.get(pubkey)
+    }
+
+    pub fn rule(&self) -> &UniqueConnectionsRule {
+        &self.rule
+    }
+
+    /// Records the hotspots that report enough unique connections to qualify
+    /// but transferred no data in the epoch, if the rule requires data
+    /// transfer. Their radios of a qualifying type are flagged
+    pub fn flag_without_data_transfer(&mut self, data_transfer: &HotspotMap) {
+        if !self.rule.require_data_transfer {
+            return;
+        }
+        let minimum = self.rule.minimum_unique_connections;
+        self.without_data_transfer = self
+            .counts
+            .iter()
+            .filter(|(_, count)| **count > minimum)
+            .filter(|(pubkey, _)| {
+                !data_transfer
+                    .get(*pubkey)
+                    .is_some_and(|transfer| transfer.rewardable_bytes > 0)
+            })
+            .map(|(pubkey, _)| pubkey.clone())
+            .collect();
+    }
+
+    /// Whether the radio claimed unique connections that would qualify it
+    /// without transferring data
+    pub fn is_flagged(&self, pubkey: &PublicKeyBinary, radio_type: &RadioType) -> bool {
+        self.rule
+            .radio_types
+            .contains(&QualifyingRadioType::from(*radio_type))
+            && self.without_data_transfer.contains(pubkey)
+    }
+}
+
 pub fn is_qualified(
     unique_connections: &UniqueConnectionCounts,
     pubkey: &PublicKeyBinary,
     radio_type: &RadioType,
 ) -> bool {
+    let rule = &unique_connections.rule;
     let uniq_conns = unique_connections.get(pubkey).cloned().unwrap_or_default();
-    radio_type.is_wifi() && uniq_conns > MINIMUM_UNIQUE_CONNECTIONS
+    rule.radio_types
+        .contains(&QualifyingRadioType::from(*radio_type))
+        && uniq_conns > rule.minimum_unique_connections
+        && !unique_connections.is_flagged(pubkey, radio_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_session::HotspotReward;
+    use helium_crypto::{KeyTag, Keypair};
+    use rand::rngs::OsRng;
+
+    fn pubkey() -> PublicKeyBinary {
+        Keypair::generate(KeyTag::default(), &mut OsRng)
+            .public_key()
+            .to_vec()
+            .into()
+    }
+
+    #[test]
+    fn rule_by_effective_time() {
+        let effective_from = "2025-01-01T00:00:00Z".parse().unwrap();
+        let rule = UniqueConnectionsRule {
+            effective_from,
+            minimum_unique_connections: 10,
+            radio_types: vec![QualifyingRadioType::IndoorWifi],
+            window: Some(Duration::from_secs(7 * 24 * 60 * 60)),
+            require_data_transfer: true,
+        };
+        let table = UniqueConnectionsTable::new(vec![rule.clone()]);
+
+        let before = effective_from - chrono::Duration::seconds(1);
+        assert_eq!(UniqueConnectionsRule::default(), table.rule(before));
+        assert_eq!(rule, table.rule(effective_from));
+
+        let epoch = effective_from..effective_from + chrono::Duration::days(1);
+        assert_eq!(
+            rule.report_range(&epoch).start,
+            epoch.end - chrono::Duration::days(7)
+        );
+        assert_eq!(UniqueConnectionsRule::default().report_range(&epoch), epoch);
+        assert_eq!(
+            table.retain_after(&epoch),
+            epoch.end + chrono::Duration::days(1) - chrono::Duration::days(7)
+        );
+    }
+
+    #[test]
+    fn qualification_by_radio_type_and_data_transfer() {
+        let rule = UniqueConnectionsRule {
+            minimum_unique_connections: 10,
+            radio_types: vec![QualifyingRadioType::OutdoorWifi],
+            ..Default::default()
+        };
+        let (busy, idle, few) = (pubkey(), pubkey(), pubkey());
+
+        let mut counts = UniqueConnectionCounts::new(
+            HashMap::from([(busy.clone(), 11), (idle.clone(), 50), (few.clone(), 10)]),
+            rule.clone(),
+        );
+        let data_transfer = HotspotMap::from([(
+            busy.clone(),
+            HotspotReward {
+                rewardable_bytes: 1_000,
+                rewardable_dc: 1,
+            },
+        )]);
+
+        // data transfer is required by default
+        assert!(rule.require_data_transfer);
+        counts.flag_without_data_transfer(&data_transfer);
+
+        assert!(is_qualified(&counts, &busy, &RadioType::OutdoorWifi));
+        assert!(!is_qualified(&counts, &busy, &RadioType::IndoorWifi));
+        assert!(!is_qualified(&counts, &idle, &RadioType::OutdoorWifi));
+        assert!(!is_qualified(&counts, &few, &RadioType::OutdoorWifi));
+        assert!(counts.is_flagged(&idle, &RadioType::OutdoorWifi));
+        // radios of a type that cannot qualify are not flagged
+        assert!(!counts.is_flagged(&idle, &RadioType::IndoorWifi));
+        assert!(!counts.is_flagged(&busy, &RadioType::OutdoorWifi));
+        assert!(!counts.is_flagged(&few, &RadioType::OutdoorWifi));
+
+        // without requiring data transfer nothing is flagged
+        let mut counts = UniqueConnectionCounts::new(
+            counts.counts,
+            UniqueConnectionsRule {
+                require_data_transfer: false,
+                ..rule
+            },
+        );
+        counts.flag_without_data_transfer(&data_transfer);
+        assert!(is_qualified(&counts, &idle, &RadioType::OutdoorWifi));
+        assert!(!counts.is_flagged(&idle, &RadioType::OutdoorWifi));
+    }
 }
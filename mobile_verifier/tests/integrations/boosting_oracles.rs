@@ -1,6 +1,7 @@
 use crate::common::{self, GatewayClientAllOwnersValid};
 use anyhow::Context;
 use chrono::{DateTime, Duration, Utc};
+use coverage_point_calculator::{SpeedtestTierTable, SpeedtestTierThresholds};
 use file_store::{
     coverage::RadioHexSignalLevel,
     heartbeat::{CbrsHeartbeat, CbrsHeartbeatIngestReport},
@@ -12,10 +13,11 @@ use helium_crypto::PublicKeyBinary;
 use helium_proto::services::poc_mobile::{
     CoverageObjectValidity, OracleBoostingHexAssignment, SignalLevel,
 };
-use hex_assignments::{Assignment, HexBoostData};
+use hex_assignments::{assignment::HexAssignments, Assignment, AssignmentRuleTable, HexBoostData};
 use hextree::Cell;
 use mobile_config::boosted_hex_info::BoostedHexes;
 use mobile_verifier::{
+    cell_type::CellTypeRegistry,
     coverage::{CoverageClaimTimeCache, CoverageObject, CoverageObjectCache},
     geofence::GeofenceValidator,
     heartbeats::{last_location::LocationCache, Heartbeat, HeartbeatReward, ValidatedHeartbeat},
@@ -222,6 +224,83 @@ async fn test_footfall_and_urbanization_report(pool: PgPool) -> anyhow::Result<(
     Ok(())
 }
 
+#[sqlx::test]
+async fn test_configured_assignment_rules(pool: PgPool) -> anyhow::Result<()> {
+    let uuid = Uuid::new_v4();
+    let urbanized_hex = "8c2681a3064d9ff";
+    let rural_hex = "8c2681a3064dbff";
+
+    let coverage_object = file_store::coverage::CoverageObject {
+        pub_key: PublicKeyBinary::from(vec![1]),
+        uuid,
+        key_type: file_store::coverage::KeyType::CbsdId(
+            "P27-SCE4255W120200039521XGB0102".to_string(),
+        ),
+        coverage_claim_time: "2022-01-01 00:00:00.000000000 UTC".parse()?,
+        indoor: true,
+        signature: Vec::new(),
+        coverage: vec![
+            signal_level(urbanized_hex, SignalLevel::High)?,
+            signal_level(rural_hex, SignalLevel::High)?,
+        ],
+        trust_score: 1000,
+    };
+    let mut transaction = pool.begin().await?;
+    CoverageObject {
+        coverage_object,
+        validity: CoverageObjectValidity::Valid,
+    }
+    .save(&mut transaction)
+    .await?;
+    transaction.commit().await?;
+
+    let urbanized = HashMap::from([
+        (hex_cell(urbanized_hex), Assignment::A),
+        (hex_cell(rural_hex), Assignment::B),
+    ]);
+    let rules: AssignmentRuleTable = serde_json::from_str(
+        r#"[
+            { "when": { "urbanized": "a" }, "multiplier": "0.8" },
+            { "multiplier": "0.25" }
+        ]"#,
+    )?;
+    let hex_boost_data = HexBoostData::builder()
+        .footfall(Assignment::C)
+        .landtype(Assignment::C)
+        .urbanization(urbanized)
+        .service_provider_override(Assignment::C)
+        .rules(rules)
+        .build()?;
+    let oba = common::set_unassigned_oracle_boosting_assignments(&pool, &hex_boost_data).await?;
+
+    let mut multipliers: Vec<_> = oba[0]
+        .assignments
+        .iter()
+        .map(|hex| (hex.location.clone(), hex.assignment_multiplier))
+        .collect();
+    multipliers.sort();
+    assert_eq!(
+        multipliers,
+        vec![
+            (urbanized_hex.to_string(), 800),
+            (rural_hex.to_string(), 250)
+        ]
+    );
+
+    // The multiplier is stored with the assignments for the rewarder
+    let multipliers: Vec<Decimal> = sqlx::query_as::<_, HexAssignments>(
+        "SELECT footfall, landtype, urbanized, service_provider_override, assignment_multiplier FROM hexes ORDER BY hex",
+    )
+    .fetch_all(&pool)
+    .await?
+    .iter()
+    .map(HexAssignments::boosting_multiplier)
+    .collect();
+    assert_eq!(multipliers, vec![dec!(0.8), dec!(0.25)]);
+
+    Ok(())
+}
+
 #[sqlx::test]
 async fn test_footfall_and_urbanization_and_landtype_and_service_provider_override(
     pool: PgPool,
@@ -365,6 +444,7 @@ async fn test_footfall_and_urbanization_and_landtype_and_service_provider_overri
     let location_cache = LocationCache::new(&pool);
 
     let epoch = start..end;
+    let cell_types = CellTypeRegistry::default();
     let mut heartbeats = pin!(ValidatedHeartbeat::validate_heartbeats(
         stream::iter(heartbeats.map(Heartbeat::from)),
         &GatewayClientAllOwnersValid,
@@ -373,6 +453,7 @@ async fn test_footfall_and_urbanization_and_landtype_and_service_provider_overri
         2000,
         &epoch,
         &MockGeofence,
+        &cell_types,
     ));
     let mut transaction = pool.begin().await?;
     while let Some(heartbeat) = heartbeats.next().await.transpose()? {
@@ -401,7 +482,10 @@ async fn test_footfall_and_urbanization_and_landtype_and_service_provider_overri
         acceptable_speedtest(owner.clone(), end),
     ];
     let mut averages = HashMap::new();
-    averages.insert(owner.clone(), SpeedtestAverage::from(owner_speedtests));
+    averages.insert(
+        owner.clone(),
+        SpeedtestAverage::new(owner_speedtests, &SpeedtestTierThresholds::DEFAULT),
+    );
     let speedtest_avgs = SpeedtestAverages { averages };
 
     let heartbeats = HeartbeatReward::validated(&pool, &epoch);
@@ -414,6 +498,7 @@ async fn test_footfall_and_urbanization_and_landtype_and_service_provider_overri
         &BannedRadios::default(),
         &UniqueConnectionCounts::default(),
         &epoch,
+        &SpeedtestTierTable::default(),
     )
     .await
     .context("aggregating points")?;
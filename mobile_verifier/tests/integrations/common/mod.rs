@@ -26,7 +26,9 @@ use mobile_config::{
     sub_dao_epoch_reward_info::EpochRewardInfo,
 };
 use mobile_verifier::{
-    boosting_oracles::AssignedCoverageObjects, GatewayResolution, GatewayResolver, PriceInfo,
+    boosting_oracles::AssignedCoverageObjects,
+    unique_connections::{UniqueConnectionsRule, UniqueConnectionsTable},
+    GatewayResolution, GatewayResolver, PriceInfo,
 };
 use rust_decimal::{prelude::ToPrimitive, Decimal};
 use rust_decimal_macros::dec;
@@ -289,6 +291,15 @@ pub fn mock_hex_boost_data_bad(
         .unwrap()
 }
 
+/// Unique connection rules that qualify radios on their reported unique
+/// connections alone, for tests that don't seed data sessions
+pub fn unique_connections_without_data_transfer() -> UniqueConnectionsTable {
+    UniqueConnectionsTable::new(vec![UniqueConnectionsRule {
+        require_data_transfer: false,
+        ..Default::default()
+    }])
+}
+
 type MockAssignmentMap = HashMap<hextree::Cell, Assignment>;
 
 #[allow(dead_code)]
@@ -401,6 +412,7 @@ impl GatewayResolver for GatewayClientAllOwnersValid {
     async fn resolve_gateway(
         &self,
         _address: &PublicKeyBinary,
+        _at: DateTime<Utc>,
     ) -> Result<GatewayResolution, Self::Error> {
         Ok(GatewayResolution::AssertedLocation(0x8c2681a3064d9ff))
     }
@@ -3,6 +3,8 @@ use crate::common::{
     RadioRewardV2Ext, EMISSIONS_POOL_IN_BONES_24_HOURS,
 };
 use chrono::{DateTime, Duration as ChronoDuration, Duration, Utc};
+use coverage_map::IncrementalCoverageMap;
+use coverage_point_calculator::SpeedtestTierTable;
 use file_store::{
     coverage::{CoverageObject as FSCoverageObject, KeyType, RadioHexSignalLevel},
     speedtest::CellSpeedtest,
@@ -21,6 +23,7 @@ use mobile_config::boosted_hex_info::BoostedHexInfo;
 use mobile_verifier::{
     cell_type::CellType,
     coverage::CoverageObject,
+    geofence::Geofences,
     heartbeats::{HbType, Heartbeat, ValidatedHeartbeat},
     reward_shares, rewarder, speedtests,
     unique_connections::{self, MINIMUM_UNIQUE_CONNECTIONS},
@@ -152,7 +155,13 @@ async fn test_poc_with_boosted_hexes(pool: PgPool) -> anyhow::Result<()> {
             &mobile_rewards_client,
             &speedtest_avg_client,
             &reward_info,
-            price_info
+            price_info,
+            &SpeedtestTierTable::default(),
+            &common::unique_connections_without_data_transfer(),
+            &Geofences::default(),
+            &mut IncrementalCoverageMap::default(),
+            None,
+            None,
         ),
         receive_expected_rewards_maybe_unallocated(
             &mut mobile_rewards,
@@ -340,7 +349,13 @@ async fn test_poc_boosted_hexes_thresholds_not_met(pool: PgPool) -> anyhow::Resu
             &mobile_rewards_client,
             &speedtest_avg_client,
             &reward_info,
-            price_info
+            price_info,
+            &SpeedtestTierTable::default(),
+            &common::unique_connections_without_data_transfer(),
+            &Geofences::default(),
+            &mut IncrementalCoverageMap::default(),
+            None,
+            None,
         ),
         receive_expected_rewards(&mut mobile_rewards)
     );
@@ -504,7 +519,13 @@ async fn test_poc_with_multi_coverage_boosted_hexes(pool: PgPool) -> anyhow::Res
             &mobile_rewards_client,
             &speedtest_avg_client,
             &reward_info,
-            price_info
+            price_info,
+            &SpeedtestTierTable::default(),
+            &common::unique_connections_without_data_transfer(),
+            &Geofences::default(),
+            &mut IncrementalCoverageMap::default(),
+            None,
+            None,
         ),
         receive_expected_rewards_maybe_unallocated(
             &mut mobile_rewards,
@@ -680,7 +701,13 @@ async fn test_expired_boosted_hex(pool: PgPool) -> anyhow::Result<()> {
             &mobile_rewards_client,
             &speedtest_avg_client,
             &reward_info,
-            price_info
+            price_info,
+            &SpeedtestTierTable::default(),
+            &common::unique_connections_without_data_transfer(),
+            &Geofences::default(),
+            &mut IncrementalCoverageMap::default(),
+            None,
+            None,
         ),
         receive_expected_rewards(&mut mobile_rewards)
     );
@@ -813,7 +840,13 @@ async fn test_reduced_location_score_with_boosted_hexes(pool: PgPool) -> anyhow:
             &mobile_rewards_client,
             &speedtest_avg_client,
             &reward_info,
-            price_info
+            price_info,
+            &SpeedtestTierTable::default(),
+            &common::unique_connections_without_data_transfer(),
+            &Geofences::default(),
+            &mut IncrementalCoverageMap::default(),
+            None,
+            None,
         ),
         receive_expected_rewards_maybe_unallocated(
             &mut mobile_rewards,
@@ -994,7 +1027,13 @@ async fn test_distance_from_asserted_removes_boosting_but_not_location_trust(
             &mobile_rewards_client,
             &speedtest_avg_client,
             &reward_info,
-            price_info
+            price_info,
+            &SpeedtestTierTable::default(),
+            &common::unique_connections_without_data_transfer(),
+            &Geofences::default(),
+            &mut IncrementalCoverageMap::default(),
+            None,
+            None,
         ),
         receive_expected_rewards_maybe_unallocated(
             &mut mobile_rewards,
@@ -1200,7 +1239,13 @@ async fn test_poc_with_cbrs_and_multi_coverage_boosted_hexes(pool: PgPool) -> an
             &mobile_rewards_client,
             &speedtest_avg_client,
             &reward_info,
-            price_info
+            price_info,
+            &SpeedtestTierTable::default(),
+            &common::unique_connections_without_data_transfer(),
+            &Geofences::default(),
+            &mut IncrementalCoverageMap::default(),
+            None,
+            None,
         ),
         receive_expected_rewards_maybe_unallocated(
             &mut mobile_rewards,
@@ -6,6 +6,7 @@ use h3o::LatLng;
 use helium_crypto::PublicKeyBinary;
 use helium_proto::services::poc_mobile::{self as proto, LocationSource};
 use mobile_verifier::{
+    cell_type::CellTypeRegistry,
     coverage::{CoverageObject, CoverageObjectCache},
     geofence::GeofenceValidator,
     heartbeats::{last_location::LocationCache, HbType, Heartbeat, ValidatedHeartbeat},
@@ -52,6 +53,7 @@ async fn heartbeat_uses_last_good_location_when_invalid_location(
         u32::MAX,
         &(epoch_start..epoch_end),
         &MockGeofence,
+        &CellTypeRegistry::default(),
     )
     .await?;
 
@@ -70,6 +72,7 @@ async fn heartbeat_uses_last_good_location_when_invalid_location(
         u32::MAX,
         &(epoch_start..epoch_end),
         &MockGeofence,
+        &CellTypeRegistry::default(),
     )
     .await?;
 
@@ -114,6 +117,7 @@ async fn heartbeat_will_use_last_good_location_from_db(pool: PgPool) -> anyhow::
         u32::MAX,
         &(epoch_start..epoch_end),
         &MockGeofence,
+        &CellTypeRegistry::default(),
     )
     .await?;
 
@@ -137,6 +141,7 @@ async fn heartbeat_will_use_last_good_location_from_db(pool: PgPool) -> anyhow::
         u32::MAX,
         &(epoch_start..epoch_end),
         &MockGeofence,
+        &CellTypeRegistry::default(),
     )
     .await?;
 
@@ -187,6 +192,7 @@ async fn heartbeat_does_not_use_last_good_location_when_more_than_24_hours(
         u32::MAX,
         &(epoch_start..epoch_end),
         &MockGeofence,
+        &CellTypeRegistry::default(),
     )
     .await?;
 
@@ -207,6 +213,7 @@ async fn heartbeat_does_not_use_last_good_location_when_more_than_24_hours(
         u32::MAX,
         &(epoch_start..epoch_end),
         &MockGeofence,
+        &CellTypeRegistry::default(),
     )
     .await?;
 
@@ -1,4 +1,5 @@
 use chrono::{DateTime, Duration, Utc};
+use coverage_point_calculator::{SpeedtestTierTable, SpeedtestTierThresholds};
 use file_store::{
     coverage::{CoverageObjectIngestReport, RadioHexSignalLevel},
     heartbeat::{CbrsHeartbeat, CbrsHeartbeatIngestReport},
@@ -16,6 +17,7 @@ use hextree::Cell;
 use mobile_config::boosted_hex_info::{BoostedHexInfo, BoostedHexes};
 
 use mobile_verifier::{
+    cell_type::CellTypeRegistry,
     coverage::{CoverageClaimTimeCache, CoverageObject, CoverageObjectCache},
     geofence::GeofenceValidator,
     heartbeats::{
@@ -24,7 +26,7 @@ use mobile_verifier::{
     reward_shares::CoverageShares,
     rewarder::boosted_hex_eligibility::BoostedHexEligibility,
     seniority::{Seniority, SeniorityUpdate},
-    sp_boosted_rewards_bans::BannedRadios,
+    sp_boosted_rewards_bans::{BanEvidence, BannedRadios},
     speedtests::Speedtest,
     speedtests_average::{SpeedtestAverage, SpeedtestAverages},
     unique_connections::UniqueConnectionCounts,
@@ -424,6 +426,7 @@ async fn process_cbrs_input(
     .await?;
 
     let mut transaction = pool.begin().await?;
+    let cell_types = CellTypeRegistry::default();
     let mut heartbeats = pin!(ValidatedHeartbeat::validate_heartbeats(
         stream::iter(heartbeats.map(Heartbeat::from)),
         &GatewayClientAllOwnersValid,
@@ -432,6 +435,7 @@ async fn process_cbrs_input(
         2000,
         epoch,
         &MockGeofence,
+        &cell_types,
     ));
     while let Some(heartbeat) = heartbeats.next().await.transpose()? {
         let coverage_claim_time = coverage_claim_time_cache
@@ -483,6 +487,7 @@ async fn process_wifi_input(
     .await?;
 
     let mut transaction = pool.begin().await?;
+    let cell_types = CellTypeRegistry::default();
     let mut heartbeats = pin!(ValidatedHeartbeat::validate_heartbeats(
         stream::iter(heartbeats.map(Heartbeat::from)),
         &GatewayClientAllOwnersValid,
@@ -491,6 +496,7 @@ async fn process_wifi_input(
         2000,
         epoch,
         &MockGeofence,
+        &cell_types,
     ));
     while let Some(heartbeat) = heartbeats.next().await.transpose()? {
         let coverage_claim_time = coverage_claim_time_cache
@@ -558,7 +564,10 @@ async fn scenario_one(pool: PgPool) -> anyhow::Result<()> {
         acceptable_speedtest(owner.clone(), end),
     ];
     let mut averages = HashMap::new();
-    averages.insert(owner.clone(), SpeedtestAverage::from(owner_speedtests));
+    averages.insert(
+        owner.clone(),
+        SpeedtestAverage::new(owner_speedtests, &SpeedtestTierThresholds::DEFAULT),
+    );
     let speedtest_avgs = SpeedtestAverages { averages };
 
     let reward_period = start..end;
@@ -572,6 +581,7 @@ async fn scenario_one(pool: PgPool) -> anyhow::Result<()> {
         &BannedRadios::default(),
         &UniqueConnectionCounts::default(),
         &reward_period,
+        &SpeedtestTierTable::default(),
     )
     .await?;
 
@@ -661,8 +671,14 @@ async fn scenario_two(pool: PgPool) -> anyhow::Result<()> {
         acceptable_speedtest(owner_2.clone(), end),
     ];
     let mut averages = HashMap::new();
-    averages.insert(owner_1.clone(), SpeedtestAverage::from(speedtests_1));
-    averages.insert(owner_2.clone(), SpeedtestAverage::from(speedtests_2));
+    averages.insert(
+        owner_1.clone(),
+        SpeedtestAverage::new(speedtests_1, &SpeedtestTierThresholds::DEFAULT),
+    );
+    averages.insert(
+        owner_2.clone(),
+        SpeedtestAverage::new(speedtests_2, &SpeedtestTierThresholds::DEFAULT),
+    );
     let speedtest_avgs = SpeedtestAverages { averages };
 
     let reward_period = start..end;
@@ -676,6 +692,7 @@ async fn scenario_two(pool: PgPool) -> anyhow::Result<()> {
         &BannedRadios::default(),
         &UniqueConnectionCounts::default(),
         &reward_period,
+        &SpeedtestTierTable::default(),
     )
     .await?;
 
@@ -905,12 +922,30 @@ async fn scenario_three(pool: PgPool) -> anyhow::Result<()> {
         acceptable_speedtest(owner_6.clone(), end),
     ];
     let mut averages = HashMap::new();
-    averages.insert(owner_1.clone(), SpeedtestAverage::from(speedtests_1));
-    averages.insert(owner_2.clone(), SpeedtestAverage::from(speedtests_2));
-    averages.insert(owner_3.clone(), SpeedtestAverage::from(speedtests_3));
-    averages.insert(owner_4.clone(), SpeedtestAverage::from(speedtests_4));
-    averages.insert(owner_5.clone(), SpeedtestAverage::from(speedtests_5));
-    averages.insert(owner_6.clone(), SpeedtestAverage::from(speedtests_6));
+    averages.insert(
+        owner_1.clone(),
+        SpeedtestAverage::new(speedtests_1, &SpeedtestTierThresholds::DEFAULT),
+    );
+    averages.insert(
+        owner_2.clone(),
+        SpeedtestAverage::new(speedtests_2, &SpeedtestTierThresholds::DEFAULT),
+    );
+    averages.insert(
+        owner_3.clone(),
+        SpeedtestAverage::new(speedtests_3, &SpeedtestTierThresholds::DEFAULT),
+    );
+    averages.insert(
+        owner_4.clone(),
+        SpeedtestAverage::new(speedtests_4, &SpeedtestTierThresholds::DEFAULT),
+    );
+    averages.insert(
+        owner_5.clone(),
+        SpeedtestAverage::new(speedtests_5, &SpeedtestTierThresholds::DEFAULT),
+    );
+    averages.insert(
+        owner_6.clone(),
+        SpeedtestAverage::new(speedtests_6, &SpeedtestTierThresholds::DEFAULT),
+    );
     let speedtest_avgs = SpeedtestAverages { averages };
 
     let mut boosted_hexes = BoostedHexes::default();
@@ -966,6 +1001,7 @@ async fn scenario_three(pool: PgPool) -> anyhow::Result<()> {
         &BannedRadios::default(),
         &UniqueConnectionCounts::default(),
         &reward_period,
+        &SpeedtestTierTable::default(),
     )
     .await?;
 
@@ -1043,7 +1079,10 @@ async fn scenario_four(pool: PgPool) -> anyhow::Result<()> {
         acceptable_speedtest(owner.clone(), end),
     ];
     let mut averages = HashMap::new();
-    averages.insert(owner.clone(), SpeedtestAverage::from(owner_speedtests));
+    averages.insert(
+        owner.clone(),
+        SpeedtestAverage::new(owner_speedtests, &SpeedtestTierThresholds::DEFAULT),
+    );
     let speedtest_avgs = SpeedtestAverages { averages };
 
     let reward_period = start..end;
@@ -1057,6 +1096,7 @@ async fn scenario_four(pool: PgPool) -> anyhow::Result<()> {
         &BannedRadios::default(),
         &UniqueConnectionCounts::default(),
         &reward_period,
+        &SpeedtestTierTable::default(),
     )
     .await?;
 
@@ -1145,8 +1185,14 @@ async fn scenario_five(pool: PgPool) -> anyhow::Result<()> {
         acceptable_speedtest(owner_2.clone(), end),
     ];
     let mut averages = HashMap::new();
-    averages.insert(owner_1.clone(), SpeedtestAverage::from(speedtests_1));
-    averages.insert(owner_2.clone(), SpeedtestAverage::from(speedtests_2));
+    averages.insert(
+        owner_1.clone(),
+        SpeedtestAverage::new(speedtests_1, &SpeedtestTierThresholds::DEFAULT),
+    );
+    averages.insert(
+        owner_2.clone(),
+        SpeedtestAverage::new(speedtests_2, &SpeedtestTierThresholds::DEFAULT),
+    );
     let speedtest_avgs = SpeedtestAverages { averages };
 
     let reward_period = start..end;
@@ -1160,6 +1206,7 @@ async fn scenario_five(pool: PgPool) -> anyhow::Result<()> {
         &BannedRadios::default(),
         &UniqueConnectionCounts::default(),
         &reward_period,
+        &SpeedtestTierTable::default(),
     )
     .await?;
 
@@ -1392,12 +1439,30 @@ async fn scenario_six(pool: PgPool) -> anyhow::Result<()> {
         acceptable_speedtest(owner_6.clone(), end),
     ];
     let mut averages = HashMap::new();
-    averages.insert(owner_1.clone(), SpeedtestAverage::from(speedtests_1));
-    averages.insert(owner_2.clone(), SpeedtestAverage::from(speedtests_2));
-    averages.insert(owner_3.clone(), SpeedtestAverage::from(speedtests_3));
-    averages.insert(owner_4.clone(), SpeedtestAverage::from(speedtests_4));
-    averages.insert(owner_5.clone(), SpeedtestAverage::from(speedtests_5));
-    averages.insert(owner_6.clone(), SpeedtestAverage::from(speedtests_6));
+    averages.insert(
+        owner_1.clone(),
+        SpeedtestAverage::new(speedtests_1, &SpeedtestTierThresholds::DEFAULT),
+    );
+    averages.insert(
+        owner_2.clone(),
+        SpeedtestAverage::new(speedtests_2, &SpeedtestTierThresholds::DEFAULT),
+    );
+    averages.insert(
+        owner_3.clone(),
+        SpeedtestAverage::new(speedtests_3, &SpeedtestTierThresholds::DEFAULT),
+    );
+    averages.insert(
+        owner_4.clone(),
+        SpeedtestAverage::new(speedtests_4, &SpeedtestTierThresholds::DEFAULT),
+    );
+    averages.insert(
+        owner_5.clone(),
+        SpeedtestAverage::new(speedtests_5, &SpeedtestTierThresholds::DEFAULT),
+    );
+    averages.insert(
+        owner_6.clone(),
+        SpeedtestAverage::new(speedtests_6, &SpeedtestTierThresholds::DEFAULT),
+    );
     let speedtest_avgs = SpeedtestAverages { averages };
 
     let reward_period = start..end;
@@ -1411,6 +1476,7 @@ async fn scenario_six(pool: PgPool) -> anyhow::Result<()> {
         &BannedRadios::default(),
         &UniqueConnectionCounts::default(),
         &reward_period,
+        &SpeedtestTierTable::default(),
     )
     .await?;
 
@@ -1484,6 +1550,7 @@ async fn ensure_lower_trust_score_for_distant_heartbeats(pool: PgPool) -> anyhow
         received_timestamp: Utc::now(),
     };
 
+    let cell_types = CellTypeRegistry::default();
     let validate = |latlng: LatLng| {
         ValidatedHeartbeat::validate(
             mk_heartbeat(latlng).into(),
@@ -1493,6 +1560,7 @@ async fn ensure_lower_trust_score_for_distant_heartbeats(pool: PgPool) -> anyhow
             max_covered_distance,
             &(DateTime::<Utc>::MIN_UTC..DateTime::<Utc>::MAX_UTC),
             &MockGeofence,
+            &cell_types,
         )
     };
 
@@ -1615,10 +1683,13 @@ async fn eligible_for_coverage_map_bad_speedtest(pool: PgPool) -> anyhow::Result
     ];
 
     let mut averages = HashMap::new();
-    averages.insert(good_hotspot.clone(), SpeedtestAverage::from(speedtests_1));
+    averages.insert(
+        good_hotspot.clone(),
+        SpeedtestAverage::new(speedtests_1, &SpeedtestTierThresholds::DEFAULT),
+    );
     averages.insert(
         bad_speedtest_hotspot.clone(),
-        SpeedtestAverage::from(speedtests_2),
+        SpeedtestAverage::new(speedtests_2, &SpeedtestTierThresholds::DEFAULT),
     );
 
     let speedtest_avgs = SpeedtestAverages { averages };
@@ -1635,6 +1706,7 @@ async fn eligible_for_coverage_map_bad_speedtest(pool: PgPool) -> anyhow::Result
         &BannedRadios::default(),
         &UniqueConnectionCounts::default(),
         &reward_period,
+        &SpeedtestTierTable::default(),
     )
     .await?;
 
@@ -1722,10 +1794,13 @@ async fn eligible_for_coverage_map_bad_trust_score(pool: PgPool) -> anyhow::Resu
     ];
 
     let mut averages = HashMap::new();
-    averages.insert(good_hotspot.clone(), SpeedtestAverage::from(speedtests_1));
+    averages.insert(
+        good_hotspot.clone(),
+        SpeedtestAverage::new(speedtests_1, &SpeedtestTierThresholds::DEFAULT),
+    );
     averages.insert(
         bad_location_hotspot.clone(),
-        SpeedtestAverage::from(speedtests_2),
+        SpeedtestAverage::new(speedtests_2, &SpeedtestTierThresholds::DEFAULT),
     );
 
     let speedtest_avgs = SpeedtestAverages { averages };
@@ -1742,6 +1817,7 @@ async fn eligible_for_coverage_map_bad_trust_score(pool: PgPool) -> anyhow::Resu
         &BannedRadios::default(),
         &UniqueConnectionCounts::default(),
         &reward_period,
+        &SpeedtestTierTable::default(),
     )
     .await?;
 
@@ -1835,8 +1911,14 @@ async fn eligible_for_coverage_map_banned(pool: PgPool) -> anyhow::Result<()> {
     ];
 
     let mut averages = HashMap::new();
-    averages.insert(good_hotspot.clone(), SpeedtestAverage::from(speedtests_1));
-    averages.insert(banned_hotspot.clone(), SpeedtestAverage::from(speedtests_2));
+    averages.insert(
+        good_hotspot.clone(),
+        SpeedtestAverage::new(speedtests_1, &SpeedtestTierThresholds::DEFAULT),
+    );
+    averages.insert(
+        banned_hotspot.clone(),
+        SpeedtestAverage::new(speedtests_2, &SpeedtestTierThresholds::DEFAULT),
+    );
 
     let speedtest_avgs = SpeedtestAverages { averages };
 
@@ -1846,7 +1928,13 @@ async fn eligible_for_coverage_map_banned(pool: PgPool) -> anyhow::Result<()> {
 
     // We are banning hotspot2 and therefore should not be in coverage map
     let mut ban_radios = BannedRadios::default();
-    ban_radios.insert_wifi(banned_hotspot.clone());
+    ban_radios.insert_wifi(
+        banned_hotspot.clone(),
+        BanEvidence {
+            received_timestamp: start,
+            until: end + Duration::days(7),
+        },
+    );
 
     let coverage_shares = CoverageShares::new(
         &pool,
@@ -1857,6 +1945,7 @@ async fn eligible_for_coverage_map_banned(pool: PgPool) -> anyhow::Result<()> {
         &ban_radios,
         &UniqueConnectionCounts::default(),
         &reward_period,
+        &SpeedtestTierTable::default(),
     )
     .await?;
 
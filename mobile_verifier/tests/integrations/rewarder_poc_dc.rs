@@ -5,6 +5,8 @@ use crate::common::{
     RadioRewardV2Ext, EMISSIONS_POOL_IN_BONES_24_HOURS,
 };
 use chrono::{DateTime, Duration as ChronoDuration, Duration, Utc};
+use coverage_map::IncrementalCoverageMap;
+use coverage_point_calculator::SpeedtestTierTable;
 use file_store::{
     coverage::{CoverageObject as FSCoverageObject, KeyType, RadioHexSignalLevel},
     speedtest::CellSpeedtest,
@@ -15,10 +17,12 @@ use mobile_verifier::{
     cell_type::CellType,
     coverage::CoverageObject,
     data_session,
+    geofence::Geofences,
     heartbeats::{HbType, Heartbeat, ValidatedHeartbeat},
     reward_shares, rewarder,
     sp_boosted_rewards_bans::{self, BannedRadioReport},
-    speedtests, unique_connections,
+    speedtests,
+    unique_connections::{self, UniqueConnectionsTable},
 };
 use rust_decimal::prelude::*;
 use rust_decimal_macros::dec;
@@ -72,7 +76,13 @@ async fn test_poc_and_dc_rewards(pool: PgPool) -> anyhow::Result<()> {
             &mobile_rewards_client,
             &speedtest_avg_client,
             &reward_info,
-            price_info
+            price_info,
+            &SpeedtestTierTable::default(),
+            &UniqueConnectionsTable::default(),
+            &Geofences::default(),
+            &mut IncrementalCoverageMap::default(),
+            None,
+            None,
         ),
         receive_expected_rewards_with_counts(&mut mobile_rewards, 3, 3, true)
     );
@@ -142,6 +152,19 @@ async fn test_poc_and_dc_rewards(pool: PgPool) -> anyhow::Result<()> {
     } else {
         panic!("no rewards received");
     };
+
+    // the eligibility is saved without reward explanations enabled
+    let hotspot_1: PublicKeyBinary = HOTSPOT_1.parse()?;
+    let eligibility = reward_shares::explanation::db::get(
+        &pool,
+        reward_info.epoch_period.start,
+        &hotspot_1,
+        None,
+    )
+    .await?;
+    assert_eq!(1, eligibility.len());
+    assert_eq!(hotspot_1, eligibility[0].hotspot_key);
+    assert!(eligibility[0].evidence.is_some());
     Ok(())
 }
 
@@ -170,6 +193,8 @@ async fn test_qualified_wifi_poc_rewards(pool: PgPool) -> anyhow::Result<()> {
     let hex_boosting_client = MockHexBoostingClient::new(boosted_hexes);
 
     let price_info = default_price_info();
+    // Both epochs are ranked from the same coverage map, as in the rewarder
+    let mut coverage_map = IncrementalCoverageMap::default();
 
     let (_, _rewards) = tokio::join!(
         rewarder::reward_poc_and_dc(
@@ -178,7 +203,13 @@ async fn test_qualified_wifi_poc_rewards(pool: PgPool) -> anyhow::Result<()> {
             &mobile_rewards_client,
             &speedtest_avg_client,
             &reward_info,
-            price_info.clone()
+            price_info.clone(),
+            &SpeedtestTierTable::default(),
+            &UniqueConnectionsTable::default(),
+            &Geofences::default(),
+            &mut coverage_map,
+            None,
+            None,
         ),
         // expecting NO poc rewards, expecting unallocated
         receive_expected_rewards_with_counts(&mut mobile_rewards, 3, 0, true)
@@ -202,7 +233,13 @@ async fn test_qualified_wifi_poc_rewards(pool: PgPool) -> anyhow::Result<()> {
             &mobile_rewards_client,
             &speedtest_avg_client,
             &reward_info,
-            price_info
+            price_info,
+            &SpeedtestTierTable::default(),
+            &UniqueConnectionsTable::default(),
+            &Geofences::default(),
+            &mut coverage_map,
+            None,
+            None,
         ),
         // expecting single radio with poc rewards, no unallocated
         receive_expected_rewards_with_counts(&mut mobile_rewards, 3, 1, false)
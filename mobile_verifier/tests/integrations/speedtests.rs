@@ -1,5 +1,6 @@
 use crate::common;
 use chrono::{DateTime, NaiveDateTime, Utc};
+use coverage_point_calculator::SpeedtestTierTable;
 use file_store::{
     file_info_poller::FileInfoStream,
     speedtest::{CellSpeedtest, CellSpeedtestIngestReport},
@@ -63,6 +64,7 @@ async fn speedtests_average_should_only_include_last_48_hours(
         rx,
         speedtest_avg_client,
         verified_client,
+        SpeedtestTierTable::default(),
     );
 
     let hotspot: PublicKeyBinary =
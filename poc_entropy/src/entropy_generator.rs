@@ -1,19 +1,16 @@
+use crate::entropy_source::{EntropySource, SourceEntropy, SourceError};
 use base64::Engine;
 use chrono::Utc;
-use file_store::file_sink;
-use futures::TryFutureExt;
-use helium_proto::EntropyReportV1;
-use jsonrpsee::{
-    core::client::ClientT,
-    http_client::{HttpClient, HttpClientBuilder},
-    rpc_params,
+use file_store::{
+    entropy_report::{ChainedEntropy, ENTROPY_VERSION_CHAINED},
+    file_sink,
 };
-use serde::{Deserialize, Serialize};
-use serde_json::json;
+use helium_crypto::Keypair;
+use helium_proto::EntropyReportV1;
+use serde::Serialize;
 use tokio::{sync::watch, time};
 
 pub const ENTROPY_TICK_TIME: time::Duration = time::Duration::from_secs(60);
-const ENTROPY_TIMEOUT: time::Duration = time::Duration::from_secs(5);
 
 pub type MessageSender = watch::Sender<Entropy>;
 pub type MessageReceiver = watch::Receiver<Entropy>;
@@ -65,53 +62,76 @@ impl From<&Entropy> for EntropyReportV1 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct JsonRpcResult {
-    context: serde_json::Map<String, serde_json::Value>,
-    value: serde_json::Map<String, serde_json::Value>,
-}
-
 pub struct EntropyGenerator {
     pub receiver: MessageReceiver,
 
-    client: HttpClient,
+    source: Box<dyn EntropySource>,
     sender: MessageSender,
+    /// when present entropy is generated as a signed, chained entropy
+    /// (version `ENTROPY_VERSION_CHAINED`)
+    keypair: Option<Keypair>,
+    /// the digest of the last published chained entropy
+    prev: [u8; 32],
+    /// the last successfully fetched source entropy, reused for chained
+    /// entropy should the source be unavailable
+    last_source: SourceEntropy,
+    /// unix timestamp at which the source entropy last changed
+    last_source_change: i64,
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum GetEntropyError {
-    #[error("no blockhash found")]
-    NoBlockHashFound,
-    #[error("failed to decode hash: {0}")]
-    DecodeError(#[from] bs58::decode::Error),
-    #[error("json rpc error: {0}")]
-    JsonRpcError(#[from] jsonrpsee::core::Error),
+    #[error("entropy source error: {0}")]
+    Source(#[from] SourceError),
+    #[error("failed to sign chained entropy: {0}")]
+    ChainedEntropy(#[from] file_store::Error),
 }
 
 impl EntropyGenerator {
-    pub async fn new(url: impl AsRef<str>) -> Result<Self, GetEntropyError> {
-        let client = HttpClientBuilder::default()
-            .request_timeout(ENTROPY_TIMEOUT)
-            .build(url)?;
-        let entropy = Self::get_entropy(&client)
-            .map_ok(|data| Entropy {
-                data,
-                timestamp: Utc::now().timestamp(),
+    pub async fn new(
+        source: Box<dyn EntropySource>,
+        keypair: Option<Keypair>,
+    ) -> Result<Self, GetEntropyError> {
+        let initial = source.get_entropy().await?;
+        let timestamp = Utc::now().timestamp();
+        // the initial entropy is served but never published, the first
+        // published chained entropy is therefore also a genesis entropy
+        let prev = ChainedEntropy::GENESIS;
+        let entropy = match &keypair {
+            Some(keypair) => Entropy {
+                data: ChainedEntropy::new(
+                    keypair,
+                    prev,
+                    initial.source,
+                    initial.slot,
+                    timestamp,
+                    &initial.data,
+                )?
+                .to_bytes(),
+                timestamp,
+                version: ENTROPY_VERSION_CHAINED,
+            },
+            None => Entropy {
+                data: initial.data.clone(),
+                timestamp,
                 version: ENTROPY_VERSION,
-            })
-            .inspect_ok(|entropy| {
-                tracing::info!(
-                    "initialized entropy: {} at: {}",
-                    entropy.to_string(),
-                    entropy.timestamp
-                )
-            })
-            .await?;
+            },
+        };
+        tracing::info!(
+            "initialized entropy: {} at: {} version: {}",
+            entropy.to_string(),
+            entropy.timestamp,
+            entropy.version
+        );
         let (sender, receiver) = watch::channel(entropy);
         Ok(Self {
-            client,
+            source,
             receiver,
             sender,
+            keypair,
+            prev,
+            last_source: initial,
+            last_source_change: timestamp,
         })
     }
 
@@ -149,21 +169,57 @@ impl EntropyGenerator {
         &mut self,
         file_sink: &file_sink::FileSinkClient<EntropyReportV1>,
     ) -> anyhow::Result<()> {
-        let source_data = match Self::get_entropy(&self.client).await {
-            Ok(data) => data,
+        let timestamp = Utc::now().timestamp();
+        let source = match self.source.get_entropy().await {
+            Ok(source) => {
+                if source != self.last_source {
+                    self.last_source_change = timestamp;
+                }
+                self.last_source = source.clone();
+                Some(source)
+            }
             Err(err) => {
                 tracing::warn!("failed to get entropy: {err:?}");
-                (*self.receiver.borrow().data).to_vec()
+                metrics::counter!("poc_entropy_source_failure_count").increment(1);
+                None
             }
         };
-        let timestamp = Utc::now().timestamp();
+        // how long the source entropy the published entropy is derived from has been frozen
+        let stale_secs = timestamp - self.last_source_change;
+        metrics::gauge!("poc_entropy_stale_seconds").set(stale_secs as f64);
+        if stale_secs >= ENTROPY_TICK_TIME.as_secs() as i64 * 2 {
+            tracing::warn!("entropy source unchanged for {stale_secs}s");
+        }
 
-        let mut hasher = blake3::Hasher::new();
-        hasher.update(&timestamp.to_le_bytes());
-        hasher.update(&source_data);
-        let data = hasher.finalize().as_bytes().to_vec();
+        let (version, data) = match &self.keypair {
+            Some(keypair) => {
+                let source = source.unwrap_or_else(|| self.last_source.clone());
+                let data = ChainedEntropy::new(
+                    keypair,
+                    self.prev,
+                    source.source,
+                    source.slot,
+                    timestamp,
+                    &source.data,
+                )?
+                .to_bytes();
+                self.prev = ChainedEntropy::digest(&data);
+                (ENTROPY_VERSION_CHAINED, data)
+            }
+            None => {
+                let source_data = match source {
+                    Some(source) => source.data,
+                    None => (*self.receiver.borrow().data).to_vec(),
+                };
+                let mut hasher = blake3::Hasher::new();
+                hasher.update(&timestamp.to_le_bytes());
+                hasher.update(&source_data);
+                (ENTROPY_VERSION, hasher.finalize().as_bytes().to_vec())
+            }
+        };
 
         self.sender.send_modify(|entry| {
+            entry.version = version;
             entry.timestamp = timestamp;
             entry.data = data;
         });
@@ -179,20 +235,4 @@ impl EntropyGenerator {
 
         Ok(())
     }
-
-    async fn get_entropy(client: &HttpClient) -> Result<Vec<u8>, GetEntropyError> {
-        let params = rpc_params!(json!({"commitment": "processed"}));
-        client
-            .request("getLatestBlockhash", params)
-            .map_err(GetEntropyError::from)
-            .and_then(|result: JsonRpcResult| async move {
-                result
-                    .value
-                    .get("blockhash")
-                    .and_then(|v| v.as_str())
-                    .ok_or(GetEntropyError::NoBlockHashFound)
-                    .and_then(|hash| bs58::decode(hash).into_vec().map_err(GetEntropyError::from))
-            })
-            .await
-    }
 }
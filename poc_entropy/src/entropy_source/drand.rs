@@ -0,0 +1,60 @@
+use super::{EntropySource, SourceEntropy, SourceError, SOURCE_TIMEOUT};
+use file_store::entropy_report::EntropySourceKind;
+use serde::Deserialize;
+
+/// The default client useragent for drand http requests
+static USERAGENT: &str = "oracle/poc_entropy/1.0";
+
+#[derive(Debug, Deserialize)]
+struct DrandBeacon {
+    round: u64,
+    /// hex encoded sha256 of the beacon signature
+    randomness: String,
+}
+
+/// Sources entropy from the latest round of a drand style http randomness
+/// beacon, ie `<url>/public/latest`
+pub struct DrandSource {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl DrandSource {
+    pub fn new(url: impl Into<String>) -> Result<Self, SourceError> {
+        let client = reqwest::Client::builder()
+            .user_agent(USERAGENT)
+            .timeout(SOURCE_TIMEOUT)
+            .build()?;
+        Ok(Self {
+            url: url.into(),
+            client,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl EntropySource for DrandSource {
+    fn name(&self) -> &str {
+        "drand"
+    }
+
+    async fn get_entropy(&self) -> Result<SourceEntropy, SourceError> {
+        let beacon = self
+            .client
+            .get(format!("{}/public/latest", self.url.trim_end_matches('/')))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<DrandBeacon>()
+            .await?;
+        let data = hex::decode(beacon.randomness)?;
+        if data.len() != 32 {
+            return Err(SourceError::InvalidLength(data.len()));
+        }
+        Ok(SourceEntropy {
+            source: EntropySourceKind::Drand,
+            slot: beacon.round,
+            data,
+        })
+    }
+}
@@ -0,0 +1,167 @@
+//! Sources of the raw entropy the entropy generator derives published entropy
+//! from.
+//!
+//! Sources are tried in their configured order by a [`FailoverSource`], the
+//! first source to return entropy wins.
+
+pub mod drand;
+pub mod local;
+pub mod solana;
+
+pub use drand::DrandSource;
+pub use local::LocalSource;
+pub use solana::SolanaRpcSource;
+
+use crate::settings::SourceSettings;
+use file_store::entropy_report::EntropySourceKind;
+use std::time::Duration;
+
+/// Timeout applied to every request made to a remote entropy source
+pub const SOURCE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Raw entropy as returned by an entropy source
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceEntropy {
+    /// the kind of source the entropy came from, qualifying `slot`
+    pub source: EntropySourceKind,
+    /// the solana slot or drand round the entropy was produced in
+    pub slot: u64,
+    /// the solana blockhash or drand randomness, always 32 bytes
+    pub data: Vec<u8>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SourceError {
+    #[error("no blockhash found")]
+    NoBlockHashFound,
+    #[error("no slot found")]
+    NoSlotFound,
+    #[error("invalid entropy length: {0}")]
+    InvalidLength(usize),
+    #[error("invalid quorum {quorum} for {endpoints} endpoints")]
+    InvalidQuorum { quorum: usize, endpoints: usize },
+    #[error("quorum not reached, {confirmations} of {quorum} endpoints confirmed")]
+    QuorumNotReached { confirmations: usize, quorum: usize },
+    #[error("all endpoints failed")]
+    AllEndpointsFailed,
+    #[error("no entropy sources configured")]
+    NoSources,
+    #[error("failed to decode hash: {0}")]
+    DecodeError(#[from] bs58::decode::Error),
+    #[error("failed to decode hex: {0}")]
+    HexError(#[from] hex::FromHexError),
+    #[error("json rpc error: {0}")]
+    JsonRpcError(#[from] jsonrpsee::core::Error),
+    #[error("http error: {0}")]
+    HttpError(#[from] reqwest::Error),
+}
+
+#[async_trait::async_trait]
+pub trait EntropySource: Send + Sync {
+    /// Name of the source, used to label metrics and logs
+    fn name(&self) -> &str;
+
+    async fn get_entropy(&self) -> Result<SourceEntropy, SourceError>;
+}
+
+/// Tries each of its sources in order, returning the entropy of the first
+/// which succeeds
+pub struct FailoverSource {
+    sources: Vec<Box<dyn EntropySource>>,
+}
+
+impl FailoverSource {
+    pub fn new(sources: Vec<Box<dyn EntropySource>>) -> Self {
+        Self { sources }
+    }
+
+    pub fn from_settings(settings: &[SourceSettings]) -> Result<Self, SourceError> {
+        let sources = settings
+            .iter()
+            .map(|source| -> Result<Box<dyn EntropySource>, SourceError> {
+                Ok(match source {
+                    SourceSettings::Solana { urls, quorum } => {
+                        Box::new(SolanaRpcSource::new(urls, *quorum)?)
+                    }
+                    SourceSettings::Drand { url } => Box::new(DrandSource::new(url)?),
+                    SourceSettings::Local { seed } => Box::new(LocalSource::new(seed)),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if sources.is_empty() {
+            return Err(SourceError::NoSources);
+        }
+        Ok(Self::new(sources))
+    }
+}
+
+#[async_trait::async_trait]
+impl EntropySource for FailoverSource {
+    fn name(&self) -> &str {
+        "failover"
+    }
+
+    async fn get_entropy(&self) -> Result<SourceEntropy, SourceError> {
+        let mut last_err = SourceError::NoSources;
+        for (idx, source) in self.sources.iter().enumerate() {
+            match source.get_entropy().await {
+                Ok(entropy) => {
+                    if idx > 0 {
+                        tracing::warn!(source = source.name(), "failed over to entropy source");
+                        metrics::counter!("poc_entropy_source_failover_count", "source" => source.name().to_string())
+                            .increment(1);
+                    }
+                    return Ok(entropy);
+                }
+                Err(err) => {
+                    tracing::warn!(source = source.name(), "entropy source failed: {err:?}");
+                    metrics::counter!("poc_entropy_source_error_count", "source" => source.name().to_string())
+                        .increment(1);
+                    last_err = err;
+                }
+            }
+        }
+        Err(last_err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FailingSource;
+
+    #[async_trait::async_trait]
+    impl EntropySource for FailingSource {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        async fn get_entropy(&self) -> Result<SourceEntropy, SourceError> {
+            Err(SourceError::AllEndpointsFailed)
+        }
+    }
+
+    #[tokio::test]
+    async fn fails_over_to_next_source() {
+        let expected = LocalSource::new("seed").get_entropy().await.unwrap();
+        let source = FailoverSource::new(vec![
+            Box::new(FailingSource),
+            Box::new(LocalSource::new("seed")),
+        ]);
+        assert_eq!(expected, source.get_entropy().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn returns_last_error_when_all_sources_fail() {
+        let source = FailoverSource::new(vec![Box::new(FailingSource)]);
+        assert!(matches!(
+            source.get_entropy().await,
+            Err(SourceError::AllEndpointsFailed)
+        ));
+        assert!(matches!(
+            FailoverSource::new(vec![]).get_entropy().await,
+            Err(SourceError::NoSources)
+        ));
+    }
+}
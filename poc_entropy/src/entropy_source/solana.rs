@@ -0,0 +1,138 @@
+use super::{EntropySource, SourceEntropy, SourceError, SOURCE_TIMEOUT};
+use file_store::entropy_report::EntropySourceKind;
+use futures::{future, TryFutureExt};
+use jsonrpsee::{
+    core::client::ClientT,
+    http_client::{HttpClient, HttpClientBuilder},
+    rpc_params,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonRpcResult<T> {
+    context: serde_json::Map<String, serde_json::Value>,
+    value: T,
+}
+
+/// Sources the latest blockhash from one of several Solana JSON-RPC endpoints.
+///
+/// The blockhash is taken from the first endpoint to respond, in configured
+/// order, and must then be confirmed as valid by the other endpoints until
+/// `quorum` endpoints, including the first, agree on it.
+pub struct SolanaRpcSource {
+    name: String,
+    endpoints: Vec<(String, HttpClient)>,
+    quorum: usize,
+}
+
+impl SolanaRpcSource {
+    pub fn new(urls: &[String], quorum: usize) -> Result<Self, SourceError> {
+        if quorum == 0 || quorum > urls.len() {
+            return Err(SourceError::InvalidQuorum {
+                quorum,
+                endpoints: urls.len(),
+            });
+        }
+        let endpoints = urls
+            .iter()
+            .map(|url| {
+                HttpClientBuilder::default()
+                    .request_timeout(SOURCE_TIMEOUT)
+                    .build(url)
+                    .map(|client| (url.clone(), client))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            name: format!("solana({}/{})", quorum, urls.len()),
+            endpoints,
+            quorum,
+        })
+    }
+
+    async fn latest_blockhash(client: &HttpClient) -> Result<SourceEntropy, SourceError> {
+        let params = rpc_params!(json!({"commitment": "processed"}));
+        client
+            .request("getLatestBlockhash", params)
+            .map_err(SourceError::from)
+            .and_then(
+                |result: JsonRpcResult<serde_json::Map<String, serde_json::Value>>| async move {
+                    let slot = result
+                        .context
+                        .get("slot")
+                        .and_then(|v| v.as_u64())
+                        .ok_or(SourceError::NoSlotFound)?;
+                    let data = result
+                        .value
+                        .get("blockhash")
+                        .and_then(|v| v.as_str())
+                        .ok_or(SourceError::NoBlockHashFound)
+                        .and_then(|hash| {
+                            bs58::decode(hash).into_vec().map_err(SourceError::from)
+                        })?;
+                    Ok(SourceEntropy {
+                        source: EntropySourceKind::Solana,
+                        slot,
+                        data,
+                    })
+                },
+            )
+            .await
+    }
+
+    async fn is_blockhash_valid(client: &HttpClient, blockhash: &str) -> bool {
+        let params = rpc_params!(blockhash, json!({"commitment": "processed"}));
+        client
+            .request("isBlockhashValid", params)
+            .await
+            .map(|result: JsonRpcResult<bool>| result.value)
+            .unwrap_or(false)
+    }
+}
+
+#[async_trait::async_trait]
+impl EntropySource for SolanaRpcSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn get_entropy(&self) -> Result<SourceEntropy, SourceError> {
+        let mut latest = None;
+        for (idx, (url, client)) in self.endpoints.iter().enumerate() {
+            match Self::latest_blockhash(client).await {
+                Ok(entropy) => {
+                    latest = Some((idx, entropy));
+                    break;
+                }
+                Err(err) => tracing::warn!(url, "failed to get latest blockhash: {err:?}"),
+            }
+        }
+        let (primary, entropy) = latest.ok_or(SourceError::AllEndpointsFailed)?;
+        if self.quorum == 1 {
+            return Ok(entropy);
+        }
+
+        let blockhash = bs58::encode(&entropy.data).into_string();
+        let confirmations = future::join_all(
+            self.endpoints
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| *idx != primary)
+                .map(|(_, (_, client))| Self::is_blockhash_valid(client, &blockhash)),
+        )
+        .await
+        .into_iter()
+        .filter(|valid| *valid)
+        .count()
+            + 1;
+
+        if confirmations >= self.quorum {
+            Ok(entropy)
+        } else {
+            Err(SourceError::QuorumNotReached {
+                confirmations,
+                quorum: self.quorum,
+            })
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod entropy_generator;
+pub mod entropy_source;
 pub mod server;
 pub mod settings;
 
@@ -6,7 +6,10 @@ use file_store::{
 };
 use futures_util::TryFutureExt;
 use helium_proto::EntropyReportV1;
-use poc_entropy::{entropy_generator::EntropyGenerator, server::ApiServer, Settings};
+use poc_entropy::{
+    entropy_generator::EntropyGenerator, entropy_source::FailoverSource, server::ApiServer,
+    Settings,
+};
 use std::{net::SocketAddr, path, time::Duration};
 use tokio::{self, signal};
 
@@ -69,7 +72,9 @@ impl Server {
         let store_base_path = path::Path::new(&settings.cache);
 
         // entropy
-        let mut entropy_generator = EntropyGenerator::new(&settings.source).await?;
+        let entropy_source = FailoverSource::from_settings(&settings.entropy_sources())?;
+        let mut entropy_generator =
+            EntropyGenerator::new(Box::new(entropy_source), settings.signing_keypair()?).await?;
         let entropy_watch = entropy_generator.receiver();
 
         let (file_upload, file_upload_server) =
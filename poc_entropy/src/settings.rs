@@ -1,3 +1,4 @@
+use anyhow::Context;
 use config::{Config, Environment, File};
 use serde::Deserialize;
 use std::path::Path;
@@ -13,8 +14,18 @@ pub struct Settings {
     /// Listen address for http requests for entropy. Default "0.0.0.0:8080"
     #[serde(default = "default_listen_addr")]
     pub listen: String,
-    /// Source URL for entropy data. Required
-    pub source: String,
+    /// Legacy single Solana JSON-RPC source URL for entropy data. Used only
+    /// when no `sources` are configured
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Entropy sources, tried in order until one returns entropy
+    #[serde(default)]
+    pub sources: Vec<SourceSettings>,
+    /// Optional file from which to load the oracle signing keypair. When
+    /// present, entropy is generated as signed, chained entropy (version 1)
+    /// rather than version 0 entropy
+    #[serde(default)]
+    pub signing_keypair: Option<String>,
     /// Target output bucket details
     pub output: file_store::Settings,
     /// Folder for locacl cache of ingest data
@@ -24,6 +35,26 @@ pub struct Settings {
     pub metrics: poc_metrics::Settings,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SourceSettings {
+    /// Solana JSON-RPC endpoints. The latest blockhash must be confirmed by
+    /// `quorum` of the endpoints. Default quorum 1
+    Solana {
+        urls: Vec<String>,
+        #[serde(default = "default_quorum")]
+        quorum: usize,
+    },
+    /// drand compatible http randomness beacon
+    Drand { url: String },
+    /// Deterministic local source for testing, never use in production
+    Local { seed: String },
+}
+
+fn default_quorum() -> usize {
+    1
+}
+
 fn default_log() -> String {
     "poc_entropy=debug,poc_store=info".to_string()
 }
@@ -58,4 +89,31 @@ impl Settings {
             .build()
             .and_then(|config| config.try_deserialize())
     }
+
+    /// The configured entropy sources, falling back to the legacy `source`
+    /// url as a single Solana endpoint
+    pub fn entropy_sources(&self) -> Vec<SourceSettings> {
+        if !self.sources.is_empty() {
+            return self.sources.clone();
+        }
+        self.source
+            .iter()
+            .map(|url| SourceSettings::Solana {
+                urls: vec![url.clone()],
+                quorum: default_quorum(),
+            })
+            .collect()
+    }
+
+    pub fn signing_keypair(&self) -> anyhow::Result<Option<helium_crypto::Keypair>> {
+        self.signing_keypair
+            .as_ref()
+            .map(|path| -> anyhow::Result<helium_crypto::Keypair> {
+                let data = std::fs::read(path)
+                    .map_err(helium_crypto::Error::from)
+                    .with_context(|| format!("reading keypair from settings: {path}"))?;
+                Ok(helium_crypto::Keypair::try_from(&data[..])?)
+            })
+            .transpose()
+    }
 }
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS reward_ledger (
+    address TEXT NOT NULL,
+    reward_type reward_type NOT NULL,
+    -- end timestamp of the reward manifest
+    epoch_end TIMESTAMPTZ NOT NULL,
+    amount BIGINT NOT NULL,
+    PRIMARY KEY (address, epoch_end, reward_type)
+);
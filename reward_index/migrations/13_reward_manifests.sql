@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS reward_manifests (
+    epoch_end TIMESTAMPTZ PRIMARY KEY,
+    manifest_key TEXT NOT NULL,
+    epoch BIGINT NOT NULL,
+    written_files_digest TEXT NOT NULL,
+    indexed_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS reward_manifest_totals (
+    epoch_end TIMESTAMPTZ NOT NULL REFERENCES reward_manifests (epoch_end),
+    reward_type reward_type NOT NULL,
+    amount BIGINT NOT NULL,
+    PRIMARY KEY (epoch_end, reward_type)
+);
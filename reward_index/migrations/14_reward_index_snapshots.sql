@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS reward_index_snapshots (
+    -- end timestamp of the reward manifest the snapshot was taken after
+    epoch_end TIMESTAMPTZ PRIMARY KEY,
+    manifest_key TEXT NOT NULL,
+    merkle_root TEXT NOT NULL,
+    entries BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,225 @@
+//
+// Read only rewards api
+// Serves the per epoch reward ledger of an address, so an owner can be told
+// what they earned on a given day and rewards can be reconciled against the
+// reward manifests
+//
+// Endpoints:
+//   GET /v1/rewards/:address?start=<rfc3339>&end=<rfc3339>
+//   GET /v1/snapshots/root?at=<rfc3339>
+//
+// Epochs are selected by the end timestamp of their reward manifest. `end`
+// defaults to now and `start` to 30 days before `end`. The snapshot root is
+// that of the latest reward index snapshot taken at or before `at`, which
+// defaults to now.
+//
+
+use crate::{indexer::RewardType, reward_index, reward_index::LedgerEntry, snapshot};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::{collections::BTreeMap, future::IntoFuture, net::SocketAddr};
+use tokio::net::TcpListener;
+
+const DEFAULT_RANGE_DAYS: i64 = 30;
+
+#[derive(Debug, Serialize)]
+pub struct AddressRewards {
+    pub address: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub total: u64,
+    pub by_type: BTreeMap<RewardType, u64>,
+    pub epochs: Vec<EpochRewards>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct EpochRewards {
+    pub epoch_end: DateTime<Utc>,
+    pub total: u64,
+    pub by_type: BTreeMap<RewardType, u64>,
+}
+
+impl AddressRewards {
+    /// Groups ledger entries, which must be ordered by epoch
+    pub fn from_ledger(
+        address: String,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        entries: Vec<LedgerEntry>,
+    ) -> Self {
+        let mut by_type = BTreeMap::<RewardType, u64>::new();
+        let mut epochs: Vec<EpochRewards> = vec![];
+        for entry in entries {
+            let amount = entry.amount as u64;
+            *by_type.entry(entry.reward_type.clone()).or_default() += amount;
+            let epoch = match epochs.last_mut() {
+                Some(epoch) if epoch.epoch_end == entry.epoch_end => epoch,
+                _ => {
+                    epochs.push(EpochRewards {
+                        epoch_end: entry.epoch_end,
+                        total: 0,
+                        by_type: BTreeMap::new(),
+                    });
+                    epochs.last_mut().unwrap()
+                }
+            };
+            epoch.total += amount;
+            *epoch.by_type.entry(entry.reward_type).or_default() += amount;
+        }
+
+        Self {
+            address,
+            start,
+            end,
+            total: by_type.values().sum(),
+            by_type,
+            epochs,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SnapshotRoot {
+    pub epoch_end: DateTime<Utc>,
+    pub merkle_root: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AtParams {
+    at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RangeParams {
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+}
+
+pub struct RewardsApiServer {
+    socket_addr: SocketAddr,
+    pool: PgPool,
+}
+
+impl RewardsApiServer {
+    pub fn new(socket_addr: SocketAddr, pool: PgPool) -> Self {
+        Self { socket_addr, pool }
+    }
+
+    pub async fn run(self, shutdown: triggered::Listener) -> anyhow::Result<()> {
+        tracing::info!(
+            listen = self.socket_addr.to_string(),
+            "starting rewards api server"
+        );
+        let app = Router::new()
+            .route("/v1/rewards/:address", get(address_rewards))
+            .route("/v1/snapshots/root", get(snapshot_root))
+            .with_state(self.pool)
+            .layer(custom_tracing::http_layer::new_with_span(make_span));
+        let listener = TcpListener::bind(self.socket_addr).await?;
+        axum::serve(listener, app)
+            .with_graceful_shutdown(shutdown)
+            .into_future()
+            .await?;
+        tracing::info!("stopping rewards api server");
+        Ok(())
+    }
+}
+
+async fn address_rewards(
+    State(pool): State<PgPool>,
+    Path(address): Path<String>,
+    Query(range): Query<RangeParams>,
+) -> Result<Json<AddressRewards>, (StatusCode, String)> {
+    metrics::counter!("reward_index_api_request").increment(1);
+
+    let end = range.end.unwrap_or_else(Utc::now);
+    let start = range
+        .start
+        .unwrap_or(end - Duration::days(DEFAULT_RANGE_DAYS));
+    if start >= end {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "start must be before end".to_string(),
+        ));
+    }
+
+    let entries = reward_index::fetch_ledger(&pool, &address, start, end)
+        .await
+        .map_err(|err| {
+            tracing::warn!("rewards lookup failed: {err:?}");
+            (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+        })?;
+    Ok(Json(AddressRewards::from_ledger(
+        address, start, end, entries,
+    )))
+}
+
+async fn snapshot_root(
+    State(pool): State<PgPool>,
+    Query(params): Query<AtParams>,
+) -> Result<Json<SnapshotRoot>, (StatusCode, String)> {
+    metrics::counter!("reward_index_api_request").increment(1);
+
+    let at = params.at.unwrap_or_else(Utc::now);
+    match snapshot::db::fetch_root(&pool, at).await {
+        Ok(Some((epoch_end, merkle_root))) => Ok(Json(SnapshotRoot {
+            epoch_end,
+            merkle_root,
+        })),
+        Ok(None) => Err((StatusCode::NOT_FOUND, format!("no snapshot at {at}"))),
+        Err(err) => {
+            tracing::warn!("snapshot root lookup failed: {err:?}");
+            Err((StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))
+        }
+    }
+}
+
+fn make_span(_request: &axum::http::Request<axum::body::Body>) -> tracing::Span {
+    tracing::info_span!(custom_tracing::DEFAULT_SPAN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_ledger_by_epoch_and_type() {
+        let day = |n| DateTime::UNIX_EPOCH + Duration::days(n);
+        let entry = |n, reward_type, amount| LedgerEntry {
+            epoch_end: day(n),
+            reward_type,
+            amount,
+        };
+        let rewards = AddressRewards::from_ledger(
+            "address".to_string(),
+            day(0),
+            day(3),
+            vec![
+                entry(1, RewardType::MobileGateway, 10),
+                entry(1, RewardType::MobileServiceProvider, 5),
+                entry(2, RewardType::MobileGateway, 20),
+            ],
+        );
+
+        assert_eq!(rewards.total, 35);
+        assert_eq!(rewards.by_type[&RewardType::MobileGateway], 30);
+        assert_eq!(rewards.by_type[&RewardType::MobileServiceProvider], 5);
+        assert_eq!(rewards.epochs.len(), 2);
+        assert_eq!(rewards.epochs[0].total, 15);
+        assert_eq!(
+            rewards.epochs[1],
+            EpochRewards {
+                epoch_end: day(2),
+                total: 20,
+                by_type: BTreeMap::from([(RewardType::MobileGateway, 20)]),
+            }
+        );
+    }
+}
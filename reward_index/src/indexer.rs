@@ -1,4 +1,9 @@
-use crate::{reward_index, settings, telemetry, Settings};
+use crate::{
+    manifest::{self, Reprocess},
+    reward_index, settings,
+    snapshot::CommittedManifest,
+    telemetry, Settings,
+};
 use anyhow::{anyhow, bail, Result};
 use chrono::Utc;
 use file_store::{
@@ -18,8 +23,11 @@ use helium_proto::{
 };
 use poc_metrics::record_duration;
 use sqlx::{Pool, Postgres, Transaction};
-use std::{collections::HashMap, str::FromStr};
-use tokio::sync::mpsc::Receiver;
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
+use tokio::sync::{mpsc::Receiver, watch};
 
 pub struct Indexer {
     pool: Pool<Postgres>,
@@ -27,10 +35,13 @@ pub struct Indexer {
     mode: settings::Mode,
     op_fund_key: String,
     unallocated_reward_key: String,
+    reprocess: Reprocess,
+    committed: watch::Sender<Option<CommittedManifest>>,
 }
 
-#[derive(sqlx::Type, Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(sqlx::Type, serde::Serialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[sqlx(type_name = "reward_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum RewardType {
     MobileGateway,
     IotGateway,
@@ -44,8 +55,8 @@ pub enum RewardType {
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct RewardKey {
-    key: String,
-    reward_type: RewardType,
+    pub key: String,
+    pub reward_type: RewardType,
 }
 
 impl Indexer {
@@ -63,9 +74,16 @@ impl Indexer {
             unallocated_reward_key: settings
                 .unallocated_reward_entity_key()
                 .ok_or_else(|| anyhow!("missing unallocated reward key"))?,
+            reprocess: settings.reprocess,
+            committed: watch::Sender::new(None),
         })
     }
 
+    /// Notified of the last manifest indexed after each committed reward file
+    pub fn subscribe_committed(&self) -> watch::Receiver<Option<CommittedManifest>> {
+        self.committed.subscribe()
+    }
+
     pub async fn run(
         &mut self,
         shutdown: triggered::Listener,
@@ -86,13 +104,18 @@ impl Indexer {
                     let mut txn = self.pool.begin().await?;
                     let mut stream = file_info_stream.into_stream(&mut txn).await?;
 
+                    let mut committed = None;
                     while let Some(reward_manifest) = stream.next().await {
-                        record_duration!(
+                        let indexed = record_duration!(
                             "reward_index_duration",
-                            self.handle_rewards(&mut txn, reward_manifest).await?
-                        )
+                            self.handle_rewards(&mut txn, key, reward_manifest).await?
+                        );
+                        committed = indexed.or(committed);
                     }
                     txn.commit().await?;
+                    if committed.is_some() {
+                        self.committed.send_replace(committed);
+                    }
                     tracing::info!(file = %key, "Completed processing reward file");
                     telemetry::last_reward_processed_time(&self.pool, Utc::now()).await?;
                 }
@@ -103,21 +126,112 @@ impl Indexer {
     async fn handle_rewards(
         &mut self,
         txn: &mut Transaction<'_, Postgres>,
+        manifest_key: &str,
         manifest: RewardManifest,
-    ) -> Result<()> {
+    ) -> Result<Option<CommittedManifest>> {
         let manifest_time = manifest.end_timestamp;
 
+        // if the token type defined in the reward data is not HNT, then bail
+        self.verify_token_type(&manifest.reward_data)?;
+
+        let previous = manifest::fetch(txn, manifest_time).await?;
+        if let Some(previous) = &previous {
+            metrics::counter!("reward_index_reprocessed_manifest").increment(1);
+            tracing::warn!(
+                manifest = manifest_key,
+                previous_manifest = previous.manifest_key,
+                same_files =
+                    previous.written_files_digest == manifest::written_files_digest(&manifest),
+                reprocess = ?self.reprocess,
+                "Epoch already indexed"
+            );
+            if self.reprocess == Reprocess::Skip {
+                return Ok(None);
+            }
+        } else if let Some(last_reward) = reward_index::last_reward(&mut *txn).await? {
+            // Epochs indexed before manifests were recorded have no manifest,
+            // indexing them again would count their rewards twice
+            if manifest_time <= last_reward {
+                metrics::counter!("reward_index_unrecorded_manifest").increment(1);
+                tracing::warn!(
+                    manifest = manifest_key,
+                    %last_reward,
+                    "Epoch ends before the last indexed reward but has no recorded manifest, skipping"
+                );
+                return Ok(None);
+            }
+        }
+
+        let hotspot_rewards = self.compute_rewards(&manifest).await?;
+
+        let mut totals = BTreeMap::<RewardType, u64>::new();
+        for (reward_key, amount) in &hotspot_rewards {
+            *totals.entry(reward_key.reward_type.clone()).or_default() += amount;
+        }
+
+        if previous.is_some() {
+            let mut indexed = reward_index::fetch_epoch_ledger(&mut *txn, &manifest_time).await?;
+            for (reward_key, amount) in hotspot_rewards {
+                let previous_amount = indexed
+                    .remove(&(reward_key.key.clone(), reward_key.reward_type.clone()))
+                    .unwrap_or_default();
+                reward_index::replace(
+                    txn,
+                    reward_key.key,
+                    previous_amount,
+                    amount,
+                    reward_key.reward_type,
+                    &manifest_time,
+                )
+                .await?;
+            }
+            // Rewarded before but not anymore
+            for ((address, reward_type), previous_amount) in indexed {
+                reward_index::replace(
+                    txn,
+                    address,
+                    previous_amount,
+                    0,
+                    reward_type,
+                    &manifest_time,
+                )
+                .await?;
+            }
+        } else {
+            for (reward_key, amount) in hotspot_rewards {
+                reward_index::insert(
+                    txn,
+                    reward_key.key,
+                    amount,
+                    reward_key.reward_type,
+                    &manifest_time,
+                )
+                .await?;
+            }
+        }
+
+        manifest::record(txn, manifest_key, &manifest, &totals).await?;
+
+        Ok(Some(CommittedManifest {
+            manifest_key: manifest_key.to_string(),
+            epoch_end: manifest_time,
+        }))
+    }
+
+    /// Sums the reward shares of the manifest's files by address and type
+    pub async fn compute_rewards(
+        &self,
+        manifest: &RewardManifest,
+    ) -> Result<HashMap<RewardKey, u64>> {
         let reward_files = stream::iter(
             manifest
                 .written_files
+                .clone()
                 .into_iter()
                 .map(|file_name| FileInfo::from_str(&file_name)),
         )
         .boxed();
 
-        // if the token type defined in the reward data is not HNT, then bail
-        self.verify_token_type(&manifest.reward_data)?;
-
         let mut reward_shares = self.verifier_store.source_unordered(5, reward_files);
         let mut hotspot_rewards: HashMap<RewardKey, u64> = HashMap::new();
 
@@ -127,18 +241,7 @@ impl Indexer {
             };
         }
 
-        for (reward_key, amount) in hotspot_rewards {
-            reward_index::insert(
-                &mut *txn,
-                reward_key.key,
-                amount,
-                reward_key.reward_type,
-                &manifest_time,
-            )
-            .await?;
-        }
-
-        Ok(())
+        Ok(hotspot_rewards)
     }
 
     fn extract_reward_share(&self, msg: &[u8]) -> Result<Option<(RewardKey, u64)>> {
@@ -1,6 +1,10 @@
+pub mod api;
 pub mod indexer;
-mod reward_index;
+pub mod manifest;
+pub mod reconcile;
+pub mod reward_index;
 pub mod settings;
+pub mod snapshot;
 pub mod telemetry;
 
 pub use indexer::Indexer;
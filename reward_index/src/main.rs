@@ -1,11 +1,18 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use clap::Parser;
 use file_store::{
     file_info_poller::LookbackBehavior, file_source, reward_manifest::RewardManifest, FileStore,
     FileType,
 };
 use futures_util::TryFutureExt;
-use reward_index::{settings::Settings, telemetry, Indexer};
+use reward_index::{
+    api::RewardsApiServer,
+    reconcile,
+    settings::Settings,
+    snapshot::{Snapshot, SnapshotExporter},
+    telemetry, Indexer,
+};
 use std::path::PathBuf;
 use tokio::signal;
 
@@ -34,12 +41,19 @@ impl Cli {
 #[derive(Debug, clap::Subcommand)]
 pub enum Cmd {
     Server(Server),
+    /// Recompute rewards from the verifier bucket and compare them with
+    /// the index
+    Reconcile(Reconcile),
+    /// Check the Merkle root and every inclusion proof of a snapshot file
+    VerifySnapshot(VerifySnapshot),
 }
 
 impl Cmd {
     pub async fn run(&self, settings: Settings) -> Result<()> {
         match self {
             Self::Server(cmd) => cmd.run(&settings).await,
+            Self::Reconcile(cmd) => cmd.run(&settings).await,
+            Self::VerifySnapshot(cmd) => cmd.run(),
         }
     }
 }
@@ -81,18 +95,135 @@ impl Server {
             .await?;
         let source_join_handle = server.start(shutdown_listener.clone()).await?;
 
+        // Rewards api
+        let api_server = settings
+            .api_listen
+            .map(|listen| RewardsApiServer::new(listen, pool.clone()));
+        let api_shutdown = shutdown_listener.clone();
+        let api = async move {
+            match api_server {
+                Some(api_server) => api_server.run(api_shutdown).await,
+                None => Ok(()),
+            }
+        };
+
         // Reward server
-        let mut indexer = Indexer::new(settings, pool).await?;
+        let mut indexer = Indexer::new(settings, pool.clone()).await?;
+
+        // Snapshots of the committed index
+        let snapshot_exporter = SnapshotExporter::from_settings(&settings.snapshot, pool).await?;
+        let snapshot_shutdown = shutdown_listener.clone();
+        let committed = indexer.subscribe_committed();
+        let snapshots = async move {
+            match snapshot_exporter {
+                Some(snapshot_exporter) => {
+                    snapshot_exporter.run(snapshot_shutdown, committed).await
+                }
+                None => Ok(()),
+            }
+        };
 
         tokio::try_join!(
             source_join_handle.map_err(anyhow::Error::from),
             indexer.run(shutdown_listener, receiver),
+            api,
+            snapshots,
         )?;
 
         Ok(())
     }
 }
 
+#[derive(Debug, clap::Args)]
+pub struct Reconcile {
+    /// Start of the range of epoch end timestamps, inclusive (RFC 3339)
+    #[clap(long)]
+    start: DateTime<Utc>,
+    /// End of the range of epoch end timestamps, exclusive (RFC 3339)
+    #[clap(long)]
+    end: DateTime<Utc>,
+}
+
+impl Reconcile {
+    pub async fn run(&self, settings: &Settings) -> Result<()> {
+        let app_name = format!("{}_{}_reconcile", settings.mode, env!("CARGO_PKG_NAME"));
+        let pool = settings.database.connect(&app_name).await?;
+
+        let epochs = reconcile::reconcile(settings, pool, self.start, self.end).await?;
+        let mut failed = 0;
+        let mut unverified = 0;
+        for epoch in &epochs {
+            let status = if !epoch.ledger {
+                unverified += 1;
+                "NO LEDGER"
+            } else if epoch.indexed && epoch.mismatches.is_empty() {
+                "ok"
+            } else {
+                failed += 1;
+                "MISMATCH"
+            };
+            println!(
+                "{} epoch {} ending {}: {status}",
+                epoch.manifest_key, epoch.epoch, epoch.epoch_end,
+            );
+            if !epoch.ledger {
+                println!("    no ledger rows, indexed before the ledger was added or not at all");
+                continue;
+            }
+            if !epoch.indexed {
+                println!("    manifest not recorded as indexed");
+            }
+            if epoch.expected_totals != epoch.indexed_totals {
+                println!("    expected totals: {:?}", epoch.expected_totals);
+                println!("    indexed totals:  {:?}", epoch.indexed_totals);
+            }
+            for mismatch in &epoch.mismatches {
+                println!(
+                    "    {} {:?}: expected {} indexed {}",
+                    mismatch.address, mismatch.reward_type, mismatch.expected, mismatch.indexed
+                );
+            }
+        }
+        println!("{} of {} epochs mismatched", failed, epochs.len());
+        if unverified > 0 {
+            println!("{unverified} epochs have no ledger rows and were not compared");
+        }
+
+        if failed > 0 {
+            anyhow::bail!("reconciliation found {failed} mismatched epochs");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct VerifySnapshot {
+    /// Path of the snapshot file
+    #[clap(long)]
+    file: PathBuf,
+}
+
+impl VerifySnapshot {
+    pub fn run(&self) -> Result<()> {
+        let snapshot = Snapshot::read(&self.file)?;
+        let invalid = snapshot.verify()?;
+        for address in &invalid {
+            println!("invalid proof: {address}");
+        }
+        println!(
+            "{}: {} entries, merkle root {}, {} invalid proofs",
+            snapshot.manifest_key,
+            snapshot.entries.len(),
+            snapshot.merkle_root,
+            invalid.len()
+        );
+        if !invalid.is_empty() {
+            anyhow::bail!("snapshot has {} invalid proofs", invalid.len());
+        }
+        Ok(())
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
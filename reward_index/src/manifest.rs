@@ -0,0 +1,142 @@
+use crate::indexer::RewardType;
+use chrono::{DateTime, Utc};
+use file_store::reward_manifest::RewardManifest;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use sqlx::{Postgres, Transaction};
+use std::collections::BTreeMap;
+
+/// What to do with a manifest for an epoch that has already been indexed
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Reprocess {
+    /// Leave the index as is
+    #[default]
+    Skip,
+    /// Apply the difference between the manifest's rewards and those already
+    /// indexed for the epoch
+    Delta,
+}
+
+/// A reward manifest that has been indexed
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct IndexedManifest {
+    pub manifest_key: String,
+    pub epoch: i64,
+    pub epoch_end: DateTime<Utc>,
+    pub written_files_digest: String,
+    pub indexed_at: DateTime<Utc>,
+}
+
+/// Identifies the reward shares of a manifest independent of the order its
+/// files were written in
+pub fn written_files_digest(manifest: &RewardManifest) -> String {
+    let mut files = manifest.written_files.clone();
+    files.sort();
+    let mut hasher = Sha256::new();
+    for file in files {
+        hasher.update(file.as_bytes());
+        hasher.update(b"\n");
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+pub async fn fetch(
+    txn: &mut Transaction<'_, Postgres>,
+    epoch_end: DateTime<Utc>,
+) -> Result<Option<IndexedManifest>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        select manifest_key, epoch, epoch_end, written_files_digest, indexed_at
+        from reward_manifests
+        where epoch_end = $1
+        "#,
+    )
+    .bind(epoch_end)
+    .fetch_optional(&mut *txn)
+    .await
+}
+
+/// Records the manifest and its per type totals, replacing those of a
+/// previously indexed manifest for the same epoch
+pub async fn record(
+    txn: &mut Transaction<'_, Postgres>,
+    manifest_key: &str,
+    manifest: &RewardManifest,
+    totals: &BTreeMap<RewardType, u64>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        insert into reward_manifests (
+                manifest_key,
+                epoch,
+                epoch_end,
+                written_files_digest,
+                indexed_at
+            ) values ($1, $2, $3, $4, $5)
+            on conflict(epoch_end) do update set
+                manifest_key = EXCLUDED.manifest_key,
+                epoch = EXCLUDED.epoch,
+                written_files_digest = EXCLUDED.written_files_digest,
+                indexed_at = EXCLUDED.indexed_at
+        "#,
+    )
+    .bind(manifest_key)
+    .bind(manifest.epoch as i64)
+    .bind(manifest.end_timestamp)
+    .bind(written_files_digest(manifest))
+    .bind(Utc::now())
+    .execute(&mut *txn)
+    .await?;
+
+    sqlx::query("delete from reward_manifest_totals where epoch_end = $1")
+        .bind(manifest.end_timestamp)
+        .execute(&mut *txn)
+        .await?;
+    for (reward_type, amount) in totals {
+        sqlx::query(
+            r#"
+            insert into reward_manifest_totals (epoch_end, reward_type, amount)
+            values ($1, $2, $3)
+            "#,
+        )
+        .bind(manifest.end_timestamp)
+        .bind(reward_type)
+        .bind(*amount as i64)
+        .execute(&mut *txn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(written_files: &[&str]) -> RewardManifest {
+        RewardManifest {
+            written_files: written_files.iter().map(ToString::to_string).collect(),
+            start_timestamp: DateTime::UNIX_EPOCH,
+            end_timestamp: DateTime::UNIX_EPOCH,
+            reward_data: None,
+            epoch: 1,
+            price: 0,
+        }
+    }
+
+    #[test]
+    fn digest_ignores_file_order() {
+        let digest = written_files_digest(&manifest(&["a.1.gz", "b.2.gz"]));
+        assert_eq!(
+            digest,
+            written_files_digest(&manifest(&["b.2.gz", "a.1.gz"]))
+        );
+        assert_ne!(digest, written_files_digest(&manifest(&["a.1.gz"])));
+        assert_eq!(digest.len(), 64);
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{
+    indexer::{RewardKey, RewardType},
+    manifest, reward_index, Indexer, Settings,
+};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use file_store::{reward_manifest::RewardManifest, traits::MsgDecode, FileStore, FileType};
+use futures::TryStreamExt;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
+
+/// Manifests are written shortly after the end of their epoch, so files are
+/// listed a little past the end of the range and filtered by epoch end.
+const MANIFEST_WRITE_SLACK_HOURS: i64 = 6;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Mismatch {
+    pub address: String,
+    pub reward_type: RewardType,
+    pub expected: u64,
+    pub indexed: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EpochReconciliation {
+    pub manifest_key: String,
+    pub epoch: u64,
+    pub epoch_end: DateTime<Utc>,
+    /// Whether the epoch has a recorded indexed manifest
+    pub indexed: bool,
+    /// Whether the ledger has any rows for the epoch. Epochs indexed before
+    /// the ledger was added have none, so they are not compared.
+    pub ledger: bool,
+    pub expected_totals: BTreeMap<RewardType, u64>,
+    pub indexed_totals: BTreeMap<RewardType, u64>,
+    pub mismatches: Vec<Mismatch>,
+}
+
+/// Compares the rewards recomputed from a manifest with those in the ledger.
+/// Mismatches are sorted by address and reward type.
+pub fn compare(
+    expected: &HashMap<RewardKey, u64>,
+    indexed: &HashMap<(String, RewardType), u64>,
+) -> Vec<Mismatch> {
+    let mut mismatches: Vec<Mismatch> = expected
+        .iter()
+        .map(|(key, amount)| {
+            (
+                key.key.clone(),
+                key.reward_type.clone(),
+                *amount,
+                indexed
+                    .get(&(key.key.clone(), key.reward_type.clone()))
+                    .copied()
+                    .unwrap_or_default(),
+            )
+        })
+        .chain(
+            indexed
+                .iter()
+                .filter_map(|((address, reward_type), amount)| {
+                    let key = RewardKey {
+                        key: address.clone(),
+                        reward_type: reward_type.clone(),
+                    };
+                    (!expected.contains_key(&key))
+                        .then(|| (address.clone(), reward_type.clone(), 0, *amount))
+                }),
+        )
+        .filter(|(_, _, expected, indexed)| expected != indexed)
+        .map(|(address, reward_type, expected, indexed)| Mismatch {
+            address,
+            reward_type,
+            expected,
+            indexed,
+        })
+        .collect();
+    mismatches.sort_by(|a, b| (&a.address, &a.reward_type).cmp(&(&b.address, &b.reward_type)));
+    mismatches
+}
+
+fn totals<'a>(
+    amounts: impl Iterator<Item = (&'a RewardType, &'a u64)>,
+) -> BTreeMap<RewardType, u64> {
+    let mut totals = BTreeMap::new();
+    for (reward_type, amount) in amounts {
+        *totals.entry(reward_type.clone()).or_default() += amount;
+    }
+    totals
+}
+
+/// Recomputes the rewards of every manifest whose epoch ends in
+/// `[start, end)` from the verifier bucket and compares them with the index
+pub async fn reconcile(
+    settings: &Settings,
+    pool: PgPool,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<EpochReconciliation>> {
+    let file_store = FileStore::from_settings(&settings.verifier).await?;
+    let indexer = Indexer::new(settings, pool.clone()).await?;
+
+    let manifest_files = file_store
+        .list_all(
+            &FileType::RewardManifest.to_string(),
+            start,
+            end + Duration::hours(MANIFEST_WRITE_SLACK_HOURS),
+        )
+        .await?;
+
+    let mut results = vec![];
+    for file_info in manifest_files {
+        let mut stream = file_store.get(file_info.key.clone()).await?;
+        while let Some(msg) = stream.try_next().await? {
+            let manifest = RewardManifest::decode(msg)?;
+            if manifest.end_timestamp < start || manifest.end_timestamp >= end {
+                continue;
+            }
+
+            let expected = indexer.compute_rewards(&manifest).await?;
+            let indexed = reward_index::fetch_epoch_ledger(&pool, &manifest.end_timestamp).await?;
+            let mut txn = pool.begin().await?;
+            let recorded = manifest::fetch(&mut txn, manifest.end_timestamp).await?;
+            txn.commit().await?;
+
+            let ledger = !indexed.is_empty();
+            if !ledger {
+                tracing::warn!(
+                    manifest = file_info.key,
+                    epoch_end = %manifest.end_timestamp,
+                    "No ledger rows for epoch, skipping comparison"
+                );
+            }
+
+            results.push(EpochReconciliation {
+                manifest_key: file_info.key.clone(),
+                epoch: manifest.epoch,
+                epoch_end: manifest.end_timestamp,
+                indexed: recorded.is_some(),
+                ledger,
+                expected_totals: totals(expected.iter().map(|(k, v)| (&k.reward_type, v))),
+                indexed_totals: totals(indexed.iter().map(|((_, t), v)| (t, v))),
+                mismatches: if ledger {
+                    compare(&expected, &indexed)
+                } else {
+                    vec![]
+                },
+            });
+        }
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_differing_and_missing_rewards() {
+        let key = |address: &str, reward_type| RewardKey {
+            key: address.to_string(),
+            reward_type,
+        };
+        let expected = HashMap::from([
+            (key("a", RewardType::MobileGateway), 10),
+            (key("b", RewardType::MobileGateway), 20),
+            (key("c", RewardType::MobileSubscriber), 30),
+        ]);
+        let indexed = HashMap::from([
+            (("a".to_string(), RewardType::MobileGateway), 10),
+            (("b".to_string(), RewardType::MobileGateway), 15),
+            (("d".to_string(), RewardType::MobileGateway), 5),
+        ]);
+
+        let mismatch = |address: &str, reward_type, expected, indexed| Mismatch {
+            address: address.to_string(),
+            reward_type,
+            expected,
+            indexed,
+        };
+        assert_eq!(
+            compare(&expected, &indexed),
+            vec![
+                mismatch("b", RewardType::MobileGateway, 20, 15),
+                mismatch("c", RewardType::MobileSubscriber, 30, 0),
+                mismatch("d", RewardType::MobileGateway, 0, 5),
+            ]
+        );
+    }
+}
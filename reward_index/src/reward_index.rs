@@ -1,16 +1,17 @@
 use crate::indexer::RewardType;
 use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::HashMap;
 
-pub async fn insert<'c, E>(
-    executor: E,
+/// Adds the reward to the cumulative index and to the per epoch ledger of the
+/// address
+pub async fn insert(
+    txn: &mut Transaction<'_, Postgres>,
     address: String,
     amount: u64,
     reward_type: RewardType,
     timestamp: &DateTime<Utc>,
-) -> Result<(), sqlx::Error>
-where
-    E: sqlx::Executor<'c, Database = sqlx::Postgres>,
-{
+) -> Result<(), sqlx::Error> {
     // Safeguard against 0 amount shares updating the last rewarded timestamp
     if amount == 0 {
         return Ok(());
@@ -29,12 +30,160 @@ where
                 last_reward = EXCLUDED.last_reward
         "#,
     )
-    .bind(address)
+    .bind(&address)
     .bind(amount as i64)
     .bind(timestamp)
+    .bind(&reward_type)
+    .execute(&mut *txn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        insert into reward_ledger (
+                address,
+                reward_type,
+                epoch_end,
+                amount
+            ) values ($1, $2, $3, $4)
+            on conflict(address, epoch_end, reward_type) do update set
+                amount = reward_ledger.amount + EXCLUDED.amount
+        "#,
+    )
+    .bind(address)
     .bind(reward_type)
-    .execute(executor)
+    .bind(timestamp)
+    .bind(amount as i64)
+    .execute(&mut *txn)
     .await?;
 
     Ok(())
 }
+
+/// Replaces the ledger amount of the address for the epoch, adjusting the
+/// cumulative index by the difference with the previously indexed amount
+pub async fn replace(
+    txn: &mut Transaction<'_, Postgres>,
+    address: String,
+    previous: u64,
+    amount: u64,
+    reward_type: RewardType,
+    timestamp: &DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let delta = amount as i64 - previous as i64;
+    if delta == 0 {
+        return Ok(());
+    }
+
+    sqlx::query(
+        r#"
+        insert into reward_index (
+                address,
+                rewards,
+                last_reward,
+                reward_type
+            ) values ($1, $2, $3, $4)
+            on conflict(address) do update set
+                rewards = reward_index.rewards + EXCLUDED.rewards,
+                last_reward = greatest(reward_index.last_reward, EXCLUDED.last_reward)
+        "#,
+    )
+    .bind(&address)
+    .bind(delta)
+    .bind(timestamp)
+    .bind(&reward_type)
+    .execute(&mut *txn)
+    .await?;
+
+    if amount == 0 {
+        sqlx::query(
+            "delete from reward_ledger where address = $1 and epoch_end = $2 and reward_type = $3",
+        )
+        .bind(address)
+        .bind(timestamp)
+        .bind(reward_type)
+        .execute(&mut *txn)
+        .await?;
+    } else {
+        sqlx::query(
+            r#"
+            insert into reward_ledger (
+                    address,
+                    reward_type,
+                    epoch_end,
+                    amount
+                ) values ($1, $2, $3, $4)
+                on conflict(address, epoch_end, reward_type) do update set
+                    amount = EXCLUDED.amount
+            "#,
+        )
+        .bind(address)
+        .bind(reward_type)
+        .bind(timestamp)
+        .bind(amount as i64)
+        .execute(&mut *txn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// The latest reward timestamp in the index, i.e. the end of the last epoch
+/// indexed
+pub async fn last_reward<'c, E>(executor: E) -> Result<Option<DateTime<Utc>>, sqlx::Error>
+where
+    E: sqlx::Executor<'c, Database = sqlx::Postgres>,
+{
+    sqlx::query_scalar("select max(last_reward) from reward_index")
+        .fetch_one(executor)
+        .await
+}
+
+/// Every ledger amount of the epoch by address and reward type
+pub async fn fetch_epoch_ledger<'c, E>(
+    executor: E,
+    epoch_end: &DateTime<Utc>,
+) -> Result<HashMap<(String, RewardType), u64>, sqlx::Error>
+where
+    E: sqlx::Executor<'c, Database = sqlx::Postgres>,
+{
+    let rows: Vec<(String, RewardType, i64)> = sqlx::query_as(
+        "select address, reward_type, amount from reward_ledger where epoch_end = $1",
+    )
+    .bind(epoch_end)
+    .fetch_all(executor)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(address, reward_type, amount)| ((address, reward_type), amount as u64))
+        .collect())
+}
+
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct LedgerEntry {
+    pub epoch_end: DateTime<Utc>,
+    pub reward_type: RewardType,
+    pub amount: i64,
+}
+
+/// Ledger entries of the address for epochs ending in `[start, end)`,
+/// ordered by epoch
+pub async fn fetch_ledger(
+    pool: &PgPool,
+    address: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<LedgerEntry>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        select epoch_end, reward_type, amount
+        from reward_ledger
+        where address = $1 and epoch_end >= $2 and epoch_end < $3
+        order by epoch_end, reward_type
+        "#,
+    )
+    .bind(address)
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await
+}
@@ -1,8 +1,9 @@
+use crate::{manifest::Reprocess, snapshot::SnapshotSettings};
 use chrono::{DateTime, Utc};
 use config::{Config, Environment, File};
 use humantime_serde::re::humantime;
 use serde::Deserialize;
-use std::{fmt, path::Path, time::Duration};
+use std::{fmt, net::SocketAddr, path::Path, time::Duration};
 
 /// Mode to start the indexer in. Each mode uses different files from
 /// the verifier
@@ -42,6 +43,17 @@ pub struct Settings {
     pub unallocated_reward_entity_key: Option<String>,
     #[serde(default = "default_start_after")]
     pub start_after: DateTime<Utc>,
+    /// Listen address for the read only rewards http api. The api is not
+    /// started if unset
+    pub api_listen: Option<SocketAddr>,
+    /// What to do with a reward manifest for an epoch that has already been
+    /// indexed, "skip" or "delta". (Default is skip)
+    #[serde(default)]
+    pub reprocess: Reprocess,
+    /// Snapshot the reward index with a Merkle root after each indexed
+    /// manifest. (Default is disabled)
+    #[serde(default)]
+    pub snapshot: Option<SnapshotSettings>,
 }
 
 fn default_interval() -> Duration {
@@ -0,0 +1,485 @@
+//
+// Snapshots of the cumulative reward index taken at a reward manifest
+// boundary, with a Merkle root over every (address, reward type, amount)
+// entry and an inclusion proof per entry.
+//
+// Leaves are sha256(0x00 || address || 0x00 || reward_type || 0x00 ||
+// amount as big endian u64) and are ordered by address then reward type.
+// Inner nodes are sha256(0x01 || left || right). The last node of an odd
+// sized level is carried up to the next level unchanged. The root of an
+// empty snapshot is sha256 of nothing.
+//
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use file_store::{FileExporter, FileStore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Postgres, Transaction};
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+use tokio::sync::watch;
+
+pub const SNAPSHOT_PREFIX: &str = "reward_index_snapshot";
+
+const LEAF_TAG: u8 = 0x00;
+const NODE_TAG: u8 = 0x01;
+
+type Hash = [u8; 32];
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SnapshotSettings {
+    /// Directory the snapshots are written to
+    pub directory: PathBuf,
+    /// Bucket the snapshots are uploaded to. Snapshots are only kept locally
+    /// if unset
+    pub output: Option<file_store::Settings>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
+pub struct SnapshotEntry {
+    pub address: String,
+    pub reward_type: String,
+    pub amount: i64,
+}
+
+impl SnapshotEntry {
+    fn leaf_hash(&self) -> Hash {
+        let mut hasher = Sha256::new();
+        hasher.update([LEAF_TAG]);
+        hasher.update(self.address.as_bytes());
+        hasher.update([0]);
+        hasher.update(self.reward_type.as_bytes());
+        hasher.update([0]);
+        hasher.update((self.amount as u64).to_be_bytes());
+        hasher.finalize().into()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// A sibling hash on the path from a leaf to the root and which side of the
+/// path it is on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofStep {
+    pub hash: String,
+    pub side: Side,
+}
+
+pub struct MerkleTree {
+    levels: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    pub fn new(leaves: Vec<Hash>) -> Self {
+        let mut levels = vec![leaves];
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Self { levels }
+    }
+
+    pub fn root(&self) -> Hash {
+        match self.levels.last().and_then(|level| level.first()) {
+            Some(root) => *root,
+            None => Sha256::digest([]).into(),
+        }
+    }
+
+    pub fn proof(&self, mut index: usize) -> Vec<ProofStep> {
+        let mut proof = vec![];
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = index ^ 1;
+            if let Some(hash) = level.get(sibling) {
+                proof.push(ProofStep {
+                    hash: encode_hash(hash),
+                    side: if sibling < index {
+                        Side::Left
+                    } else {
+                        Side::Right
+                    },
+                });
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
+
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_TAG]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+fn encode_hash(hash: &Hash) -> String {
+    hash.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn decode_hash(hash: &str) -> Option<Hash> {
+    if hash.len() != 64 {
+        return None;
+    }
+    let mut decoded = [0; 32];
+    for (i, byte) in decoded.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hash.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+    Some(decoded)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotLeaf {
+    pub address: String,
+    pub reward_type: String,
+    pub amount: u64,
+    pub proof: Vec<ProofStep>,
+}
+
+impl SnapshotLeaf {
+    /// Whether the proof leads from this entry to the given root
+    pub fn verify(&self, root: &str) -> bool {
+        let entry = SnapshotEntry {
+            address: self.address.clone(),
+            reward_type: self.reward_type.clone(),
+            amount: self.amount as i64,
+        };
+        let mut hash = entry.leaf_hash();
+        for step in &self.proof {
+            let Some(sibling) = decode_hash(&step.hash) else {
+                return false;
+            };
+            hash = match step.side {
+                Side::Left => node_hash(&sibling, &hash),
+                Side::Right => node_hash(&hash, &sibling),
+            };
+        }
+        encode_hash(&hash) == root
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub manifest_key: String,
+    pub epoch_end: DateTime<Utc>,
+    pub merkle_root: String,
+    pub entries: Vec<SnapshotLeaf>,
+}
+
+impl Snapshot {
+    pub fn new(
+        manifest_key: String,
+        epoch_end: DateTime<Utc>,
+        mut entries: Vec<SnapshotEntry>,
+    ) -> Self {
+        entries.sort();
+        let tree = MerkleTree::new(entries.iter().map(SnapshotEntry::leaf_hash).collect());
+        let entries = entries
+            .into_iter()
+            .enumerate()
+            .map(|(index, entry)| SnapshotLeaf {
+                address: entry.address,
+                reward_type: entry.reward_type,
+                amount: entry.amount as u64,
+                proof: tree.proof(index),
+            })
+            .collect();
+        Self {
+            manifest_key,
+            epoch_end,
+            merkle_root: encode_hash(&tree.root()),
+            entries,
+        }
+    }
+
+    pub fn file_name(&self) -> String {
+        format!(
+            "{SNAPSHOT_PREFIX}.{}.json",
+            self.epoch_end.timestamp_millis()
+        )
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+    }
+
+    /// Recomputes the root from the entries and checks every proof against
+    /// it. Returns the addresses whose proof does not verify.
+    pub fn verify(&self) -> Result<Vec<String>> {
+        let entries = self
+            .entries
+            .iter()
+            .map(|leaf| SnapshotEntry {
+                address: leaf.address.clone(),
+                reward_type: leaf.reward_type.clone(),
+                amount: leaf.amount as i64,
+            })
+            .collect();
+        let rebuilt = Self::new(self.manifest_key.clone(), self.epoch_end, entries);
+        if rebuilt.merkle_root != self.merkle_root {
+            anyhow::bail!(
+                "merkle root {} does not match entries, expected {}",
+                self.merkle_root,
+                rebuilt.merkle_root
+            );
+        }
+        Ok(self
+            .entries
+            .iter()
+            .filter(|leaf| !leaf.verify(&self.merkle_root))
+            .map(|leaf| leaf.address.clone())
+            .collect())
+    }
+}
+
+/// The manifest most recently committed to the index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommittedManifest {
+    pub manifest_key: String,
+    pub epoch_end: DateTime<Utc>,
+}
+
+/// Writes a snapshot of the reward index after manifests are committed. Runs
+/// apart from the indexer and only snapshots the latest committed manifest,
+/// so manifests indexed while a snapshot is being written are skipped.
+pub struct SnapshotExporter {
+    pool: PgPool,
+    exporter: FileExporter,
+}
+
+impl SnapshotExporter {
+    pub async fn from_settings(
+        settings: &Option<SnapshotSettings>,
+        pool: PgPool,
+    ) -> Result<Option<Self>> {
+        let Some(settings) = settings else {
+            return Ok(None);
+        };
+        let file_store = match &settings.output {
+            Some(output) => Some(FileStore::from_settings(output).await?),
+            None => None,
+        };
+        Ok(Some(Self {
+            pool,
+            exporter: FileExporter::new(settings.directory.clone(), file_store),
+        }))
+    }
+
+    pub async fn run(
+        self,
+        shutdown: triggered::Listener,
+        mut indexed: watch::Receiver<Option<CommittedManifest>>,
+    ) -> Result<()> {
+        tracing::info!("starting reward index snapshots");
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown.clone() => break,
+                changed = indexed.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                    let Some(manifest) = indexed.borrow_and_update().clone() else {
+                        continue;
+                    };
+                    if let Err(err) = self.export(&manifest).await {
+                        metrics::counter!("reward_index_snapshot_errors").increment(1);
+                        tracing::error!(
+                            manifest = manifest.manifest_key,
+                            ?err,
+                            "failed to export reward index snapshot"
+                        );
+                    }
+                }
+            }
+        }
+        tracing::info!("stopping reward index snapshots");
+        Ok(())
+    }
+
+    /// Snapshots the committed reward index if `manifest` is still the last
+    /// manifest indexed. Returns None if the index has since moved on.
+    pub async fn export(&self, manifest: &CommittedManifest) -> Result<Option<Snapshot>> {
+        let mut txn = self.pool.begin().await?;
+        sqlx::query("set transaction isolation level repeatable read, read only")
+            .execute(&mut *txn)
+            .await?;
+        if db::fetch_last_indexed(&mut txn).await?.as_ref() != Some(manifest) {
+            tracing::info!(
+                manifest = manifest.manifest_key,
+                "index has moved on, skipping snapshot"
+            );
+            return Ok(None);
+        }
+        let entries = db::fetch_entries(&mut txn).await?;
+        txn.commit().await?;
+
+        let snapshot = Snapshot::new(manifest.manifest_key.clone(), manifest.epoch_end, entries);
+        let path = {
+            let snapshot = snapshot.clone();
+            self.exporter
+                .export(&snapshot.file_name(), move |writer| {
+                    Ok(serde_json::to_writer(writer, &snapshot)?)
+                })
+                .await?
+        };
+        // Only published snapshots have their root recorded
+        db::record(&self.pool, &snapshot).await?;
+
+        metrics::gauge!("reward_index_snapshot_entries").set(snapshot.entries.len() as f64);
+        tracing::info!(
+            manifest = manifest.manifest_key,
+            merkle_root = snapshot.merkle_root,
+            entries = snapshot.entries.len(),
+            path = %path.display(),
+            "exported reward index snapshot"
+        );
+        Ok(Some(snapshot))
+    }
+}
+
+pub mod db {
+    use super::*;
+
+    pub async fn fetch_entries(
+        txn: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<SnapshotEntry>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            select address, reward_type::text as reward_type, rewards as amount
+            from reward_index
+            order by address, reward_type
+            "#,
+        )
+        .fetch_all(&mut *txn)
+        .await
+    }
+
+    /// The manifest most recently recorded as indexed
+    pub async fn fetch_last_indexed(
+        txn: &mut Transaction<'_, Postgres>,
+    ) -> Result<Option<CommittedManifest>, sqlx::Error> {
+        let row: Option<(String, DateTime<Utc>)> = sqlx::query_as(
+            r#"
+            select manifest_key, epoch_end
+            from reward_manifests
+            order by indexed_at desc
+            limit 1
+            "#,
+        )
+        .fetch_optional(&mut *txn)
+        .await?;
+        Ok(row.map(|(manifest_key, epoch_end)| CommittedManifest {
+            manifest_key,
+            epoch_end,
+        }))
+    }
+
+    pub async fn record(pool: &PgPool, snapshot: &Snapshot) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            insert into reward_index_snapshots (
+                    epoch_end,
+                    manifest_key,
+                    merkle_root,
+                    entries,
+                    created_at
+                ) values ($1, $2, $3, $4, $5)
+                on conflict(epoch_end) do update set
+                    manifest_key = EXCLUDED.manifest_key,
+                    merkle_root = EXCLUDED.merkle_root,
+                    entries = EXCLUDED.entries,
+                    created_at = EXCLUDED.created_at
+            "#,
+        )
+        .bind(snapshot.epoch_end)
+        .bind(&snapshot.manifest_key)
+        .bind(&snapshot.merkle_root)
+        .bind(snapshot.entries.len() as i64)
+        .bind(Utc::now())
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Merkle root of the latest snapshot taken at or before `epoch_end`
+    pub async fn fetch_root(
+        pool: &PgPool,
+        epoch_end: DateTime<Utc>,
+    ) -> Result<Option<(DateTime<Utc>, String)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            select epoch_end, merkle_root
+            from reward_index_snapshots
+            where epoch_end <= $1
+            order by epoch_end desc
+            limit 1
+            "#,
+        )
+        .bind(epoch_end)
+        .fetch_optional(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(address: &str, amount: i64) -> SnapshotEntry {
+        SnapshotEntry {
+            address: address.to_string(),
+            reward_type: "mobile_gateway".to_string(),
+            amount,
+        }
+    }
+
+    #[test]
+    fn every_proof_verifies() -> Result<()> {
+        for size in 0..=9 {
+            let entries = (0..size)
+                .map(|i| entry(&format!("address{i}"), i * 10))
+                .collect();
+            let snapshot = Snapshot::new("manifest".to_string(), DateTime::UNIX_EPOCH, entries);
+            assert_eq!(snapshot.entries.len(), size as usize);
+            assert!(snapshot.verify()?.is_empty());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn root_is_independent_of_order_and_binds_amounts() {
+        let snapshot =
+            |entries| Snapshot::new("manifest".to_string(), DateTime::UNIX_EPOCH, entries);
+        let a = snapshot(vec![entry("a", 1), entry("b", 2), entry("c", 3)]);
+        let b = snapshot(vec![entry("c", 3), entry("a", 1), entry("b", 2)]);
+        assert_eq!(a.merkle_root, b.merkle_root);
+
+        let mut tampered = a.entries[1].clone();
+        assert!(tampered.verify(&a.merkle_root));
+        tampered.amount += 1;
+        assert!(!tampered.verify(&a.merkle_root));
+
+        let c = snapshot(vec![entry("a", 1), entry("b", 3), entry("c", 3)]);
+        assert_ne!(a.merkle_root, c.merkle_root);
+    }
+}
@@ -33,6 +33,46 @@ pub trait SolanaNetwork: Send + Sync + 'static {
     ) -> Result<(), SolanaRpcError>;
 
     async fn confirm_transaction(&self, txn: &str) -> Result<bool, SolanaRpcError>;
+
+    /// The authority signing start boost instructions. Without one the
+    /// default pubkey stands in for it.
+    fn start_authority(&self) -> Pubkey {
+        Pubkey::default()
+    }
+
+    /// The start boost instructions for a batch, as they would be submitted,
+    /// without building or signing a transaction
+    fn start_boost_instructions(
+        &self,
+        batch: &[BoostedHexActivation],
+    ) -> Result<Vec<Instruction>, SolanaRpcError> {
+        batch
+            .iter()
+            .map(|activation| start_boost_instruction(self.start_authority(), activation))
+            .collect()
+    }
+}
+
+/// The `StartBoostV0` instruction activating a boosted hex
+pub fn start_boost_instruction(
+    start_authority: Pubkey,
+    activation: &BoostedHexActivation,
+) -> Result<Instruction, SolanaRpcError> {
+    let account = accounts::StartBoostV0 {
+        start_authority,
+        boost_config: activation.boost_config_pubkey.parse()?,
+        boosted_hex: activation.boosted_hex_pubkey.parse()?,
+    };
+    let args = instruction::StartBoostV0 {
+        _args: hexboosting::StartBoostArgsV0 {
+            start_ts: activation.activation_ts.timestamp(),
+        },
+    };
+    Ok(Instruction {
+        program_id: hexboosting::id(),
+        accounts: account.to_account_metas(None),
+        data: args.data(),
+    })
 }
 
 #[derive(Debug, Deserialize)]
@@ -83,22 +123,7 @@ impl SolanaNetwork for SolanaRpc {
                 std::rc::Rc::new(Keypair::from_bytes(&self.keypair).unwrap()),
                 Some(CommitmentConfig::finalized()),
             );
-            for update in batch {
-                let account = accounts::StartBoostV0 {
-                    start_authority: self.start_authority,
-                    boost_config: update.boost_config_pubkey.parse()?,
-                    boosted_hex: update.boosted_hex_pubkey.parse()?,
-                };
-                let args = instruction::StartBoostV0 {
-                    _args: hexboosting::StartBoostArgsV0 {
-                        start_ts: update.activation_ts.timestamp(),
-                    },
-                };
-                let instruction = Instruction {
-                    program_id: hexboosting::id(),
-                    accounts: account.to_account_metas(None),
-                    data: args.data(),
-                };
+            for instruction in self.start_boost_instructions(batch)? {
                 request = request.instruction(instruction);
             }
             request.instructions().unwrap()
@@ -135,6 +160,10 @@ impl SolanaNetwork for SolanaRpc {
         }
     }
 
+    fn start_authority(&self) -> Pubkey {
+        self.start_authority
+    }
+
     async fn confirm_transaction(&self, signature: &str) -> Result<bool, SolanaRpcError> {
         let txn: Signature = signature.parse()?;
         Ok(matches!(
@@ -201,4 +230,9 @@ impl SolanaNetwork for Option<Arc<SolanaRpc>> {
             panic!("We will not confirm transactions when Solana is disabled");
         }
     }
+
+    fn start_authority(&self) -> Pubkey {
+        self.as_ref()
+            .map_or_else(Pubkey::default, |rpc| rpc.start_authority())
+    }
 }